@@ -0,0 +1,301 @@
+// The runtime of the TypeScript code generated by Punybuf.
+// Every type has a `PbCodec`, which (de)serializes its values.
+
+/** (De)serializes the values of a type */
+export interface PbCodec<T> {
+	serialize(w: PbWriter, value: T): void;
+	deserialize(r: PbReader): T;
+	/** `undefined` is one of the values, like it is for `Optional`s */
+	hasUndefined?: boolean;
+}
+
+/** The greatest value of a `UInt` */
+const UINT_MAX = 1152921573328437375n;
+/** The most bytes or items in `Bytes`, `String` and `Array` */
+const LENGTH_LIMIT = 4294967296;
+
+const textEncoder = new TextEncoder();
+const textDecoder = new TextDecoder();
+
+/** A growable buffer values are serialized into */
+export class PbWriter {
+	private buffer = new Uint8Array(64);
+	private view = new DataView(this.buffer.buffer);
+	private length = 0;
+
+	/** Makes room for `size` bytes and returns where they start. Call it before using `view`, which it may replace */
+	private reserve(size: number): number {
+		const offset = this.length;
+		if (offset + size > this.buffer.length) {
+			const grown = new Uint8Array(Math.max(this.buffer.length * 2, offset + size));
+			grown.set(this.buffer);
+			this.buffer = grown;
+			this.view = new DataView(grown.buffer);
+		}
+		this.length += size;
+		return offset;
+	}
+	u8(value: number) { const at = this.reserve(1); this.view.setUint8(at, value); }
+	u16(value: number, littleEndian = false) { const at = this.reserve(2); this.view.setUint16(at, value, littleEndian); }
+	u32(value: number, littleEndian = false) { const at = this.reserve(4); this.view.setUint32(at, value, littleEndian); }
+	u64(value: bigint, littleEndian = false) { const at = this.reserve(8); this.view.setBigUint64(at, value, littleEndian); }
+	i32(value: number, littleEndian = false) { const at = this.reserve(4); this.view.setInt32(at, value, littleEndian); }
+	i64(value: bigint, littleEndian = false) { const at = this.reserve(8); this.view.setBigInt64(at, value, littleEndian); }
+	f32(value: number, littleEndian = false) { const at = this.reserve(4); this.view.setFloat32(at, value, littleEndian); }
+	f64(value: number, littleEndian = false) { const at = this.reserve(8); this.view.setFloat64(at, value, littleEndian); }
+	/** Writes `bytes` as they are, without their length */
+	raw(bytes: Uint8Array) { const at = this.reserve(bytes.length); this.buffer.set(bytes, at); }
+	uint(value: bigint | number) {
+		let uint = BigInt(value);
+		if (uint < 0n || uint > UINT_MAX) {
+			throw new RangeError(`number out of range for a UInt (max ${UINT_MAX})`);
+		}
+		let size: number, prefix: number;
+		if (uint < 128n) {
+			size = 1; prefix = 0;
+		} else if (uint < 16512n) {
+			uint -= 128n; size = 2; prefix = 0b10_000000;
+		} else if (uint < 2113664n) {
+			uint -= 16512n; size = 3; prefix = 0b110_00000;
+		} else if (uint < 68721590400n) {
+			uint -= 2113664n; size = 5; prefix = 0b1110_0000;
+		} else {
+			uint -= 68721590400n; size = 8; prefix = 0b1111_0000;
+		}
+		const offset = this.reserve(size);
+		for (let i = size - 1; i >= 0; i--) {
+			this.buffer[offset + i] = Number(uint & 0xffn);
+			uint >>= 8n;
+		}
+		this.buffer[offset] |= prefix;
+	}
+	/** Writes a ZigZag-encoded `SInt`, so that 0, -1, 1, -2... become 0, 1, 2, 3... */
+	sint(value: bigint | number) {
+		const sint = BigInt(value);
+		this.uint(sint < 0n ? -sint * 2n - 1n : sint * 2n);
+	}
+	/** The bytes written so far */
+	finish(): Uint8Array {
+		return this.buffer.slice(0, this.length);
+	}
+}
+
+/** Reads values from a buffer, front to back */
+export class PbReader {
+	// no parameter properties, so that the types can simply be stripped
+	private bytes: Uint8Array;
+	private view: DataView;
+	private offset = 0;
+
+	constructor(bytes: Uint8Array) {
+		this.bytes = bytes;
+		this.view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+	}
+	private take(size: number): number {
+		const offset = this.offset;
+		if (offset + size > this.bytes.length) {
+			throw new RangeError(`unexpected end of input: needed ${size} bytes at offset ${offset}`);
+		}
+		this.offset += size;
+		return offset;
+	}
+	/** How many bytes are left */
+	get remaining(): number {
+		return this.bytes.length - this.offset;
+	}
+	u8(): number { return this.view.getUint8(this.take(1)); }
+	u16(littleEndian = false): number { return this.view.getUint16(this.take(2), littleEndian); }
+	u32(littleEndian = false): number { return this.view.getUint32(this.take(4), littleEndian); }
+	u64(littleEndian = false): bigint { return this.view.getBigUint64(this.take(8), littleEndian); }
+	i32(littleEndian = false): number { return this.view.getInt32(this.take(4), littleEndian); }
+	i64(littleEndian = false): bigint { return this.view.getBigInt64(this.take(8), littleEndian); }
+	f32(littleEndian = false): number { return this.view.getFloat32(this.take(4), littleEndian); }
+	f64(littleEndian = false): number { return this.view.getFloat64(this.take(8), littleEndian); }
+	/** Reads `size` bytes, without a length before them */
+	raw(size: number): Uint8Array {
+		const offset = this.take(size);
+		return this.bytes.slice(offset, offset + size);
+	}
+	uintBig(): bigint {
+		const first = this.u8();
+		let size: number, mask: number, add: bigint;
+		if (first >> 7 === 0) {
+			return BigInt(first);
+		} else if ((first & 0b010_00000) === 0) {
+			size = 2; mask = 0b00_111111; add = 128n;
+		} else if ((first & 0b001_00000) === 0) {
+			size = 3; mask = 0b000_11111; add = 16512n;
+		} else if ((first & 0b0001_0000) === 0) {
+			size = 5; mask = 0b0000_1111; add = 2113664n;
+		} else {
+			size = 8; mask = 0b0000_1111; add = 68721590400n;
+		}
+		let uint = BigInt(first & mask);
+		for (let i = 1; i < size; i++) {
+			uint = (uint << 8n) | BigInt(this.u8());
+		}
+		return uint + add;
+	}
+	/** Reads a `UInt` that has to fit into a `number` */
+	uint(): number {
+		const uint = this.uintBig();
+		if (uint > BigInt(Number.MAX_SAFE_INTEGER)) {
+			throw new RangeError(`UInt ${uint} doesn't fit into a number`);
+		}
+		return Number(uint);
+	}
+	/** Reads an `SInt` that has to fit into a `number` */
+	sint(): number {
+		const zigzag = this.uintBig();
+		const sint = zigzag & 1n ? -(zigzag >> 1n) - 1n : zigzag >> 1n;
+		if (sint > BigInt(Number.MAX_SAFE_INTEGER) || sint < BigInt(Number.MIN_SAFE_INTEGER)) {
+			throw new RangeError(`SInt ${sint} doesn't fit into a number`);
+		}
+		return Number(sint);
+	}
+	/** Reads the length of `Bytes`, a `String` or an `Array` */
+	length(): number {
+		const length = this.uint();
+		if (length > LENGTH_LIMIT) {
+			throw new RangeError(`length ${length} is over the limit of ${LENGTH_LIMIT}`);
+		}
+		return length;
+	}
+	/** Reads the extensions of a struct or enum, prefixed with their length */
+	extensions(): PbReader {
+		return new PbReader(this.raw(this.length()));
+	}
+}
+
+/** Serializes `value` into a new buffer */
+export function pbSerialize<T>(codec: PbCodec<T>, value: T): Uint8Array {
+	const w = new PbWriter();
+	codec.serialize(w, value);
+	return w.finish();
+}
+
+/** Deserializes a value from the start of `bytes` */
+export function pbDeserialize<T>(codec: PbCodec<T>, bytes: Uint8Array): T {
+	return codec.deserialize(new PbReader(bytes));
+}
+
+/** Serializes `value` with its length before it, like extensions are */
+export function pbSerializeExtension<T>(w: PbWriter, codec: PbCodec<T>, value: T) {
+	const bytes = pbSerialize(codec, value);
+	w.uint(bytes.length);
+	w.raw(bytes);
+}
+
+export const pbU8: PbCodec<number> = { serialize: (w, v) => w.u8(v), deserialize: (r) => r.u8() };
+export const pbU16: PbCodec<number> = { serialize: (w, v) => w.u16(v), deserialize: (r) => r.u16() };
+export const pbU32: PbCodec<number> = { serialize: (w, v) => w.u32(v), deserialize: (r) => r.u32() };
+export const pbU64: PbCodec<bigint> = { serialize: (w, v) => w.u64(v), deserialize: (r) => r.u64() };
+export const pbI32: PbCodec<number> = { serialize: (w, v) => w.i32(v), deserialize: (r) => r.i32() };
+export const pbI64: PbCodec<bigint> = { serialize: (w, v) => w.i64(v), deserialize: (r) => r.i64() };
+export const pbF32: PbCodec<number> = { serialize: (w, v) => w.f32(v), deserialize: (r) => r.f32() };
+export const pbF64: PbCodec<number> = { serialize: (w, v) => w.f64(v), deserialize: (r) => r.f64() };
+export const pbUInt: PbCodec<number> = { serialize: (w, v) => w.uint(v), deserialize: (r) => r.uint() };
+export const pbSInt: PbCodec<number> = { serialize: (w, v) => w.sint(v), deserialize: (r) => r.sint() };
+/** `null`, so that it's not mistaken for a missing value */
+export const pbVoid: PbCodec<null> = { serialize: () => {}, deserialize: () => null };
+
+/** `true` is `0` and `false` is `1`, like the variants of an enum `[True, False]` */
+export const pbBoolean: PbCodec<boolean> = {
+	serialize: (w, value) => w.u8(value ? 0 : 1),
+	deserialize(r) {
+		const byte = r.u8();
+		if (byte > 1) {
+			throw new RangeError(`invalid Boolean ${byte}, expected 0 or 1`);
+		}
+		return byte === 0;
+	},
+};
+
+export const pbBytes: PbCodec<Uint8Array> = {
+	serialize(w, value) {
+		w.uint(value.length);
+		w.raw(value);
+	},
+	deserialize: (r) => r.raw(r.length()),
+};
+
+/** Invalid UTF-8 is replaced with U+FFFD */
+export const pbString: PbCodec<string> = {
+	serialize: (w, value) => pbBytes.serialize(w, textEncoder.encode(value)),
+	deserialize: (r) => textDecoder.decode(pbBytes.deserialize(r)),
+};
+
+export function pbArray<T>(item: PbCodec<T>): PbCodec<T[]> {
+	return {
+		serialize(w, value) {
+			w.uint(value.length);
+			for (const v of value) {
+				item.serialize(w, v);
+			}
+		},
+		deserialize(r) {
+			const length = r.length();
+			const result: T[] = [];
+			for (let i = 0; i < length; i++) {
+				result.push(item.deserialize(r));
+			}
+			return result;
+		},
+	};
+}
+
+/**
+ * A value that's there, as opposed to `undefined`. If `undefined` is a value
+ * of `T` too, like it is in `Optional<Optional<T>>`, it's wrapped in `{ value }`.
+ */
+export type PbSome<T> = undefined extends T ? { value: T } : T;
+/** `undefined` if there's no value */
+export type PbOptional<T> = PbSome<T> | undefined;
+
+/** The values of `value` that are there, for `Optional`s and the values of flags */
+export function pbSome<T>(value: PbCodec<T>): PbCodec<PbSome<T>> {
+	if (!value.hasUndefined) {
+		return value as unknown as PbCodec<PbSome<T>>;
+	}
+	return {
+		serialize: (w, v) => value.serialize(w, (v as { value: T }).value),
+		deserialize: (r) => ({ value: value.deserialize(r) }) as PbSome<T>,
+	};
+}
+
+/** `undefined` is `0`, and any other value is `1` followed by the value */
+export function pbOptional<T>(value: PbCodec<T>): PbCodec<PbOptional<T>> {
+	const some = pbSome(value);
+	return {
+		serialize(w, v) {
+			if (v === undefined) {
+				w.u8(0);
+			} else {
+				w.u8(1);
+				some.serialize(w, v);
+			}
+		},
+		deserialize(r) {
+			const byte = r.u8();
+			if (byte > 1) {
+				throw new RangeError(`invalid Optional ${byte}, expected 0 or 1`);
+			}
+			return byte === 0 ? undefined : some.deserialize(r);
+		},
+		hasUndefined: true,
+	};
+}
+
+/** A point in time, in UTC. Leap seconds have 1 000 000 000 or more nanoseconds */
+export interface PbDateTime {
+	secondsSinceEpoch: bigint;
+	nanoseconds: number;
+}
+
+export const pbDateTime: PbCodec<PbDateTime> = {
+	serialize(w, value) {
+		w.i64(value.secondsSinceEpoch);
+		w.u32(value.nanoseconds);
+	},
+	deserialize: (r) => ({ secondsSinceEpoch: r.i64(), nanoseconds: r.u32() }),
+};
@@ -0,0 +1,150 @@
+//! The attributes known to the compiler, and the values they take.
+//!
+//! The lexer keeps attribute values as raw strings. They are checked against
+//! [`KNOWN_ATTRIBUTES`] once, right after lexing, so the rest of the compiler
+//! can rely on e.g. `@flags` always having a number. Unknown attributes are
+//! left alone, since implementations may define their own, unless the
+//! [`UnknownAttribute`](crate::lints::Lint::UnknownAttribute) lint is enabled.
+
+use std::collections::HashMap;
+
+use crate::{
+	errors::{pb_err, PunybufError},
+	lexer::{Span, Token, TokenData},
+};
+
+/// The shape of an attribute's value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttrValue {
+	/// `@attribute`
+	None,
+	/// `@attribute(42)`, at most `max`
+	Number { max: u64 },
+	/// `@attribute(anything at all)`, but not empty
+	String,
+	/// `@attribute(First, Second)`
+	IdentList,
+}
+
+pub const KNOWN_ATTRIBUTES: &[(&str, AttrValue)] = &[
+	("@resolve", AttrValue::None),
+	("@extension", AttrValue::None),
+	("@extension_flags", AttrValue::None),
+	("@capability", AttrValue::String),
+	("@stream", AttrValue::None),
+	("@sealed", AttrValue::None),
+	("@bitmap", AttrValue::None),
+	("@default", AttrValue::None),
+	("@name", AttrValue::String),
+	("@id", AttrValue::Number { max: u32::MAX as u64 }),
+	("@builtin", AttrValue::None),
+	("@void", AttrValue::None),
+	("@flags", AttrValue::Number { max: u64::MAX }),
+	("@map_convertible", AttrValue::None),
+	("@compress", AttrValue::String),
+	("@le", AttrValue::None),
+	("@be", AttrValue::None),
+	("@lazy_array", AttrValue::None),
+	("@phantom", AttrValue::IdentList),
+	("@strict_strings", AttrValue::None),
+	("@experimental", AttrValue::None),
+	("@rust:ignore", AttrValue::None),
+	("@rust:use", AttrValue::String),
+	("@rust:needs_lifetime", AttrValue::None),
+];
+
+fn expected(attr: &str, kind: AttrValue) -> String {
+	match kind {
+		AttrValue::None => format!("`{attr}` doesn't take a value"),
+		AttrValue::Number { .. } => format!("`{attr}` expects a number, like `{attr}(8)`"),
+		AttrValue::String => format!("`{attr}` expects a value, like `{attr}(something)`"),
+		AttrValue::IdentList => format!("`{attr}` expects a list of names, like `{attr}(First, Second)`"),
+	}
+}
+
+/// The names in the value of an [`AttrValue::IdentList`] attribute
+pub fn ident_list(value: &Option<String>) -> Vec<&str> {
+	value.iter().flat_map(|value| value.split(',')).map(str::trim).collect()
+}
+
+fn is_ident(s: &str) -> bool {
+	let mut chars = s.chars();
+	chars.next().is_some_and(|ch| ch.is_alphabetic() || ch == '_') &&
+		chars.all(|ch| ch.is_alphanumeric() || ch == '_')
+}
+
+/// Checks `value` against the schema of `attr`, if it's a known attribute
+pub fn validate_attribute(attr: &str, value: &Option<String>, span: &Span) -> Result<(), PunybufError> {
+	let Some((_, kind)) = KNOWN_ATTRIBUTES.iter().find(|(name, _)| *name == attr) else {
+		return Ok(());
+	};
+	let problem = match (kind, value) {
+		(AttrValue::None, None) => return Ok(()),
+		(AttrValue::None, Some(value)) => format!("got `{value}`"),
+		(_, None) => "the value is missing".to_string(),
+		(AttrValue::Number { max }, Some(value)) => match value.trim().parse::<u64>() {
+			Ok(n) if n <= *max => return Ok(()),
+			Ok(n) => format!("`{n}` is larger than {max}"),
+			Err(_) => format!("`{}` is not a number", value.trim()),
+		},
+		(AttrValue::String, Some(value)) => {
+			if !value.trim().is_empty() {
+				return Ok(());
+			}
+			"the value is empty".to_string()
+		}
+		(AttrValue::IdentList, Some(value)) => {
+			match value.split(',').map(str::trim).find(|name| !is_ident(name)) {
+				None => return Ok(()),
+				Some("") => "the list has an empty name".to_string(),
+				Some(name) => format!("`{name}` is not a valid name"),
+			}
+		}
+	};
+	Err(pb_err!(
+		span,
+		format!("invalid value for `{attr}`: {problem}"),
+		after_error: vec![
+			diagnostic!(Tip, Span::impossible(), expected(attr, *kind))
+		]
+	))
+}
+
+/// Checks every attribute in the token tree, see [`validate_attribute`]
+pub fn validate_tokens(tokens: &[Token]) -> Result<(), PunybufError> {
+	for tk in tokens {
+		match &tk.data {
+			TokenData::Attribute(attr, value) => validate_attribute(attr, value, &tk.span)?,
+			TokenData::CurlyBraces(inner) |
+			TokenData::SquareBrackets(inner) |
+			TokenData::Parentheses(inner) |
+			TokenData::AngleBrackets(inner) => validate_tokens(inner)?,
+			_ => {}
+		}
+	}
+	Ok(())
+}
+
+/// Finds the attributes that aren't known and aren't namespaced like `@rust:ignore`,
+/// which are likely typos
+pub fn unknown_attributes(tokens: &[Token], problems: &mut Vec<PunybufError>) {
+	for tk in tokens {
+		match &tk.data {
+			TokenData::Attribute(attr, _) if !attr.contains(':') && !KNOWN_ATTRIBUTES.iter().any(|(name, _)| name == attr) => {
+				problems.push(pb_err!(tk.span, format!("unknown attribute `{attr}`")));
+			}
+			TokenData::CurlyBraces(inner) |
+			TokenData::SquareBrackets(inner) |
+			TokenData::Parentheses(inner) |
+			TokenData::AngleBrackets(inner) => unknown_attributes(inner, problems),
+			_ => {}
+		}
+	}
+}
+
+/// The value of a known `AttrValue::Number` attribute, if it's present.
+///
+/// Only meant for attributes that went through [`validate_tokens`].
+pub fn number(attrs: &HashMap<String, Option<String>>, attr: &str) -> Option<u64> {
+	attrs.get(attr)?.as_ref()?.trim().parse().ok()
+}
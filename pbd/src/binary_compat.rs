@@ -1,18 +1,119 @@
-use crate::{errors::PunybufError, flattener::PunybufDefinition};
+use std::collections::HashMap;
+
+use crate::{
+	converter::parse_full_definition,
+	errors::{pb_err, ErrorInfo, PunybufError},
+	flattener::{PBCommandArg, PBEnumVariant, PBTypeDef, PunybufDefinition},
+	lexer::Span,
+};
 
 pub(crate) struct BinaryCompat<'a> {
-	prev_json: &'a str,
+	prev: PunybufDefinition,
 	next: &'a PunybufDefinition,
 }
 
 impl<'a> BinaryCompat<'a> {
 	pub(crate) fn new(prev_json: &'a str, next: &'a PunybufDefinition) -> Result<Self, String> {
 		Ok(Self {
-			prev_json, next
+			prev: parse_full_definition(prev_json).map_err(|e| format!("failed to read the previous version: {e}"))?,
+			next
 		})
 	}
 	pub(crate) fn check(&self) -> Result<(), PunybufError> {
-		
-		Ok(())
+		let mut errors = vec![];
+		for tp in &self.next.types {
+			let prev = self.prev.types.iter().find(|prev| {
+				prev.get_name().0 == tp.get_name().0 && prev.get_layer() == tp.get_layer()
+			});
+			let (name, span) = tp.get_name();
+			let result = match (prev, tp) {
+				(Some(PBTypeDef::Struct { attrs: prev_attrs, .. }), PBTypeDef::Struct { attrs, .. }) => {
+					Self::check_sealed(name, span, *tp.get_layer(), prev_attrs, attrs)
+				}
+				(Some(PBTypeDef::Enum { variants: prev_variants, .. }), PBTypeDef::Enum { variants, .. }) => {
+					Self::check_new_variants(name, *tp.get_layer(), prev_variants, variants)
+				}
+				_ => Ok(()),
+			};
+			if let Err(e) = result {
+				errors.push(e);
+			}
+		}
+		for cmd in &self.next.commands {
+			if !matches!(cmd.argument, PBCommandArg::Struct { .. }) {
+				continue;
+			}
+			let prev = self.prev.commands.iter().find(|prev| {
+				prev.name == cmd.name && prev.layer == cmd.layer
+			});
+			if
+				let Some(prev) = prev && matches!(prev.argument, PBCommandArg::Struct { .. }) &&
+				let Err(e) = Self::check_sealed(&cmd.name, &cmd.name_span, cmd.layer, &prev.attrs, &cmd.attrs)
+			{
+				errors.push(e);
+			}
+		}
+		match PunybufError::chain(errors) {
+			Some(e) => Err(e),
+			None => Ok(()),
+		}
+	}
+	/// The old version decodes variants it doesn't know as its `@default` one, and skips
+	/// their values by their length, which only `@extension` variants start with
+	fn check_new_variants(
+		name: &str, layer: u32, prev: &[PBEnumVariant], next: &[PBEnumVariant],
+	) -> Result<(), PunybufError> {
+		if !prev.iter().any(|v| v.attrs.contains_key("@default")) {
+			return Ok(());
+		}
+		let errors = next.iter()
+			.filter(|v| !v.attrs.contains_key("@extension") && !prev.iter().any(|p| p.discriminant == v.discriminant))
+			.map(|v| pb_err!(
+				v.name_span,
+				format!("`{}` was added to `{name}` of layer {layer} without `@extension`", v.name),
+				ErrorInfo::error_and(vec![
+					diagnostic!(Info,
+						Span::impossible(),
+						format!("note: the old version decodes it as its `@default` variant, \
+							but can't tell where its value ends, since only `@extension` variants start with their length")
+					),
+					diagnostic!(Tip,
+						Span::impossible(),
+						format!("tip: mark `{}` as `@extension`, or declare the changed `{name}` in a new layer", v.name)
+					),
+				])
+			))
+			.collect();
+		match PunybufError::chain(errors) {
+			Some(e) => Err(e),
+			None => Ok(()),
+		}
 	}
-}
\ No newline at end of file
+	/// Only structs that aren't `@sealed` end with the length of their extensions,
+	/// so adding or removing `@sealed` changes how every value is encoded
+	fn check_sealed(
+		name: &str, span: &Span, layer: u32,
+		prev: &HashMap<String, Option<String>>, next: &HashMap<String, Option<String>>,
+	) -> Result<(), PunybufError> {
+		let was_sealed = prev.contains_key("@sealed");
+		if was_sealed == next.contains_key("@sealed") {
+			return Ok(());
+		}
+		let change = if was_sealed { "isn't `@sealed` anymore" } else { "became `@sealed`" };
+		Err(pb_err!(
+			span,
+			format!("`{name}` of layer {layer} {change}"),
+			ErrorInfo::error_and(vec![
+				diagnostic!(Info,
+					Span::impossible(),
+					"note: only structs that aren't `@sealed` end with the length of their extensions, \
+						so the old and the new version would read each other's values wrong".to_string()
+				),
+				diagnostic!(Tip,
+					Span::impossible(),
+					format!("tip: declare the changed `{name}` in a new layer instead")
+				),
+			])
+		))
+	}
+}
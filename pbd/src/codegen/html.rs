@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use super::sorted_attrs;
 use crate::flattener::{PBCommandArg, PBCommandDef, PBEnumVariant, PBField, PBTypeDef, PBTypeRef, PunybufDefinition};
 
 const DEFAULT_TEMPLATE: &str = include_str!("../../baked/template.html");
@@ -155,7 +156,7 @@ impl<'d> HTMLCodegen<'d> {
 			if !field.attrs.is_empty() {
 				appendf!(self, r##"    <tr class="attr-list">"##);
 				appendf!(self, r##"      <td colspan="2">"##);
-				for (attr, val) in &field.attrs {
+				for (attr, val) in sorted_attrs(&field.attrs) {
 					appendf!(self, r##"<span class="attr code">{}"##, attr);
 					if let Some(val) = val {
 						appendf!(self, r##"({val})"##)
@@ -196,7 +197,7 @@ impl<'d> HTMLCodegen<'d> {
 				if !flag.attrs.is_empty() {
 					appendf!(self, r##"    <tr class="flag attr-list">"##);
 					appendf!(self, r##"      <td colspan="2">"##);
-					for (attr, val) in &flag.attrs {
+					for (attr, val) in sorted_attrs(&flag.attrs) {
 						appendf!(self, r##"<span class="attr code">{}"##, attr);
 						if let Some(val) = val {
 							appendf!(self, r##"({val})"##)
@@ -241,7 +242,7 @@ impl<'d> HTMLCodegen<'d> {
 			if !variant.attrs.is_empty() {
 				appendf!(self, r##"    <tr class="attr-list">"##);
 				appendf!(self, r##"      <td colspan="2">"##);
-				for (attr, val) in &variant.attrs {
+				for (attr, val) in sorted_attrs(&variant.attrs) {
 					self.gen_attr(attr, val);
 				}
 				appendf!(self, r##"      </td>"##);
@@ -295,7 +296,7 @@ impl<'d> HTMLCodegen<'d> {
 		appendf!(self, r##"<div class="item-content">"##);
 		if !cmd.attrs.is_empty() {
 			appendf!(self, r##"<div class="item-attr-list">"##);
-			for (attr, val) in &cmd.attrs {
+			for (attr, val) in sorted_attrs(&cmd.attrs) {
 				self.gen_attr(attr, val);
 			}
 			appendf!(self, r##"</div>"##);
@@ -382,7 +383,7 @@ impl<'d> HTMLCodegen<'d> {
 		appendf!(self, r##"<div class="item-content">"##);
 		if !tp.get_attrs().is_empty() {
 			appendf!(self, r##"<div class="item-attr-list">"##);
-			for (attr, val) in tp.get_attrs() {
+			for (attr, val) in sorted_attrs(tp.get_attrs()) {
 				self.gen_attr(attr, val);
 			}
 			appendf!(self, r##"</div>"##);
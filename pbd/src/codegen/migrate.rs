@@ -0,0 +1,221 @@
+use crate::flattener::{PBEnumVariant, PBField, PBTypeDef, PBTypeRef, PunybufDefinition};
+
+use super::RustCodegen;
+
+/// Generates stubs of functions that convert a type from one layer to the next
+/// one, auto-mapping everything that didn't change between the layers.
+pub struct MigrationCodegen<'def> {
+	def: &'def PunybufDefinition,
+	rust: RustCodegen<'def>,
+	buffer: String,
+}
+
+macro_rules! appendf {
+	($s:ident, $x:literal, $($rpt:expr),*) => {
+		$s.buffer.push_str(&format!($x, $($rpt),*))
+	};
+	($s:ident, $x:literal) => {
+		$s.buffer.push_str(&format!($x))
+	};
+}
+
+/// A struct field, as it's seen from Rust: flags are separate fields
+pub(super) enum Member<'a> {
+	Field(&'a PBTypeRef),
+	Flag(Option<&'a PBTypeRef>),
+}
+
+pub(super) fn members(fields: &Vec<PBField>) -> Vec<(&str, Member<'_>)> {
+	let mut result = vec![];
+	for field in fields {
+		if let Some(flags) = &field.flags {
+			for flag in flags {
+				result.push((flag.name.as_str(), Member::Flag(flag.value.as_ref())));
+			}
+		} else {
+			result.push((field.name.as_str(), Member::Field(&field.value)));
+		}
+	}
+	result
+}
+
+pub(super) fn same_type(a: &PBTypeRef, b: &PBTypeRef) -> bool {
+	a.reference == b.reference &&
+	a.resolved_layer == b.resolved_layer &&
+	a.is_global == b.is_global &&
+	a.generics.len() == b.generics.len() &&
+	a.generics.iter().zip(&b.generics).all(|(a, b)| same_type(a, b))
+}
+
+pub(super) fn same_member(a: &Member, b: &Member) -> bool {
+	match (a, b) {
+		(Member::Field(a), Member::Field(b)) => same_type(a, b),
+		(Member::Flag(None), Member::Flag(None)) => true,
+		(Member::Flag(Some(a)), Member::Flag(Some(b))) => same_type(a, b),
+		_ => false,
+	}
+}
+
+fn snake_case(name: &str) -> String {
+	let mut result = String::with_capacity(name.len());
+	for (i, ch) in name.chars().enumerate() {
+		if ch.is_ascii_uppercase() {
+			if i != 0 && !result.ends_with('_') {
+				result.push('_');
+			}
+			result.push(ch.to_ascii_lowercase());
+		} else {
+			result.push(ch);
+		}
+	}
+	result
+}
+
+impl<'def> MigrationCodegen<'def> {
+	pub fn new(def: &'def PunybufDefinition) -> Self {
+		let mut rust = RustCodegen::new(false, false, def);
+		rust.prepare();
+		Self { def, rust, buffer: String::new() }
+	}
+	fn migration_name(name: &str, from: u32, to: u32) -> String {
+		format!("migrate_{}_v{from}_to_v{to}", snake_case(name))
+	}
+	/// Returns the layer that comes right after `layer` for the type `name`, if there is one
+	fn next_layer(&self, name: &str, layer: u32) -> Option<u32> {
+		self.def.types.iter()
+			.filter(|t| t.get_name().0 == name && *t.get_layer() > layer)
+			.map(|t| *t.get_layer())
+			.min()
+	}
+	/// Converts a value of the old type into the new one, if that's possible
+	/// without human intervention
+	fn convert(&self, value: &str, old: &PBTypeRef, new: &PBTypeRef) -> Option<String> {
+		if same_type(old, new) {
+			return Some(value.to_string());
+		}
+		// the same type, but from a newer layer, which we also have a migration for
+		if
+			old.reference == new.reference && old.is_global && new.is_global &&
+			old.generics.is_empty() && new.generics.is_empty() &&
+			let (Some(from), Some(to)) = (old.resolved_layer, new.resolved_layer) &&
+			self.next_layer(&old.reference, from) == Some(to)
+		{
+			return Some(format!("{}({value})", Self::migration_name(&old.reference, from, to)));
+		}
+		None
+	}
+	fn gen_struct(&mut self, old_fields: &Vec<PBField>, new_fields: &Vec<PBField>, new_name: &str, phantom: bool) {
+		let old_members = members(old_fields);
+		appendf!(self, "    {new_name} {{\n");
+		for (name, new_member) in members(new_fields) {
+			let Some((_, old_member)) = old_members.iter().find(|m| m.0 == name) else {
+				appendf!(self, "        {name}: todo!(\"`{name}` was added\"),\n");
+				continue;
+			};
+			let converted = match (old_member, &new_member) {
+				_ if same_member(old_member, &new_member) => Some(format!("old.{name}")),
+				(Member::Field(old), Member::Field(new)) => self.convert(&format!("old.{name}"), old, new),
+				(Member::Flag(Some(old)), Member::Flag(Some(new))) => self.convert("v", old, new)
+					.map(|v| format!("old.{name}.map(|v| {v})")),
+				_ => None,
+			};
+			match converted {
+				Some(converted) => appendf!(self, "        {name}: {converted},\n"),
+				None => appendf!(self, "        {name}: todo!(\"`{name}` has changed\"),\n"),
+			}
+		}
+		if phantom {
+			appendf!(self, "        _phantom: std::marker::PhantomData,\n");
+		}
+		appendf!(self, "    }}\n");
+	}
+	fn gen_enum(
+		&mut self,
+		old_variants: &Vec<PBEnumVariant>, new_variants: &Vec<PBEnumVariant>,
+		old_name: &str, new_name: &str
+	) {
+		appendf!(self, "    match old {{\n");
+		for variant in old_variants {
+			let name = &variant.name;
+			let new_variant = new_variants.iter().find(|v| v.name == variant.name);
+			match (&variant.value, new_variant.map(|v| &v.value)) {
+				(None, Some(None)) => {
+					appendf!(self, "        {old_name}::{name} => {new_name}::{name},\n");
+				}
+				(Some(old), Some(Some(new))) => match self.convert("v", old, new) {
+					Some(converted) => {
+						appendf!(self, "        {old_name}::{name}(v) => {new_name}::{name}({converted}),\n");
+					}
+					None => {
+						appendf!(self, "        {old_name}::{name}(_v) => todo!(\"`{name}` has changed\"),\n");
+					}
+				},
+				(None, _) => {
+					appendf!(self, "        {old_name}::{name} => todo!(\"`{name}` was removed or changed\"),\n");
+				}
+				(Some(_), _) => {
+					appendf!(self, "        {old_name}::{name}(_v) => todo!(\"`{name}` was removed or changed\"),\n");
+				}
+			}
+		}
+		appendf!(self, "    }}\n");
+	}
+	fn gen_migration(&mut self, old: &PBTypeDef, new: &PBTypeDef) {
+		let name = old.get_name().0;
+		let (old_layer, new_layer) = (*old.get_layer(), *new.get_layer());
+		let old_type = self.rust.get_type_name(old);
+		let new_type = self.rust.get_type_name(new);
+
+		let mut generics = vec!["'x".to_string()];
+		generics.extend(new.get_generics().0.iter().cloned());
+
+		appendf!(self, "/// Converts `{name}` from layer {old_layer} to layer {new_layer}\n");
+		appendf!(self, "pub fn {}<{}>(old: {old_type}) -> {new_type} {{\n",
+			Self::migration_name(name, old_layer, new_layer),
+			generics.join(", ")
+		);
+		// struct literals and patterns can't have generic arguments without a turbofish
+		let old_path = old_type.split('<').next().unwrap().to_string();
+		let new_path = new_type.split('<').next().unwrap().to_string();
+		match (old, new) {
+			(PBTypeDef::Struct { fields: old_fields, .. }, PBTypeDef::Struct { fields: new_fields, attrs, .. }) => {
+				self.gen_struct(old_fields, new_fields, &new_path, attrs.contains_key("@phantom"));
+			}
+			(PBTypeDef::Enum { variants: old_variants, .. }, PBTypeDef::Enum { variants: new_variants, .. }) => {
+				self.gen_enum(old_variants, new_variants, &old_path, &new_path);
+			}
+			_ => {
+				appendf!(self, "    todo!(\"`{name}` has changed its kind\")\n");
+			}
+		}
+		appendf!(self, "}}\n\n");
+	}
+	pub fn codegen(mut self) -> String {
+		appendf!(self, "///! Migration stubs, generated by Punybuf.\n");
+		appendf!(self, "///! Fill in the `todo!()`s - everything else was mapped automatically.\n\n");
+		appendf!(self, "// This assumes the file is a submodule of the generated Rust code\n");
+		appendf!(self, "use super::*;\n\n");
+
+		for old in &self.def.types {
+			if
+				old.get_attrs().contains_key("@builtin") ||
+				old.get_attrs().contains_key("@rust:ignore") ||
+				old.get_attrs().contains_key("@resolve")
+			{
+				continue;
+			}
+			if let PBTypeDef::Alias { .. } = old {
+				continue;
+			}
+			let Some(next_layer) = self.next_layer(old.get_name().0, *old.get_layer()) else {
+				continue;
+			};
+			let new = self.def.types.iter()
+				.find(|t| t.get_name().0 == old.get_name().0 && *t.get_layer() == next_layer)
+				.expect("bad state: next layer not found");
+			self.gen_migration(old, new);
+		}
+
+		self.buffer
+	}
+}
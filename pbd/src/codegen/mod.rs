@@ -1,5 +1,19 @@
+use std::collections::HashMap;
+
 mod html;
+mod migrate;
 mod rust;
+mod typescript;
 
 pub use html::*;
-pub use rust::*;
\ No newline at end of file
+pub use migrate::*;
+pub use rust::*;
+pub use typescript::*;
+
+/// Returns the attributes sorted by name, so that the generated output doesn't
+/// depend on `HashMap` iteration order
+fn sorted_attrs(attrs: &HashMap<String, Option<String>>) -> Vec<(&String, &Option<String>)> {
+	let mut pairs = attrs.iter().collect::<Vec<_>>();
+	pairs.sort();
+	pairs
+}
@@ -1,13 +1,14 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use crate::{errors::{BOLD, NORMAL, YELLOW}, flattener::{
+use super::{sorted_attrs, migrate::{members, same_member, same_type, Member}};
+use crate::{attributes, errors::{BOLD, NORMAL, YELLOW}, flattener::{
 	PBCommandArg, PBCommandDef, PBEnumVariant, PBField,
 	PBTypeDef, PBTypeRef, PunybufDefinition,
 }};
 
 const TO_MAP: &str = r#"
-    fn to_map_allow_duplicates(self) -> (std::collections::HashMap<K, V>, bool) {
-        let mut hm = std::collections::HashMap::new();
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
         let mut duplicates = false;
         for pair in self {
             if hm.insert(pair.key, pair.value).is_some() {
@@ -16,7 +17,7 @@ const TO_MAP: &str = r#"
         }
         (hm, duplicates)
     }
-    fn from_map(map: std::collections::HashMap<K, V>) -> Self {
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
         let mut this = Self::new();
         for (key, value) in map.into_iter() {
             this.push(KeyPair { key, value });
@@ -32,10 +33,10 @@ pub struct DuplicateKeysFound;
 pub trait HashMapConvertible<K, V>: Sized {
     /// Converts the value to a `HashMap`, overriding duplicate keys.  
     /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
-    fn to_map_allow_duplicates(self) -> (std::collections::HashMap<K, V>, bool);
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
 
     /// Returns an error if there were any duplicate keys in the Map
-    fn try_to_map(self) -> Result<std::collections::HashMap<K, V>, DuplicateKeysFound> {
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
         let (map, duplicates_found) = self.to_map_allow_duplicates();
         if !duplicates_found {
             Ok(map)
@@ -43,13 +44,50 @@ pub trait HashMapConvertible<K, V>: Sized {
             Err(DuplicateKeysFound)
         }
     }
-    fn from_map(map: std::collections::HashMap<K, V>) -> Self;
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
 }
 "#;
 
+/// The visibility of the generated items, fields and methods
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+	/// `pub`
+	Public,
+	/// `pub(crate)`
+	Crate,
+	/// No visibility at all, for files that are `include!`d into a module
+	Private,
+}
+
+/// A trait that can be derived for the generated items, besides `Debug` and `Clone`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Derive {
+	PartialEq,
+	/// Also derives `PartialEq`. Skipped for items containing floats
+	Eq,
+	/// Skipped for items containing floats
+	Hash,
+}
+
 pub struct RustCodegen<'def> {
 	use_tokio: bool,
+	visibility: Visibility,
+	/// Whether the items are generated inside the `sync` and `r#async` modules
+	in_module: bool,
 	uses_common: bool,
+	strict_strings: bool,
+	derive_copy: bool,
+	derive_ord: bool,
+	derives: Vec<Derive>,
+	private_fields: bool,
+	wire_sizes: bool,
+	inline: bool,
+	reflect: bool,
+	metrics: bool,
+	allow_unknown_enum: bool,
+	keep_extensions: bool,
+	flatten_enums: bool,
+	layer_conversions: bool,
 	gen_docs: bool,
 	buffer: String,
 	def: &'def PunybufDefinition,
@@ -65,6 +103,178 @@ macro_rules! appendf {
 	};
 }
 
+/// The smallest size of a value, which may depend on the sizes of generic arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MinSize {
+	Bytes(usize),
+	/// The `MIN_SIZE` of a generic parameter
+	Param(String),
+	/// All of these, one after another
+	Sum(Vec<MinSize>),
+	/// Any one of these
+	Smallest(Vec<MinSize>),
+}
+
+impl MinSize {
+	fn plus(self, other: Self) -> Self {
+		match (self, other) {
+			(Self::Bytes(a), Self::Bytes(b)) => Self::Bytes(a + b),
+			(Self::Bytes(0), x) | (x, Self::Bytes(0)) => x,
+			(a, b) => {
+				let mut bytes = 0;
+				let mut terms = vec![];
+				for term in [a, b] {
+					match term {
+						Self::Sum(inner) => terms.extend(inner),
+						term => terms.push(term),
+					}
+				}
+				terms.retain(|term| match term {
+					Self::Bytes(n) => { bytes += n; false }
+					_ => true,
+				});
+				if bytes != 0 {
+					terms.insert(0, Self::Bytes(bytes));
+				}
+				Self::Sum(terms)
+			}
+		}
+	}
+	fn or(self, other: Self) -> Self {
+		match (self, other) {
+			(Self::Bytes(a), Self::Bytes(b)) => Self::Bytes(a.min(b)),
+			(Self::Bytes(0), _) | (_, Self::Bytes(0)) => Self::Bytes(0),
+			(a, b) if a == b => a,
+			(a, b) => {
+				let mut options = vec![];
+				for option in [a, b] {
+					match option {
+						Self::Smallest(inner) => options.extend(inner),
+						option => options.push(option),
+					}
+				}
+				Self::Smallest(options)
+			}
+		}
+	}
+	/// The size if all the generic arguments took no bytes
+	fn lower_bound(&self) -> usize {
+		match self {
+			Self::Bytes(n) => *n,
+			Self::Param(_) => 0,
+			Self::Sum(terms) => terms.iter().map(Self::lower_bound).sum(),
+			Self::Smallest(options) => options.iter().map(Self::lower_bound).min().unwrap_or(0),
+		}
+	}
+	/// A constant expression with the size
+	fn to_rust(&self) -> String {
+		match self {
+			Self::Bytes(n) => n.to_string(),
+			Self::Param(param) => format!("{param}::MIN_SIZE"),
+			Self::Sum(terms) => terms.iter().map(Self::to_rust).collect::<Vec<_>>().join(" + "),
+			Self::Smallest(options) => format!(
+				"min_size_of(&[{}])", options.iter().map(Self::to_rust).collect::<Vec<_>>().join(", ")
+			),
+		}
+	}
+}
+
+/// How many bytes a value takes when serialized
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WireSize {
+	/// The smallest possible size
+	min: MinSize,
+	/// Whether values always take exactly `min` bytes. Structs that aren't `@sealed`
+	/// and enums with a `@default` variant aren't fixed, since the values they decode
+	/// can be longer than the ones they write
+	fixed: bool,
+	/// The most bytes the generated code writes, if there's a limit
+	max: Option<usize>,
+}
+
+impl WireSize {
+	/// Anything could be here, like the value of a generic parameter
+	const UNKNOWN: Self = Self { min: MinSize::Bytes(0), fixed: false, max: None };
+	fn fixed(size: usize) -> Self {
+		Self { min: MinSize::Bytes(size), fixed: true, max: Some(size) }
+	}
+	fn at_least(min: usize) -> Self {
+		Self { min: MinSize::Bytes(min), fixed: false, max: None }
+	}
+	fn between(min: usize, max: usize) -> Self {
+		Self { min: MinSize::Bytes(min), fixed: min == max, max: Some(max) }
+	}
+	/// A value of this size, followed by a value of the `next` size
+	fn then(self, next: Self) -> Self {
+		Self {
+			min: self.min.plus(next.min),
+			fixed: self.fixed && next.fixed,
+			max: self.max.zip(next.max).map(|(a, b)| a + b),
+		}
+	}
+	/// Either a value of this size, or a value of the `other` size
+	fn or(self, other: Self) -> Self {
+		Self {
+			fixed: self.fixed && other.fixed && self.min == other.min,
+			min: self.min.or(other.min),
+			max: self.max.zip(other.max).map(|(a, b)| a.max(b)),
+		}
+	}
+	/// This value, but inside `Bytes`, prefixed with its length as a `UInt`
+	fn length_prefixed(self) -> Self {
+		Self {
+			min: MinSize::Bytes(1).plus(self.min),
+			fixed: false,
+			max: self.max.map(|max| uint_size(max as u64) + max),
+		}
+	}
+}
+
+/// How many bytes `value` takes when encoded as a `UInt`
+fn uint_size(value: u64) -> usize {
+	match value {
+		0..128 => 1,
+		128..16512 => 2,
+		16512..2113664 => 3,
+		2113664..68721590400 => 5,
+		_ => 8,
+	}
+}
+
+impl std::fmt::Display for WireSize {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let min = self.min.lower_bound();
+		let unit = if min == 1 { "byte" } else { "bytes" };
+		if self.fixed {
+			write!(f, "{min} {unit} fixed")
+		} else {
+			write!(f, "≥{min} {unit}")
+		}
+	}
+}
+
+/// `"le"` or `"be"` if the byte order of `field` is overridden with `@le` or `@be`
+fn byte_order(field: &PBField) -> Option<&'static str> {
+	if field.attrs.contains_key("@le") {
+		Some("le")
+	} else if field.attrs.contains_key("@be") {
+		Some("be")
+	} else {
+		None
+	}
+}
+
+/// How a value is converted from the previous layer of its type
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LayerStep {
+	/// The type didn't change
+	Move,
+	/// With `From`
+	Into,
+	/// With `TryFrom`, which fails if a flag that became a required field isn't set
+	TryInto,
+}
+
 fn deserialize_suffix(stream: bool) -> &'static str {
 	if stream {
 		"_stream"
@@ -77,13 +287,164 @@ impl<'def> RustCodegen<'def> {
 	pub fn new(use_tokio: bool, gen_docs: bool, def: &'def PunybufDefinition) -> Self {
 		Self {
 			use_tokio,
+			visibility: Visibility::Public,
+			in_module: false,
 			uses_common: true,
+			strict_strings: false,
+			derive_copy: false,
+			derive_ord: false,
+			derives: vec![],
+			private_fields: false,
+			wire_sizes: false,
+			inline: false,
+			reflect: false,
+			metrics: false,
+			allow_unknown_enum: false,
+			keep_extensions: false,
+			flatten_enums: false,
+			layer_conversions: false,
 			gen_docs,
 			buffer: String::new(),
 			def,
 			lifetime: "'x"
 		}
 	}
+	/// Make the generated items, fields and methods `pub(crate)` or private, instead of `pub`
+	pub fn visibility(mut self, visibility: Visibility) -> Self {
+		self.visibility = visibility;
+		self
+	}
+	/// Derive `Copy` for types that consist only of `Copy` values
+	pub fn derive_copy(mut self, derive_copy: bool) -> Self {
+		self.derive_copy = derive_copy;
+		self
+	}
+	/// Derive `PartialEq`, `Eq`, `PartialOrd` and `Ord` for types that consist only of
+	/// ordered values (no floats or maps). Values are compared field by field in the
+	/// order of declaration, and enums by the order of their variants first
+	pub fn derive_ord(mut self, derive_ord: bool) -> Self {
+		self.derive_ord = derive_ord;
+		self
+	}
+	/// Derive `derives` for the generated types, command structs and command errors,
+	/// where all the values inside them implement it
+	pub fn derive(mut self, derives: &[Derive]) -> Self {
+		self.derives = derives.to_vec();
+		self
+	}
+	/// Make the fields of structs private, and generate getters and setters for them, with
+	/// the visibility of the struct. Every struct then gets a `new` function
+	pub fn private_fields(mut self, private_fields: bool) -> Self {
+		self.private_fields = private_fields;
+		self
+	}
+	/// Mention the size of each type on the wire in its doc comment
+	pub fn wire_sizes(mut self, wire_sizes: bool) -> Self {
+		self.wire_sizes = wire_sizes;
+		self
+	}
+	/// Put `#[inline]` on the generated `serialize` and `deserialize` methods. Off by
+	/// default, since it makes the compiled code bigger for larger definitions
+	pub fn inline(mut self, inline: bool) -> Self {
+		self.inline = inline;
+		self
+	}
+	/// Implement `PBReflect` for all the generated structs and enums
+	pub fn reflect(mut self, reflect: bool) -> Self {
+		self.reflect = reflect;
+		self
+	}
+	/// Record metrics of every command that's serialized or deserialized, see
+	/// `punybuf_common::metrics`. The generated code needs punybuf_common's `metrics` feature
+	pub fn metrics(mut self, metrics: bool) -> Self {
+		self.metrics = metrics;
+		self
+	}
+	/// Keep unknown variants of extensible enums in an `UnknownVariant(u8, Bytes)`
+	/// variant instead of replacing them with the `@default` one, so that they
+	/// can be serialized again unchanged
+	pub fn allow_unknown_enum(mut self, allow_unknown_enum: bool) -> Self {
+		self.allow_unknown_enum = allow_unknown_enum;
+		self
+	}
+	/// Keep the flags and the extensions of extensible structs that aren't in the definition,
+	/// e.g. when decoding a newer version of a struct, so that they can be serialized again unchanged.
+	/// They're in the `_unknown_<flags field>` and `_unknown_extensions` fields
+	pub fn keep_extensions(mut self, keep_extensions: bool) -> Self {
+		self.keep_extensions = keep_extensions;
+		self
+	}
+	/// Encode enums with a single variant carrying a value as just that value,
+	/// without the discriminant. This changes the wire format, so every side has to agree on it
+	pub fn flatten_enums(mut self, flatten_enums: bool) -> Self {
+		self.flatten_enums = flatten_enums;
+		self
+	}
+	/// Implement `From` for structs and commands from their previous layer, if the new layer
+	/// only adds flags, or `TryFrom` if some flags became required fields
+	pub fn layer_conversions(mut self, layer_conversions: bool) -> Self {
+		self.layer_conversions = layer_conversions;
+		self
+	}
+	/// `#[inline]` before a generated method, if asked for
+	fn gen_inline(&mut self) {
+		if self.inline {
+			appendf!(self, "    #[inline]\n");
+		}
+	}
+	/// Generates the body of a method encoding or decoding `cmd` with `gen_body`,
+	/// recording its metrics if asked for. `setup` wraps `r` or `w` to count the bytes,
+	/// and `count` is how many there were after the body ran
+	fn gen_metered(&mut self, cmd: &PBCommandDef, op: &str, setup: &str, count: &str, gen_body: impl FnOnce(&mut Self)) {
+		if !self.metrics {
+			gen_body(self);
+			return;
+		}
+		appendf!(self, "        {setup}\n");
+		// `?` in the body has to return to here, instead of from the method
+		let start = self.buffer.len();
+		gen_body(self);
+		let body = self.buffer.split_off(start);
+		appendf!(self, "        let result = {}{{\n", if self.use_tokio { "async " } else { "(|| " });
+		for line in body.lines() {
+			appendf!(self, "    {line}\n");
+		}
+		appendf!(self, "        }}{};\n", if self.use_tokio { ".await" } else { ")()" });
+		let labels = format!("\"command\" => \"{}\"", cmd.name);
+		appendf!(self, "        punybuf_common::metrics::counter!(\"punybuf_{op}_total\", {labels}).increment(1);\n");
+		appendf!(self, "        match &result {{\n");
+		appendf!(self, "            Ok(_) => punybuf_common::metrics::histogram!(\"punybuf_{op}_bytes\", {labels}).record({count} as f64),\n");
+		appendf!(self, "            Err(_) => punybuf_common::metrics::counter!(\"punybuf_{op}_errors_total\", {labels}).increment(1),\n");
+		appendf!(self, "        }}\n"); // match
+		appendf!(self, "        result\n");
+	}
+	/// The attributes of a method generated by [`Self::gen_metered`]
+	fn gen_metered_attrs(&mut self) {
+		if self.metrics && !self.use_tokio {
+			appendf!(self, "    #[allow(clippy::redundant_closure_call)]\n");
+		}
+	}
+	/// The visibility to put before generated items, with a trailing space
+	fn vis(&self) -> &'static str {
+		match self.visibility {
+			Visibility::Public => "pub ",
+			Visibility::Crate => "pub(crate) ",
+			// Private items in `sync` and `r#async` still need to be visible next to them
+			Visibility::Private if self.in_module => "pub(super) ",
+			Visibility::Private => "",
+		}
+	}
+	fn keeps_unknown_variants(&self, variants: &Vec<PBEnumVariant>) -> bool {
+		self.allow_unknown_enum && variants.iter().any(|v| v.attrs.contains_key("@default"))
+	}
+	/// Whether a struct has the `_unknown_` fields, see [`Self::keep_extensions`]
+	fn keeps_extensions(&self, attrs: &HashMap<String, Option<String>>) -> bool {
+		self.keep_extensions && !attrs.contains_key("@sealed")
+	}
+	/// The only variant of `tp`, if it's encoded without the discriminant
+	fn flattened_variant<'a>(&self, tp: &'a PBTypeDef) -> Option<&'a PBEnumVariant> {
+		tp.single_variant().filter(|_| self.flatten_enums)
+	}
 	fn gen_lifetime_generics_if(&self, condition: bool) -> String {
 		if condition {
 			format!("<{}>", self.lifetime)
@@ -118,80 +479,53 @@ impl<'def> RustCodegen<'def> {
 		match typdef {
 			PBTypeDef::Struct { fields, .. } => {
 				for field in fields {
-					if !field.value.is_global {
-						continue;
+					if self.ref_needs_lifetime_with_context(&field.value, path) {
+						return true;
 					}
-					let path_elem = (field.value.reference.clone(), field.value.resolved_layer.expect(
-						&format!("layer of {} not resolved", field.name)
-					));
-					if path.contains(&path_elem) {
-						continue;
-					}
-					let result = self.needs_lifetime_with_context(
-						&field.value.reference,
-						field.value.resolved_layer.expect("bad state: layer not resolved"),
-						path
-					);
-					path.remove(&path_elem);
-					if result { return true }
 					let Some(flags) = &field.flags else { continue };
 					for flag in flags {
 						let Some(value) = &flag.value else { continue };
-						if !value.is_global {
-							continue;
+						if self.ref_needs_lifetime_with_context(value, path) {
+							return true;
 						}
-						let path_elem = (value.reference.clone(), value.resolved_layer.unwrap());
-						if path.contains(&path_elem) {
-							continue;
-						}
-						let result = self.needs_lifetime_with_context(
-							&value.reference,
-							value.resolved_layer.expect("bad state: layer not resolved"),
-							path
-						);
-						path.remove(&path_elem);
-						if result { return true }
 					}
 				}
 			}
 			PBTypeDef::Enum { variants, .. } => {
+				if self.keeps_unknown_variants(variants) {
+					return true;
+				}
 				for variant in variants {
 					let Some(value) = &variant.value else { continue };
-					if !value.is_global {
-						continue;
-					}
-					let path_elem = (value.reference.clone(), value.resolved_layer.unwrap());
-					if path.contains(&path_elem) {
-						continue;
+					if self.ref_needs_lifetime_with_context(value, path) {
+						return true;
 					}
-					let result = self.needs_lifetime_with_context(
-						&value.reference,
-						value.resolved_layer.expect("bad state: layer not resolved"),
-						path
-					);
-					path.remove(&path_elem);
-					if result { return true }
 				}
 			}
 			PBTypeDef::Alias { alias, .. } => {
-				if !alias.is_global {
-					return false;
-				}
-				let path_elem = (alias.reference.clone(), alias.resolved_layer.unwrap());
-				if path.contains(&path_elem) {
-					return false;
-				}
-				let result = self.needs_lifetime_with_context(
-					&alias.reference,
-					alias.resolved_layer.expect("bad state: layer not resolved"),
-					path
-				);
-				path.remove(&path_elem);
-				return result;
+				return self.ref_needs_lifetime_with_context(alias, path);
 			}
 		}
 		false
 	}
+	/// Whether the type `refr` points to needs a lifetime, or any of its generic arguments do,
+	/// like `Array<String>`
+	fn ref_needs_lifetime_with_context(&self, refr: &PBTypeRef, path: &mut HashSet<(String, u32)>) -> bool {
+		// `Void` isn't resolved to a layer, e.g. in `Optional<Void>`
+		if !refr.is_global || refr.reference == "Void" {
+			return false;
+		}
+		if refr.generics.iter().any(|g| self.ref_needs_lifetime_with_context(g, path)) {
+			return true;
+		}
+		let path_elem = (refr.reference.clone(), refr.resolved_layer.expect("bad state: layer not resolved"));
+		if path.contains(&path_elem) {
+			return false;
+		}
+		let result = self.needs_lifetime_with_context(&path_elem.0, path_elem.1, path);
+		path.remove(&path_elem);
+		result
+	}
 	fn needs_lifetime_ref(&self, refr: &PBTypeRef) -> bool {
 		if refr.reference == "Void" || !refr.is_global {
 			return false
@@ -205,6 +539,293 @@ impl<'def> RustCodegen<'def> {
 		let mut path = HashSet::new();
 		self.needs_lifetime_with_context(name, layer, &mut path)
 	}
+	/// Like [`Self::needs_lifetime_ref`], but also checks the generic arguments,
+	/// since the reference itself borrows if they do
+	fn contains_lifetime(&self, refr: &PBTypeRef) -> bool {
+		refr.reference != "Void" && self.ref_needs_lifetime_with_context(refr, &mut HashSet::new())
+	}
+	/// Whether the reference points to a `@builtin` type
+	fn is_builtin_ref(&self, refr: &PBTypeRef) -> bool {
+		refr.is_global && self.def.types.iter().any(|t| {
+			t.get_name().0 == refr.reference &&
+			Some(*t.get_layer()) == refr.resolved_layer &&
+			t.get_attrs().contains_key("@builtin")
+		})
+	}
+	fn is_copy_ref(&self, refr: &PBTypeRef, path: &mut HashSet<(String, u32)>) -> bool {
+		if !refr.is_global {
+			// we don't know anything about generic arguments
+			return false;
+		}
+		if self.uses_common {
+			match refr.reference.as_str() {
+				"U8" | "U16" | "U32" | "U64" | "I32" | "I64" | "F32" | "F64" | "UInt" | "SInt" | "Boolean" | "Void" => return true,
+				"DateTime" if self.is_builtin_ref(refr) => return true,
+				"Optional" => return refr.generics.iter().all(|g| self.is_copy_ref(g, path)),
+				_ => {}
+			}
+		}
+		let layer = refr.resolved_layer.expect("bad state: layer not resolved");
+		if !path.insert((refr.reference.clone(), layer)) {
+			// a type that contains itself can't be `Copy`
+			return false;
+		}
+		let result = self.is_copy_with_context(&refr.reference, layer, path);
+		path.remove(&(refr.reference.clone(), layer));
+		result
+	}
+	fn is_copy_with_context(
+		&self, name: &str, layer: u32,
+		path: &mut HashSet<(String, u32)>
+	) -> bool {
+		let typdef = self.def.types.iter().find(|t| {
+			t.get_name().0 == name &&
+			t.get_layer() == &layer
+		}).expect("bad state: unknown type referenced");
+		if
+			!typdef.get_generics().0.is_empty() ||
+			typdef.get_attrs().contains_key("@builtin") ||
+			typdef.get_attrs().contains_key("@rust:ignore") ||
+			typdef.get_attrs().contains_key("@rust:needs_lifetime")
+		{
+			return false;
+		}
+		match typdef {
+			// `_unknown_extensions` is a `Vec`
+			PBTypeDef::Struct { attrs, .. } if self.keeps_extensions(attrs) => false,
+			PBTypeDef::Struct { fields, .. } => fields.iter().all(|field| match &field.flags {
+				// flags are either `bool`s or `Option`s of their values
+				Some(flags) => flags.iter().all(|flag| flag.value.as_ref().is_none_or(|v| self.is_copy_ref(v, path))),
+				None => self.is_copy_ref(&field.value, path),
+			}),
+			PBTypeDef::Enum { variants, .. } => !self.keeps_unknown_variants(variants) && variants.iter().all(|variant| {
+				variant.value.as_ref().is_none_or(|v| self.is_copy_ref(v, path))
+			}),
+			PBTypeDef::Alias { alias, .. } => self.is_copy_ref(alias, path),
+		}
+	}
+	/// The references a value of the type contains, without going into `Array`s
+	fn contained_refs<'a>(&'a self, refr: &'a PBTypeRef) -> Vec<&'a PBTypeRef> {
+		if refr.reference == "Array" && self.is_builtin_ref(refr) {
+			return vec![];
+		}
+		// generic arguments are counted as contained even when they're only used in an `Array`
+		let mut refs: Vec<&PBTypeRef> = refr.generics.iter().collect();
+		let Some(typdef) = self.def.types.iter().find(|t| {
+			t.get_name().0 == refr.reference &&
+			Some(*t.get_layer()) == refr.resolved_layer
+		}) else {
+			return refs;
+		};
+		// builtins are aliases of themselves, e.g. `U8 = U8`
+		if typdef.get_attrs().contains_key("@builtin") {
+			return refs;
+		}
+		match typdef {
+			PBTypeDef::Struct { fields, .. } => for field in fields {
+				match &field.flags {
+					Some(flags) => refs.extend(flags.iter().filter_map(|flag| flag.value.as_ref())),
+					None => refs.push(&field.value),
+				}
+			}
+			PBTypeDef::Enum { variants, .. } => refs.extend(variants.iter().filter_map(|v| v.value.as_ref())),
+			PBTypeDef::Alias { alias, .. } => refs.push(alias),
+		}
+		refs
+	}
+	/// Whether a value of the type can contain another one of the same type, other than in an `Array`.
+	/// In Rust, these have to be boxed when they're in an `Option`, or the type would be infinitely large.
+	fn contains_itself(&self, refr: &PBTypeRef) -> bool {
+		if !refr.is_global {
+			return false;
+		}
+		let mut visited = HashSet::new();
+		let mut stack = self.contained_refs(refr);
+		while let Some(inner) = stack.pop() {
+			if !inner.is_global {
+				continue;
+			}
+			if inner.reference == refr.reference && inner.resolved_layer == refr.resolved_layer {
+				return true;
+			}
+			if visited.insert((inner.reference.as_str(), inner.resolved_layer)) {
+				stack.extend(self.contained_refs(inner));
+			}
+		}
+		false
+	}
+	/// The type of the value inside an `Option`, boxed if it contains itself
+	fn gen_optional_value(&self, refr: &PBTypeRef, turbofish: bool) -> String {
+		let value = self.gen_reference(refr, turbofish);
+		match (self.contains_itself(refr), turbofish) {
+			(false, _) => value,
+			(true, false) => format!("Box<{value}>"),
+			(true, true) => format!("Box::<{value}>"),
+		}
+	}
+	/// Whether every value inside the type is `Copy`
+	fn is_copy(&self, tp: &PBTypeDef) -> bool {
+		let mut path = HashSet::new();
+		path.insert((tp.get_name().0.to_string(), *tp.get_layer()));
+		self.is_copy_with_context(tp.get_name().0, *tp.get_layer(), &mut path)
+	}
+	fn is_ord_ref(&self, refr: &PBTypeRef, path: &mut HashSet<(String, u32)>) -> bool {
+		if !refr.is_global {
+			// the derive requires generic arguments to be `Ord` by itself
+			return true;
+		}
+		if self.uses_common {
+			match refr.reference.as_str() {
+				"U8" | "U16" | "U32" | "U64" | "I32" | "I64" | "UInt" | "SInt" | "Boolean" | "Void" | "String" | "Bytes" => return true,
+				"F32" | "F64" => return false,
+				"DateTime" if self.is_builtin_ref(refr) => return true,
+				"Array" | "Optional" => return refr.generics.iter().all(|g| self.is_ord_ref(g, path)),
+				_ => {}
+			}
+		}
+		if !refr.generics.iter().all(|g| self.is_ord_ref(g, path)) {
+			return false;
+		}
+		let layer = refr.resolved_layer.expect("bad state: layer not resolved");
+		if !path.insert((refr.reference.clone(), layer)) {
+			// the type is already being checked
+			return true;
+		}
+		let result = self.is_ord_with_context(&refr.reference, layer, path);
+		path.remove(&(refr.reference.clone(), layer));
+		result
+	}
+	fn is_ord_with_context(
+		&self, name: &str, layer: u32,
+		path: &mut HashSet<(String, u32)>
+	) -> bool {
+		let typdef = self.def.types.iter().find(|t| {
+			t.get_name().0 == name &&
+			t.get_layer() == &layer
+		}).expect("bad state: unknown type referenced");
+		if
+			typdef.get_attrs().contains_key("@builtin") ||
+			typdef.get_attrs().contains_key("@rust:ignore") ||
+			typdef.get_attrs().contains_key("@map_convertible")
+		{
+			return false;
+		}
+		match typdef {
+			PBTypeDef::Struct { fields, .. } => fields.iter().all(|field| match &field.flags {
+				Some(flags) => flags.iter().all(|flag| flag.value.as_ref().is_none_or(|v| self.is_ord_ref(v, path))),
+				// the bytes of a `LazyArray` aren't ordered like its items
+				None if field.attrs.contains_key("@lazy_array") => false,
+				None => self.is_ord_ref(&field.value, path),
+			}),
+			PBTypeDef::Enum { variants, .. } => variants.iter().all(|variant| {
+				variant.value.as_ref().is_none_or(|v| self.is_ord_ref(v, path))
+			}),
+			PBTypeDef::Alias { alias, .. } => self.is_ord_ref(alias, path),
+		}
+	}
+	/// Whether every value inside the type is `Ord`, assuming its generic arguments are
+	fn is_ord(&self, tp: &PBTypeDef) -> bool {
+		let mut path = HashSet::new();
+		path.insert((tp.get_name().0.to_string(), *tp.get_layer()));
+		self.is_ord_with_context(tp.get_name().0, *tp.get_layer(), &mut path)
+	}
+	fn is_comparable_ref(&self, refr: &PBTypeRef, floats: bool, path: &mut HashSet<(String, u32)>) -> bool {
+		if !refr.is_global {
+			// the derive requires generic arguments to implement the trait by itself
+			return true;
+		}
+		if self.uses_common {
+			match refr.reference.as_str() {
+				"U8" | "U16" | "U32" | "U64" | "I32" | "I64" | "UInt" | "SInt" | "Boolean" | "Void" | "String" | "Bytes" => return true,
+				"F32" | "F64" => return floats,
+				"DateTime" if self.is_builtin_ref(refr) => return true,
+				"Array" | "Optional" => return refr.generics.iter().all(|g| self.is_comparable_ref(g, floats, path)),
+				_ => {}
+			}
+		}
+		if !refr.generics.iter().all(|g| self.is_comparable_ref(g, floats, path)) {
+			return false;
+		}
+		let layer = refr.resolved_layer.expect("bad state: layer not resolved");
+		if !path.insert((refr.reference.clone(), layer)) {
+			// the type is already being checked
+			return true;
+		}
+		let typdef = self.def.types.iter().find(|t| {
+			t.get_name().0 == refr.reference &&
+			t.get_layer() == &layer
+		}).expect("bad state: unknown type referenced");
+		let result = self.is_comparable_with_context(typdef, floats, path);
+		path.remove(&(refr.reference.clone(), layer));
+		result
+	}
+	fn is_comparable_fields(&self, fields: &[PBField], floats: bool, path: &mut HashSet<(String, u32)>) -> bool {
+		fields.iter().all(|field| match &field.flags {
+			Some(flags) => flags.iter().all(|flag| flag.value.as_ref().is_none_or(|v| self.is_comparable_ref(v, floats, path))),
+			None => self.is_comparable_ref(&field.value, floats, path),
+		})
+	}
+	fn is_comparable_variants(&self, variants: &[PBEnumVariant], floats: bool, path: &mut HashSet<(String, u32)>) -> bool {
+		variants.iter().all(|variant| {
+			variant.value.as_ref().is_none_or(|v| self.is_comparable_ref(v, floats, path))
+		})
+	}
+	fn is_comparable_with_context(&self, typdef: &PBTypeDef, floats: bool, path: &mut HashSet<(String, u32)>) -> bool {
+		if typdef.get_attrs().contains_key("@builtin") || typdef.get_attrs().contains_key("@rust:ignore") {
+			return false;
+		}
+		match typdef {
+			PBTypeDef::Struct { fields, .. } => self.is_comparable_fields(fields, floats, path),
+			PBTypeDef::Enum { variants, .. } => self.is_comparable_variants(variants, floats, path),
+			PBTypeDef::Alias { alias, .. } => self.is_comparable_ref(alias, floats, path),
+		}
+	}
+	/// Whether every value inside the type is `PartialEq`, or `Eq` and `Hash` if `floats` is false,
+	/// assuming its generic arguments are
+	fn is_comparable(&self, tp: &PBTypeDef, floats: bool) -> bool {
+		let mut path = HashSet::new();
+		path.insert((tp.get_name().0.to_string(), *tp.get_layer()));
+		self.is_comparable_with_context(tp, floats, &mut path)
+	}
+	fn is_argument_comparable(&self, argument: &PBCommandArg, floats: bool) -> bool {
+		match argument {
+			PBCommandArg::None => true,
+			PBCommandArg::Ref(refr) => self.is_comparable_ref(refr, floats, &mut HashSet::new()),
+			PBCommandArg::Struct { fields } => self.is_comparable_fields(fields, floats, &mut HashSet::new()),
+		}
+	}
+	/// The derives of an item, given whether it can be `Copy`, and whether the values inside it
+	/// are `PartialEq`, or `Eq` and `Hash`. `ord` is whether `PartialOrd` and `Ord` are derived
+	fn derive_list(&self, partial_eq: impl Fn() -> bool, eq: impl Fn() -> bool, copy: bool, ord: bool) -> String {
+		let wants = |derive| self.derives.contains(&derive);
+		let hashable = (wants(Derive::Eq) || wants(Derive::Hash)) && eq();
+		let mut derives = vec!["Debug", "Clone"];
+		if copy {
+			derives.push("Copy");
+		}
+		if ord || (wants(Derive::PartialEq) || wants(Derive::Eq)) && partial_eq() {
+			derives.push("PartialEq");
+		}
+		if ord || wants(Derive::Eq) && hashable {
+			derives.push("Eq");
+		}
+		if ord {
+			derives.extend(["PartialOrd", "Ord"]);
+		}
+		if wants(Derive::Hash) && hashable {
+			derives.push("Hash");
+		}
+		derives.join(", ")
+	}
+	fn gen_derives(&mut self, tp: &PBTypeDef) {
+		let derives = self.derive_list(
+			|| self.is_comparable(tp, true),
+			|| self.is_comparable(tp, false),
+			self.derive_copy && self.is_copy(tp),
+			self.derive_ord && self.is_ord(tp),
+		);
+		appendf!(self, "#[derive({derives})]\n");
+	}
 	fn get_fn(&self) -> &str {
 		if self.use_tokio {
 			"async fn"
@@ -241,7 +862,7 @@ impl<'def> RustCodegen<'def> {
 					"U8" | "U16" | "U32" | "U64" | "I32" | "I64" | "F32" | "F64"
 				) => return s.to_ascii_lowercase(),
 				s @ (
-					"String" | "Bytes" | "UInt"
+					"String" | "Bytes" | "UInt" | "SInt"
 				) => return s.to_string(),
 				"Array" => return "Vec".to_string(),
 				_ => {}
@@ -260,30 +881,45 @@ impl<'def> RustCodegen<'def> {
 				s @ (
 					"U8" | "U16" | "U32" | "U64" | "I32" | "I64" | "F32" | "F64"
 				) => return s.to_ascii_lowercase(),
-				s @ "UInt" => return s.to_string(),
+				s @ ("UInt" | "SInt") => return s.to_string(),
+				"Boolean" => return "bool".to_string(),
 				"Bytes" => {
 					return if turbofish {
-						"Bytes::<'x>".to_string()
+						format!("Bytes::<{}>", self.lifetime)
+					} else {
+						format!("Bytes<{}>", self.lifetime)
+					};
+				}
+				"String" if self.strict_strings => {
+					return if turbofish {
+						format!("StrictString::<{}>", self.lifetime)
 					} else {
-						"Bytes<'x>".to_string()
+						format!("StrictString<{}>", self.lifetime)
 					};
 				}
 				"String" => {
 					return if turbofish {
-						"Cow::<'x, str>".to_string()
+						format!("Cow::<{}, str>", self.lifetime)
 					} else {
-						"Cow<'x, str>".to_string()
+						format!("Cow<{}, str>", self.lifetime)
+					};
+				}
+				// requires the `chrono` feature of punybuf_common
+				"DateTime" if self.is_builtin_ref(refr) => {
+					return if turbofish {
+						"DateTime::<Utc>".to_string()
+					} else {
+						"DateTime<Utc>".to_string()
 					};
 				}
 				_ => {}
 			}
 		}
-		let mut result = if self.uses_common && refr.reference == "Array" {
-			"Vec".to_string()
-		} else if refr.is_highest_layer || refr.resolved_layer.is_none() {
-			refr.reference.clone()
-		} else {
-			format!("{}Layer{}", refr.reference, refr.resolved_layer.unwrap())
+		let mut result = match (refr.reference.as_str(), refr.resolved_layer) {
+			("Array", _) if self.uses_common => "Vec".to_string(),
+			("Optional", _) if self.uses_common => "Option".to_string(),
+			(_, Some(layer)) if !refr.is_highest_layer => format!("{}Layer{}", refr.reference, layer),
+			_ => refr.reference.clone(),
 		};
 		let needs_lifetime = self.needs_lifetime_ref(refr);
 		if refr.generics.is_empty() && !needs_lifetime {
@@ -298,11 +934,16 @@ impl<'def> RustCodegen<'def> {
 
 		result.push_str(&self.gen_lifetime_if(needs_lifetime, !refr.generics.is_empty()));
 
+		let is_optional = refr.reference == "Optional" && self.uses_common;
 		for (i, generics) in refr.generics.iter().enumerate() {
 			if i != 0 {
 				result.push_str(", ");
 			}
-			result.push_str(&self.gen_reference(generics, turbofish));
+			if is_optional {
+				result.push_str(&self.gen_optional_value(generics, turbofish));
+			} else {
+				result.push_str(&self.gen_reference(generics, turbofish));
+			}
 		}
 		result.push('>');
 		return result;
@@ -324,7 +965,7 @@ impl<'def> RustCodegen<'def> {
 			format!("{}Layer{}{}", cmd.name, cmd.layer, self.gen_lifetime_generics_if(needs_lifetime))
 		}
 	}
-	fn get_type_name(&self, tp: &PBTypeDef) -> String {
+	pub(super) fn get_type_name(&self, tp: &PBTypeDef) -> String {
 		let mut result = if tp.is_highest_layer() {
 			tp.get_name().0.to_string()
 		} else {
@@ -377,6 +1018,16 @@ impl<'def> RustCodegen<'def> {
 			format!("{}Layer{}Error{}", cmd.name, cmd.layer, self.gen_lifetime_generics_if(true))
 		}
 	}
+	/// The type of the variant of `CommandReturn`. `@stream` commands return one
+	/// frame at a time, where `None` marks the end of the stream
+	fn gen_command_return(&self, cmd: &PBCommandDef, turbofish: bool) -> String {
+		let ret = self.gen_reference(&cmd.ret, false);
+		match (cmd.attrs.contains_key("@stream"), turbofish) {
+			(true, true) => format!("Option::<{ret}>"),
+			(true, false) => format!("Option<{ret}>"),
+			(false, _) => self.gen_reference(&cmd.ret, turbofish),
+		}
+	}
 	fn get_command_err(&self, cmd: &PBCommandDef) -> String {
 		if cmd.is_highest_layer {
 			format!("{}Error", cmd.name)
@@ -384,6 +1035,14 @@ impl<'def> RustCodegen<'def> {
 			format!("{}Layer{}Error", cmd.name, cmd.layer)
 		}
 	}
+	/// The module with the functions for `@compress`ed fields
+	fn compression_module(&self) -> &str {
+		if self.use_tokio {
+			"punybuf_common::compression::tokio"
+		} else {
+			"punybuf_common::compression"
+		}
+	}
 	fn maybe_await(&self) -> &str {
 		if self.use_tokio {
 			".await"
@@ -394,14 +1053,14 @@ impl<'def> RustCodegen<'def> {
 	fn command_needs_lifetime(&self, cmd: &PBCommandDef) -> bool {
 		match &cmd.argument {
 			PBCommandArg::None => false,
-			PBCommandArg::Ref(refr) => self.needs_lifetime_ref(&refr),
+			PBCommandArg::Ref(refr) => self.contains_lifetime(&refr),
 			PBCommandArg::Struct { fields } => {
 				fields.iter().any(|f| {
-					self.needs_lifetime_ref(&f.value) ||
+					self.contains_lifetime(&f.value) ||
 					f.flags.as_ref().is_some_and(|flags| {
 						flags.iter().any(|flag| {
 							flag.value.as_ref().is_some_and(|v|
-								self.needs_lifetime_ref(v)
+								self.contains_lifetime(v)
 							)
 						})
 					})
@@ -409,19 +1068,68 @@ impl<'def> RustCodegen<'def> {
 			},
 		}
 	}
+	/// Generates `TYPE_NAMES`, and `COMMAND_IDS_TO_NAMES` if there are commands, so the
+	/// names can be logged without the definition
+	fn gen_name_tables(&mut self) {
+		if self.gen_docs {
+			appendf!(self, "/// The names of all types in this file\n");
+		}
+		appendf!(self, "{}const TYPE_NAMES: &[&str] = &[\n", self.vis());
+		for tp in &self.def.types {
+			let attrs = tp.get_attrs();
+			let skipped = attrs.contains_key("@builtin") || attrs.contains_key("@rust:ignore") || attrs.contains_key("@resolve");
+			if skipped && !matches!(attrs.get("@rust:use"), Some(Some(_))) {
+				continue;
+			}
+			let name = if tp.is_highest_layer() {
+				tp.get_name().0.to_string()
+			} else {
+				format!("{}Layer{}", tp.get_name().0, tp.get_layer())
+			};
+			appendf!(self, "    {:?},\n", name);
+		}
+		appendf!(self, "];\n\n");
+
+		if self.def.commands.is_empty() {
+			return;
+		}
+		if self.gen_docs {
+			appendf!(self, "/// The IDs of all commands in this file, with their names\n");
+		}
+		appendf!(self, "{}const COMMAND_IDS_TO_NAMES: &[(u32, &str)] = &[\n", self.vis());
+		for cmd in &self.def.commands {
+			if cmd.attrs.contains_key("@rust:ignore") {
+				continue;
+			}
+			appendf!(self, "    ({}, {:?}),\n", cmd.command_id, self.get_command_name(cmd));
+		}
+		appendf!(self, "];\n\n");
+	}
 	fn gen_command_enums(&mut self) {
 		appendf!(self, "/// This enum contains all possible commands in the RPC definition.\n");
 		appendf!(self, "#[derive(Debug, Clone)]\n");
+		appendf!(self, "#[must_use = \"commands must be serialized or dispatched\"]\n");
 		let need_generics = self.def.commands.iter().any(|cmd| {
 			self.command_needs_lifetime(cmd)
 		});
-		appendf!(self, "pub enum Command{} {{\n", self.gen_lifetime_generics_if(need_generics));
+		// Without the feature, the enums could end up empty or with an unused lifetime
+		let has_experimental = self.def.commands.iter().any(|cmd| cmd.attrs.contains_key("@experimental"));
+		let stable = self.def.commands.iter().filter(|cmd| {
+			!cmd.attrs.contains_key("@rust:ignore") && !cmd.attrs.contains_key("@experimental")
+		});
+		let stable_empty = stable.clone().next().is_none();
+		let cmd_placeholder = has_experimental && (
+			stable_empty || need_generics && !stable.clone().any(|cmd| self.command_needs_lifetime(cmd))
+		);
+		appendf!(self, "{}enum Command{} {{\n", self.vis(), self.gen_lifetime_generics_if(need_generics));
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 4);
 			appendf!(self, "    {}({}),\n", self.get_command_name(cmd), self.gen_command_name(cmd));
 		}
+		self.gen_experimental_placeholder(cmd_placeholder, need_generics);
 		appendf!(self, "}}\n"); // enum Command
 
 		appendf!(self, "impl{} PBCommand for Command{} {{\n",
@@ -434,8 +1142,10 @@ impl<'def> RustCodegen<'def> {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self, "            Self::{}(_) => {},\n", self.get_command_name(cmd), cmd.command_id);
 		}
+		self.gen_experimental_placeholder_arm(cmd_placeholder);
 		appendf!(self, "        }}\n"); // match
 		appendf!(self, "    }}\n"); // fn id()
 
@@ -445,19 +1155,38 @@ impl<'def> RustCodegen<'def> {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self, "            Self::{}(_) => {},\n", self.get_command_name(cmd), cmd.ret.reference == "Void");
 		}
+		self.gen_experimental_placeholder_arm(cmd_placeholder);
 		appendf!(self, "        }}\n"); // match
 		appendf!(self, "    }}\n"); // fn is_void()
 
+		if self.def.commands.iter().any(|cmd| cmd.attrs.contains_key("@stream")) {
+			appendf!(self, "    fn is_stream(&self) -> bool {{\n");
+			appendf!(self, "        match self {{\n");
+			for cmd in &self.def.commands {
+				if cmd.attrs.contains_key("@rust:ignore") {
+					continue;
+				}
+				self.gen_cfg_experimental(&cmd.attrs, 12);
+				appendf!(self, "            Self::{}(_) => {},\n", self.get_command_name(cmd), cmd.attrs.contains_key("@stream"));
+			}
+			self.gen_experimental_placeholder_arm(cmd_placeholder);
+			appendf!(self, "        }}\n"); // match
+			appendf!(self, "    }}\n"); // fn is_stream()
+		}
+
 		appendf!(self, "    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {{\n");
 		appendf!(self, "        match self {{\n");
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self, "            Self::{}(_) => {}::ATTRIBUTES,\n", self.get_command_name(cmd), self.get_command_name(cmd));
 		}
+		self.gen_experimental_placeholder_arm(cmd_placeholder);
 		appendf!(self, "        }}\n"); // match
 		appendf!(self, "    }}\n"); // fn attributes()
 
@@ -467,8 +1196,10 @@ impl<'def> RustCodegen<'def> {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self, "            Self::{}(_) => {}::REQUIRED_CAPABILITY,\n", self.get_command_name(cmd), self.get_command_name(cmd));
 		}
+		self.gen_experimental_placeholder_arm(cmd_placeholder);
 		appendf!(self, "        }}\n"); // match
 		appendf!(self, "    }}\n"); // fn required_capability()
 
@@ -478,8 +1209,10 @@ impl<'def> RustCodegen<'def> {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self, "            Self::{}(c) => c.serialize_self(r){},\n", self.get_command_name(cmd), self.maybe_await());
 		}
+		self.gen_experimental_placeholder_arm(cmd_placeholder);
 		appendf!(self, "        }}\n"); // match
 		appendf!(self, "    }}\n"); // fn serialize_self()
 		appendf!(self, "}}\n\n"); // impl PBCommand
@@ -489,7 +1222,7 @@ impl<'def> RustCodegen<'def> {
 			self.gen_lifetime_generics_if(need_generics)
 		); // impl Command
 		appendf!(self, "    /// Reads both the ID of the command and its value\n");
-		appendf!(self, "    pub {} deserialize_stream<R: {}>(r: &mut R) -> io::Result<Self> {{\n", self.get_fn(), self.read());
+		appendf!(self, "    {}{} deserialize_stream<R: {}>(r: &mut R) -> io::Result<Self> {{\n", self.vis(), self.get_fn(), self.read());
 		appendf!(self, "        let mut id = [0; 4];\n");
 		appendf!(self, "        r.{};\n", self.read_exact("&mut id"));
 		appendf!(self, "        let id = u32::from_be_bytes(id);\n");
@@ -498,6 +1231,7 @@ impl<'def> RustCodegen<'def> {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self,
 				"            {} => Self::{}({}::deserialize_stream(r){}?),\n",
 				cmd.command_id, self.get_command_name(cmd), self.get_command_name(cmd), self.maybe_await()
@@ -508,7 +1242,9 @@ impl<'def> RustCodegen<'def> {
 		appendf!(self, "        }})\n"); // match
 		appendf!(self, "    }}\n"); // fn deserialize_stream
 		if !self.use_tokio {
-			appendf!(self, "    pub fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {{\n");
+			appendf!(self, "    {}fn deserialize<'a{}>(r: &mut &'a [u8]) -> io::Result<Self> {{\n",
+				self.vis(), if need_generics { ": 'x" } else { "" }
+			);
 			appendf!(self, "        let (a, b) = r.split_at_checked(4)\n");
 			appendf!(self, "            .ok_or(io::Error::new(io::ErrorKind::UnexpectedEof, \"buffer too small\"))?;\n");
 			appendf!(self, "        let arr = a.try_into().unwrap(); // has to be 4 bytes\n");
@@ -519,6 +1255,7 @@ impl<'def> RustCodegen<'def> {
 				if cmd.attrs.contains_key("@rust:ignore") {
 					continue;
 				}
+				self.gen_cfg_experimental(&cmd.attrs, 12);
 				appendf!(self,
 					"            {} => Self::{}({}::deserialize_stream(r){}?),\n",
 					cmd.command_id, self.get_command_name(cmd), self.get_command_name(cmd), self.maybe_await()
@@ -533,17 +1270,22 @@ impl<'def> RustCodegen<'def> {
 
 
 		let ret_needs_lifetime = self.def.commands.iter().any(|cmd| {
-			self.needs_lifetime_ref(&cmd.ret)
+			self.contains_lifetime(&cmd.ret)
 		});
 		appendf!(self, "/// This enum contains all possible command return types in the RPC definition.\n");
 		appendf!(self, "#[derive(Debug, Clone)]\n");
-		appendf!(self, "pub enum CommandReturn{} {{\n", self.gen_lifetime_generics_if(ret_needs_lifetime));
+		let ret_placeholder = has_experimental && (
+			stable_empty || ret_needs_lifetime && !stable.clone().any(|cmd| self.contains_lifetime(&cmd.ret))
+		);
+		appendf!(self, "{}enum CommandReturn{} {{\n", self.vis(), self.gen_lifetime_generics_if(ret_needs_lifetime));
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
-			appendf!(self, "    {}({}),\n", self.get_command_name(cmd), self.gen_reference(&cmd.ret, false));
+			self.gen_cfg_experimental(&cmd.attrs, 4);
+			appendf!(self, "    {}({}),\n", self.get_command_name(cmd), self.gen_command_return(cmd, false));
 		}
+		self.gen_experimental_placeholder(ret_placeholder, ret_needs_lifetime);
 		appendf!(self, "}}\n"); // enum CommandReturn
 
 
@@ -552,30 +1294,33 @@ impl<'def> RustCodegen<'def> {
 			self.gen_lifetime_generics_if(ret_needs_lifetime)
 		);
 
-		appendf!(self, "    pub {} serialize<W: {}>(&self, w: &mut W) -> io::Result<()> {{\n", self.get_fn(), self.write());
+		appendf!(self, "    {}{} serialize<W: {}>(&self, w: &mut W) -> io::Result<()> {{\n", self.vis(), self.get_fn(), self.write());
 		appendf!(self, "        match self {{\n");
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self,
 				"            Self::{}(c) => c.serialize(w){}?,\n",
 				self.get_command_name(cmd), self.maybe_await()
 			);
 		}
+		self.gen_experimental_placeholder_arm(ret_placeholder);
 		appendf!(self, "        }}\n"); // match
 		appendf!(self, "        Ok(())\n");
 		appendf!(self, "    }}\n"); // fn serialize
 
-		appendf!(self, "    pub {} deserialize_return_stream<R: {}>(id: u32, r: &mut R) -> io::Result<Self> {{\n", self.get_fn(), self.read());
+		appendf!(self, "    {}{} deserialize_return_stream<R: {}>(id: u32, r: &mut R) -> io::Result<Self> {{\n", self.vis(), self.get_fn(), self.read());
 		appendf!(self, "        Ok(match id {{\n");
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self,
 				"            {} => Self::{}({}::deserialize_stream(r){}?),\n",
-				cmd.command_id, self.get_command_name(cmd), self.gen_reference(&cmd.ret, true), self.maybe_await()
+				cmd.command_id, self.get_command_name(cmd), self.gen_command_return(cmd, true), self.maybe_await()
 			);
 		}
 		appendf!(self, r#"            _ => Err(io::Error::other("Invalid or unsupported command ID"))?"#);
@@ -583,15 +1328,18 @@ impl<'def> RustCodegen<'def> {
 		appendf!(self, "        }})\n"); // match
 		appendf!(self, "    }}\n"); // fn deserialize_return_stream
 		if !self.use_tokio {
-			appendf!(self, "    pub fn deserialize_return<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {{\n");
+			appendf!(self, "    {}fn deserialize_return<'a{}>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {{\n",
+				self.vis(), if ret_needs_lifetime { ": 'x" } else { "" }
+			);
 			appendf!(self, "        Ok(match id {{\n");
 			for cmd in &self.def.commands {
 				if cmd.attrs.contains_key("@rust:ignore") {
 					continue;
 				}
+				self.gen_cfg_experimental(&cmd.attrs, 12);
 				appendf!(self,
 					"            {} => Self::{}({}::deserialize(r){}?),\n",
-					cmd.command_id, self.get_command_name(cmd), self.gen_reference(&cmd.ret, true), self.maybe_await()
+					cmd.command_id, self.get_command_name(cmd), self.gen_command_return(cmd, true), self.maybe_await()
 				);
 			}
 			appendf!(self, r#"            _ => Err(io::Error::other("Invalid or unsupported command ID"))?"#);
@@ -603,40 +1351,46 @@ impl<'def> RustCodegen<'def> {
 
 		appendf!(self, "/// This enum contains all possible command error types in the RPC definition.\n");
 		appendf!(self, "#[derive(Debug, Clone)]\n");
-		appendf!(self, "pub enum CommandError{} {{\n", self.gen_lifetime_generics_if(true));
+		let err_placeholder = has_experimental && stable_empty;
+		appendf!(self, "{}enum CommandError{} {{\n", self.vis(), self.gen_lifetime_generics_if(true));
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 4);
 			appendf!(self, "    {}({}),\n", self.get_command_name(cmd), self.gen_command_err(cmd));
 		}
+		self.gen_experimental_placeholder(err_placeholder, true);
 		appendf!(self, "}}\n"); // enum CommandError
 
 		appendf!(self, "impl{} CommandError{} {{\n",
 			self.gen_lifetime_generics_if(true),
 			self.gen_lifetime_generics_if(true)
 		);
-		appendf!(self, "    pub {} serialize<W: {}>(&self, w: &mut W) -> io::Result<()> {{\n", self.get_fn(), self.write());
+		appendf!(self, "    {}{} serialize<W: {}>(&self, w: &mut W) -> io::Result<()> {{\n", self.vis(), self.get_fn(), self.write());
 		appendf!(self, "        match self {{\n");
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self,
 				"            Self::{}(c) => c.serialize(w){}?,\n",
 				self.get_command_name(cmd), self.maybe_await()
 			);
 		}
+		self.gen_experimental_placeholder_arm(err_placeholder);
 		appendf!(self, "        }}\n"); // match
 		appendf!(self, "        Ok(())\n");
 		appendf!(self, "    }}\n"); // fn serialize
 
-		appendf!(self, "    pub {} deserialize_error_stream<R: {}>(id: u32, r: &mut R) -> io::Result<Self> {{\n", self.get_fn(), self.read());
+		appendf!(self, "    {}{} deserialize_error_stream<R: {}>(id: u32, r: &mut R) -> io::Result<Self> {{\n", self.vis(), self.get_fn(), self.read());
 		appendf!(self, "        Ok(match id {{\n");
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 12);
 			appendf!(self,
 				"            {} => Self::{}({}::deserialize_stream(r){}?),\n",
 				cmd.command_id, self.get_command_name(cmd), self.get_command_err(cmd), self.maybe_await()
@@ -648,12 +1402,13 @@ impl<'def> RustCodegen<'def> {
 		appendf!(self, "    }}\n"); // fn deserialize_error_stream
 
 		if !self.use_tokio {
-			appendf!(self, "    pub fn deserialize_error<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {{\n");
+			appendf!(self, "    {}fn deserialize_error<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {{\n", self.vis());
 			appendf!(self, "        Ok(match id {{\n");
 			for cmd in &self.def.commands {
 				if cmd.attrs.contains_key("@rust:ignore") {
 					continue;
 				}
+				self.gen_cfg_experimental(&cmd.attrs, 12);
 				appendf!(self,
 					"            {} => Self::{}({}::deserialize(r){}?),\n",
 					cmd.command_id, self.get_command_name(cmd), self.get_command_err(cmd), self.maybe_await()
@@ -666,44 +1421,377 @@ impl<'def> RustCodegen<'def> {
 		}
 		appendf!(self, "}}\n\n"); // impl CommandError
 	}
-	fn gen_fields(&mut self, fields: &Vec<PBField>) {
-		for field in fields {
-			if let Some(flags) = &field.flags {
-				for flag in flags {
-					self.gen_doc(&flag.doc, 1);
-					appendf!(self, "    pub {}: ", flag.name);
-					if let Some(val) = &flag.value {
-						appendf!(self, "Option<{}>,", self.gen_reference(val, false));
-					} else {
-						appendf!(self, "bool,");
+	fn field_vis(&self) -> &'static str {
+		if self.private_fields {
+			""
+		} else {
+			self.vis()
+		}
+	}
+	fn gen_fields(&mut self, fields: &Vec<PBField>) {
+		for field in fields {
+			if let Some(flags) = &field.flags {
+				for flag in flags {
+					self.gen_doc(&flag.doc, 1);
+					appendf!(self, "    {}{}: ", self.field_vis(), flag.name);
+					if let Some(val) = &flag.value {
+						appendf!(self, "Option<{}>,", self.gen_optional_value(val, false));
+					} else {
+						appendf!(self, "bool,");
+					}
+					appendf!(self, " // Flag of `{}`\n", field.name);
+				}
+			} else {
+				// Flag fields are an implementation detail and we would like
+				// to hide it (so that the struct is easily constructable)
+				self.gen_doc(&field.doc, 1);
+				appendf!(self, "    {}{}: {},\n", self.field_vis(), field.name, self.gen_field_type(field, false));
+			}
+		}
+	}
+	/// The type of a field that isn't a flag field. `@lazy_array`s are kept encoded in a `LazyArray`
+	fn gen_field_type(&self, field: &PBField, turbofish: bool) -> String {
+		if field.attrs.contains_key("@lazy_array") {
+			let item = self.gen_reference(&field.value.generics[0], false);
+			return if turbofish { format!("LazyArray::<{item}>") } else { format!("LazyArray<{item}>") };
+		}
+		self.gen_reference(&field.value, turbofish)
+	}
+	/// The `<field>_iter` methods of the `@lazy_array` fields of a struct, decoding their items one at a time.
+	/// `name` is the name of the struct, with its generics, and `impl_generics` are the generics of the `impl`
+	fn gen_lazy_array_iters(&mut self, impl_generics: &str, name: &str, fields: &[PBField]) {
+		appendf!(self, "impl{impl_generics} {name} {{\n");
+		for field in fields.iter().filter(|field| field.attrs.contains_key("@lazy_array")) {
+			let item = &field.value.generics[0];
+			if self.gen_docs {
+				appendf!(self, "    /// Decodes the items of `{}` one at a time. Stops after the first error\n", field.name);
+			}
+			appendf!(self, "    {}fn {}_iter(&self) -> impl Iterator<Item = io::Result<{}>> + '_ {{\n",
+				self.vis(), field.name, self.gen_reference(item, false)
+			);
+			if self.use_tokio {
+				appendf!(self, "        self.{}.iter_with(|r| decode_now({}::deserialize_stream(r)))\n",
+					field.name, self.gen_reference(item, true)
+				);
+			} else {
+				appendf!(self, "        self.{}.iter()\n", field.name);
+			}
+			appendf!(self, "    }}\n");
+		}
+		appendf!(self, "}}\n"); // impl
+	}
+	/// Whether a struct gets a `new` function, see [`Self::gen_constructors`].
+	/// `hidden` is whether it has a `_phantom` or `_unknown_` fields
+	fn has_constructors(&self, fields: &[PBField], hidden: bool) -> bool {
+		self.private_fields || hidden || fields.iter().any(|field| field.flags.is_some())
+	}
+	/// The generic parameters of `tp` marked `@phantom`
+	fn phantom_params(tp: &PBTypeDef) -> Vec<&str> {
+		tp.get_attrs().get("@phantom").map(attributes::ident_list).unwrap_or_default()
+	}
+	/// The `_phantom` field of a struct with `@phantom` generic parameters
+	fn gen_phantom_field(&mut self, phantom: &[&str]) {
+		let params = match phantom {
+			[param] => param.to_string(),
+			params => format!("({})", params.join(", ")),
+		};
+		appendf!(self, "    {}_phantom: core::marker::PhantomData<{params}>,\n", self.field_vis());
+	}
+	/// The flags and the extensions of a struct that aren't in the definition, see [`Self::keep_extensions`]
+	fn gen_unknown_fields(&mut self, fields: &[PBField]) {
+		for field in fields.iter().filter(|field| field.flags.is_some()) {
+			appendf!(self, "    {}_unknown_{}: {}, // Unknown flags of `{}`\n",
+				self.field_vis(), field.name, self.gen_reference(&field.value, false), field.name
+			);
+		}
+		appendf!(self, "    {}_unknown_extensions: Vec<u8>,\n", self.field_vis());
+	}
+	/// The flags of `field` that aren't in the definition, in `{field}`. The known ones are the lowest bits
+	fn unknown_flags(field: &PBField) -> String {
+		let known = field.flags.as_ref().map_or(0, |flags| flags.len());
+		let mask = if known >= 64 { u64::MAX } else { (1 << known) - 1 };
+		format!("field_{} & !{mask:#x}", field.name)
+	}
+	/// The `new` function of a struct, taking the fields that aren't flags, and a `with_` method
+	/// for every flag. With private fields, also the getters and setters of every field.
+	/// `name` is the name of the struct, with its generics, `phantom` is whether it has a `_phantom` field,
+	/// and `unknown` whether it has the `_unknown_` fields
+	fn gen_constructors(&mut self, name: &str, fields: &Vec<PBField>, phantom: bool, unknown: bool) {
+		// (name, type, doc, whether it's a flag and the type of its value) of every Rust field
+		let mut rust_fields = vec![];
+		for field in fields {
+			match &field.flags {
+				Some(flags) => for flag in flags {
+					let value = flag.value.as_ref().map(|val| self.gen_optional_value(val, false));
+					let tp = match &value {
+						Some(value) => format!("Option<{value}>"),
+						None => "bool".to_string(),
+					};
+					rust_fields.push((&flag.name, tp, &flag.doc, Some(value)));
+				}
+				None => rust_fields.push((&field.name, self.gen_field_type(field, false), &field.doc, None)),
+			}
+		}
+		let generics = name.find('<').map_or("", |i| &name[i..]);
+		appendf!(self, "impl{generics} {name} {{\n");
+		let required: Vec<_> = rust_fields.iter().filter(|(.., flag)| flag.is_none()).collect();
+		if required.len() > 7 {
+			appendf!(self, "    #[allow(clippy::too_many_arguments)]\n");
+		}
+		let params: Vec<String> = required.iter().map(|(field, tp, ..)| format!("{field}: {tp}")).collect();
+		appendf!(self, "    {}fn new({}) -> Self {{\n", self.vis(), params.join(", "));
+		let mut values: Vec<String> = rust_fields.iter().map(|(field, _, _, flag)| match flag {
+			None => field.to_string(),
+			Some(None) => format!("{field}: false"),
+			Some(Some(_)) => format!("{field}: None"),
+		}).collect();
+		if phantom {
+			values.push("_phantom: core::marker::PhantomData".to_string());
+		}
+		if unknown {
+			for field in fields.iter().filter(|field| field.flags.is_some()) {
+				values.push(format!("_unknown_{}: Default::default()", field.name));
+			}
+			values.push("_unknown_extensions: Vec::new()".to_string());
+		}
+		appendf!(self, "        Self {{ {} }}\n", values.join(", "));
+		appendf!(self, "    }}\n"); // fn new
+		for (field, _, doc, flag) in &rust_fields {
+			let Some(value) = flag else { continue };
+			self.gen_doc(doc, 1);
+			match value {
+				Some(value) => {
+					appendf!(self, "    {}fn with_{field}(mut self, {field}: {value}) -> Self {{\n", self.vis());
+					appendf!(self, "        self.{field} = Some({field});\n");
+				}
+				None => {
+					appendf!(self, "    {}fn with_{field}(mut self, {field}: bool) -> Self {{\n", self.vis());
+					appendf!(self, "        self.{field} = {field};\n");
+				}
+			}
+			appendf!(self, "        self\n");
+			appendf!(self, "    }}\n"); // fn with_
+		}
+		if self.private_fields {
+			for (field, tp, doc, _) in &rust_fields {
+				self.gen_doc(doc, 1);
+				appendf!(self, "    {}fn {field}(&self) -> &{tp} {{\n", self.vis());
+				appendf!(self, "        &self.{field}\n");
+				appendf!(self, "    }}\n");
+				appendf!(self, "    {}fn set_{field}(&mut self, {field}: {tp}) {{\n", self.vis());
+				appendf!(self, "        self.{field} = {field};\n");
+				appendf!(self, "    }}\n");
+			}
+		}
+		appendf!(self, "}}\n"); // impl
+	}
+	fn gen_variants(&mut self, variants: &Vec<PBEnumVariant>) {
+		for variant in variants {
+			self.gen_doc(&variant.doc, 1);
+			appendf!(self, "    {}", variant.name);
+			if let Some(val) = &variant.value {
+				appendf!(self, "({})", self.gen_reference(val, false))
+			}
+			appendf!(self, ",\n")
+		}
+		if self.keeps_unknown_variants(variants) {
+			appendf!(self, "    /// A variant added in a later version, with its discriminant and encoded value\n");
+			appendf!(self, "    UnknownVariant(u8, Bytes<{}>),\n", self.lifetime);
+		}
+	}
+	/* fn gen_flags_type(&self, flags_type: &PBTypeRef) -> &str {
+		
+	} */
+	/// The size of a type that is always serialized with the same amount of bytes
+	fn fixed_size(&self, refr: &PBTypeRef) -> Option<usize> {
+		if !self.uses_common || !refr.is_global {
+			return None;
+		}
+		match refr.reference.as_str() {
+			"U8" => Some(1),
+			"U16" => Some(2),
+			"U32" | "I32" | "F32" => Some(4),
+			"U64" | "I64" | "F64" => Some(8),
+			_ => None,
+		}
+	}
+	/// `generics` are the sizes of the generic arguments of the type containing `refr`
+	fn wire_size_ref(
+		&self, refr: &PBTypeRef, generics: &HashMap<&str, WireSize>,
+		path: &mut HashSet<(String, u32)>
+	) -> WireSize {
+		if !refr.is_global {
+			return generics.get(refr.reference.as_str()).cloned().unwrap_or(WireSize::UNKNOWN);
+		}
+		if let Some(size) = self.fixed_size(refr) {
+			return WireSize::fixed(size);
+		}
+		if self.uses_common {
+			match refr.reference.as_str() {
+				"Void" => return WireSize::fixed(0),
+				"Boolean" => return WireSize::fixed(1),
+				"UInt" | "SInt" => return WireSize::between(1, 8),
+				// all of these start with a `UInt` length
+				"Bytes" | "String" | "Array" => return WireSize::at_least(1),
+				// a discriminant, and then maybe the value
+				"Optional" => {
+					let value = self.wire_size_ref(&refr.generics[0], generics, path);
+					return WireSize::fixed(1).then(WireSize::fixed(0).or(value));
+				}
+				"DateTime" if self.is_builtin_ref(refr) => return WireSize::fixed(12),
+				_ => {}
+			}
+		}
+		let layer = refr.resolved_layer.expect("bad state: layer not resolved");
+		if !path.insert((refr.reference.clone(), layer)) {
+			// a type containing itself, the recursion has to end somewhere
+			return WireSize::UNKNOWN;
+		}
+		let typdef = self.def.types.iter().find(|t| {
+			t.get_name().0 == refr.reference &&
+			t.get_layer() == &layer
+		}).expect("bad state: unknown type referenced");
+		let args = refr.generics.iter().map(|g| self.wire_size_ref(g, generics, path));
+		let generics = typdef.get_generics().0.iter().map(|g| g.as_str()).zip(args).collect();
+		let result = self.wire_size_with_context(typdef, &generics, path);
+		path.remove(&(refr.reference.clone(), layer));
+		result
+	}
+	fn wire_size_with_context(
+		&self, typdef: &PBTypeDef, generics: &HashMap<&str, WireSize>,
+		path: &mut HashSet<(String, u32)>
+	) -> WireSize {
+		if typdef.get_attrs().contains_key("@builtin") {
+			return WireSize::UNKNOWN;
+		}
+		match typdef {
+			PBTypeDef::Struct { fields, attrs, .. } => {
+				let mut size = WireSize::fixed(0);
+				let mut has_extensions = false;
+				for field in fields {
+					if field.attrs.contains_key("@extension_flags") {
+						// it's inside the extension bytes
+						has_extensions = true;
+						continue;
+					}
+					if field.attrs.contains_key("@compress") {
+						// the uncompressed length, and then the compressed bytes
+						size = size.then(WireSize::at_least(2));
+						continue;
+					}
+					size = size.then(self.wire_size_ref(&field.value, generics, path));
+					for flag in field.flags.iter().flatten() {
+						let Some(value) = &flag.value else { continue };
+						// values of flags are only present when the flag is set
+						size.fixed = false;
+						if flag.attrs.contains_key("@extension") {
+							has_extensions = true;
+						} else if let Some(max) = size.max {
+							size.max = self.wire_size_ref(value, generics, path).max.map(|v| max + v);
+						}
 					}
-					appendf!(self, " // Flag of `{}`\n", field.name);
 				}
-			} else {
-				// Flag fields are an implementation detail and we would like
-				// to hide it (so that the struct is easily constructable)
-				self.gen_doc(&field.doc, 1);
-				appendf!(self, "    pub {}: {},\n", field.name, self.gen_reference(&field.value, false));
+				if !attrs.contains_key("@sealed") {
+					// the length of the extensions, which may appear in the future
+					let mut extensions = WireSize::at_least(1);
+					if !has_extensions {
+						// but for now, it's always zero
+						extensions.max = Some(1);
+					}
+					size = size.then(extensions);
+				}
+				size
 			}
+			PBTypeDef::Enum { variants, .. } => match self.flattened_variant(typdef).and_then(|v| v.value.as_ref()) {
+				Some(value) => self.wire_size_ref(value, generics, path),
+				None => self.wire_size_variants(variants, generics, path),
+			},
+			PBTypeDef::Alias { alias, .. } => self.wire_size_ref(alias, generics, path),
 		}
 	}
-	fn gen_variants(&mut self, variants: &Vec<PBEnumVariant>) {
+	/// The discriminant, followed by the value of one of the `variants`
+	fn wire_size_variants(
+		&self, variants: &Vec<PBEnumVariant>, generics: &HashMap<&str, WireSize>,
+		path: &mut HashSet<(String, u32)>
+	) -> WireSize {
+		let mut size: Option<WireSize> = None;
 		for variant in variants {
-			self.gen_doc(&variant.doc, 1);
-			appendf!(self, "    {}", variant.name);
-			if let Some(val) = &variant.value {
-				appendf!(self, "({})", self.gen_reference(val, false))
+			let mut variant_size = variant.value.as_ref()
+				.map(|v| self.wire_size_ref(v, generics, path))
+				.unwrap_or(WireSize::fixed(0));
+			if variant.attrs.contains_key("@extension") {
+				variant_size = variant_size.length_prefixed();
+			}
+			size = Some(match size {
+				Some(size) => size.or(variant_size),
+				None => variant_size,
+			});
+		}
+		let mut size = WireSize::fixed(1).then(size.unwrap_or(WireSize::fixed(0)));
+		if variants.iter().any(|v| v.attrs.contains_key("@default")) {
+			// unknown variants come with their own length
+			size.fixed = false;
+			if self.keeps_unknown_variants(variants) {
+				size.max = None;
 			}
-			appendf!(self, ",\n")
 		}
+		size
+	}
+	/// The size of the type on the wire, with the generic parameters contributing
+	/// their `MIN_SIZE`, but nothing else
+	fn wire_size(&self, tp: &PBTypeDef) -> WireSize {
+		let mut path = HashSet::new();
+		path.insert((tp.get_name().0.to_string(), *tp.get_layer()));
+		let generics = tp.get_generics().0.iter().map(|param| {
+			(param.as_str(), WireSize { min: MinSize::Param(param.clone()), ..WireSize::UNKNOWN })
+		}).collect();
+		self.wire_size_with_context(tp, &generics, &mut path)
+	}
+	fn gen_wire_size_doc(&mut self, tp: &PBTypeDef) {
+		if !self.wire_sizes {
+			return;
+		}
+		if self.gen_docs && !tp.get_doc().is_empty() {
+			appendf!(self, "/// \n");
+		}
+		appendf!(self, "/// Wire size: {}\n", self.wire_size(tp));
+	}
+	/// With tokio, each `write_all` is an await point (and often a syscall),
+	/// so the leading fields of a fixed size are written all at once.
+	/// Returns the amount of fields that were written.
+	fn gen_serialize_fixed_fields(&mut self, fields: &Vec<PBField>) -> usize {
+		let fixed: Vec<_> = fields.iter()
+			.map_while(|f| if f.flags.is_some() { None } else { Some((f, self.fixed_size(&f.value)?)) })
+			.collect();
+		if !self.use_tokio || fixed.len() < 2 {
+			return 0;
+		}
+		let total: usize = fixed.iter().map(|(_, size)| size).sum();
+		appendf!(self, "        let mut fixed = [0u8; {total}];\n");
+		let mut offset = 0;
+		for (field, size) in &fixed {
+			let order = byte_order(field).unwrap_or("be");
+			appendf!(self, "        fixed[{offset}..{}].copy_from_slice(&self.{}.to_{order}_bytes());\n", offset + size, field.name);
+			offset += size;
+		}
+		appendf!(self, "        w.write_all(&fixed).await?;\n");
+		fixed.len()
+	}
+	/// Makes sure every flag of `field` has a bit, even if the validator missed it,
+	/// since shifting past the width of the type would panic or wrap around
+	fn gen_flags_capacity_assert(&mut self, field: &PBField, count: usize) {
+		appendf!(self,
+			"        const _: () = assert!({count} <= 8 * core::mem::size_of::<{}>(), \"not enough bits for the flags of `{}`\");\n",
+			self.gen_reference(&field.value, true), field.name
+		);
 	}
-	/* fn gen_flags_type(&self, flags_type: &PBTypeRef) -> &str {
-		
-	} */
 	fn gen_serialize_fields(&mut self, fields: &Vec<PBField>, extensible: bool) {
+		// see `Self::keep_extensions`
+		let unknown = self.keep_extensions && extensible;
 		let mut has_extensions = false;
 		let mut has_extension_flags = false;
-		for field in fields {
+		let written = self.gen_serialize_fixed_fields(fields);
+		for field in fields.iter().skip(written) {
 			if field.attrs.contains_key("@extension_flags") {
 				has_extension_flags = true;
 				continue;
@@ -716,6 +1804,7 @@ impl<'def> RustCodegen<'def> {
 					"        let mut flags: {} = 0.try_into().unwrap();\n",
 					self.gen_reference(&field.value, false)
 				);
+				self.gen_flags_capacity_assert(field, flags.len());
 				for (i, flag) in flags.iter().enumerate() {
 					if flag.value.is_some() {
 						appendf!(self, "        if self.{}.is_some() {{ flags |= 1 << {i} }}\n", flag.name);
@@ -723,6 +1812,9 @@ impl<'def> RustCodegen<'def> {
 						appendf!(self, "        if self.{} {{ flags |= 1 << {i} }}\n", flag.name);
 					}
 				}
+				if unknown {
+					appendf!(self, "        flags |= self._unknown_{};\n", field.name);
+				}
 				appendf!(self, "        flags.serialize(w){}?;\n", self.maybe_await());
 				for flag in flags {
 					if flag.value.is_none() { continue }
@@ -735,17 +1827,28 @@ impl<'def> RustCodegen<'def> {
 					appendf!(self, "            v.serialize(w){}?;\n", self.maybe_await());
 					appendf!(self, "        }}\n");
 				}
+			} else if field.attrs.contains_key("@compress") {
+				appendf!(self, "        {}::serialize_zstd(&self.{}, w){}?;\n",
+					self.compression_module(), field.name, self.maybe_await()
+				);
+			} else if let Some(order) = byte_order(field) {
+				appendf!(self, "        w.write_all(&self.{}.to_{order}_bytes()){}?;\n", field.name, self.maybe_await());
 			} else {
 				appendf!(self, "        self.{}.serialize(w){}?;\n", field.name, self.maybe_await());
 			}
 		}
-		if extensible && (has_extensions || has_extension_flags) {
-			// TODO: this is currently a mess.
-			appendf!(self, "        let real_w = w;\n");
-			appendf!(self, "        let mut bytes = Bytes(Vec::new());\n");
-			appendf!(self, "        let w = &mut bytes.0;\n");
+		if !extensible {
+			return;
+		}
+		if !has_extensions && !has_extension_flags && !unknown {
+			appendf!(self, "        UInt(0).serialize(w){}?;\n", self.maybe_await());
+			return;
 		}
-		if extensible && has_extensions {
+		// TODO: this is currently a mess.
+		appendf!(self, "        let real_w = w;\n");
+		appendf!(self, "        let mut bytes = Bytes(Cow::Owned(Vec::new()));\n");
+		appendf!(self, "        let w = bytes.0.to_mut();\n");
+		if has_extensions {
 			// Probably better to do this with a temporary Vec
 			for field in fields {
 				let Some(flags) = &field.flags else { continue };
@@ -759,8 +1862,8 @@ impl<'def> RustCodegen<'def> {
 					appendf!(self, "        }}\n");
 				}
 			}
-			appendf!(self, "        bytes.serialize(real_w){}?;\n", self.maybe_await());
-		} else if extensible && has_extension_flags {
+		}
+		if has_extension_flags {
 			let extension_flags_field = fields.iter().find(
 				|f| f.attrs.contains_key("@extension_flags")
 			).expect("bad state: has_extension_flags, but no extension flags present");
@@ -773,6 +1876,7 @@ impl<'def> RustCodegen<'def> {
 			);
 			let flags = extension_flags_field.flags.as_ref()
 				.expect("validator error: @extension_flags has no flags");
+			self.gen_flags_capacity_assert(extension_flags_field, flags.len());
 			for (i, flag) in flags.iter().enumerate() {
 				if flag.value.is_some() {
 					appendf!(self,
@@ -786,25 +1890,49 @@ impl<'def> RustCodegen<'def> {
 					);
 				}
 			}
+			if unknown {
+				appendf!(self, "        flags |= self._unknown_{};\n", extension_flags_field.name);
+			}
 			appendf!(self, "        flags.serialize(w){}?;\n", self.maybe_await());
 			for flag in flags {
 				if flag.value.is_none() { continue }
 				appendf!(self, "        if let Some(ref v) = self.{} {{\n", flag.name);
 				appendf!(self, "            v.serialize(w){}?;\n", self.maybe_await());
 				appendf!(self, "        }}\n");
-				appendf!(self, "        bytes.serialize(real_w){}?;\n", self.maybe_await());
 			}
-		} else if extensible {
-			appendf!(self, "        UInt(0).serialize(w){}?;\n", self.maybe_await());
 		}
+		if unknown {
+			// whatever a newer version put after the extensions we know about
+			appendf!(self, "        w.extend_from_slice(&self._unknown_extensions);\n");
+		}
+		appendf!(self, "        bytes.serialize(real_w){}?;\n", self.maybe_await());
 	}
-	fn gen_deserialize_fields(&mut self, fields: &Vec<PBField>, extensible: bool, stream: bool) {
+	fn gen_deserialize_fields(&mut self, fields: &Vec<PBField>, extensible: bool, stream: bool, phantom: bool) {
 		let stream = deserialize_suffix(stream);
 		for field in fields {
 			if field.attrs.contains_key("@extension_flags") { continue }
-			appendf!(self, "        let field_{} = {}::deserialize{stream}(r){}?;\n",
-				field.name, self.gen_reference(&field.value, true),
-				self.maybe_await()
+			if field.attrs.contains_key("@compress") {
+				appendf!(self, "        let field_{} = {}::deserialize_zstd(r){}.at_path(\"{}\")?;\n",
+					field.name, self.compression_module(), self.maybe_await(), field.name
+				);
+				continue;
+			}
+			if let Some(order) = byte_order(field) {
+				let size = self.fixed_size(&field.value).expect("validator error: byte order of a type without a fixed size");
+				appendf!(self, "        let field_{} = {}::from_{order}_bytes({{\n", field.name, self.gen_reference(&field.value, true));
+				appendf!(self, "            let mut bytes = [0; {size}];\n");
+				if self.use_tokio {
+					appendf!(self, "            r.read_exact(&mut bytes).await.at_path(\"{}\")?;\n", field.name);
+				} else {
+					appendf!(self, "            io::Read::read_exact(r, &mut bytes).at_path(\"{}\")?;\n", field.name);
+				}
+				appendf!(self, "            bytes\n");
+				appendf!(self, "        }});\n");
+				continue;
+			}
+			appendf!(self, "        let field_{} = {}::deserialize{stream}(r){}.at_path(\"{}\")?;\n",
+				field.name, self.gen_field_type(field, true),
+				self.maybe_await(), field.name
 			);
 			if let Some(flags) = &field.flags {
 				for (i, flag) in flags.iter().enumerate() {
@@ -817,8 +1945,8 @@ impl<'def> RustCodegen<'def> {
 							flag.name, field.name
 						);
 						appendf!(self,
-							"            Some({}::deserialize{stream}(r){}?)\n",
-							self.gen_reference(val, true), self.maybe_await()
+							"            Some({}::deserialize{stream}(r){}.at_path(\"{}\")?)\n",
+							self.gen_optional_value(val, true), self.maybe_await(), flag.name
 						);
 						appendf!(self,
 							"        }} else {{ None }};\n"
@@ -835,6 +1963,8 @@ impl<'def> RustCodegen<'def> {
 		if extensible {
 			appendf!(self, "        let mut _extension_bytes = Bytes::deserialize{stream}(r){}?;\n", self.maybe_await());
 			appendf!(self, "        let _extension_reader = &mut &_extension_bytes.0[..];\n");
+			// `_extension_bytes` is a local, so the values in it can't borrow
+			// from the input, even when deserializing from a slice
 			for field in fields {
 				let Some(flags) = &field.flags else { continue };
 				for (i, flag) in flags.iter().enumerate() {
@@ -849,8 +1979,8 @@ impl<'def> RustCodegen<'def> {
 							flag.name, field.name
 						);
 						appendf!(self,
-							"            Some({}::deserialize{stream}(_extension_reader){}?)\n",
-							self.gen_reference(val, true), self.maybe_await()
+							"            Some({}::deserialize_stream(_extension_reader){}.at_path(\"{}\")?)\n",
+							self.gen_optional_value(val, true), self.maybe_await(), flag.name
 						);
 						appendf!(self,
 							"        }} else {{ None }};\n"
@@ -868,9 +1998,9 @@ impl<'def> RustCodegen<'def> {
 			if let Some(extension_flags_field) = fields.iter()
 				.find(|f| f.attrs.contains_key("@extension_flags"))
 			{
-				appendf!(self, "        let field_{} = {}::deserialize{stream}(_extension_reader){}?;\n",
+				appendf!(self, "        let field_{} = {}::deserialize_stream(_extension_reader){}.at_path(\"{}\")?;\n",
 					extension_flags_field.name, self.gen_reference(&extension_flags_field.value, true),
-					self.maybe_await()
+					self.maybe_await(), extension_flags_field.name
 				);
 				for (i, flag) in extension_flags_field.flags.as_ref()
 					.expect("validator error: @extension_flags must have flags")
@@ -881,8 +2011,8 @@ impl<'def> RustCodegen<'def> {
 							"        let flag_{} = if (field_{} & (1 << {i})) != 0 {{\n",
 							flag.name, extension_flags_field.name);
 						appendf!(self,
-							"            Some({}::deserialize{stream}(_extension_reader){}?)\n",
-							self.gen_reference(val, true), self.maybe_await());
+							"            Some({}::deserialize_stream(_extension_reader){}.at_path(\"{}\")?)\n",
+							self.gen_optional_value(val, true), self.maybe_await(), flag.name);
 						appendf!(self,
 							"        }} else {{ None }};\n");
 
@@ -905,8 +2035,84 @@ impl<'def> RustCodegen<'def> {
 				appendf!(self, "            {}: field_{},\n", field.name, field.name);
 			}
 		}
+		if phantom {
+			appendf!(self, "            _phantom: core::marker::PhantomData,\n");
+		}
+		if extensible && self.keep_extensions {
+			for field in fields.iter().filter(|field| field.flags.is_some()) {
+				appendf!(self, "            _unknown_{}: {},\n", field.name, Self::unknown_flags(field));
+			}
+			appendf!(self, "            _unknown_extensions: _extension_reader.to_vec(),\n");
+		}
 		appendf!(self, "        }})\n");
 	}
+	fn gen_deserialize_into_flags(&mut self, field: &PBField, extension: Option<bool>, reader: &str) {
+		let Some(flags) = &field.flags else { return };
+		for (i, flag) in flags.iter().enumerate() {
+			if extension.is_some_and(|ext| ext != flag.attrs.contains_key("@extension")) {
+				continue;
+			}
+			if flag.value.is_some() {
+				appendf!(self,
+					"        deserialize_option_into(&mut self.{}, (field_{} & (1 << {i})) != 0, {reader}).at_path(\"{}\")?;\n",
+					flag.name, field.name, flag.name
+				);
+			} else {
+				appendf!(self, "        self.{} = (field_{} & (1 << {i})) != 0;\n", flag.name, field.name);
+			}
+		}
+	}
+	/// Like [`Self::gen_deserialize_fields`], but reuses the existing values in `self`
+	fn gen_deserialize_into_fields(&mut self, fields: &Vec<PBField>, extensible: bool) {
+		for field in fields {
+			if field.attrs.contains_key("@extension_flags") { continue }
+			if field.flags.is_some() {
+				appendf!(self, "        let field_{} = {}::deserialize_stream(r).at_path(\"{}\")?;\n",
+					field.name, self.gen_reference(&field.value, true), field.name
+				);
+				self.gen_deserialize_into_flags(field, Some(false), "r");
+				if extensible && self.keep_extensions {
+					appendf!(self, "        self._unknown_{} = {};\n", field.name, Self::unknown_flags(field));
+				}
+			} else if field.attrs.contains_key("@compress") {
+				appendf!(self, "        self.{} = {}::deserialize_zstd(r).at_path(\"{}\")?;\n",
+					field.name, self.compression_module(), field.name
+				);
+			} else if let Some(order) = byte_order(field) {
+				let size = self.fixed_size(&field.value).expect("validator error: byte order of a type without a fixed size");
+				appendf!(self, "        let mut bytes = [0; {size}];\n");
+				appendf!(self, "        io::Read::read_exact(r, &mut bytes).at_path(\"{}\")?;\n", field.name);
+				appendf!(self, "        self.{} = {}::from_{order}_bytes(bytes);\n", field.name, self.gen_reference(&field.value, true));
+			} else {
+				appendf!(self, "        self.{}.deserialize_into(r).at_path(\"{}\")?;\n", field.name, field.name);
+			}
+		}
+		if extensible {
+			appendf!(self, "        let mut _extension_bytes = Bytes::deserialize_stream(r)?;\n");
+			appendf!(self, "        let _extension_reader = &mut &_extension_bytes.0[..];\n");
+			for field in fields {
+				if field.attrs.contains_key("@extension_flags") { continue }
+				self.gen_deserialize_into_flags(field, Some(true), "_extension_reader");
+			}
+			if let Some(extension_flags_field) = fields.iter()
+				.find(|f| f.attrs.contains_key("@extension_flags"))
+			{
+				appendf!(self, "        let field_{} = {}::deserialize_stream(_extension_reader).at_path(\"{}\")?;\n",
+					extension_flags_field.name, self.gen_reference(&extension_flags_field.value, true),
+					extension_flags_field.name
+				);
+				self.gen_deserialize_into_flags(extension_flags_field, None, "_extension_reader");
+				if self.keep_extensions {
+					appendf!(self, "        self._unknown_{} = {};\n", extension_flags_field.name, Self::unknown_flags(extension_flags_field));
+				}
+			}
+			if self.keep_extensions {
+				appendf!(self, "        self._unknown_extensions.clear();\n");
+				appendf!(self, "        self._unknown_extensions.extend_from_slice(_extension_reader);\n");
+			}
+		}
+		appendf!(self, "        Ok(())\n");
+	}
 	fn gen_serialize_variants(&mut self, variants: &Vec<PBEnumVariant>) {
 		for variant in variants {
 			appendf!(self, "            Self::{}", variant.name);
@@ -919,8 +2125,8 @@ impl<'def> RustCodegen<'def> {
 				if variant.value.is_some() {
 					appendf!(self, "                // Extension:\n");
 					appendf!(self, "                let real_w = w;\n");
-					appendf!(self, "                let mut bytes = Bytes(Vec::new());\n");
-					appendf!(self, "                let w = &mut bytes.0;\n");
+					appendf!(self, "                let mut bytes = Bytes(Cow::Owned(Vec::new()));\n");
+					appendf!(self, "                let w = bytes.0.to_mut();\n");
 				} else {
 					appendf!(self, "                // Skipped extension:\n");
 					appendf!(self, "                UInt(0).serialize(w){}?;\n", self.maybe_await());
@@ -934,6 +2140,13 @@ impl<'def> RustCodegen<'def> {
 			}
 			appendf!(self, "            }}\n");
 		}
+		if self.keeps_unknown_variants(variants) {
+			// `Bytes` are prefixed with their length, just like extensions
+			appendf!(self, "            Self::UnknownVariant(discriminant, bytes) => {{\n");
+			appendf!(self, "                discriminant.serialize(w){}?;\n", self.maybe_await());
+			appendf!(self, "                bytes.serialize(w){}?;\n", self.maybe_await());
+			appendf!(self, "            }}\n");
+		}
 	}
 	fn gen_deserialize_variants(&mut self, variants: &Vec<PBEnumVariant>, stream: bool) {
 		let stream = deserialize_suffix(stream);
@@ -944,18 +2157,26 @@ impl<'def> RustCodegen<'def> {
 			}
 			appendf!(self, "            {} => {{\n", variant.discriminant);
 			if variant.attrs.contains_key("@extension") {
-				appendf!(self, "                _ = UInt::deserialize{stream}(r);\n");
+				appendf!(self, "                UInt::deserialize{stream}(r){}?;\n", self.maybe_await());
 			}
 			if let Some(refr) = &variant.value {
-				appendf!(self, "                Self::{}({}::deserialize{stream}(r){}?)\n", variant.name, self.gen_reference(refr, true), self.maybe_await());
+				appendf!(self, "                Self::{}({}::deserialize{stream}(r){}.at_path(\"{}\")?)\n",
+					variant.name, self.gen_reference(refr, true), self.maybe_await(), variant.name
+				);
 			} else {
 				appendf!(self, "                Self::{}\n", variant.name);
 			}
 			appendf!(self, "            }}\n");
 		}
-		if let Some(default_variant) = default_variant {
+		if self.keeps_unknown_variants(variants) {
+			appendf!(self, "            discriminant => {{\n");
+			appendf!(self, "                Self::UnknownVariant(discriminant, Bytes::deserialize{stream}(r){}?)\n", self.maybe_await());
+			appendf!(self, "            }}\n");
+		} else if let Some(default_variant) = default_variant {
+			// Unknown discriminants can only belong to `@extension` variants
+			// added later, which are always prefixed with their length
 			appendf!(self, "            _ => {{\n");
-			appendf!(self, "                _ = Bytes::deserialize{stream}(r){}?;\n", self.maybe_await());
+			appendf!(self, "                discard_extension(r){}?;\n", self.maybe_await());
 			appendf!(self, "                Self::{}\n", default_variant.name);
 			appendf!(self, "            }}\n");
 		} else {
@@ -964,6 +2185,42 @@ impl<'def> RustCodegen<'def> {
 			appendf!(self, "            }}\n");
 		}
 	}
+	/// The body of `deserialize_stream` or `deserialize` of an enum without a discriminant
+	fn gen_deserialize_flattened(&mut self, variant: &PBEnumVariant, stream: bool) {
+		let refr = variant.value.as_ref().expect("flattened variants have a value");
+		appendf!(self, "        Ok(Self::{}({}::deserialize{}(r){}.at_path(\"{}\")?))\n",
+			variant.name, self.gen_reference(refr, true), deserialize_suffix(stream), self.maybe_await(), variant.name
+		);
+	}
+	fn gen_cfg_experimental(&mut self, attrs: &HashMap<String, Option<String>>, indent: usize) {
+		if attrs.contains_key("@experimental") {
+			self.gen_cfg_experimental_if(true, indent);
+		}
+	}
+	fn gen_cfg_experimental_if(&mut self, condition: bool, indent: usize) {
+		if condition {
+			appendf!(self, "{}#[cfg(feature = \"experimental\")]\n", " ".repeat(indent));
+		}
+	}
+	/// An uninhabited variant that stands in for the experimental commands
+	/// when the feature is disabled
+	fn gen_experimental_placeholder(&mut self, condition: bool, lifetime: bool) {
+		if !condition {
+			return;
+		}
+		appendf!(self, "    #[cfg(not(feature = \"experimental\"))]\n");
+		appendf!(self, "    #[doc(hidden)]\n");
+		appendf!(self, "    __Experimental({}core::convert::Infallible),\n",
+			if lifetime { format!("core::marker::PhantomData<&{} ()>, ", self.lifetime) } else { String::new() }
+		);
+	}
+	fn gen_experimental_placeholder_arm(&mut self, condition: bool) {
+		if !condition {
+			return;
+		}
+		appendf!(self, "            #[cfg(not(feature = \"experimental\"))]\n");
+		appendf!(self, "            Self::__Experimental(..) => unreachable!(),\n");
+	}
 	fn gen_doc(&mut self, doc: &str, indent: usize) {
 		if !self.gen_docs || doc == "" {
 			return;
@@ -973,32 +2230,97 @@ impl<'def> RustCodegen<'def> {
 			appendf!(self, "/// {}\n", line);
 		}
 	}
+	/// A `Client` that invokes commands over a stream, one at a time
+	fn gen_client(&mut self) {
+		if self.gen_docs {
+			appendf!(self, "/// Invokes commands over a stream, one at a time.\n");
+			appendf!(self, "///\n");
+			appendf!(self, "/// A command is written with its ID, and answered with a byte, `0` if it failed\n");
+			appendf!(self, "/// and `1` if it returned, followed by the error or the return value.\n");
+			appendf!(self, "/// Commands returning `Void` aren't answered.\n");
+		}
+		appendf!(self, "#[derive(Debug)]\n");
+		appendf!(self, "{}struct Client<RW> {{\n", self.vis());
+		appendf!(self, "    {}stream: RW,\n", self.vis());
+		appendf!(self, "}}\n");
+		appendf!(self, "impl<RW: AsyncReadExt + AsyncWriteExt + Unpin + Send> Client<RW> {{\n");
+		appendf!(self, "    {}fn new(stream: RW) -> Self {{\n", self.vis());
+		appendf!(self, "        Self {{ stream }}\n");
+		appendf!(self, "    }}\n");
+		appendf!(self, "    {}fn into_inner(self) -> RW {{\n", self.vis());
+		appendf!(self, "        self.stream\n");
+		appendf!(self, "    }}\n");
+		if self.gen_docs {
+			appendf!(self, "    /// Sends `cmd`, and waits for its error or return value.\n");
+			appendf!(self, "    /// `@stream` commands aren't supported, since they return any number of values.\n");
+		}
+		appendf!(self, "    {}async fn call<'x, C: PBCommand + PBCommandExt<'x>>(&mut self, cmd: C) -> io::Result<Result<C::Return<'static>, C::Error<'static>>> {{\n", self.vis());
+		appendf!(self, "        if C::IS_STREAM {{\n");
+		appendf!(self, "            return Err(io::Error::new(io::ErrorKind::Unsupported, \"`@stream` commands can't be called with `Client::call`\"));\n");
+		appendf!(self, "        }}\n");
+		appendf!(self, "        cmd.serialize(&mut self.stream).await?;\n");
+		appendf!(self, "        self.stream.flush().await?;\n");
+		appendf!(self, "        if C::IS_VOID {{\n");
+		appendf!(self, "            // `Void` takes up no bytes\n");
+		appendf!(self, "            return Ok(Ok(cmd.deserialize_return_stream(&mut &[][..]).await?));\n");
+		appendf!(self, "        }}\n");
+		appendf!(self, "        let is_error = match u8::deserialize_stream(&mut self.stream).await? {{\n");
+		appendf!(self, "            0 => true,\n");
+		appendf!(self, "            1 => false,\n");
+		appendf!(self, "            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, \"the response is neither an error nor a return value\")),\n");
+		appendf!(self, "        }};\n");
+		appendf!(self, "        Ok(cmd.deserialize_outcome(&mut self.stream, is_error).await?.into_result())\n");
+		appendf!(self, "    }}\n"); // fn call
+		appendf!(self, "}}\n\n");
+	}
 	fn gen_commands(&mut self) {
 		for cmd in &self.def.commands {
 			if cmd.attrs.contains_key("@rust:ignore") {
 				continue;
 			}
 			self.gen_doc(&cmd.doc, 0);
-			appendf!(self, "#[derive(Debug, Clone)]\n");
+			self.gen_cfg_experimental(&cmd.attrs, 0);
+			let derives = self.derive_list(
+				|| self.is_argument_comparable(&cmd.argument, true),
+				|| self.is_argument_comparable(&cmd.argument, false),
+				false, false
+			);
+			appendf!(self, "#[derive({derives})]\n");
+			appendf!(self, "#[must_use = \"commands must be serialized or dispatched\"]\n");
 			let cmd_needs_lifetime = self.command_needs_lifetime(cmd);
-			appendf!(self, "pub struct {}", self.gen_command_name(cmd));
+			appendf!(self, "{}struct {}", self.vis(), self.gen_command_name(cmd));
 			match &cmd.argument {
 				PBCommandArg::None => {
 					appendf!(self, ";\n")
 				}
 				PBCommandArg::Ref(refr) => {
-					appendf!(self, "(pub {});\n", self.gen_reference(refr, false))
+					appendf!(self, "({}{});\n", self.vis(), self.gen_reference(refr, false))
 				}
 				PBCommandArg::Struct { fields } => {
-					if fields.is_empty() {
+					let unknown = self.keeps_extensions(&cmd.attrs);
+					if fields.is_empty() && !unknown {
 						appendf!(self, ";\n");
 					} else {
 						appendf!(self, " {{\n");
 						self.gen_fields(fields);
+						if unknown {
+							self.gen_unknown_fields(fields);
+						}
 						appendf!(self, "}}\n");
+						if self.has_constructors(fields, unknown) {
+							self.gen_cfg_experimental(&cmd.attrs, 0);
+							self.gen_constructors(&self.gen_command_name(cmd), fields, false, unknown);
+						}
+						if fields.iter().any(|field| field.attrs.contains_key("@lazy_array")) {
+							self.gen_cfg_experimental(&cmd.attrs, 0);
+							let name = self.gen_command_name(cmd);
+							let generics = name.find('<').map_or("", |i| &name[i..]);
+							self.gen_lazy_array_iters(generics, &name, fields);
+						}
 					}
 				}
 			}
+			self.gen_cfg_experimental(&cmd.attrs, 0);
 			appendf!(self, "impl<'x> PBCommandExt<'x> for {} {{\n",
 				self.gen_command_name(cmd)
 			);
@@ -1010,9 +2332,12 @@ impl<'def> RustCodegen<'def> {
 			if cmd.ret.reference == "Void" {
 				appendf!(self, "    const IS_VOID: bool = true;\n");
 			}
+			if cmd.attrs.contains_key("@stream") {
+				appendf!(self, "    const IS_STREAM: bool = true;\n");
+			}
 			if !cmd.attrs.is_empty() {
 				appendf!(self, "    const ATTRIBUTES: &'static [(&'static str, Option<&'static str>)] = &[\n");
-				for (name, value) in &cmd.attrs {
+				for (name, value) in sorted_attrs(&cmd.attrs) {
 					appendf!(self, "        ({name:?}, {value:?}),\n");
 				}
 				appendf!(self, "    ];\n"); // attributes
@@ -1020,32 +2345,41 @@ impl<'def> RustCodegen<'def> {
 			if let Some(Some(cap)) = cmd.attrs.get("@capability") {
 				appendf!(self, "    const REQUIRED_CAPABILITY: Option<&'static str> = Some(&{cap:?});\n");
 			}
+			self.gen_inline();
+			self.gen_metered_attrs();
 			appendf!(self, "    {} deserialize_stream<R: {}>(r: &mut R) -> io::Result<Self> {{\n", self.get_fn(), self.read());
-			match &cmd.argument {
-				PBCommandArg::None => {
-					appendf!(self, "        Ok(Self)\n");
-				},
-				PBCommandArg::Ref(refr) => {
-					appendf!(self, "        Self({}::deserialize_stream(r){}?)\n", self.gen_reference(refr, true), self.maybe_await());
-				},
-				PBCommandArg::Struct { fields } => self.gen_deserialize_fields(fields, !cmd.attrs.contains_key("@sealed"), true),
-			}
-			appendf!(self, "    }}\n"); // fn deserialize_stream
-			if !self.use_tokio {
-				appendf!(self, "    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {{\n");
+			self.gen_metered(cmd, "decode", "let r = &mut punybuf_common::metrics::CountingReader::new(r);", "r.count()", |this| {
 				match &cmd.argument {
 					PBCommandArg::None => {
-						appendf!(self, "        Ok(Self)\n");
+						appendf!(this, "        Ok(Self)\n");
 					},
 					PBCommandArg::Ref(refr) => {
-						appendf!(self, "        Self({}::deserialize(r)?)\n", self.gen_reference(refr, true));
+						appendf!(this, "        Ok(Self({}::deserialize_stream(r){}?))\n", this.gen_reference(refr, true), this.maybe_await());
 					},
-					PBCommandArg::Struct { fields } => self.gen_deserialize_fields(fields, !cmd.attrs.contains_key("@sealed"), false),
+					PBCommandArg::Struct { fields } => this.gen_deserialize_fields(fields, !cmd.attrs.contains_key("@sealed"), true, false),
 				}
+			});
+			appendf!(self, "    }}\n"); // fn deserialize_stream
+			if !self.use_tokio {
+				self.gen_inline();
+				self.gen_metered_attrs();
+				appendf!(self, "    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {{\n");
+				self.gen_metered(cmd, "decode", "let len = r.len();", "(len - r.len())", |this| {
+					match &cmd.argument {
+						PBCommandArg::None => {
+							appendf!(this, "        Ok(Self)\n");
+						},
+						PBCommandArg::Ref(refr) => {
+							appendf!(this, "        Ok(Self({}::deserialize(r)?))\n", this.gen_reference(refr, true));
+						},
+						PBCommandArg::Struct { fields } => this.gen_deserialize_fields(fields, !cmd.attrs.contains_key("@sealed"), false, false),
+					}
+				});
 				appendf!(self, "    }}\n"); // fn deserialize
 			}
 			appendf!(self, "}}\n"); // impl PBCommandExt
 
+			self.gen_cfg_experimental(&cmd.attrs, 0);
 			appendf!(self, "impl{} PBCommand for {} {{\n",
 				self.gen_lifetime_generics_if(cmd_needs_lifetime),
 				self.gen_command_name(cmd)
@@ -1054,6 +2388,9 @@ impl<'def> RustCodegen<'def> {
 			if cmd.ret.reference == "Void" {
 				appendf!(self, "    fn is_void(&self) -> bool {{ true }}\n");
 			}
+			if cmd.attrs.contains_key("@stream") {
+				appendf!(self, "    fn is_stream(&self) -> bool {{ true }}\n");
+			}
 			if !cmd.attrs.is_empty() {
 				appendf!(self, "    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {{ \n");
 				appendf!(self, "        Self::ATTRIBUTES\n");
@@ -1064,27 +2401,42 @@ impl<'def> RustCodegen<'def> {
 				appendf!(self, "        Self::REQUIRED_CAPABILITY\n");
 				appendf!(self, "    }}\n"); // required_capability
 			}
+			self.gen_inline();
+			self.gen_metered_attrs();
 			appendf!(self, "    {} serialize_self<W: {}>(&self, w: &mut W) -> io::Result<()> {{\n", self.get_fn(), self.write());
-			match &cmd.argument {
-				PBCommandArg::None => {},
-				PBCommandArg::Ref(_) => {
-					appendf!(self, "        self.0.serialize(w){}?;\n", self.maybe_await());
-				},
-				PBCommandArg::Struct { fields } => self.gen_serialize_fields(fields, !cmd.attrs.contains_key("@sealed")),
-			}
-			appendf!(self, "        Ok(())\n");
+			self.gen_metered(cmd, "encode", "let w = &mut punybuf_common::metrics::CountingWriter::new(w);", "w.count()", |this| {
+				match &cmd.argument {
+					PBCommandArg::None => {},
+					PBCommandArg::Ref(_) => {
+						appendf!(this, "        self.0.serialize(w){}?;\n", this.maybe_await());
+					},
+					PBCommandArg::Struct { fields } => this.gen_serialize_fields(fields, !cmd.attrs.contains_key("@sealed")),
+				}
+				appendf!(this, "        Ok(())\n");
+			});
 			appendf!(self, "    }}\n"); // serialize_self
 			appendf!(self, "}}\n\n"); // impl PBCommand
 
-			appendf!(self, "#[derive(Debug, Clone)]\n");
-			appendf!(self, "pub enum {} {{\n", self.gen_command_err(cmd));
+			self.gen_cfg_experimental(&cmd.attrs, 0);
+			let derives = self.derive_list(
+				|| self.is_comparable_variants(&cmd.err, true, &mut HashSet::new()),
+				|| self.is_comparable_variants(&cmd.err, false, &mut HashSet::new()),
+				false, false
+			);
+			appendf!(self, "#[derive({derives})]\n");
+			appendf!(self, "{}enum {} {{\n", self.vis(), self.gen_command_err(cmd));
 			// Since we have this, all error enums need a lifetime
 			appendf!(self, "    UnexpectedError(Cow<'x, str>),\n");
 			self.gen_variants(&cmd.err);
 			appendf!(self, "}}\n"); // enum
+			self.gen_cfg_experimental(&cmd.attrs, 0);
 			appendf!(self, "impl<'x> PBType<'x> for {} {{\n",
 				self.gen_command_err(cmd)
 			);
+			// `UnexpectedError` is the discriminant and at least the length of the string
+			let size = self.wire_size_variants(&cmd.err, &HashMap::new(), &mut HashSet::new());
+			appendf!(self, "    const MIN_SIZE: usize = {};\n", size.min.or(MinSize::Bytes(2)).to_rust());
+			self.gen_inline();
 			appendf!(self, "    {} serialize<W: {}>(&self, w: &mut W) -> io::Result<()> {{\n", self.get_fn(), self.write());
 			appendf!(self, "        match self {{\n");
 			appendf!(self, "            Self::UnexpectedError(x) => {{ 0u8.serialize(w){}?; x.serialize(w){}?; }}\n", self.maybe_await(), self.maybe_await());
@@ -1092,6 +2444,7 @@ impl<'def> RustCodegen<'def> {
 			appendf!(self, "        }}\n"); // match
 			appendf!(self, "        Ok(())\n");
 			appendf!(self, "    }}\n"); // fn serialize
+			self.gen_inline();
 			appendf!(self, "    {} deserialize_stream<R: {}>(r: &mut R) -> io::Result<Self> {{\n", self.get_fn(), self.read());
 			appendf!(self, "        let discriminant = u8::deserialize_stream(r){}?;\n", self.maybe_await());
 			appendf!(self, "        Ok(match discriminant {{\n");
@@ -1100,6 +2453,7 @@ impl<'def> RustCodegen<'def> {
 			appendf!(self, "        }})\n"); // match
 			appendf!(self, "    }}\n"); // fn deserialize_stream
 			if !self.use_tokio {
+				self.gen_inline();
 				appendf!(self, "    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {{\n");
 				appendf!(self, "        let discriminant = u8::deserialize(r){}?;\n", self.maybe_await());
 				appendf!(self, "        Ok(match discriminant {{\n");
@@ -1108,12 +2462,82 @@ impl<'def> RustCodegen<'def> {
 				appendf!(self, "        }})\n"); // match
 				appendf!(self, "    }}\n"); // fn deserialize
 			}
-			appendf!(self, "}}\n\n"); // impl PBType
+			appendf!(self, "}}\n"); // impl PBType
+
+			// so that handlers can use `?` on transport errors
+			self.gen_cfg_experimental(&cmd.attrs, 0);
+			appendf!(self, "impl<'x> From<io::Error> for {} {{\n", self.gen_command_err(cmd));
+			appendf!(self, "    fn from(err: io::Error) -> Self {{\n");
+			appendf!(self, "        Self::UnexpectedError(Cow::Owned(err.to_string()))\n");
+			appendf!(self, "    }}\n");
+			appendf!(self, "}}\n\n"); // impl From
+		}
+	}
+	fn gen_reflect(&mut self, tp: &PBTypeDef) {
+		let needs_lifetime = self.needs_lifetime(tp.get_name().0, *tp.get_layer());
+		let mut impl_generics = vec![];
+		if needs_lifetime {
+			impl_generics.push(self.lifetime.to_string());
+		}
+		for g in tp.get_generics().0 {
+			impl_generics.push(format!("{g}: AsFieldValue + core::fmt::Debug + Send + Sync"));
+		}
+		let impl_generics = if impl_generics.is_empty() {
+			String::new()
+		} else {
+			format!("<{}>", impl_generics.join(", "))
+		};
+		let type_name = self.get_type_name(tp);
+
+		let experimental = self.def.is_experimental(tp);
+		self.gen_cfg_experimental_if(experimental, 0);
+		appendf!(self, "impl{impl_generics} PBReflect for {type_name} {{\n");
+		appendf!(self, "    fn type_name(&self) -> &'static str {{ {:?} }}\n", tp.get_name().0);
+		appendf!(self, "    fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)> {{\n");
+		match tp {
+			PBTypeDef::Struct { fields, .. } => {
+				appendf!(self, "        vec![\n");
+				for field in fields {
+					if let Some(flags) = &field.flags {
+						for flag in flags {
+							appendf!(self, "            ({:?}, self.{}.as_field_value()),\n", flag.name, flag.name);
+						}
+					} else {
+						appendf!(self, "            ({:?}, self.{}.as_field_value()),\n", field.name, field.name);
+					}
+				}
+				appendf!(self, "        ]\n");
+			}
+			PBTypeDef::Enum { variants, .. } if variants.is_empty() => {
+				appendf!(self, "        match *self {{}}\n");
+			}
+			PBTypeDef::Enum { variants, .. } => {
+				appendf!(self, "        match self {{\n");
+				for variant in variants {
+					if variant.value.is_some() {
+						appendf!(self, "            Self::{}(v) => vec![({:?}, v.as_field_value())],\n", variant.name, variant.name);
+					} else {
+						appendf!(self, "            Self::{} => vec![({:?}, FieldValue::Unit)],\n", variant.name, variant.name);
+					}
+				}
+				if self.keeps_unknown_variants(variants) {
+					appendf!(self, "            Self::UnknownVariant(_, bytes) => vec![(\"UnknownVariant\", bytes.as_field_value())],\n");
+				}
+				appendf!(self, "        }}\n");
+			}
+			PBTypeDef::Alias { .. } => unreachable!()
 		}
+		appendf!(self, "    }}\n"); // fn fields
+		appendf!(self, "}}\n"); // impl PBReflect
+		self.gen_cfg_experimental_if(experimental, 0);
+		appendf!(self, "impl{impl_generics} AsFieldValue for {type_name} {{\n");
+		appendf!(self, "    fn as_field_value(&self) -> FieldValue<'_> {{ FieldValue::Reflect(self) }}\n");
+		appendf!(self, "}}\n\n"); // impl AsFieldValue
 	}
 	fn gen_types(&mut self) {
 		let mut should_include_hash_map_convertible = false;
 		for tp in &self.def.types {
+			let experimental = self.def.is_experimental(tp);
 			if
 				tp.get_attrs().contains_key("@builtin") ||
 				tp.get_attrs().contains_key("@rust:ignore") ||
@@ -1121,15 +2545,17 @@ impl<'def> RustCodegen<'def> {
 			{
 				if let Some(Some(qualified)) = tp.get_attrs().get("@rust:use") {
 					// treat as alias
-					appendf!(self, "pub type {} = {};\n", self.get_type_name(tp), qualified);
+					self.gen_cfg_experimental_if(experimental, 0);
+					appendf!(self, "{}type {} = {};\n", self.vis(), self.get_type_name(tp), qualified);
 					continue;
 				}
 				continue;
 			}
 			if tp.get_attrs().contains_key("@map_convertible") {
+				self.gen_cfg_experimental_if(experimental, 0);
 				appendf!(
 					self,
-					"impl<'x, K: PBType<'x> + std::hash::Hash + Eq, V: PBType<'x>> \
+					"impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> \
 					HashMapConvertible<K, V> for {} {{",
 					self.get_type_name(tp)
 				);
@@ -1141,29 +2567,72 @@ impl<'def> RustCodegen<'def> {
 			match tp {
 				PBTypeDef::Alias { alias, doc, .. } => {
 					self.gen_doc(doc, 0);
-					appendf!(self, "pub type {} = {};\n", self.get_type_name(tp), self.gen_reference(alias, false));
+					self.gen_wire_size_doc(tp);
+					self.gen_cfg_experimental_if(experimental, 0);
+					appendf!(self, "{}type {} = {};\n", self.vis(), self.get_type_name(tp), self.gen_reference(alias, false));
 					// impls for aliases are generated automatically
 					continue;
 				}
-				PBTypeDef::Struct { fields, doc, .. } => {
+				PBTypeDef::Struct { fields, doc, attrs, .. } => {
 					self.gen_doc(doc, 0);
-					appendf!(self, "#[derive(Debug, Clone)]\n");
-					appendf!(self, "pub struct {} {{\n", self.get_type_name(tp));
-					self.gen_fields(fields);
-					appendf!(self, "}}\n");
+					self.gen_wire_size_doc(tp);
+					self.gen_cfg_experimental_if(experimental, 0);
+					self.gen_derives(tp);
+					let phantom = Self::phantom_params(tp);
+					let unknown = self.keeps_extensions(attrs);
+					if fields.is_empty() && phantom.is_empty() && !unknown {
+						// `Self {}` still works for unit structs
+						appendf!(self, "{}struct {};\n", self.vis(), self.get_type_name(tp));
+					} else {
+						appendf!(self, "{}struct {} {{\n", self.vis(), self.get_type_name(tp));
+						self.gen_fields(fields);
+						if !phantom.is_empty() {
+							self.gen_phantom_field(&phantom);
+						}
+						if unknown {
+							self.gen_unknown_fields(fields);
+						}
+						appendf!(self, "}}\n");
+						if self.has_constructors(fields, !phantom.is_empty() || unknown) {
+							self.gen_cfg_experimental_if(experimental, 0);
+							self.gen_constructors(&self.get_type_name(tp), fields, !phantom.is_empty(), unknown);
+						}
+						if fields.iter().any(|field| field.attrs.contains_key("@lazy_array")) {
+							self.gen_cfg_experimental_if(experimental, 0);
+							let name = self.get_type_name(tp);
+							let generics = if tp.get_generics().0.is_empty() {
+								name.find('<').map_or(String::new(), |i| name[i..].to_string())
+							} else {
+								// the items have to be `PBType`s to be decoded
+								self.get_type_impl_generics(tp)
+							};
+							self.gen_lazy_array_iters(&generics, &name, fields);
+						}
+					}
 				}
 				PBTypeDef::Enum { variants, doc, .. } => {
 					self.gen_doc(doc, 0);
-					appendf!(self, "#[derive(Debug, Clone)]\n");
-					appendf!(self, "pub enum {} {{\n", self.get_type_name(tp));
+					self.gen_wire_size_doc(tp);
+					self.gen_cfg_experimental_if(experimental, 0);
+					self.gen_derives(tp);
+					appendf!(self, "{}enum {} {{\n", self.vis(), self.get_type_name(tp));
 					self.gen_variants(variants);
 					appendf!(self, "}}\n");
 				}
 			}
+			self.gen_cfg_experimental_if(experimental, 0);
 			appendf!(self, "impl{} PBType<'x> for {} {{\n", self.get_type_impl_generics(tp), self.get_type_name(tp));
+			appendf!(self, "    const TYPE_NAME: &'static str = {:?};\n", tp.get_name().0);
+			let size = self.wire_size(tp);
+			appendf!(self, "    const MAX_SIZE: Option<usize> = {:?};\n", size.max);
+			appendf!(self, "    const MIN_SIZE: usize = {};\n", size.min.to_rust());
+			if let (true, MinSize::Bytes(fixed)) = (size.fixed, &size.min) {
+				// what newer versions of the type send has to be this size too, see `WireSize::fixed`
+				appendf!(self, "    const FIXED_SIZE: Option<usize> = Some({fixed});\n");
+			}
 			if !tp.get_attrs().is_empty() {
 				appendf!(self, "    fn attributes() -> &'static [(&'static str, Option<&'static str>)] {{ &[\n");
-				for (name, value) in tp.get_attrs() {
+				for (name, value) in sorted_attrs(tp.get_attrs()) {
 					appendf!(self, "        ({name:?}, {value:?}),\n");
 				}
 				appendf!(self, "    ] }}\n"); // fn attributes
@@ -1181,12 +2650,18 @@ impl<'def> RustCodegen<'def> {
 				appendf!(self, "    // that's because when using async, currently\n");
 				appendf!(self, "    // no cyclic types are supported at all. Sorry!\n");
 			}
+			self.gen_inline();
 			appendf!(self, "    {} serialize<W: {}>(&self, w: &mut W) -> io::Result<()> {{\n", self.get_fn(), self.write());
 			match tp {
 				PBTypeDef::Struct { fields, attrs, .. } => {
 					self.gen_serialize_fields(fields, !attrs.contains_key("@sealed"));
 					appendf!(self, "        Ok(())\n");
 				}
+				PBTypeDef::Enum { .. } if let Some(variant) = self.flattened_variant(tp) => {
+					// the discriminant is left out
+					appendf!(self, "        let Self::{}(value) = self;\n", variant.name);
+					appendf!(self, "        value.serialize(w){}\n", self.maybe_await());
+				}
 				PBTypeDef::Enum { variants, .. } => {
 					appendf!(self, "        match self {{\n");
 					self.gen_serialize_variants(variants);
@@ -1202,10 +2677,14 @@ impl<'def> RustCodegen<'def> {
 				appendf!(self, "    // that's because when using async, currently\n");
 				appendf!(self, "    // no cyclic types are supported at all. Sorry!\n");
 			}
+			self.gen_inline();
 			appendf!(self, "    {} deserialize_stream<R: {}>(r: &mut R) -> io::Result<Self> {{\n", self.get_fn(), self.read());
 			match tp {
 				PBTypeDef::Struct { fields, attrs, .. } => {
-					self.gen_deserialize_fields(fields, !attrs.contains_key("@sealed"), true);
+					self.gen_deserialize_fields(fields, !attrs.contains_key("@sealed"), true, attrs.contains_key("@phantom"));
+				}
+				PBTypeDef::Enum { .. } if let Some(variant) = self.flattened_variant(tp) => {
+					self.gen_deserialize_flattened(variant, true);
 				}
 				PBTypeDef::Enum { variants, .. } => {
 					appendf!(self, "        let discriminant = u8::deserialize_stream(r){}?;\n", self.maybe_await());
@@ -1217,10 +2696,14 @@ impl<'def> RustCodegen<'def> {
 			}
 			appendf!(self, "    }}\n"); // fn deserialize_stream
 			if !self.use_tokio {
+				self.gen_inline();
 				appendf!(self, "    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {{\n");
 				match tp {
 					PBTypeDef::Struct { fields, attrs, .. } => {
-						self.gen_deserialize_fields(fields, !attrs.contains_key("@sealed"), false);
+						self.gen_deserialize_fields(fields, !attrs.contains_key("@sealed"), false, attrs.contains_key("@phantom"));
+					}
+					PBTypeDef::Enum { .. } if let Some(variant) = self.flattened_variant(tp) => {
+						self.gen_deserialize_flattened(variant, false);
 					}
 					PBTypeDef::Enum { variants, .. } => {
 						appendf!(self, "        let discriminant = u8::deserialize(r)?;\n");
@@ -1231,50 +2714,246 @@ impl<'def> RustCodegen<'def> {
 					_ => unreachable!()
 				}
 				appendf!(self, "    }}\n"); // fn deserialize
+				// enums are simply overwritten by the default implementation
+				if let PBTypeDef::Struct { fields, attrs, .. } = tp {
+					self.gen_inline();
+					appendf!(self, "    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {{\n");
+					self.gen_deserialize_into_fields(fields, !attrs.contains_key("@sealed"));
+					appendf!(self, "    }}\n"); // fn deserialize_into
+				}
 			}
 			appendf!(self, "}}\n\n"); // impl PBType
+			if self.reflect {
+				self.gen_reflect(tp);
+			}
 		}
 		if should_include_hash_map_convertible {
 			// HACK: Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
-			appendf!(self, "{}", HASH_MAP_CONVERTIBLE);
+			appendf!(self, "{}", HASH_MAP_CONVERTIBLE.replace("pub ", self.vis()));
 			appendf!(self, "\n\n");
 		}
 	}
-	pub fn codegen(mut self) -> String {
-		if self.use_tokio {
-			eprintln!(
-				"{YELLOW}{BOLD}warning:{NORMAL} rust tokio builds are currently broken \
-				for several reasons. see https://github.com/rust-lang/rust/issues/100013 \
-				and https://github.com/rust-lang/rust/issues/135062. \
-				the errors in the generated code will maybe be fixed after those issues \
-				are resolved. sorry!"
-			);
-			eprintln!(
-				"{YELLOW}{BOLD}warning:{NORMAL} if you aren't using any commands \
-				or recursive types, this should probably be fine."
-			);
+	/// Reads the definition-wide settings. Called by [`Self::codegen`], but other
+	/// codegens that reuse the naming helpers have to call it themselves.
+	pub(super) fn prepare(&mut self) {
+		self.uses_common = self.def.includes_common;
+		self.strict_strings = self.def.attrs.contains_key("@strict_strings");
+	}
+	fn warn_tokio(&self) {
+		eprintln!(
+			"{YELLOW}{BOLD}warning:{NORMAL} rust tokio builds are currently broken \
+			for several reasons. see https://github.com/rust-lang/rust/issues/100013 \
+			and https://github.com/rust-lang/rust/issues/135062. \
+			the errors in the generated code will maybe be fixed after those issues \
+			are resolved. sorry!"
+		);
+		eprintln!(
+			"{YELLOW}{BOLD}warning:{NORMAL} if you aren't using any commands \
+			or recursive types, this should probably be fine."
+		);
+	}
+	/// The fields of a struct type that can get conversions between its layers
+	fn layered_fields<'a>(&self, tp: &'a PBTypeDef) -> Option<&'a Vec<PBField>> {
+		let attrs = tp.get_attrs();
+		if attrs.contains_key("@builtin") || attrs.contains_key("@rust:ignore") || attrs.contains_key("@resolve") {
+			return None;
+		}
+		// the unknown flags of the old layer may be known ones in the new layer,
+		// with their values somewhere in the unknown extensions
+		if self.keeps_extensions(attrs) {
+			return None;
+		}
+		match tp {
+			PBTypeDef::Struct { fields, .. } if tp.get_generics().0.is_empty() => Some(fields),
+			_ => None,
+		}
+	}
+	/// The layer of the type `name` that comes right before `layer`
+	fn previous_type_layer(&self, name: &str, layer: u32) -> Option<&'def PBTypeDef> {
+		self.def.types.iter()
+			.filter(|tp| tp.get_name().0 == name && *tp.get_layer() < layer)
+			.max_by_key(|tp| *tp.get_layer())
+	}
+	/// How a value of `old` is converted into `new`, if it can be. `visiting` are the
+	/// types whose conversions are being checked, so that recursive types don't loop forever
+	fn layer_step(&self, old: &PBTypeRef, new: &PBTypeRef, visiting: &mut Vec<(String, u32)>) -> Option<LayerStep> {
+		if same_type(old, new) {
+			return Some(LayerStep::Move);
+		}
+		let (Some(old_layer), Some(new_layer)) = (old.resolved_layer, new.resolved_layer) else {
+			return None;
+		};
+		if
+			old.reference != new.reference || !old.is_global || !new.is_global ||
+			!old.generics.is_empty() || !new.generics.is_empty() ||
+			visiting.contains(&(new.reference.clone(), new_layer))
+		{
+			return None;
+		}
+		let old_tp = self.previous_type_layer(&new.reference, new_layer).filter(|tp| *tp.get_layer() == old_layer)?;
+		let new_tp = self.def.types.iter().find(|tp| tp.get_name().0 == new.reference && *tp.get_layer() == new_layer)?;
+		let (old_fields, new_fields) = (self.layered_fields(old_tp)?, self.layered_fields(new_tp)?);
+
+		visiting.push((new.reference.clone(), new_layer));
+		let conversion = self.layer_conversion(&new.reference, new_layer, old_fields, new_fields, visiting);
+		visiting.pop();
+		conversion.map(|(_, step)| step)
+	}
+	/// The struct literal fields converting `old_fields` into `new_fields`, the next layer of `name`,
+	/// and whether that can fail. `None` if the new layer isn't the old one with more flags
+	fn layer_conversion(
+		&self, name: &str, layer: u32,
+		old_fields: &Vec<PBField>, new_fields: &Vec<PBField>,
+		visiting: &mut Vec<(String, u32)>
+	) -> Option<(Vec<(String, String)>, LayerStep)> {
+		let old_members = members(old_fields);
+		let new_members = members(new_fields);
+		// nothing can be dropped
+		if old_members.iter().any(|(old_name, _)| !new_members.iter().any(|(new_name, _)| new_name == old_name)) {
+			return None;
+		}
+		let lazy = |fields: &Vec<PBField>, member: &str| fields.iter().any(|field| field.name == member && field.attrs.contains_key("@lazy_array"));
+		let missing = |member: &str| format!(
+			"ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, \"`{member}` is required by layer {layer} of `{name}`\"))?"
+		);
+
+		let mut result = vec![];
+		let mut fallible = false;
+		for (member, new_member) in &new_members {
+			let old_member = old_members.iter().find(|(old_name, _)| old_name == member).map(|m| &m.1);
+			let step = match (old_member, new_member) {
+				(Some(old_member), _) if same_member(old_member, new_member) => LayerStep::Move,
+				(Some(Member::Field(old)) | Some(Member::Flag(Some(old))), Member::Field(new) | Member::Flag(Some(new))) => {
+					self.layer_step(old, new, visiting)?
+				}
+				(None, Member::Flag(_)) => LayerStep::Move,
+				_ => return None,
+			};
+			if step != LayerStep::Move && (lazy(old_fields, member) || lazy(new_fields, member)) {
+				return None;
+			}
+			fallible |= step == LayerStep::TryInto;
+			let value = match (old_member, new_member, step) {
+				(None, Member::Flag(None), _) => "false".to_string(),
+				(None, _, _) => "None".to_string(),
+				(Some(_), _, LayerStep::Move) if matches!((old_member, new_member), (Some(Member::Flag(_)), Member::Field(_))) => {
+					fallible = true;
+					format!("old.{member}.{}", missing(member))
+				}
+				(Some(Member::Flag(_)), Member::Field(_), _) => {
+					fallible = true;
+					let convert = if step == LayerStep::Into { "into()" } else { "try_into()?" };
+					format!("old.{member}.{}.{convert}", missing(member))
+				}
+				(Some(_), _, LayerStep::Move) => format!("old.{member}"),
+				(Some(Member::Flag(_)), _, LayerStep::Into) => format!("old.{member}.map(Into::into)"),
+				(Some(Member::Flag(_)), _, LayerStep::TryInto) => format!("old.{member}.map(TryInto::try_into).transpose()?"),
+				(Some(_), _, LayerStep::Into) => format!("old.{member}.into()"),
+				(Some(_), _, LayerStep::TryInto) => format!("old.{member}.try_into()?"),
+			};
+			result.push((member.to_string(), value));
+		}
+		Some((result, if fallible { LayerStep::TryInto } else { LayerStep::Into }))
+	}
+	/// `impl From<{old_name}> for {new_name}`, or `TryFrom`
+	fn gen_layer_conversion(&mut self, old_name: &str, new_name: &str, fields: Vec<(String, String)>, step: LayerStep) {
+		let generics = if old_name.contains("'x") || new_name.contains("'x") { "<'x>" } else { "" };
+		let old = if fields.iter().any(|(_, value)| value.starts_with("old.")) { "old" } else { "_old" };
+		if step == LayerStep::TryInto {
+			appendf!(self, "impl{generics} TryFrom<{old_name}> for {new_name} {{\n");
+			appendf!(self, "    type Error = io::Error;\n");
+			appendf!(self, "    fn try_from({old}: {old_name}) -> io::Result<Self> {{\n");
+			appendf!(self, "        Ok(Self {{\n");
+		} else {
+			appendf!(self, "impl{generics} From<{old_name}> for {new_name} {{\n");
+			appendf!(self, "    fn from({old}: {old_name}) -> Self {{\n");
+			appendf!(self, "        Self {{\n");
+		}
+		for (member, value) in fields {
+			appendf!(self, "            {member}: {value},\n");
+		}
+		appendf!(self, "        }}{}\n", if step == LayerStep::TryInto { ")" } else { "" });
+		appendf!(self, "    }}\n");
+		appendf!(self, "}}\n\n");
+	}
+	/// Conversions of structs and commands from their previous layer, see [`Self::layer_conversions`]
+	fn gen_layer_conversions(&mut self) {
+		for new in &self.def.types {
+			let Some(new_fields) = self.layered_fields(new) else {
+				continue;
+			};
+			let Some(old) = self.previous_type_layer(new.get_name().0, *new.get_layer()) else {
+				continue;
+			};
+			let Some(old_fields) = self.layered_fields(old) else {
+				continue;
+			};
+			let conversion = self.layer_conversion(new.get_name().0, *new.get_layer(), old_fields, new_fields, &mut vec![]);
+			if let Some((fields, step)) = conversion {
+				self.gen_cfg_experimental_if(self.def.is_experimental(old) || self.def.is_experimental(new), 0);
+				self.gen_layer_conversion(&self.get_type_name(old), &self.get_type_name(new), fields, step);
+			}
+		}
+		for new in &self.def.commands {
+			let PBCommandArg::Struct { fields: new_fields } = &new.argument else {
+				continue;
+			};
+			let old = self.def.commands.iter()
+				.filter(|cmd| cmd.name == new.name && cmd.layer < new.layer)
+				.max_by_key(|cmd| cmd.layer);
+			let Some(old) = old else {
+				continue;
+			};
+			let PBCommandArg::Struct { fields: old_fields } = &old.argument else {
+				continue;
+			};
+			if old.attrs.contains_key("@rust:ignore") || new.attrs.contains_key("@rust:ignore") {
+				continue;
+			}
+			if self.keeps_extensions(&old.attrs) || self.keeps_extensions(&new.attrs) {
+				continue;
+			}
+			let conversion = self.layer_conversion(&new.name, new.layer, old_fields, new_fields, &mut vec![]);
+			if let Some((fields, step)) = conversion {
+				let experimental = old.attrs.contains_key("@experimental") || new.attrs.contains_key("@experimental");
+				self.gen_cfg_experimental_if(experimental, 0);
+				self.gen_layer_conversion(&self.gen_command_name(old), &self.gen_command_name(new), fields, step);
+			}
 		}
+	}
+	fn gen_file(&mut self) {
 		appendf!(self, "#![allow(nonstandard_style)]\n");
 		appendf!(self, "///! This file was automatically generated by Punybuf.\n");
 		appendf!(self, "///! It's best you don't change anything.\n\n");
-		appendf!(self, "use std::io;\n");
+		// only `core` and `alloc`, so the code can be used without `std` too
+		appendf!(self, "extern crate alloc;\n\n");
+		appendf!(self, "#[allow(unused_imports)]\n");
+		appendf!(self, "use alloc::{{boxed::Box, string::ToString, vec, vec::Vec}};\n");
+		appendf!(self, "use punybuf_common::io;\n");
 
 		if self.use_tokio {
 			appendf!(self, "// if you get an error: tokio's \"io\" feature must be enabled.\n");
 			appendf!(self, "use tokio::io::{{AsyncReadExt, AsyncWriteExt}};\n");
 		}
 
-		self.uses_common = self.def.includes_common;
+		self.prepare();
 
-		if self.def.includes_common {
-			if self.use_tokio {
-				appendf!(self, "// if you get an error: punybuf_common's \"tokio\" feature must be enabled.\n");
-			}
-			appendf!(self, "use punybuf_common{}::*;\n", if	self.use_tokio { "::tokio" } else { "" })
+		// even without `include common`, the traits and `Void` come from here.
+		// The types declared in the file take precedence over the ones imported
+		if self.use_tokio {
+			appendf!(self, "// if you get an error: punybuf_common's \"tokio\" feature must be enabled.\n");
 		}
+		appendf!(self, "use punybuf_common{}::*;\n", if	self.use_tokio { "::tokio" } else { "" });
 
 		appendf!(self, "\n");
 
+		if self.gen_docs {
+			appendf!(self, "/// The highest layer of the definition, i.e. the newest version of the protocol this code supports\n");
+		}
+		appendf!(self, "{}const HIGHEST_LAYER: u32 = {};\n\n", self.vis(), self.def.highest_layer());
+
+		self.gen_name_tables();
+
 		if !self.def.commands.is_empty() {
 			self.gen_command_enums();
 		}
@@ -1283,10 +2962,42 @@ impl<'def> RustCodegen<'def> {
 			self.gen_commands();
 		}
 
+		if self.use_tokio && !self.def.commands.is_empty() {
+			self.gen_client();
+		}
+
 		if !self.def.types.is_empty() {
 			self.gen_types();
 		}
 
+		if self.layer_conversions {
+			self.gen_layer_conversions();
+		}
+	}
+	pub fn codegen(mut self) -> String {
+		if self.use_tokio {
+			self.warn_tokio();
+		}
+		self.gen_file();
 		self.buffer
 	}
+	/// Generates both the synchronous and the tokio code, in the modules
+	/// `sync` and `r#async` respectively, ignoring `use_tokio`
+	pub fn codegen_both(mut self) -> String {
+		self.warn_tokio();
+		let mut result = String::new();
+		result.push_str("///! This file was automatically generated by Punybuf.\n");
+		result.push_str("///! It contains both the synchronous and the tokio code.\n\n");
+		let module_vis = self.vis();
+		self.in_module = true;
+		for (module, use_tokio) in [("sync", false), ("r#async", true)] {
+			self.use_tokio = use_tokio;
+			self.buffer = String::new();
+			self.gen_file();
+			result.push_str(&format!("{module_vis}mod {module} {{\n"));
+			result.push_str(&self.buffer);
+			result.push_str(&format!("}} // mod {module}\n\n"));
+		}
+		result
+	}
 }
\ No newline at end of file
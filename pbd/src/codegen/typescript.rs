@@ -0,0 +1,638 @@
+use crate::flattener::{PBCommandArg, PBCommandDef, PBEnumVariant, PBField, PBTypeDef, PBTypeRef, PunybufDefinition};
+
+/// The classes and codecs the generated code is built on, copied into every file
+const RUNTIME: &str = include_str!("../../baked/punybuf.ts");
+
+/// Generates TypeScript interfaces for structs, discriminated unions for enums,
+/// and a `PbCodec` (de)serializing each of them, in one self-contained file.
+pub struct TypeScriptCodegen<'def> {
+	def: &'def PunybufDefinition,
+	gen_docs: bool,
+	buffer: String,
+}
+
+macro_rules! appendf {
+	($s:ident, $x:literal, $($rpt:expr),*) => {
+		$s.buffer.push_str(&format!($x, $($rpt),*))
+	};
+	($s:ident, $x:literal) => {
+		$s.buffer.push_str(&format!($x))
+	};
+}
+
+/// The methods of `PbWriter` and `PbReader` for a flag field of this type,
+/// whose flags are kept in a `bigint`
+fn flags_accessors(name: &str) -> Option<(&'static str, &'static str)> {
+	Some(match name {
+		"U8" => ("w.u8(Number({}))", "BigInt(r.u8())"),
+		"U16" => ("w.u16(Number({}))", "BigInt(r.u16())"),
+		"U32" => ("w.u32(Number({}))", "BigInt(r.u32())"),
+		"U64" => ("w.u64({})", "r.u64()"),
+		"UInt" => ("w.uint({})", "r.uintBig()"),
+		_ => return None,
+	})
+}
+
+impl<'def> TypeScriptCodegen<'def> {
+	pub fn new(def: &'def PunybufDefinition, gen_docs: bool) -> Self {
+		Self { def, gen_docs, buffer: String::new() }
+	}
+	fn find_type(&self, refr: &PBTypeRef) -> &'def PBTypeDef {
+		let layer = refr.resolved_layer.expect("bad state: layer not resolved");
+		self.def.types.iter().find(|t| {
+			t.get_name().0 == refr.reference && *t.get_layer() == layer
+		}).expect("bad state: unknown type referenced")
+	}
+	fn type_name(tp: &PBTypeDef) -> String {
+		if tp.is_highest_layer() {
+			tp.get_name().0.to_string()
+		} else {
+			format!("{}Layer{}", tp.get_name().0, tp.get_layer())
+		}
+	}
+	fn command_name(cmd: &PBCommandDef) -> String {
+		if cmd.is_highest_layer {
+			cmd.name.clone()
+		} else {
+			format!("{}Layer{}", cmd.name, cmd.layer)
+		}
+	}
+	/// `<A, B>` for generic types, or nothing
+	fn generics_of(params: &[String]) -> String {
+		if params.is_empty() {
+			String::new()
+		} else {
+			format!("<{}>", params.join(", "))
+		}
+	}
+	/// The `@builtin` type `refr` points to, if it does
+	fn builtin_name(&self, refr: &PBTypeRef) -> Option<&'def str> {
+		if refr.reference == "Void" {
+			// `Void` isn't resolved to a layer
+			return Some("Void");
+		}
+		if !refr.is_global {
+			return None;
+		}
+		let tp = self.find_type(refr);
+		tp.get_attrs().contains_key("@builtin").then(|| tp.get_name().0)
+	}
+	/// Whether `undefined` is one of the values of `refr`, or `None` if it depends on a generic parameter
+	fn has_undefined(&self, refr: &PBTypeRef) -> Option<bool> {
+		if !refr.is_global {
+			return None;
+		}
+		if let Some(builtin) = self.builtin_name(refr) {
+			return Some(builtin == "Optional");
+		}
+		match self.find_type(refr) {
+			PBTypeDef::Alias { alias, generic_params, .. } => {
+				let bindings = generic_params.iter().map(String::as_str).zip(refr.generics.iter().cloned()).collect();
+				self.has_undefined(&alias.substitute(&bindings).ok()?)
+			}
+			_ => Some(false),
+		}
+	}
+	/// The TypeScript type of the values of `refr` that are there, see `PbSome`
+	fn gen_some_type(&self, refr: &PBTypeRef) -> Result<String, String> {
+		let tp = self.gen_type(refr)?;
+		Ok(if self.has_undefined(refr) == Some(false) { tp } else { format!("PbSome<{tp}>") })
+	}
+	/// The `PbCodec` of the values of `refr` that are there, see `pbSome`
+	fn gen_some_codec(&self, refr: &PBTypeRef) -> Result<String, String> {
+		let codec = self.gen_codec(refr)?;
+		Ok(if self.has_undefined(refr) == Some(false) { codec } else { format!("pbSome({codec})") })
+	}
+	/// The TypeScript type of the values of `refr`
+	fn gen_type(&self, refr: &PBTypeRef) -> Result<String, String> {
+		if !refr.is_global {
+			return Ok(refr.reference.clone());
+		}
+		if let Some(builtin) = self.builtin_name(refr) {
+			return Ok(match builtin {
+				"U8" | "U16" | "U32" | "I32" | "F32" | "F64" | "UInt" | "SInt" => "number".to_string(),
+				"U64" | "I64" => "bigint".to_string(),
+				"Boolean" => "boolean".to_string(),
+				"Bytes" => "Uint8Array".to_string(),
+				"String" => "string".to_string(),
+				"Void" => "null".to_string(),
+				"DateTime" => "PbDateTime".to_string(),
+				"Array" => format!("{}[]", self.gen_type(&refr.generics[0])?),
+				"Optional" => match self.has_undefined(&refr.generics[0]) {
+					Some(false) => format!("{} | undefined", self.gen_type(&refr.generics[0])?),
+					_ => format!("PbOptional<{}>", self.gen_type(&refr.generics[0])?),
+				},
+				other => return Err(format!("the `@builtin` type `{other}` isn't supported")),
+			});
+		}
+		let mut result = Self::type_name(self.find_type(refr));
+		if !refr.generics.is_empty() {
+			let generics = refr.generics.iter().map(|g| self.gen_type(g)).collect::<Result<Vec<_>, _>>()?;
+			result.push_str(&format!("<{}>", generics.join(", ")));
+		}
+		Ok(result)
+	}
+	/// An expression evaluating to the `PbCodec` of `refr`
+	fn gen_codec(&self, refr: &PBTypeRef) -> Result<String, String> {
+		if !refr.is_global {
+			// generic types take the codecs of their parameters as arguments with the same names
+			return Ok(refr.reference.clone());
+		}
+		if let Some(builtin) = self.builtin_name(refr) {
+			return Ok(match builtin {
+				"U8" | "U16" | "U32" | "U64" | "I32" | "I64" | "F32" | "F64" | "UInt" | "SInt" | "Boolean"
+				| "Bytes" | "String" | "Void" | "DateTime" => format!("pb{builtin}"),
+				"Array" => format!("pbArray({})", self.gen_codec(&refr.generics[0])?),
+				"Optional" => format!("pbOptional({})", self.gen_codec(&refr.generics[0])?),
+				other => return Err(format!("the `@builtin` type `{other}` isn't supported")),
+			});
+		}
+		let mut result = Self::type_name(self.find_type(refr));
+		if !refr.generics.is_empty() {
+			let generics = refr.generics.iter().map(|g| self.gen_codec(g)).collect::<Result<Vec<_>, _>>()?;
+			result.push_str(&format!("({})", generics.join(", ")));
+		}
+		Ok(result)
+	}
+	/// The accessors of the flag field `field`, following aliases to the type of the flags
+	fn flags_accessors(&self, field: &PBField) -> Result<(&'static str, &'static str), String> {
+		let mut refr = &field.value;
+		loop {
+			if let Some(builtin) = self.builtin_name(refr) {
+				return flags_accessors(builtin)
+					.ok_or(format!("flag fields of type `{builtin}` aren't supported, in `{}`", field.name));
+			}
+			match self.find_type(refr) {
+				PBTypeDef::Alias { alias, .. } if refr.is_global => refr = alias,
+				_ => return Err(format!("the flag field `{}` isn't a number", field.name)),
+			}
+		}
+	}
+	fn gen_doc(&mut self, doc: &str, indent: usize) {
+		if !self.gen_docs || doc.is_empty() {
+			return;
+		}
+		let indent = "\t".repeat(indent);
+		appendf!(self, "{indent}/**\n");
+		for line in doc.lines() {
+			// `*/` would end the comment early
+			appendf!(self, "{indent} * {}\n", line.replace("*/", "*\\/"));
+		}
+		appendf!(self, "{indent} */\n");
+	}
+	fn gen_fields(&mut self, fields: &[PBField]) -> Result<(), String> {
+		for field in fields {
+			if let Some(flags) = &field.flags {
+				for flag in flags {
+					self.gen_doc(&flag.doc, 1);
+					match &flag.value {
+						Some(value) => appendf!(self, "\t{}?: {};\n", flag.name, self.gen_some_type(value)?),
+						None => appendf!(self, "\t{}: boolean;\n", flag.name),
+					}
+				}
+			} else {
+				self.gen_doc(&field.doc, 1);
+				appendf!(self, "\t{}: {};\n", field.name, self.gen_type(&field.value)?);
+			}
+		}
+		Ok(())
+	}
+	/// Collects the flags of `field`, numbered `index`, into the local `flags{index}`, and writes them into `writer`
+	fn gen_serialize_flags(&mut self, field: &PBField, index: usize, writer: &str, indent: &str) -> Result<(), String> {
+		let flags = field.flags.as_ref().expect("bad state: not a flag field");
+		let (write, _) = self.flags_accessors(field)?;
+		appendf!(self, "{indent}let flags{index} = 0n;\n");
+		for (i, flag) in flags.iter().enumerate() {
+			let condition = match flag.value {
+				Some(_) => format!("value.{} !== undefined", flag.name),
+				None => format!("value.{}", flag.name),
+			};
+			appendf!(self, "{indent}if ({condition}) flags{index} |= {}n;\n", 1u64 << i);
+		}
+		appendf!(self, "{indent}{};\n", write.replacen("w.", &format!("{writer}."), 1).replace("{}", &format!("flags{index}")));
+		Ok(())
+	}
+	/// Serializes the value of `flag`, if it has one and is set
+	fn gen_serialize_flag_value(&mut self, name: &str, value: &PBTypeRef, writer: &str, indent: &str) -> Result<(), String> {
+		appendf!(self, "{indent}if (value.{name} !== undefined) {}.serialize({writer}, value.{name});\n", self.gen_some_codec(value)?);
+		Ok(())
+	}
+	fn gen_serialize_fields(&mut self, fields: &[PBField], extensible: bool, indent: &str) -> Result<(), String> {
+		let mut has_extensions = false;
+		let mut extension_flags = None;
+		for (index, field) in fields.iter().enumerate() {
+			if field.attrs.contains_key("@compress") {
+				return Err(format!("`@compress` isn't supported, on `{}`", field.name));
+			}
+			if field.attrs.contains_key("@extension_flags") {
+				extension_flags = Some((index, field));
+				continue;
+			}
+			if field.attrs.contains_key("@le") {
+				let method = self.builtin_name(&field.value).expect("validator error: `@le` on a type that isn't builtin").to_lowercase();
+				appendf!(self, "{indent}w.{method}(value.{}, true);\n", field.name);
+				continue;
+			}
+			let Some(flags) = &field.flags else {
+				appendf!(self, "{indent}{}.serialize(w, value.{});\n", self.gen_codec(&field.value)?, field.name);
+				continue;
+			};
+			self.gen_serialize_flags(field, index, "w", indent)?;
+			for flag in flags {
+				let Some(value) = &flag.value else { continue };
+				if flag.attrs.contains_key("@extension") {
+					has_extensions = true;
+					continue;
+				}
+				self.gen_serialize_flag_value(&flag.name, value, "w", indent)?;
+			}
+		}
+		if !extensible {
+			return Ok(());
+		}
+		if !has_extensions && extension_flags.is_none() {
+			appendf!(self, "{indent}w.uint(0);\n");
+			return Ok(());
+		}
+		// everything after the length of the extensions is written into `ext` first
+		appendf!(self, "{indent}const ext = new PbWriter();\n");
+		for field in fields {
+			let Some(flags) = &field.flags else { continue };
+			if field.attrs.contains_key("@extension_flags") {
+				continue;
+			}
+			for flag in flags {
+				let Some(value) = &flag.value else { continue };
+				if flag.attrs.contains_key("@extension") {
+					self.gen_serialize_flag_value(&flag.name, value, "ext", indent)?;
+				}
+			}
+		}
+		if let Some((index, field)) = extension_flags {
+			let flags = field.flags.as_ref().expect("validator error: @extension_flags has no flags");
+			self.gen_serialize_flags(field, index, "ext", indent)?;
+			for flag in flags {
+				if let Some(value) = &flag.value {
+					self.gen_serialize_flag_value(&flag.name, value, "ext", indent)?;
+				}
+			}
+		}
+		appendf!(self, "{indent}pbBytes.serialize(w, ext.finish());\n");
+		Ok(())
+	}
+	/// Reads the flags of `field`, numbered `index`, from `reader`, then the values
+	/// of the flags that are set and aren't extensions, unless `extensions`
+	fn gen_deserialize_flags(
+		&mut self, field: &PBField, index: usize, reader: &str, extensions: bool, indent: &str,
+	) -> Result<(), String> {
+		let flags = field.flags.as_ref().expect("bad state: not a flag field");
+		let (_, read) = self.flags_accessors(field)?;
+		let read = read.replacen("r.", &format!("{reader}."), 1);
+		appendf!(self, "{indent}const flags{index} = {read};\n");
+		for (i, flag) in flags.iter().enumerate() {
+			let set = format!("(flags{index} & {}n) !== 0n", 1u64 << i);
+			match &flag.value {
+				None => appendf!(self, "{indent}const ${} = {set};\n", flag.name),
+				Some(_) if flag.attrs.contains_key("@extension") && !extensions => {}
+				Some(value) => appendf!(self,
+					"{indent}const ${} = {set} ? {}.deserialize({reader}) : undefined;\n",
+					flag.name, self.gen_some_codec(value)?
+				),
+			}
+		}
+		Ok(())
+	}
+	fn gen_deserialize_fields(&mut self, fields: &[PBField], extensible: bool, indent: &str) -> Result<(), String> {
+		for (index, field) in fields.iter().enumerate() {
+			if field.attrs.contains_key("@extension_flags") {
+				continue;
+			}
+			if field.attrs.contains_key("@le") {
+				let method = self.builtin_name(&field.value).expect("validator error: `@le` on a type that isn't builtin").to_lowercase();
+				appendf!(self, "{indent}const ${} = r.{method}(true);\n", field.name);
+			} else if field.flags.is_some() {
+				self.gen_deserialize_flags(field, index, "r", false, indent)?;
+			} else {
+				appendf!(self, "{indent}const ${} = {}.deserialize(r);\n", field.name, self.gen_codec(&field.value)?);
+			}
+		}
+		let has_extensions = fields.iter().any(|f| {
+			f.attrs.contains_key("@extension_flags")
+				|| f.flags.iter().flatten().any(|flag| flag.attrs.contains_key("@extension"))
+		});
+		if extensible && !has_extensions {
+			appendf!(self, "{indent}r.extensions();\n");
+		} else if extensible {
+			// unknown extensions are skipped along with the rest of `ext`
+			appendf!(self, "{indent}const ext = r.extensions();\n");
+			for (index, field) in fields.iter().enumerate() {
+				let Some(flags) = &field.flags else { continue };
+				if field.attrs.contains_key("@extension_flags") {
+					continue;
+				}
+				for (i, flag) in flags.iter().enumerate() {
+					let Some(value) = &flag.value else { continue };
+					if flag.attrs.contains_key("@extension") {
+						appendf!(self,
+							"{indent}const ${} = (flags{index} & {}n) !== 0n ? {}.deserialize(ext) : undefined;\n",
+							flag.name, 1u64 << i, self.gen_some_codec(value)?
+						);
+					}
+				}
+			}
+			if let Some((index, field)) = fields.iter().enumerate().find(|(_, f)| f.attrs.contains_key("@extension_flags")) {
+				self.gen_deserialize_flags(field, index, "ext", true, indent)?;
+			}
+		}
+		appendf!(self, "{indent}return {{");
+		let mut first = true;
+		for field in fields {
+			let names = match &field.flags {
+				Some(flags) => flags.iter().map(|f| &f.name).collect(),
+				None => vec![&field.name],
+			};
+			for name in names {
+				appendf!(self, "{} {name}: ${name}", if first { "" } else { "," });
+				first = false;
+			}
+		}
+		appendf!(self, " }};\n");
+		Ok(())
+	}
+	fn gen_variants(&mut self, variants: &[PBEnumVariant]) -> Result<(), String> {
+		for variant in variants {
+			self.gen_doc(&variant.doc, 1);
+			match &variant.value {
+				Some(value) => appendf!(self, "\t| {{ kind: {:?}, value: {} }}\n", variant.name, self.gen_type(value)?),
+				None => appendf!(self, "\t| {{ kind: {:?} }}\n", variant.name),
+			}
+		}
+		Ok(())
+	}
+	/// Serializes the variants of an enum, or of the error of a command with `unexpected_error`
+	fn gen_serialize_variants(&mut self, variants: &[PBEnumVariant], unexpected_error: bool) -> Result<(), String> {
+		appendf!(self, "\t\tswitch (value.kind) {{\n");
+		if unexpected_error {
+			appendf!(self, "\t\t\tcase \"UnexpectedError\":\n");
+			appendf!(self, "\t\t\t\tw.u8(0);\n");
+			appendf!(self, "\t\t\t\tpbString.serialize(w, value.value);\n");
+			appendf!(self, "\t\t\t\tbreak;\n");
+		}
+		for variant in variants {
+			appendf!(self, "\t\t\tcase {:?}:\n", variant.name);
+			appendf!(self, "\t\t\t\tw.u8({});\n", variant.discriminant);
+			let extension = variant.attrs.contains_key("@extension");
+			match &variant.value {
+				Some(value) if extension => appendf!(self, "\t\t\t\tpbSerializeExtension(w, {}, value.value);\n", self.gen_codec(value)?),
+				Some(value) => appendf!(self, "\t\t\t\t{}.serialize(w, value.value);\n", self.gen_codec(value)?),
+				None if extension => appendf!(self, "\t\t\t\tw.uint(0);\n"),
+				None => {}
+			}
+			appendf!(self, "\t\t\t\tbreak;\n");
+		}
+		appendf!(self, "\t\t}}\n");
+		Ok(())
+	}
+	fn gen_deserialize_variants(&mut self, variants: &[PBEnumVariant], name: &str, unexpected_error: bool) -> Result<(), String> {
+		appendf!(self, "\t\tconst discriminant = r.u8();\n");
+		appendf!(self, "\t\tswitch (discriminant) {{\n");
+		if unexpected_error {
+			appendf!(self, "\t\t\tcase 0:\n");
+			appendf!(self, "\t\t\t\treturn {{ kind: \"UnexpectedError\", value: pbString.deserialize(r) }};\n");
+		}
+		for variant in variants {
+			appendf!(self, "\t\t\tcase {}:\n", variant.discriminant);
+			if variant.attrs.contains_key("@extension") {
+				appendf!(self, "\t\t\t\tr.uint();\n");
+			}
+			match &variant.value {
+				Some(value) => appendf!(self,
+					"\t\t\t\treturn {{ kind: {:?}, value: {}.deserialize(r) }};\n",
+					variant.name, self.gen_codec(value)?
+				),
+				None => appendf!(self, "\t\t\t\treturn {{ kind: {:?} }};\n", variant.name),
+			}
+		}
+		appendf!(self, "\t\t\tdefault:\n");
+		match variants.iter().find(|v| v.attrs.contains_key("@default")) {
+			Some(default) => {
+				// unknown discriminants belong to `@extension` variants added later
+				appendf!(self, "\t\t\t\tr.extensions();\n");
+				appendf!(self, "\t\t\t\treturn {{ kind: {:?} }};\n", default.name);
+			}
+			None => appendf!(self,
+				"\t\t\t\tthrow new Error(`unknown discriminant ${{discriminant}} of {name}, which isn't extensible`);\n"
+			),
+		}
+		appendf!(self, "\t\t}}\n");
+		Ok(())
+	}
+	/// Starts the codec of a type named `name`, taking the codecs of `generics`
+	fn gen_codec_start(&mut self, name: &str, generics: &[String]) {
+		if generics.is_empty() {
+			appendf!(self, "export const {name}: PbCodec<{name}> = {{\n");
+		} else {
+			let generics_list = Self::generics_of(generics);
+			let params: Vec<String> = generics.iter().map(|g| format!("{g}: PbCodec<{g}>")).collect();
+			appendf!(self, "export function {name}{generics_list}({}): PbCodec<{name}{generics_list}> {{\n", params.join(", "));
+			appendf!(self, "\treturn {{\n");
+		}
+	}
+	fn gen_codec_end(&mut self, generics: &[String]) {
+		if generics.is_empty() {
+			appendf!(self, "}};\n\n");
+		} else {
+			appendf!(self, "\t}};\n");
+			appendf!(self, "}}\n\n");
+		}
+	}
+	/// The methods of a codec, indented for `gen_codec_start`
+	fn gen_methods(&mut self, generics: &[String], serialize: &str, deserialize: &str) {
+		let extra = if generics.is_empty() { "" } else { "\t" };
+		let indent = |code: &str| code.lines().map(|l| format!("{extra}{l}\n")).collect::<String>();
+		appendf!(self, "{}", indent(&format!("\tserialize(w, value) {{\n{serialize}\t}},\n")));
+		appendf!(self, "{}", indent(&format!("\tdeserialize(r) {{\n{deserialize}\t}},\n")));
+	}
+	/// Generates the body of the methods of a codec with `generate`, and returns it
+	fn capture(&mut self, generate: impl FnOnce(&mut Self) -> Result<(), String>) -> Result<String, String> {
+		let outer = std::mem::take(&mut self.buffer);
+		let result = generate(self);
+		Ok(std::mem::replace(&mut self.buffer, outer)).and_then(|body| result.map(|_| body))
+	}
+	fn gen_types(&mut self) -> Result<(), String> {
+		for tp in &self.def.types {
+			let attrs = tp.get_attrs();
+			if attrs.contains_key("@builtin") || attrs.contains_key("@resolve") {
+				continue;
+			}
+			let name = Self::type_name(tp);
+			let generics = tp.get_generics().0;
+			let generics_list = Self::generics_of(generics);
+			match tp {
+				PBTypeDef::Alias { alias, doc, .. } => {
+					self.gen_doc(doc, 0);
+					appendf!(self, "export type {name}{generics_list} = {};\n", self.gen_type(alias)?);
+					let codec = self.gen_codec(alias)?;
+					if generics.is_empty() {
+						// the codec is looked up on every use, since it may be declared further down
+						appendf!(self, "export const {name}: PbCodec<{name}> = {{\n");
+						appendf!(self, "\tserialize: (w, value) => {codec}.serialize(w, value),\n");
+						appendf!(self, "\tdeserialize: (r) => {codec}.deserialize(r),\n");
+						if self.has_undefined(alias) == Some(true) {
+							appendf!(self, "\thasUndefined: true,\n");
+						}
+						appendf!(self, "}};\n\n");
+					} else {
+						let params: Vec<String> = generics.iter().map(|g| format!("{g}: PbCodec<{g}>")).collect();
+						appendf!(self, "export function {name}{generics_list}({}): PbCodec<{name}{generics_list}> {{\n", params.join(", "));
+						appendf!(self, "\treturn {codec};\n");
+						appendf!(self, "}}\n\n");
+					}
+				}
+				PBTypeDef::Struct { fields, doc, attrs, .. } => {
+					self.gen_doc(doc, 0);
+					appendf!(self, "export interface {name}{generics_list} {{\n");
+					self.gen_fields(fields)?;
+					appendf!(self, "}}\n");
+					let extensible = !attrs.contains_key("@sealed");
+					let serialize = self.capture(|s| s.gen_serialize_fields(fields, extensible, "\t\t"))?;
+					let deserialize = self.capture(|s| s.gen_deserialize_fields(fields, extensible, "\t\t"))?;
+					self.gen_codec_start(&name, generics);
+					self.gen_methods(generics, &serialize, &deserialize);
+					self.gen_codec_end(generics);
+				}
+				PBTypeDef::Enum { variants, doc, .. } => {
+					self.gen_doc(doc, 0);
+					appendf!(self, "export type {name}{generics_list} =\n");
+					self.gen_variants(variants)?;
+					if variants.is_empty() {
+						appendf!(self, "\tnever\n");
+					}
+					self.buffer.insert(self.buffer.len() - 1, ';');
+					let serialize = self.capture(|s| s.gen_serialize_variants(variants, false))?;
+					let deserialize = self.capture(|s| s.gen_deserialize_variants(variants, &name, false))?;
+					self.gen_codec_start(&name, generics);
+					self.gen_methods(generics, &serialize, &deserialize);
+					self.gen_codec_end(generics);
+				}
+			}
+		}
+		Ok(())
+	}
+	fn gen_commands(&mut self) -> Result<(), String> {
+		for cmd in &self.def.commands {
+			let name = Self::command_name(cmd);
+			self.gen_doc(&cmd.doc, 0);
+			let extensible = !cmd.attrs.contains_key("@sealed");
+			let argument = match &cmd.argument {
+				PBCommandArg::None => {
+					appendf!(self, "export type {name} = null;\n");
+					"pbVoid".to_string()
+				}
+				PBCommandArg::Ref(refr) => {
+					appendf!(self, "export type {name} = {};\n", self.gen_type(refr)?);
+					self.gen_codec(refr)?
+				}
+				PBCommandArg::Struct { fields } => {
+					appendf!(self, "export interface {name} {{\n");
+					self.gen_fields(fields)?;
+					appendf!(self, "}}\n");
+					let serialize = self.capture(|s| s.gen_serialize_fields(fields, extensible, "\t\t\t"))?;
+					let deserialize = self.capture(|s| s.gen_deserialize_fields(fields, extensible, "\t\t\t"))?;
+					format!(
+						"{{\n\t\tserialize(w: PbWriter, value: {name}) {{\n{serialize}\t\t}},\n\t\t\
+						deserialize(r: PbReader): {name} {{\n{deserialize}\t\t}},\n\t}}"
+					)
+				}
+			};
+
+			let error = format!("{name}Error");
+			appendf!(self, "export type {error} =\n");
+			appendf!(self, "\t| {{ kind: \"UnexpectedError\", value: string }}\n");
+			self.gen_variants(&cmd.err)?;
+			self.buffer.insert(self.buffer.len() - 1, ';');
+			let serialize = self.capture(|s| s.gen_serialize_variants(&cmd.err, true))?;
+			let deserialize = self.capture(|s| s.gen_deserialize_variants(&cmd.err, &error, true))?;
+			self.gen_codec_start(&error, &[]);
+			self.gen_methods(&[], &serialize, &deserialize);
+			self.gen_codec_end(&[]);
+
+			appendf!(self, "export const {name}: PbCommand<{name}, {}, {error}> = {{\n", self.gen_type(&cmd.ret)?);
+			appendf!(self, "\tid: {},\n", cmd.command_id);
+			appendf!(self, "\tname: {:?},\n", cmd.name);
+			appendf!(self, "\tlayer: {},\n", cmd.layer);
+			appendf!(self, "\targument: {argument},\n");
+			appendf!(self, "\treturns: {},\n", self.gen_codec(&cmd.ret)?);
+			appendf!(self, "\terror: {error},\n");
+			appendf!(self, "\tisVoid: {},\n", cmd.ret.reference == "Void");
+			appendf!(self, "\tisStream: {},\n", cmd.attrs.contains_key("@stream"));
+			appendf!(self, "}};\n\n");
+		}
+		Ok(())
+	}
+	/// The `Command` union of all commands, tagged by their names, and its codec,
+	/// which prefixes each command with its ID
+	fn gen_command_union(&mut self) {
+		if self.def.commands.is_empty() {
+			return;
+		}
+		appendf!(self, "/** All the commands of the definition */\n");
+		appendf!(self, "export type Command =\n");
+		for cmd in &self.def.commands {
+			let name = Self::command_name(cmd);
+			appendf!(self, "\t| {{ command: {name:?}, argument: {name} }}\n");
+		}
+		self.buffer.insert(self.buffer.len() - 1, ';');
+		appendf!(self, "export const Command: PbCodec<Command> = {{\n");
+		appendf!(self, "\tserialize(w, value) {{\n");
+		appendf!(self, "\t\tswitch (value.command) {{\n");
+		for cmd in &self.def.commands {
+			let name = Self::command_name(cmd);
+			appendf!(self, "\t\t\tcase {name:?}:\n");
+			appendf!(self, "\t\t\t\tw.u32({name}.id);\n");
+			appendf!(self, "\t\t\t\t{name}.argument.serialize(w, value.argument);\n");
+			appendf!(self, "\t\t\t\tbreak;\n");
+		}
+		appendf!(self, "\t\t}}\n");
+		appendf!(self, "\t}},\n");
+		appendf!(self, "\tdeserialize(r) {{\n");
+		appendf!(self, "\t\tconst id = r.u32();\n");
+		appendf!(self, "\t\tswitch (id) {{\n");
+		for cmd in &self.def.commands {
+			let name = Self::command_name(cmd);
+			appendf!(self, "\t\t\tcase {}:\n", cmd.command_id);
+			appendf!(self, "\t\t\t\treturn {{ command: {name:?}, argument: {name}.argument.deserialize(r) }};\n");
+		}
+		appendf!(self, "\t\t\tdefault:\n");
+		appendf!(self, "\t\t\t\tthrow new Error(`invalid or unsupported command ID ${{id}}`);\n");
+		appendf!(self, "\t\t}}\n");
+		appendf!(self, "\t}},\n");
+		appendf!(self, "}};\n\n");
+	}
+	pub fn codegen(mut self) -> Result<String, String> {
+		appendf!(self, "// This file was automatically generated by Punybuf.\n\n");
+		appendf!(self, "{}\n", RUNTIME);
+		appendf!(self, "/** A command, with the codecs of its argument, return value and error */\n");
+		appendf!(self, "export interface PbCommand<A, R, E> {{\n");
+		appendf!(self, "\tid: number;\n");
+		appendf!(self, "\tname: string;\n");
+		appendf!(self, "\tlayer: number;\n");
+		appendf!(self, "\targument: PbCodec<A>;\n");
+		appendf!(self, "\treturns: PbCodec<R>;\n");
+		appendf!(self, "\terror: PbCodec<E>;\n");
+		appendf!(self, "\t/** The command doesn't get a response */\n");
+		appendf!(self, "\tisVoid: boolean;\n");
+		appendf!(self, "\t/** The command responds with any number of `Optional<R>`s, ending with `None` */\n");
+		appendf!(self, "\tisStream: boolean;\n");
+		appendf!(self, "}}\n\n");
+		let highest_layer = self.def.types.iter().map(|tp| *tp.get_layer())
+			.chain(self.def.commands.iter().map(|cmd| cmd.layer))
+			.max().unwrap_or(0);
+		appendf!(self, "/** The highest layer of the definition */\n");
+		appendf!(self, "export const HIGHEST_LAYER = {highest_layer};\n\n");
+		self.gen_types()?;
+		self.gen_commands()?;
+		self.gen_command_union();
+		Ok(self.buffer)
+	}
+}
@@ -2,6 +2,7 @@
 	schema:
 	{
 		includes_common: boolean
+		attrs: Attrs
 		types: {
 			name: string
 			layer: number
@@ -46,8 +47,8 @@
 			attrs: Attrs
 			is_highest_layer: boolean
 
-			argument?: {
-				is: "ref" | "struct"
+			arg: {
+				is?: "ref" | "struct"
 
 				// is ref?
 				ref?: Ref
@@ -101,11 +102,13 @@ fn convert_attrs(attrs: &HashMap<String, Option<String>>) -> json::JsonValue {
 	obj
 }
 
-fn convert_ref(refr: &PBTypeRef) -> json::JsonValue {
+/// Converts a reference to the `Ref` format of the JSON IR:
+/// `[name, layer | null, generic_params, is_highest_layer]`
+pub fn pbtyperef_to_json(refr: &PBTypeRef) -> json::JsonValue {
 	json::array![
 		refr.reference.as_str(),
 		refr.resolved_layer,
-		refr.generics.iter().map(|r| convert_ref(r)).collect::<Vec<_>>(),
+		refr.generics.iter().map(pbtyperef_to_json).collect::<Vec<_>>(),
 		refr.is_highest_layer
 	]
 }
@@ -118,7 +121,7 @@ fn convert_fields(fields: &Vec<PBField>) -> json::JsonValue {
 					name: v.name.as_str(),
 					attrs: convert_attrs(&v.attrs),
 					doc: v.doc.as_str(),
-					value: convert_ref(&v.value),
+					value: pbtyperef_to_json(&v.value),
 					flags: v.flags.as_ref().map(|flags| {
 						json::JsonValue::from(
 							flags.iter()
@@ -127,7 +130,7 @@ fn convert_fields(fields: &Vec<PBField>) -> json::JsonValue {
 									name: flag.name.as_str(),
 									attrs: convert_attrs(&flag.attrs),
 									doc: flag.doc.as_str(),
-									value: flag.value.as_ref().map(convert_ref)
+									value: flag.value.as_ref().map(pbtyperef_to_json)
 								}
 							})
 							.collect::<Vec<_>>()
@@ -148,7 +151,7 @@ fn convert_enum_variants(variants: &Vec<PBEnumVariant>) -> json::JsonValue {
 					discriminant: v.discriminant,
 					attrs: convert_attrs(&v.attrs),
 					doc: v.doc.as_str(),
-					value: v.value.as_ref().map(|rf| convert_ref(rf))
+					value: v.value.as_ref().map(pbtyperef_to_json)
 				}
 			})
 			.collect::<Vec<_>>()
@@ -169,7 +172,7 @@ fn convert_type(tp: &PBTypeDef) -> json::JsonValue {
 	match tp {
 		PBTypeDef::Alias { alias, .. } => {
 			obj.insert("is", "alias").unwrap();
-			obj.insert("alias", convert_ref(alias)).unwrap();
+			obj.insert("alias", pbtyperef_to_json(alias)).unwrap();
 		}
 		PBTypeDef::Struct { fields, .. } => {
 			obj.insert("is", "struct").unwrap();
@@ -190,7 +193,7 @@ fn convert_command(cmd: &PBCommandDef) -> json::JsonValue {
 	match &cmd.argument {
 		PBCommandArg::Ref(refr) => {
 			arg.insert("is", "ref").unwrap();
-			arg.insert("ref", convert_ref(refr)).unwrap();
+			arg.insert("ref", pbtyperef_to_json(refr)).unwrap();
 		}
 		PBCommandArg::Struct { fields } => {
 			arg.insert("is", "struct").unwrap();
@@ -206,149 +209,167 @@ fn convert_command(cmd: &PBCommandDef) -> json::JsonValue {
 		attrs: convert_attrs(&cmd.attrs),
 		doc: cmd.doc.as_str(),
 		arg: arg,
-		ret: convert_ref(&cmd.ret),
+		ret: pbtyperef_to_json(&cmd.ret),
 		err: convert_enum_variants(&cmd.err),
 		is_highest_layer: cmd.is_highest_layer
 	}
 }
 
+/// Converts a whole definition to the JSON IR, in the schema at the top of this file
 pub fn convert_full_definition(def: &PunybufDefinition) -> String {
 	json::stringify(json::object! {
 		includes_common: def.includes_common,
+		attrs: convert_attrs(&def.attrs),
 		types: def.types.iter().map(convert_type).collect::<Vec<_>>(),
 		commands: def.commands.iter().map(convert_command).collect::<Vec<_>>(),
 	})
 }
 
-pub fn from_json(input: &str) -> Result<PunybufDefinition, String> {
+/// Reads a definition back from the JSON IR, see [`convert_full_definition`].
+///
+/// The IR has no spans, so errors found later point nowhere.
+pub fn parse_full_definition(input: &str) -> Result<PunybufDefinition, String> {
 	let mut object = json::parse(input).map_err(|e| e.to_string())?;
-	let includes_common = object.remove("includes_common").as_bool().unwrap_or(false);
-	let mut object_types = object.remove("types");
-	let mut result = PunybufDefinition::new(includes_common);
-	for obj_typ in object_types.members_mut() {
-		result.types.push(type_from_json(obj_typ)?);
+	let mut result = PunybufDefinition::new(take_bool(&mut object, "includes_common")?);
+	result.attrs = attrs_from_json(&mut object.remove("attrs"))?;
+	for (i, obj_typ) in take_array(&mut object, "types")?.members_mut().enumerate() {
+		result.types.push(type_from_json(obj_typ).map_err(|e| format!("invalid type #{i}: {e}"))?);
 	}
-	let mut object_commands = object.remove("commands");
-	for obj_cmd in object_commands.members_mut() {
-		result.commands.push(cmd_from_json(obj_cmd)?);
+	for (i, obj_cmd) in take_array(&mut object, "commands")?.members_mut().enumerate() {
+		result.commands.push(cmd_from_json(obj_cmd).map_err(|e| format!("invalid command #{i}: {e}"))?);
 	}
 	Ok(result)
 }
 
+fn take_string(obj: &mut JsonValue, key: &str) -> Result<String, String> {
+	obj.remove(key).as_str().map(str::to_string).ok_or_else(|| format!("`{key}` should be a string"))
+}
+
+fn take_u32(obj: &mut JsonValue, key: &str) -> Result<u32, String> {
+	obj.remove(key).as_u32().ok_or_else(|| format!("`{key}` should be a number"))
+}
+
+fn take_bool(obj: &mut JsonValue, key: &str) -> Result<bool, String> {
+	obj.remove(key).as_bool().ok_or_else(|| format!("`{key}` should be a boolean"))
+}
+
+fn take_array(obj: &mut JsonValue, key: &str) -> Result<JsonValue, String> {
+	let array = obj.remove(key);
+	if !array.is_array() {
+		return Err(format!("`{key}` should be an array"));
+	}
+	Ok(array)
+}
+
 fn type_from_json(obj_typ: &mut JsonValue) -> Result<PBTypeDef, String> {
-	match obj_typ.remove("is").as_str().unwrap_or("<nothing>") {
-		"struct" => {
+	let name = take_string(obj_typ, "name")?;
+	let in_type = |e: String| format!("`{name}`: {e}");
+	let doc = take_string(obj_typ, "doc").map_err(in_type)?;
+	let layer = take_u32(obj_typ, "layer").map_err(in_type)?;
+	let attrs = attrs_from_json(&mut obj_typ.remove("attrs")).map_err(in_type)?;
+	let mut generic_params = vec![];
+	for param in take_array(obj_typ, "generic_params").map_err(in_type)?.members() {
+		generic_params.push(param.as_str().ok_or_else(|| in_type("generic parameters should be strings".into()))?.to_string());
+	}
+	let inline_owner = obj_typ.remove("inline_owner").as_str()
+		.map(|x| (x.to_string(), Span::impossible()));
+	let is_highest_layer = take_bool(obj_typ, "is_highest_layer").map_err(in_type)?;
+	let (name_span, generic_span) = (Span::impossible(), Span::impossible());
+	match obj_typ.remove("is").as_str() {
+		Some("struct") => {
 			Ok(PBTypeDef::Struct {
-				name: obj_typ.remove("name").to_string(),
-				name_span: Span::impossible(),
-				doc: obj_typ.remove("doc").to_string(),
-				layer: obj_typ.remove("layer").as_u32().unwrap_or(0),
-				attrs: attrs_from_json(&mut obj_typ.remove("attrs")),
-				generic_params: obj_typ.remove("generic_params").members().map(|v| {
-					v.to_string()
-				}).collect(),
-				generic_span: Span::impossible(),
-				fields: fields_from_json(&mut obj_typ.remove("fields"))?,
-				inline_owner: obj_typ.remove("inline_owner").as_str()
-					.map(|x| (x.to_string(), Span::impossible())),
-				is_highest_layer: obj_typ.remove("is_highest_owner").as_bool().unwrap_or(false)
+				fields: fields_from_json(&mut take_array(obj_typ, "fields").map_err(in_type)?).map_err(in_type)?,
+				name, name_span, doc, layer, attrs, generic_params, generic_span, inline_owner, is_highest_layer,
 			})
 		}
-		"enum" => {
+		Some("enum") => {
 			Ok(PBTypeDef::Enum {
-				name: obj_typ.remove("name").to_string(),
-				name_span: Span::impossible(),
-				doc: obj_typ.remove("doc").to_string(),
-				layer: obj_typ.remove("layer").as_u32().unwrap_or(0),
-				attrs: attrs_from_json(&mut obj_typ.remove("attrs")),
-				generic_params: obj_typ.remove("generic_params").members().map(|v| {
-					v.to_string()
-				}).collect(),
-				generic_span: Span::impossible(),
-				variants: variants_from_json(&mut obj_typ.remove("variants"))?,
-				inline_owner: obj_typ.remove("inline_owner").as_str()
-					.map(|x| (x.to_string(), Span::impossible())),
-				is_highest_layer: obj_typ.remove("is_highest_owner").as_bool().unwrap_or(false)
+				variants: variants_from_json(&mut take_array(obj_typ, "variants").map_err(in_type)?).map_err(in_type)?,
+				name, name_span, doc, layer, attrs, generic_params, generic_span, inline_owner, is_highest_layer,
 			})
 		}
-		"alias" => {
+		Some("alias") => {
 			Ok(PBTypeDef::Alias {
-				name: obj_typ.remove("name").to_string(),
-				name_span: Span::impossible(),
-				doc: obj_typ.remove("doc").to_string(),
-				layer: obj_typ.remove("layer").as_u32().unwrap_or(0),
-				attrs: attrs_from_json(&mut obj_typ.remove("attrs")),
-				generic_params: obj_typ.remove("generic_params").members().map(|v| {
-					v.to_string()
-				}).collect(),
-				generic_span: Span::impossible(),
-				alias: ref_from_json(&mut obj_typ.remove("alias"))?,
-				is_highest_layer: obj_typ.remove("is_highest_owner").as_bool().unwrap_or(false)
+				alias: pbtyperef_from_json(&obj_typ.remove("alias")).map_err(in_type)?,
+				name, name_span, doc, layer, attrs, generic_params, generic_span, is_highest_layer,
 			})
 		}
 		_ => {
-			Err("invalid `is` value".into())
+			Err(in_type("`is` should be \"struct\", \"enum\" or \"alias\"".into()))
 		}
 	}
 }
 
 fn cmd_from_json(obj_cmd: &mut JsonValue) -> Result<PBCommandDef, String> {
+	let name = take_string(obj_cmd, "name")?;
+	let in_command = |e: String| format!("`{name}`: {e}");
 	Ok(PBCommandDef {
-		name: obj_cmd.remove("name").to_string(),
-		name_span: Span::impossible(),
-		argument: arg_from_json(&mut obj_cmd.remove("argument"))?,
+		argument: arg_from_json(&mut obj_cmd.remove("arg")).map_err(in_command)?,
 		argument_span: Span::impossible(),
-		attrs: attrs_from_json(&mut obj_cmd.remove("attrs")),
-		doc: obj_cmd.remove("doc").to_string(),
-		layer: obj_cmd.remove("layer").as_u32().unwrap_or(0),
-		command_id: obj_cmd.remove("id").as_u32().ok_or("invalid command id")?,
-		ret: ref_from_json(&mut obj_cmd.remove("ret"))?,
-		err: variants_from_json(&mut obj_cmd.remove("err"))?,
+		attrs: attrs_from_json(&mut obj_cmd.remove("attrs")).map_err(in_command)?,
+		doc: take_string(obj_cmd, "doc").map_err(in_command)?,
+		layer: take_u32(obj_cmd, "layer").map_err(in_command)?,
+		command_id: take_u32(obj_cmd, "id").map_err(in_command)?,
+		ret: pbtyperef_from_json(&obj_cmd.remove("ret")).map_err(in_command)?,
+		err: variants_from_json(&mut take_array(obj_cmd, "err").map_err(in_command)?).map_err(in_command)?,
 		err_span: Span::impossible(),
-		is_highest_layer: obj_cmd.remove("is_highest_layer").as_bool().unwrap_or(false)
+		is_highest_layer: take_bool(obj_cmd, "is_highest_layer").map_err(in_command)?,
+		name,
+		name_span: Span::impossible(),
 	})
 }
 
 fn arg_from_json(obj_arg: &mut JsonValue) -> Result<PBCommandArg, String> {
-	if obj_arg.is_null() {
+	if !obj_arg.is_object() {
+		return Err("`arg` should be an object".into());
+	}
+	// commands without an argument have an empty object
+	if obj_arg.is_empty() {
 		return Ok(PBCommandArg::None);
 	}
-	match obj_arg.remove("is").as_str().unwrap_or("<unknown>") {
-		"ref" => {
-			Ok(PBCommandArg::Ref(ref_from_json(&mut obj_arg.remove("ref"))?))
+	match obj_arg.remove("is").as_str() {
+		Some("ref") => {
+			Ok(PBCommandArg::Ref(pbtyperef_from_json(&obj_arg.remove("ref"))?))
 		}
-		"struct" => {
+		Some("struct") => {
 			Ok(PBCommandArg::Struct {
-				fields: fields_from_json(&mut obj_arg.remove("fields"))?
+				fields: fields_from_json(&mut take_array(obj_arg, "fields")?)?
 			})
 		}
 		_ => {
-			Err("invalid `is` value in a command".into())
+			Err("`is` of the argument should be \"ref\" or \"struct\"".into())
 		}
 	}
 }
 
-fn attrs_from_json(obj_attrs: &mut JsonValue) -> HashMap<String, Option<String>> {
+fn attrs_from_json(obj_attrs: &mut JsonValue) -> Result<HashMap<String, Option<String>>, String> {
+	if !obj_attrs.is_object() {
+		return Err("`attrs` should be an object".into());
+	}
 	let mut result = HashMap::new();
 	for (name, val) in obj_attrs.entries() {
-		result.insert(
-			name.into(),
-			if let Some(v) = val.as_str() { Some(v.into()) } else { None }
-		);
+		let value = match val.as_str() {
+			Some(v) => Some(v.to_string()),
+			None if val.is_null() => None,
+			None => return Err(format!("the value of `{name}` should be a string or null")),
+		};
+		result.insert(name.into(), value);
 	}
-	result
+	Ok(result)
 }
 
 fn fields_from_json(obj_fields: &mut JsonValue) -> Result<Vec<PBField>, String> {
 	let mut fields = vec![];
 	for obj_field in obj_fields.members_mut() {
+		let name = take_string(obj_field, "name").map_err(|e| format!("invalid field: {e}"))?;
+		let in_field = |e: String| format!("the field `{name}`: {e}");
 		fields.push(PBField {
-			name: obj_field.remove("name").to_string(),
+			value: pbtyperef_from_json(&obj_field.remove("value")).map_err(in_field)?,
+			flags: flags_from_json(&mut obj_field.remove("flags")).map_err(in_field)?,
+			attrs: attrs_from_json(&mut obj_field.remove("attrs")).map_err(in_field)?,
+			doc: take_string(obj_field, "doc").map_err(in_field)?,
+			name,
 			name_span: Span::impossible(),
-			value: ref_from_json(&mut obj_field.remove("value"))?,
-			flags: flags_from_json(&mut obj_field.remove("flags"))?,
-			attrs: attrs_from_json(&mut obj_field.remove("attrs")),
-			doc: obj_field.remove("doc").to_string()
 		});
 	}
 	Ok(fields)
@@ -358,18 +379,23 @@ fn flags_from_json(obj_flags: &mut JsonValue) -> Result<Option<Vec<PBFieldFlag>>
 	if obj_flags.is_null() {
 		return Ok(None);
 	}
+	if !obj_flags.is_array() {
+		return Err("`flags` should be an array or null".into());
+	}
 	let mut flags = vec![];
 	for obj_flag in obj_flags.members_mut() {
+		let name = take_string(obj_flag, "name").map_err(|e| format!("invalid flag: {e}"))?;
+		let in_flag = |e: String| format!("the flag `{name}`: {e}");
 		flags.push(PBFieldFlag {
-			name: obj_flag.remove("name").to_string(),
-			name_span: Span::impossible(),
-			value: if let mut val = obj_flag.remove("value") && !val.is_null() {
-				Some(ref_from_json(&mut val)?)
+			value: if let val = obj_flag.remove("value") && !val.is_null() {
+				Some(pbtyperef_from_json(&val).map_err(in_flag)?)
 			} else {
 				None
 			},
-			attrs: attrs_from_json(&mut obj_flag.remove("attrs")),
-			doc: obj_flag.remove("doc").to_string()
+			attrs: attrs_from_json(&mut obj_flag.remove("attrs")).map_err(in_flag)?,
+			doc: take_string(obj_flag, "doc").map_err(in_flag)?,
+			name,
+			name_span: Span::impossible(),
 		});
 	}
 	Ok(Some(flags))
@@ -378,32 +404,42 @@ fn flags_from_json(obj_flags: &mut JsonValue) -> Result<Option<Vec<PBFieldFlag>>
 fn variants_from_json(obj_variants: &mut JsonValue) -> Result<Vec<PBEnumVariant>, String> {
 	let mut variants = vec![];
 	for obj_var in obj_variants.members_mut() {
+		let name = take_string(obj_var, "name").map_err(|e| format!("invalid variant: {e}"))?;
+		let in_variant = |e: String| format!("the variant `{name}`: {e}");
 		variants.push(PBEnumVariant {
-			name: obj_var.remove("name").to_string(),
-			name_span: Span::impossible(),
-			discriminant: obj_var.remove("discriminant").as_u8().ok_or("invalid discriminant")?,
-			value: if let mut val = obj_var.remove("value") && !val.is_null() {
-				Some(ref_from_json(&mut val)?)
+			discriminant: obj_var.remove("discriminant").as_u8()
+				.ok_or_else(|| in_variant("`discriminant` should be a number from 0 to 255".into()))?,
+			value: if let val = obj_var.remove("value") && !val.is_null() {
+				Some(pbtyperef_from_json(&val).map_err(in_variant)?)
 			} else {
 				None
 			},
-			attrs: attrs_from_json(&mut obj_var.remove("attrs")),
-			doc: obj_var.remove("doc").to_string()
+			attrs: attrs_from_json(&mut obj_var.remove("attrs")).map_err(in_variant)?,
+			doc: take_string(obj_var, "doc").map_err(in_variant)?,
+			name,
+			name_span: Span::impossible(),
 		});
 	}
 	Ok(variants)
 }
 
-fn ref_from_json(obj_ref: &mut JsonValue) -> Result<PBTypeRef, String> {
+/// Reads a reference in the `Ref` format of the JSON IR, see [`pbtyperef_to_json`].
+///
+/// A `null` layer means the reference is to a generic parameter.
+pub fn pbtyperef_from_json(obj_ref: &JsonValue) -> Result<PBTypeRef, String> {
 	// Ref = [name: string, layer: number | null, generic_params: Ref[], is_highest_layer: boolean]
-	let mut iter = obj_ref.members_mut();
-	let name = iter.next().ok_or("invalid reference: no name")?.to_string();
-	let layer = iter.next().ok_or("invalid reference: no layer")?.as_u32()
-		.ok_or("invalid reference: incorrect layer")?;
+	let mut iter = obj_ref.members();
+	let name = iter.next().and_then(|name| name.as_str()).ok_or("invalid reference: no name")?.to_string();
+	let layer = iter.next().ok_or("invalid reference: no layer")?;
+	let layer = if layer.is_null() {
+		None
+	} else {
+		Some(layer.as_u32().ok_or("invalid reference: incorrect layer")?)
+	};
 	let obj_generic_params = iter.next().ok_or("invalid reference: no generic_params")?;
 	let mut generic_params = vec![];
-	for obj_ref in obj_generic_params.members_mut() {
-		generic_params.push(ref_from_json(obj_ref)?);
+	for obj_ref in obj_generic_params.members() {
+		generic_params.push(pbtyperef_from_json(obj_ref)?);
 	}
 	let is_highest_layer = iter.next().ok_or("invalid reference: no is_highest_layer")?
 		.as_bool().unwrap_or(false);
@@ -412,9 +448,9 @@ fn ref_from_json(obj_ref: &mut JsonValue) -> Result<PBTypeRef, String> {
 		reference_span: Span::impossible(),
 		generics: generic_params,
 		generic_span: Span::impossible(),
-		resolved_layer: Some(layer),
+		resolved_layer: layer,
 		is_highest_layer,
-		// TODO: currently not included in json
-		is_global: true,
+		// Generic parameters are the only references without a layer
+		is_global: layer.is_some(),
 	})
-}
\ No newline at end of file
+}
@@ -0,0 +1,378 @@
+//! Decoding values without generated code, by interpreting the definition at runtime.
+//!
+//! [`DynDecoder`] follows the same rules as the generated Rust code: flags are
+//! read from their flag field, extensions from after the extension length, and
+//! unknown enum variants fall back to the `@default` one. Values are returned as
+//! a [`DynValue`] tree, with the names from the definition.
+
+use std::{collections::HashMap, io::{self, Read}};
+
+use crate::flattener::{PBCommandArg, PBEnumVariant, PBField, PBTypeDef, PBTypeRef, PunybufDefinition};
+
+/// The largest length of `Bytes`, `String`s and `Array`s, see [the binary format](../../docs/BinaryFormat.md#bytes)
+const MAX_LENGTH: u64 = 4 * 1024 * 1024 * 1024;
+/// How deeply values may be nested, so recursive types can't overflow the stack
+const MAX_DEPTH: usize = 256;
+
+/// A decoded value
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynValue {
+	Void,
+	U8(u8),
+	U16(u16),
+	U32(u32),
+	U64(u64),
+	I32(i32),
+	I64(i64),
+	F32(f32),
+	F64(f64),
+	UInt(u64),
+	SInt(i64),
+	Bytes(Vec<u8>),
+	String(String),
+	/// Seconds since the Unix epoch, and nanoseconds
+	DateTime(i64, u32),
+	Array(Vec<DynValue>),
+	/// Fields in the order they're declared in. A flag field is replaced by its flags,
+	/// as [`Bool`](Self::Bool)s for flags without a value and [`Optional`](Self::Optional)s for the rest
+	Struct(Vec<(String, DynValue)>),
+	Bool(bool),
+	Optional(Option<Box<DynValue>>),
+	Enum {
+		variant: String,
+		value: Option<Box<DynValue>>,
+	},
+}
+
+impl DynValue {
+	/// The value of an integer, used for flag fields
+	fn as_u64(&self) -> Option<u64> {
+		match *self {
+			Self::U8(n) => Some(n as u64),
+			Self::U16(n) => Some(n as u64),
+			Self::U32(n) => Some(n as u64),
+			Self::U64(n) | Self::UInt(n) => Some(n),
+			_ => None,
+		}
+	}
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn not_found(message: String) -> io::Error {
+	io::Error::new(io::ErrorKind::NotFound, message)
+}
+
+fn read_array<const N: usize>(r: &mut &[u8]) -> io::Result<[u8; N]> {
+	let mut buf = [0; N];
+	r.read_exact(&mut buf)?;
+	Ok(buf)
+}
+
+fn read_uint(r: &mut &[u8]) -> io::Result<u64> {
+	let [first] = read_array(r)?;
+	let (len, offset, value) = match first.leading_ones() {
+		0 => return Ok(first as u64),
+		1 => (1, 128, first & 0b0011_1111),
+		2 => (2, 16512, first & 0b0001_1111),
+		3 => (4, 2113664, first & 0b0000_1111),
+		_ => (7, 68721590400, first & 0b0000_1111),
+	};
+	let mut value = value as u64;
+	for byte in read_bytes(r, len)? {
+		value = (value << 8) | *byte as u64;
+	}
+	Ok(value + offset)
+}
+
+fn read_bytes<'a>(r: &mut &'a [u8], len: u64) -> io::Result<&'a [u8]> {
+	if len > MAX_LENGTH {
+		return Err(invalid("Bytes length too large"));
+	}
+	if len > r.len() as u64 {
+		return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"));
+	}
+	let (bytes, rest) = r.split_at(len as usize);
+	*r = rest;
+	Ok(bytes)
+}
+
+/// The state of decoding the fields of a struct, shared by all of its flag fields
+struct FieldsContext<'b, 'd> {
+	/// The values of the generic parameters of the struct
+	bindings: &'b HashMap<&'b str, PBTypeRef>,
+	/// The fields and flags decoded so far, by name
+	values: HashMap<&'d str, DynValue>,
+	depth: usize,
+}
+
+/// Decodes values of the types and commands of a definition
+pub struct DynDecoder<'d> {
+	def: &'d PunybufDefinition,
+	strict_strings: bool,
+}
+
+impl<'d> DynDecoder<'d> {
+	pub fn new(def: &'d PunybufDefinition) -> Self {
+		Self { def, strict_strings: def.attrs.contains_key("@strict_strings") }
+	}
+
+	/// Decodes the argument of the command `name` on the highest layer, without its ID.
+	/// A command without an argument decodes to [`DynValue::Void`]
+	pub fn decode_command(&self, name: &str, bytes: &[u8]) -> io::Result<DynValue> {
+		let cmd = self.def.commands.iter()
+			.find(|cmd| cmd.name == name && cmd.is_highest_layer)
+			.ok_or_else(|| not_found(format!("unknown command `{name}`")))?;
+		self.decode_all(bytes, |r| match &cmd.argument {
+			PBCommandArg::None => Ok(DynValue::Void),
+			PBCommandArg::Ref(refr) => self.decode_ref(refr, r, 0),
+			PBCommandArg::Struct { fields } => {
+				self.decode_fields(fields, !cmd.attrs.contains_key("@sealed"), &HashMap::new(), r, 0)
+			}
+		})
+	}
+
+	/// Decodes a value of the type `name` on the highest layer, which mustn't be generic
+	pub fn decode_type(&self, name: &str, bytes: &[u8]) -> io::Result<DynValue> {
+		let tp = self.def.types.iter()
+			.find(|tp| tp.get_name().0 == name && tp.is_highest_layer())
+			.ok_or_else(|| not_found(format!("unknown type `{name}`")))?;
+		if !tp.get_generics().0.is_empty() {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("`{name}` is generic, use `decode` instead")));
+		}
+		let refr = PBTypeRef::new(name, Some(*tp.get_layer()), vec![], true);
+		self.decode(&refr, bytes)
+	}
+
+	/// Decodes a value of the type `refr` refers to, like the ones in the JSON IR
+	pub fn decode(&self, refr: &PBTypeRef, bytes: &[u8]) -> io::Result<DynValue> {
+		self.decode_all(bytes, |r| self.decode_ref(refr, r, 0))
+	}
+
+	fn decode_all(&self, mut bytes: &[u8], decode: impl FnOnce(&mut &[u8]) -> io::Result<DynValue>) -> io::Result<DynValue> {
+		let value = decode(&mut bytes)?;
+		if !bytes.is_empty() {
+			return Err(invalid("trailing bytes"));
+		}
+		Ok(value)
+	}
+
+	fn find_type(&self, refr: &PBTypeRef) -> io::Result<&'d PBTypeDef> {
+		self.def.find_type(refr).map_err(not_found)
+	}
+
+	fn substitute(refr: &PBTypeRef, bindings: &HashMap<&str, PBTypeRef>) -> io::Result<PBTypeRef> {
+		refr.substitute(bindings).map_err(not_found)
+	}
+
+	/// Decodes a value of `refr`, which mustn't contain generic parameters
+	fn decode_ref(&self, refr: &PBTypeRef, r: &mut &[u8], depth: usize) -> io::Result<DynValue> {
+		if depth > MAX_DEPTH {
+			return Err(invalid("value nested too deeply"));
+		}
+		let depth = depth + 1;
+		let tp = self.find_type(refr)?;
+		if tp.get_attrs().contains_key("@builtin") {
+			return self.decode_builtin(refr, r, depth);
+		}
+		let bindings: HashMap<&str, PBTypeRef> = tp.get_generics().0.iter()
+			.map(String::as_str)
+			.zip(refr.generics.iter().cloned())
+			.collect();
+		match tp {
+			PBTypeDef::Alias { alias, .. } => self.decode_ref(&Self::substitute(alias, &bindings)?, r, depth),
+			PBTypeDef::Struct { fields, attrs, .. } => {
+				self.decode_fields(fields, !attrs.contains_key("@sealed"), &bindings, r, depth)
+			}
+			PBTypeDef::Enum { variants, .. } => self.decode_variant(variants, &bindings, r, depth),
+		}
+	}
+
+	/// Decodes a number of a field marked `@le`, which has its bytes in the opposite order
+	fn decode_little_endian(&self, refr: &PBTypeRef, r: &mut &[u8]) -> io::Result<DynValue> {
+		let size = match refr.reference.as_str() {
+			"U16" => 2,
+			"U32" | "I32" | "F32" => 4,
+			_ => 8,
+		};
+		let mut bytes = read_bytes(r, size)?.to_vec();
+		bytes.reverse();
+		self.decode_builtin(refr, &mut &bytes[..], 0)
+	}
+
+	fn decode_builtin(&self, refr: &PBTypeRef, r: &mut &[u8], depth: usize) -> io::Result<DynValue> {
+		Ok(match refr.reference.as_str() {
+			"Void" => DynValue::Void,
+			"U8" => DynValue::U8(u8::from_be_bytes(read_array(r)?)),
+			"U16" => DynValue::U16(u16::from_be_bytes(read_array(r)?)),
+			"U32" => DynValue::U32(u32::from_be_bytes(read_array(r)?)),
+			"U64" => DynValue::U64(u64::from_be_bytes(read_array(r)?)),
+			"I32" => DynValue::I32(i32::from_be_bytes(read_array(r)?)),
+			"I64" => DynValue::I64(i64::from_be_bytes(read_array(r)?)),
+			"F32" => DynValue::F32(f32::from_be_bytes(read_array(r)?)),
+			"F64" => DynValue::F64(f64::from_be_bytes(read_array(r)?)),
+			"UInt" => DynValue::UInt(read_uint(r)?),
+			"Boolean" => match read_array(r)? {
+				[0] => DynValue::Bool(true),
+				[1] => DynValue::Bool(false),
+				[other] => return Err(invalid(format!("invalid Boolean {other}, expected 0 or 1"))),
+			},
+			"SInt" => {
+				let zigzag = read_uint(r)?;
+				DynValue::SInt((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64))
+			}
+			"Bytes" => {
+				let len = read_uint(r)?;
+				DynValue::Bytes(read_bytes(r, len)?.to_vec())
+			}
+			"String" => {
+				let len = read_uint(r)?;
+				let bytes = read_bytes(r, len)?;
+				DynValue::String(if self.strict_strings {
+					str::from_utf8(bytes).map_err(|e| invalid(e.to_string()))?.to_string()
+				} else {
+					String::from_utf8_lossy(bytes).into_owned()
+				})
+			}
+			"DateTime" => {
+				let secs = i64::from_be_bytes(read_array(r)?);
+				let nanos = u32::from_be_bytes(read_array(r)?);
+				DynValue::DateTime(secs, nanos)
+			}
+			"Array" => {
+				let [item] = &refr.generics[..] else {
+					return Err(invalid("`Array` takes one generic argument"));
+				};
+				let len = read_uint(r)?;
+				if len > MAX_LENGTH {
+					return Err(invalid("Array length too large"));
+				}
+				// every item takes at least a byte, unless it's `Void`
+				let mut items = Vec::with_capacity((len as usize).min(r.len()));
+				for _ in 0..len {
+					items.push(self.decode_ref(item, r, depth)?);
+				}
+				DynValue::Array(items)
+			}
+			"Optional" => {
+				let [value] = &refr.generics[..] else {
+					return Err(invalid("`Optional` takes one generic argument"));
+				};
+				match read_array(r)? {
+					[0] => DynValue::Optional(None),
+					[1] => DynValue::Optional(Some(Box::new(self.decode_ref(value, r, depth)?))),
+					[other] => return Err(invalid(format!("invalid Optional {other}, expected 0 or 1"))),
+				}
+			}
+			name => return Err(io::Error::new(io::ErrorKind::Unsupported, format!("unknown builtin `{name}`"))),
+		})
+	}
+
+	/// Decodes the flags of `field`, either the ones marked `@extension` or the rest
+	fn decode_flags(
+		&self, field: &'d PBField, bits: u64, extensions: bool, cx: &mut FieldsContext<'_, 'd>, r: &mut &[u8],
+	) -> io::Result<()> {
+		let Some(flags) = &field.flags else { return Ok(()) };
+		for (i, flag) in flags.iter().enumerate() {
+			if flag.attrs.contains_key("@extension") != extensions {
+				continue;
+			}
+			let set = bits & (1 << i) != 0;
+			let value = match &flag.value {
+				None => DynValue::Bool(set),
+				Some(refr) if set => {
+					let value = self.decode_ref(&Self::substitute(refr, cx.bindings)?, r, cx.depth)?;
+					DynValue::Optional(Some(Box::new(value)))
+				}
+				Some(_) => DynValue::Optional(None),
+			};
+			cx.values.insert(flag.name.as_str(), value);
+		}
+		Ok(())
+	}
+
+	fn decode_fields(
+		&self, fields: &'d [PBField], extensible: bool, bindings: &HashMap<&str, PBTypeRef>,
+		r: &mut &[u8], depth: usize,
+	) -> io::Result<DynValue> {
+		let mut cx = FieldsContext { bindings, values: HashMap::new(), depth };
+		let mut bits = HashMap::new();
+		for field in fields {
+			if field.attrs.contains_key("@extension_flags") {
+				continue;
+			}
+			if field.attrs.contains_key("@compress") {
+				return Err(io::Error::new(
+					io::ErrorKind::Unsupported,
+					format!("`{}` is compressed, which isn't supported without generated code", field.name)
+				));
+			}
+			let value = if field.attrs.contains_key("@le") {
+				self.decode_little_endian(&field.value, r)?
+			} else {
+				self.decode_ref(&Self::substitute(&field.value, bindings)?, r, depth)?
+			};
+			if field.flags.is_none() {
+				cx.values.insert(field.name.as_str(), value);
+				continue;
+			}
+			let field_bits = value.as_u64()
+				.ok_or_else(|| invalid(format!("`{}` isn't a number, so it can't hold flags", field.name)))?;
+			bits.insert(field.name.as_str(), field_bits);
+			self.decode_flags(field, field_bits, false, &mut cx, r)?;
+		}
+		if extensible {
+			let len = read_uint(r)?;
+			let extension = &mut read_bytes(r, len)?;
+			for field in fields {
+				if let Some(&field_bits) = bits.get(field.name.as_str()) {
+					self.decode_flags(field, field_bits, true, &mut cx, extension)?;
+				}
+			}
+			if let Some(field) = fields.iter().find(|f| f.attrs.contains_key("@extension_flags")) {
+				let field_bits = self.decode_ref(&Self::substitute(&field.value, bindings)?, extension, depth)?
+					.as_u64()
+					.ok_or_else(|| invalid(format!("`{}` isn't a number, so it can't hold flags", field.name)))?;
+				self.decode_flags(field, field_bits, false, &mut cx, extension)?;
+			}
+			// the rest are extensions this definition doesn't know about
+		}
+
+		let mut result = vec![];
+		for field in fields {
+			let Some(flags) = &field.flags else {
+				result.push((field.name.clone(), cx.values.remove(field.name.as_str()).unwrap_or(DynValue::Void)));
+				continue;
+			};
+			for flag in flags {
+				result.push((flag.name.clone(), cx.values.remove(flag.name.as_str()).unwrap_or(DynValue::Bool(false))));
+			}
+		}
+		Ok(DynValue::Struct(result))
+	}
+
+	fn decode_variant(
+		&self, variants: &[PBEnumVariant], bindings: &HashMap<&str, PBTypeRef>,
+		r: &mut &[u8], depth: usize,
+	) -> io::Result<DynValue> {
+		let [discriminant] = read_array(r)?;
+		let Some(variant) = variants.iter().find(|v| v.discriminant == discriminant) else {
+			let default = variants.iter().find(|v| v.attrs.contains_key("@default"))
+				.ok_or_else(|| invalid("Unknown enum discriminant; enum is not extensible"))?;
+			// unknown discriminants can only belong to `@extension` variants added later
+			let len = read_uint(r)?;
+			read_bytes(r, len)?;
+			return Ok(DynValue::Enum { variant: default.name.clone(), value: None });
+		};
+		if variant.attrs.contains_key("@extension") {
+			read_uint(r)?;
+		}
+		let value = match &variant.value {
+			Some(refr) => Some(Box::new(self.decode_ref(&Self::substitute(refr, bindings)?, r, depth)?)),
+			None => None,
+		};
+		Ok(DynValue::Enum { variant: variant.name.clone(), value })
+	}
+}
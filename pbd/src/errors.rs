@@ -11,6 +11,8 @@ pub struct PunybufError {
 	pub error: Diagnostic,
 	pub before_error: Vec<Diagnostic>,
 	pub after_error: Vec<Diagnostic>,
+	/// The errors found after this one, if the compiler could keep going
+	pub more: Vec<PunybufError>,
 }
 
 impl PunybufError {
@@ -19,9 +21,37 @@ impl PunybufError {
 			error: Diagnostic {
 				content: "".into(), span: Span::impossible(), level: InfoLevel::Info
 			},
-			display_error: true, before_error: vec![], after_error: vec![]
+			display_error: true, before_error: vec![], after_error: vec![], more: vec![]
 		}
 	}
+	/// Chains all the `errors` into one, with the first of them in front.
+	/// Returns `None` if there are no errors.
+	pub fn chain(errors: Vec<PunybufError>) -> Option<Self> {
+		let mut errors = errors.into_iter();
+		let mut first = errors.next()?;
+		first.more.extend(errors);
+		Some(first)
+	}
+	/// Displays this error and the ones after it, but no more than `max_errors` in total.
+	/// `0` means there's no limit.
+	pub fn display_at_most(&self, max_errors: usize) -> String {
+		let mut result = format!("{}\n{}", self.error.content, self.explain());
+		let shown = if max_errors == 0 { self.more.len() } else { self.more.len().min(max_errors - 1) };
+		for error in &self.more[..shown] {
+			result.push_str(&format!("\n\n{RED}{BOLD}error:{NORMAL} {}", error.display_at_most(1)));
+		}
+		match self.more.len() - shown {
+			0 => {}
+			1 => result.push_str("\n\n... and 1 more error"),
+			hidden => result.push_str(&format!("\n\n... and {hidden} more errors")),
+		}
+		result
+	}
+	/// Turns the error into a warning, which doesn't stop the compilation
+	pub fn into_warning(mut self) -> Self {
+		self.error.level = InfoLevel::Warning;
+		self
+	}
 	pub fn wrap_before(self, mut wrapper: PunybufError) -> PunybufError {
 		for d in self.before_error {
 			wrapper.before_error.push(d);
@@ -71,7 +101,7 @@ impl PunybufError {
 
 impl Display for PunybufError {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}\n{}", self.error.content, self.explain())
+		write!(f, "{}", self.display_at_most(0))
 	}
 }
 
@@ -165,7 +195,7 @@ impl Diagnostic {
 				row = row + 1,
 				line = fmt_line
 			));
-			let row_digits = (row as f64).log10().abs() as usize + 1;
+			let row_digits = (row + 1).ilog10() as usize + 1;
 			if row_digits > digits {
 				digits = row_digits;
 			}
@@ -266,6 +296,7 @@ macro_rules! pb_err {
 				before_error: e.before_error,
 				after_error: e.after_error,
 				display_error: e.explain_error,
+				more: vec![],
 				error: diagnostic!(Error,
 					$span.clone(),
 					$err
@@ -291,6 +322,7 @@ macro_rules! pb_err {
 			before_error: vec![],
 			after_error: vec![],
 			display_error: true,
+			more: vec![],
 			error: crate::errors::diagnostic!(Error,
 				$span.clone(),
 				$err
@@ -1,9 +1,9 @@
-use std::{env, fs::read_to_string, io, path::Path, rc::Rc};
+use std::{env, fs, io, path::{Path, PathBuf}, rc::Rc};
 
 use crate::{
 	diagnostic, errors::{
 		BOLD, NORMAL, PunybufError, YELLOW
-	}, lexer::{IncludeDisallowed, IncludeHandler, Lexer, Loc, Span, Token}, pb_err
+	}, lexer::{IncludeDisallowed, IncludeHandler, Lexer, Loc, Span, Token, TokenData}, pb_err
 };
 
 const COMMON: &str = include_str!("../baked/common.pbd");
@@ -14,25 +14,74 @@ fn io_err(error: &str) -> io::Error {
 /// Returns `(output_tokens, includes_common)`
 // I don't particularly like the lexer being destroyed here, so perhaps Rc<RefCell> wasn't that bad.
 // If it ever causes problems, look at fe8a47f.
-pub fn tokens_from_file<'a>(file: &'a Path) -> Result<Result<(Vec<Token>, bool), PunybufError>, io::Error> {
+/// 
+/// Identifiers and attribute names longer than `max_identifier_length` are an error,
+/// in included files too.
+///
+/// The files that were read are added to `read_files`, including the ones that couldn't be.
+/// `common` isn't a file, so it's never added.
+pub fn tokens_from_file<'a>(
+	file: &'a Path, max_identifier_length: usize, read_files: &mut Vec<PathBuf>
+) -> Result<Result<(Vec<Token>, bool), PunybufError>, io::Error> {
+	let root_path = file.parent().ok_or(io::Error::other("cannot find parent directory of a file"))?;
+	let f_str = file.to_str().ok_or(io_err("Invalid UTF-8"))?;
+	read_files.push(file.into());
+	tokens_from_source(fs::read(file)?, f_str, root_path, max_identifier_length, read_files)
+}
+/// Same as [`tokens_from_file`], but for a definition that isn't in a file, like one from stdin.
+/// `name` is what the errors call it, and the includes are relative to `include_root`.
+///
+/// Only the included files are added to `read_files`.
+pub fn tokens_from_source(
+	source: Vec<u8>, name: &str, include_root: &Path, max_identifier_length: usize, read_files: &mut Vec<PathBuf>
+) -> Result<Result<(Vec<Token>, bool), PunybufError>, io::Error> {
+	let content = read_source(source)?;
 	let mut a = FileIncludeHandler {
-		root_path: file.parent().ok_or(io::Error::other("cannot find parent directory of a file"))?.into(),
-		included: vec![
-			(file.to_str().ok_or(io_err("Invalid UTF-8"))?.to_string(), Span::impossible())
-		]
+		max_identifier_length,
+		root_path: include_root.into(),
+		included: vec![(name.to_string(), Span::impossible())]
 	};
-	let mut l = lexer_from_file(file, &mut a).map(|x| Box::new(x))?;
-	Ok(l.lex().map(|tokens| (tokens, l.includes_common)))
+	let mut l = Lexer::new(content, name, &mut a).max_identifier_length(max_identifier_length);
+	let result = l.lex().map(|tokens| (tokens, l.includes_common));
+	read_files.extend(a.included.into_iter().skip(1).filter(|(path, _)| path != "common").map(|(path, _)| PathBuf::from(path)));
+	Ok(result)
 }
 fn lexer_from_file<'a>(file: &'a Path, include_handler: &'a mut FileIncludeHandler) -> Result<Lexer<'a, FileIncludeHandler>, io::Error> {
-	let content = read_to_string(&file)?;
+	let content = read_source(fs::read(file)?)?;
 
 	let f_str = file.to_str().ok_or(io_err("Invalid UTF-8"))?;
+	let max_identifier_length = include_handler.max_identifier_length;
+
+	Ok(Lexer::new(content, f_str, include_handler).max_identifier_length(max_identifier_length))
+}
+
+/// Decodes the contents of a .pbd file, which must be UTF-8, without the byte order mark if it has one
+fn read_source(mut bytes: Vec<u8>) -> Result<String, io::Error> {
+	let utf16 = match bytes.get(..2) {
+		Some([0xFF, 0xFE]) => Some("UTF-16LE"),
+		Some([0xFE, 0xFF]) => Some("UTF-16BE"),
+		_ => None,
+	};
+	if let Some(encoding) = utf16 {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+			"the file is encoded as {encoding}, but only UTF-8 is supported. Convert it first, e.g. with `iconv -f {encoding} -t UTF-8`"
+		)));
+	}
 
-	Ok(Lexer::new(content, f_str, include_handler))
+	let bom = if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) { 3 } else { 0 };
+	bytes.drain(..bom);
+	String::from_utf8(bytes).map_err(|e| {
+		let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+		let line = valid.iter().filter(|b| **b == b'\n').count() + 1;
+		io::Error::new(io::ErrorKind::InvalidData, format!(
+			"the file isn't valid UTF-8: invalid byte at offset {} (line {line})",
+			bom + valid.len()
+		))
+	})
 }
 
 struct FileIncludeHandler {
+	max_identifier_length: usize,
 	root_path: Box<Path>,
 	included: Vec<(String, Span)>
 }
@@ -134,4 +183,168 @@ impl IncludeHandler for FileIncludeHandler {
 		}
 	
 	}
-}
\ No newline at end of file
+}
+/// Records the includes of a file instead of including them
+struct IncludeRecorder {
+	includes: Vec<(String, Span)>,
+}
+
+impl IncludeHandler for IncludeRecorder {
+	fn handle_include(&mut self, include_path: String, include_span: Span) -> Result<Vec<Token>, PunybufError> {
+		self.includes.push((include_path, include_span));
+		Ok(vec![])
+	}
+}
+
+/// Returns the contents of `file` with every file it includes inlined, and warnings
+/// about the bundle. `include common` is kept once at the top, unless `inline_common`.
+pub fn bundle_file(file: &Path, inline_common: bool, max_identifier_length: usize) -> Result<Result<(String, Vec<PunybufError>), PunybufError>, io::Error> {
+	let f_str = file.to_str().ok_or(io_err("Invalid UTF-8"))?;
+	let mut bundler = Bundler {
+		max_identifier_length,
+		inline_common,
+		root_path: file.parent().ok_or(io::Error::other("cannot find parent directory of a file"))?.into(),
+		included: vec![f_str.to_string()],
+		warnings: vec![],
+	};
+	let content = read_source(fs::read(file)?)?;
+	let mut bundle = format!("# Bundled from \"{f_str}\" by pbd\n");
+	Ok(bundler.bundle(content, f_str, 0).map(|(source, _)| {
+		if !bundler.inline_common && bundler.included.iter().any(|i| i == "common") {
+			bundle.push_str("include common\n");
+		}
+		bundle.push_str(&source);
+		(bundle, bundler.warnings)
+	}))
+}
+
+struct Bundler {
+	max_identifier_length: usize,
+	inline_common: bool,
+	root_path: Box<Path>,
+	/// The paths already inlined, and `common` once it is
+	included: Vec<String>,
+	warnings: Vec<PunybufError>,
+}
+
+impl Bundler {
+	/// Inlines the includes of `content`, which starts on `layer`.
+	/// Returns the bundled source, and the layer it ends on.
+	fn bundle(&mut self, content: String, file_name: &str, mut layer: u32) -> Result<(String, u32), PunybufError> {
+		let mut recorder = IncludeRecorder { includes: vec![] };
+		let tokens = Lexer::new(content.clone(), file_name, &mut recorder)
+			.max_identifier_length(self.max_identifier_length)
+			.lex()?;
+		// the layers declared in this file, with where they're declared
+		let mut layers = tokens.windows(2).filter_map(|pair| match (&pair[0].data, &pair[1].data) {
+			(TokenData::LayerKeyword, TokenData::Numeric(layer)) if !pair[0].span.is_implicit_layer_definition() => {
+				Some(((pair[0].span.start().row, pair[0].span.start().col), *layer))
+			}
+			_ => None,
+		}).peekable();
+
+		let mut result = String::new();
+		let mut includes = recorder.includes.into_iter().peekable();
+		for (row, line) in content.split_inclusive('\n').enumerate() {
+			let Some((path, span)) = includes.next_if(|(_, span)| span.start().row == row) else {
+				result.push_str(line);
+				continue;
+			};
+			while let Some((_, declared)) = layers.next_if(|(at, _)| *at < (row, span.start().col)) {
+				layer = declared;
+			}
+			// `include` takes the rest of the line, so only keep what's before it
+			let before: String = line.chars().take(span.start().col).collect();
+			let before = &before[..before.rfind("include").unwrap_or(before.len())];
+			if !before.trim().is_empty() {
+				result.push_str(before.trim_end());
+				result.push('\n');
+			}
+			let (inlined, included) = self.inline(path, span, layer)?;
+			result.push_str(&inlined);
+			if included {
+				// every included file ends with an implicit `layer 0:`
+				layer = 0;
+			}
+		}
+		for (_, declared) in layers {
+			layer = declared;
+		}
+		Ok((result, layer))
+	}
+
+	/// The source replacing `include {path}`, and whether it was included,
+	/// which resets the layer to 0
+	fn inline(&mut self, path: String, span: Span, layer: u32) -> Result<(String, bool), PunybufError> {
+		if path == "common" {
+			let first = !self.included.iter().any(|i| i == "common");
+			if first {
+				self.included.push(path.clone());
+			}
+			if !self.inline_common {
+				// it's at the top of the bundle, so only the implicit `layer 0:` after it is left
+				if first && layer != 0 {
+					self.warnings.push(pb_err!(
+						span,
+						format!("`include common` is on layer {layer}, so the bundle goes back to layer 0 after it"),
+						after_error: vec![
+							diagnostic!(Tip,
+								Span::impossible(),
+								"use `--allow-nonmonotonic-layers` when compiling the bundle".to_string()
+							)
+						]
+					));
+					return Ok(("layer 0:\n".to_string(), true));
+				}
+				return Ok((String::new(), first));
+			}
+			if !first {
+				return Ok((String::new(), false));
+			}
+			return self.inline_source(COMMON.to_string(), "<common>", path, span, layer).map(|s| (s, true));
+		}
+		let real_path = self.root_path.join(Path::new(&path));
+		let rp_string = real_path.to_str().unwrap().to_string();
+		if self.included.contains(&rp_string) {
+			// the compiler would ignore it with a warning, so the bundle just leaves it out
+			return Ok((format!("# \"{path}\" is already included above\n"), false));
+		}
+		self.included.push(rp_string.clone());
+
+		let content = fs::read(&real_path).and_then(read_source).map_err(|err| {
+			pb_err!(
+				span,
+				format!("I/O error while including \"{}\": {err}", real_path.display()),
+				after_error: vec![
+					diagnostic!(Tip,
+						Span::impossible(),
+						format!("does this file exist?")
+					)
+				]
+			)
+		})?;
+		self.inline_source(content, &rp_string, path, span, layer).map(|s| (s, true))
+	}
+
+	fn inline_source(&mut self, content: String, file_name: &str, path: String, span: Span, layer: u32) -> Result<String, PunybufError> {
+		let (mut inlined, end_layer) = self.bundle(content, file_name, layer)?;
+		if !inlined.ends_with('\n') {
+			inlined.push('\n');
+		}
+		if end_layer != 0 {
+			// the implicit `layer 0:` has to be spelled out, which makes the layers go down
+			self.warnings.push(pb_err!(
+				span,
+				format!("\"{path}\" ends on layer {end_layer}, so the bundle goes back to layer 0 after it"),
+				after_error: vec![
+					diagnostic!(Tip,
+						Span::impossible(),
+						format!("use `--allow-nonmonotonic-layers` when compiling the bundle")
+					)
+				]
+			));
+			inlined.push_str("layer 0:\n");
+		}
+		Ok(format!("# included from \"{path}\"\n{inlined}"))
+	}
+}
@@ -2,10 +2,11 @@ use crc::{Crc, CRC_32_CKSUM};
 use std::collections::HashMap;
 
 use crate::{
+	attributes,
 	errors::{PunybufError, parser_err},
 	lexer::Span,
 	parser::{
-		CommandArgument, Declaration, DeclarationValue, EnumVariant, Field,
+		CommandArgument, Declaration, DeclarationValue, EnumVariant, Field, FieldFlag,
 		FlexibleDeclarationValue, ValueEnumVariant, ValueReference,
 	}, pb_err,
 };
@@ -14,7 +15,7 @@ pub(crate) const PB_CRC: Crc<u32> = Crc::<u32>::new(&CRC_32_CKSUM);
 
 #[derive(Debug, Clone)]
 #[allow(unused)]
-pub(crate) struct PBTypeRef {
+pub struct PBTypeRef {
 	pub(crate) reference: String,
 	pub(crate) reference_span: Span,
 	pub(crate) generics: Vec<PBTypeRef>,
@@ -33,6 +34,48 @@ pub(crate) struct PBTypeRef {
 	pub(crate) is_global: bool,
 }
 
+#[allow(unused)]
+impl PBTypeRef {
+	/// A resolved reference to `name` on `layer`, like the ones in the JSON IR.
+	/// 
+	/// References to generic parameters have no layer.
+	pub fn new(name: impl Into<String>, layer: Option<u32>, generics: Vec<PBTypeRef>, is_highest_layer: bool) -> Self {
+		Self {
+			reference: name.into(),
+			reference_span: Span::impossible(),
+			generics,
+			generic_span: Span::impossible(),
+			resolved_layer: layer,
+			is_highest_layer,
+			is_global: layer.is_some(),
+		}
+	}
+	pub fn name(&self) -> &str {
+		&self.reference
+	}
+	pub fn layer(&self) -> Option<u32> {
+		self.resolved_layer
+	}
+	pub fn generics(&self) -> &[PBTypeRef] {
+		&self.generics
+	}
+	pub fn is_highest_layer(&self) -> bool {
+		self.is_highest_layer
+	}
+	/// Replaces the generic parameters in this reference with the types they're bound to
+	pub(crate) fn substitute(&self, bindings: &HashMap<&str, PBTypeRef>) -> Result<PBTypeRef, String> {
+		if !self.is_global {
+			return bindings.get(self.reference.as_str()).cloned()
+				.ok_or_else(|| format!("unbound generic parameter `{}`", self.reference));
+		}
+		let mut refr = self.clone();
+		for generic in &mut refr.generics {
+			*generic = generic.substitute(bindings)?;
+		}
+		Ok(refr)
+	}
+}
+
 #[derive(Debug, Clone)]
 #[allow(unused)]
 pub(crate) struct PBFieldFlag {
@@ -155,6 +198,16 @@ impl PBTypeDef {
 			Self::Struct { is_highest_layer, .. } => *is_highest_layer
 		}
 	}
+	/// The variant of an enum with a single variant, which carries a value.
+	///
+	/// Such an enum can't have a `@default` variant, so it's just its value after
+	/// a discriminant that's always the same.
+	pub(crate) fn single_variant(&self) -> Option<&PBEnumVariant> {
+		match self {
+			Self::Enum { variants, .. } if variants.len() == 1 && variants[0].value.is_some() => variants.first(),
+			_ => None
+		}
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -190,6 +243,10 @@ pub struct PunybufDefinition {
 	pub(crate) types: Vec<PBTypeDef>,
 	pub(crate) commands: Vec<PBCommandDef>,
 	pub(crate) includes_common: bool,
+	/// Definition-level attributes, written as `@attribute;`
+	pub(crate) attrs: HashMap<String, Option<String>>,
+	/// Command IDs no command may have, written as `reserve_id 12345`
+	pub(crate) reserved_ids: Vec<(u32, Span)>,
 	context_inline_owner: Option<(String, Span)>,
 }
 
@@ -199,9 +256,35 @@ impl PunybufDefinition {
 			types: vec![],
 			commands: vec![],
 			includes_common,
+			attrs: HashMap::new(),
+			reserved_ids: vec![],
 			context_inline_owner: None,
 		}
 	}
+	/// Whether the type is marked `@experimental`, either directly or
+	/// through the command or type it was declared inline in.
+	pub(crate) fn is_experimental(&self, tp: &PBTypeDef) -> bool {
+		if tp.get_attrs().contains_key("@experimental") {
+			return true;
+		}
+		let Some((owner, _)) = tp.get_inline_owner() else {
+			return false;
+		};
+		self.commands.iter().any(|cmd| cmd.name == *owner && cmd.attrs.contains_key("@experimental")) ||
+		self.types.iter().any(|typ| typ.get_name().0 == owner && self.is_experimental(typ))
+	}
+	/// The type a resolved reference points to
+	pub(crate) fn find_type(&self, refr: &PBTypeRef) -> Result<&PBTypeDef, String> {
+		self.types.iter()
+			.find(|tp| tp.get_name().0 == refr.reference && Some(*tp.get_layer()) == refr.resolved_layer)
+			.ok_or_else(|| format!("unknown type `{}`", refr.reference))
+	}
+	/// The highest layer any type or command is declared in
+	pub(crate) fn highest_layer(&self) -> u32 {
+		let types = self.types.iter().map(|tp| *tp.get_layer());
+		let commands = self.commands.iter().map(|cmd| cmd.layer);
+		types.chain(commands).max().unwrap_or(0)
+	}
 }
 
 impl PunybufDefinition {
@@ -348,7 +431,7 @@ impl PunybufDefinition {
 					is_highest_layer: false,
 				})
 			}
-			FlexibleDeclarationValue::StructDeclaration { inline, layer, fields } => {
+			FlexibleDeclarationValue::StructDeclaration { inline, layer, fields, .. } => {
 				if inline_owner == None && inline {
 					panic!("bad state: root-level declaration marked inline")
 				}
@@ -381,10 +464,66 @@ impl PunybufDefinition {
 	}
 }
 
-pub(crate) fn flatten(decls: Vec<Declaration>, includes_common: bool) -> Result<PunybufDefinition, PunybufError> {
+/// The flag field holding the optional fields of a `@bitmap` struct, sized to their count.
+/// It goes before all other fields
+fn bitmap_field(
+	name_span: &Span, attrs: &HashMap<String, Option<String>>,
+	optional_fields: Vec<FieldFlag>, includes_common: bool
+) -> Result<Option<Field>, PunybufError> {
+	let Some(first) = optional_fields.first() else {
+		return Ok(None);
+	};
+	if !attrs.contains_key("@bitmap") {
+		return Err(pb_err!(
+			first.name_span,
+			format!("optional fields may only be defined on flag fields"),
+			after_error: vec![
+				diagnostic!(Tip,
+					name_span.clone(),
+					format!("mark this struct `@bitmap` to collect its optional fields into one flag field")
+				)
+			]
+		));
+	}
+	if !includes_common {
+		return Err(parser_err!(
+			name_span, "`@bitmap` structs need `include common` for the type of their bitmap"
+		));
+	}
+	let bitmap_type = match optional_fields.len() {
+		0..=8 => "U8",
+		9..=16 => "U16",
+		17..=32 => "U32",
+		33..=64 => "U64",
+		n => return Err(pb_err!(
+			optional_fields[64].name_span,
+			format!("a `@bitmap` struct can have at most 64 optional fields, got {n}")
+		)),
+	};
+	Ok(Some(Field {
+		name: "0_bitmap".to_string(),
+		name_span: name_span.clone(),
+		value: ValueReference::Reference {
+			name: bitmap_type.to_string(),
+			name_span: name_span.clone(),
+			generics: vec![],
+			generic_span: Span::impossible(),
+		},
+		flags: Some(optional_fields),
+		attrs: HashMap::new(),
+		doc: String::new(),
+	}))
+}
+
+pub(crate) fn flatten(
+	decls: Vec<Declaration>, attrs: HashMap<String, Option<String>>,
+	reserved_ids: Vec<(u32, Span)>, includes_common: bool
+) -> Result<PunybufDefinition, PunybufError> {
 	let mut def = PunybufDefinition::new(includes_common);
+	def.attrs = attrs;
+	def.reserved_ids = reserved_ids;
 
-	for decl in decls {
+	for mut decl in decls {
 		match decl.value {
 			DeclarationValue::CommandDeclaration { argument, argument_span, layer, ret, err, err_span } => {
 				let pb_arg = match argument {
@@ -416,19 +555,15 @@ pub(crate) fn flatten(decls: Vec<Declaration>, includes_common: bool) -> Result<
 
 				let ret = def.flatten_reference(*ret);
 
-				let command_id = if let Some(Some(override_id)) = decl.attrs.get("@id") {
+				let command_id = if let Some(override_id) = attributes::number(&decl.attrs, "@id") {
 					if let Some(_) = decl.attrs.get("@name") {
 						return Err(pb_err!(
 							decl.symbol_span,
 							format!("cannot have both @id and @name on one command")
 						));
 					}
-					override_id.parse::<u32>().map_err(|e|
-						pb_err!(
-							decl.symbol_span,
-							format!("failed to parse @id({override_id}): {e}")
-						)
-					)?
+					// `@id` is at most `u32::MAX`, see `attributes::KNOWN_ATTRIBUTES`
+					override_id as u32
 				} else {
 					let attr = decl.attrs.get("@name");
 					PB_CRC.checksum(format!(
@@ -465,7 +600,17 @@ pub(crate) fn flatten(decls: Vec<Declaration>, includes_common: bool) -> Result<
 					is_highest_layer: false,
 				});
 			}
-			DeclarationValue::Flexible { val, generic_params, generic_span, .. } => {
+			DeclarationValue::Flexible { mut val, generic_params, generic_span, .. } => {
+				// `@void` implies `@builtin`, so codegens never generate `Void`
+				if decl.attrs.contains_key("@void") {
+					decl.attrs.entry("@builtin".to_string()).or_insert(None);
+				}
+				if let FlexibleDeclarationValue::StructDeclaration { fields, optional_fields, .. } = &mut val {
+					let optional_fields = std::mem::take(optional_fields);
+					if let Some(bitmap) = bitmap_field(&decl.symbol_span, &decl.attrs, optional_fields, includes_common)? {
+						fields.insert(0, bitmap);
+					}
+				}
 				def.flatten_flexible_decl(
 					decl.symbol,
 					decl.symbol_span,
@@ -4,7 +4,7 @@ use std::{
 	rc::Rc,
 };
 
-use crate::{errors::{ErrorInfo, PunybufError, parser_err}, pb_err};
+use crate::{errors::{diagnostic, ErrorInfo, PunybufError, parser_err}, pb_err};
 
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) enum TokenData {
@@ -28,6 +28,8 @@ pub(crate) enum TokenData {
 
 	Docs(String),
 	Attribute(String, Option<String>),
+	/// `"..."`, with the escapes (`\n`, `\"` and `\\`) already replaced
+	StringLiteral(String),
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -48,6 +50,8 @@ impl Loc {
 	}
 }
 
+const IMPLICIT_LAYER_DEFINITION: &str = "<implicit layer definition>";
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct Span {
 	pub(crate) loc_start: Loc,
@@ -89,6 +93,10 @@ impl Span {
 	pub fn end(&self) -> &Loc {
 		&self.loc_end
 	}
+	/// Whether this span points to the `layer 0:` added at the end of every file
+	pub(crate) fn is_implicit_layer_definition(&self) -> bool {
+		self.file_contents.ends_with(IMPLICIT_LAYER_DEFINITION)
+	}
 }
 
 #[derive(PartialEq, Eq)]
@@ -162,6 +170,9 @@ impl Token {
 			TokenData::LayerKeyword => {
 				loc_end.col = loc.col + "layer".len();
 			},
+			// The escapes make the value shorter than the source,
+			// so the lexer uses `token_end_loc` for these instead
+			TokenData::StringLiteral(_) => {}
 			TokenData::Equals | TokenData::Colon | TokenData::Comma |
 			TokenData::Semicolon | TokenData::Bang | TokenData::Dot |
 			TokenData::Question => {}
@@ -200,6 +211,7 @@ impl Display for Token {
 			TokenData::Symbol(val) => val.clone(),
 			TokenData::Attribute(attr, val) =>
 				if let Some(val) = val { format!("{}({})", attr, val) } else { attr.clone() },
+			TokenData::StringLiteral(val) => format!("{val:?}"),
 		})?;
 		Ok(())
 	}
@@ -216,12 +228,20 @@ impl IncludeHandler for IncludeDisallowed {
 	}
 }
 
+/// The default limit on the length of identifiers and attribute names, in bytes
+pub const DEFAULT_MAX_IDENTIFIER_LENGTH: usize = 1024;
+/// The limit on the length of a doc block, in bytes
+const MAX_DOC_LENGTH: usize = 64 * 1024;
+/// The limit on the length of a string literal, in bytes
+const MAX_STRING_LENGTH: usize = 64 * 1024;
+
 pub struct Lexer<'a, I> {
 	pub(crate) contents: Rc<String>,
 	pub(crate) file_name: &'a str,
 	pub(crate) current_loc: Loc,
 	pub(crate) include_handler: &'a mut I,
 	pub(crate) includes_common: bool,
+	max_identifier_length: usize,
 }
 
 impl<'a, I: IncludeHandler> Lexer<'a, I> {
@@ -234,10 +254,15 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 			current_loc: Loc::zero(),
 			include_handler,
 			includes_common: false,
+			max_identifier_length: DEFAULT_MAX_IDENTIFIER_LENGTH,
 		}
 	}
+	/// Limit the length of identifiers and attribute names, in bytes
+	pub fn max_identifier_length(mut self, max: usize) -> Self {
+		self.max_identifier_length = max;
+		self
+	}
 	fn implicit_layer_definition(&self) -> Span {
-		const IMPLICIT_LAYER_DEFINITION: &str = "<implicit layer definition>";
 		Span {
 			loc_start: Loc { row: self.current_loc.row + 1, col: 0 },
 			loc_end: Loc { row: self.current_loc.row + 1, col: IMPLICIT_LAYER_DEFINITION.len() },
@@ -295,6 +320,13 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 			error
 		)
 	}
+	fn too_long(&self, what: &str, max: usize, tip: Option<&str>) -> PunybufError {
+		let mut error = self.lex_error(format!("{what} is longer than {max} bytes"));
+		if let Some(tip) = tip {
+			error.after_error.push(diagnostic!(Tip, Span::impossible(), tip.to_string()));
+		}
+		error
+	}
 	fn lex_internal<Iter>(
 		&mut self, tokens: &mut Vec<Token>, peekable: &mut Peekable<Iter>, stop_on: Option<char>
 	) -> Result<bool, PunybufError>
@@ -324,6 +356,9 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 								}
 
 								doc.push(x);
+								if doc.len() > MAX_DOC_LENGTH {
+									return Err(self.too_long("this doc-comment", MAX_DOC_LENGTH, None));
+								}
 							}
 
 							if !stopped {
@@ -351,6 +386,58 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 						continue;
 					}
 				}
+				'/' if peekable.peek() == Some(&'/') => {
+					while let Some(x) = peekable.next() {
+						self.current_loc.col += 1;
+						if x == '\n' {
+							self.current_loc.col = 0;
+							self.current_loc.row += 1;
+							break;
+						}
+					}
+					continue;
+				}
+				'/' if peekable.peek() == Some(&'*') => {
+					peekable.next();
+					let loc_begin = self.current_loc.clone();
+					self.current_loc.col += 2;
+
+					// nested like `#[ ... ]`, so that commenting out a block with comments works
+					let mut nesting = 1;
+					while let Some(x) = peekable.next() {
+						self.current_loc.col += 1;
+						match (x, peekable.peek()) {
+							('\n', _) => {
+								self.current_loc.col = 0;
+								self.current_loc.row += 1;
+							}
+							('/', Some('*')) => {
+								peekable.next();
+								self.current_loc.col += 1;
+								nesting += 1;
+							}
+							('*', Some('/')) => {
+								peekable.next();
+								self.current_loc.col += 1;
+								nesting -= 1;
+								if nesting == 0 {
+									break;
+								}
+							}
+							_ => {}
+						}
+					}
+
+					if nesting > 0 {
+						self.current_loc = loc_begin;
+						return Err(self.lex_error(format!(
+							"expected `*/` to end the comment at {}:{}:{}",
+							self.file_name,
+							self.current_loc.row + 1, self.current_loc.col + 1
+						)));
+					}
+					continue;
+				}
 				' ' | '\r' | '\t' => {}
 				'\n' => {
 					self.current_loc.col = 0;
@@ -455,11 +542,66 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 					tokens.push(bracks);
 					continue;
 				}
+				'"' => {
+					let mut string = String::new();
+					// Right after the opening quote
+					let mut loc_end = Loc { row: self.current_loc.row, col: self.current_loc.col + 1 };
+
+					let mut stopped = false;
+					while let Some(chn) = peekable.next() {
+						loc_end.col += 1;
+						match chn {
+							'"' => {
+								stopped = true;
+								break;
+							}
+							'\\' => {
+								loc_end.col += 1;
+								match peekable.next() {
+									Some('n') => string.push('\n'),
+									Some('"') => string.push('"'),
+									Some('\\') => string.push('\\'),
+									Some(other) => {
+										self.current_loc = Loc { row: loc_end.row, col: loc_end.col - 2 };
+										return Err(self.lex_error(format!(
+											"unknown escape `\\{other}` in the string literal; \
+											only `\\n`, `\\\"` and `\\\\` are supported"
+										)));
+									}
+									None => break,
+								}
+							}
+							'\n' => {
+								loc_end.col = 0;
+								loc_end.row += 1;
+								string.push(chn);
+							}
+							_ => string.push(chn),
+						}
+						if string.len() > MAX_STRING_LENGTH {
+							return Err(self.too_long("this string literal", MAX_STRING_LENGTH, None));
+						}
+					}
+
+					if !stopped {
+						return Err(self.lex_error(format!(
+							"expected a closing quote (`\"`) to end the string literal at {}:{}:{}",
+							self.file_name,
+							self.current_loc.row + 1, self.current_loc.col + 1
+						)));
+					}
+
+					let tk = self.token_end_loc(TokenData::StringLiteral(string), loc_end.clone());
+					self.current_loc = loc_end;
+					tokens.push(tk);
+					continue;
+				}
 				'@' => {
 					let mut attr = ch.to_string();
 					let mut value: Option<String> = None;
 					while let Some(chn) = peekable.peek() {
-						if chn.is_whitespace() {
+						if chn.is_whitespace() || *chn == ';' {
+							// `;` ends definition-level attributes
 							break;
 						} else if *chn == '(' {
 							_ = peekable.next().unwrap();
@@ -496,6 +638,12 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 						} else {
 							let chn = peekable.next().unwrap();
 							attr.push(chn);
+							if attr.len() > self.max_identifier_length {
+								return Err(self.too_long(
+									"this attribute's name", self.max_identifier_length,
+									Some("you can raise the limit with `--max-identifier-length`")
+								));
+							}
 						}
 					}
 					let tk = self.token(TokenData::Attribute(attr, value));
@@ -514,6 +662,12 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 							if chn.is_alphanumeric() || *chn == '_' {
 								let chn = peekable.next().unwrap();
 								symbol.push(chn);
+								if symbol.len() > self.max_identifier_length {
+									return Err(self.too_long(
+										"this identifier", self.max_identifier_length,
+										Some("you can raise the limit with `--max-identifier-length`")
+									));
+								}
 							} else {
 								break;
 							}
@@ -579,8 +733,18 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 
 					} else if ch.is_ascii_digit() {
 						let mut string = ch.to_string();
+						// `0x1F` and `0b1010`
+						let (radix, base) = match (ch, peekable.peek()) {
+							('0', Some('x')) => (16, Some("hexadecimal")),
+							('0', Some('b')) => (2, Some("binary")),
+							_ => (10, None),
+						};
+						if base.is_some() {
+							string.push(peekable.next().unwrap());
+						}
 						while let Some(chn) = peekable.peek() {
-							if chn.is_ascii_digit() || *chn == '_' {
+							// invalid digits are taken too, so that they can be reported
+							if chn.is_ascii_digit() || *chn == '_' || base.is_some() && chn.is_alphanumeric() {
 								let chn = peekable.next().unwrap();
 								string.push(chn);
 							} else {
@@ -588,20 +752,37 @@ impl<'a, I: IncludeHandler> Lexer<'a, I> {
 							}
 						}
 
-						let number: u32 = match string.parse() {
+						let mut loc_end = self.current_loc.clone();
+						loc_end.col += string.chars().count();
+						let span_of = |start: usize, end: usize| Span {
+							loc_start: Loc { row: self.current_loc.row, col: self.current_loc.col + start },
+							loc_end: Loc { row: self.current_loc.row, col: self.current_loc.col + end },
+							file_name: self.file_name.to_string(),
+							file_contents: self.contents.clone()
+						};
+
+						let prefix_len = if base.is_some() { 2 } else { 0 };
+						let digits: String = string.chars().skip(prefix_len).filter(|ch| *ch != '_').collect();
+						if let Some(base) = base {
+							if let Some((i, invalid)) = string.chars().enumerate().skip(prefix_len)
+								.find(|(_, ch)| *ch != '_' && !ch.is_digit(radix))
+							{
+								return Err(parser_err!(span_of(i, i + 1), "`{invalid}` is not a {base} digit"));
+							}
+							if digits.is_empty() {
+								return Err(parser_err!(span_of(0, prefix_len), "expected {base} digits after `{string}`"));
+							}
+						}
+						let number = match u32::from_str_radix(&digits, radix) {
 							Ok(x) => x,
 							Err(err) => {
-								let mut loc_end = self.current_loc.clone();
-								loc_end.col += string.len();
-
-								return Err(parser_err!(Span {
-									loc_start: self.current_loc.clone(), loc_end,
-									file_name: self.file_name.to_string(),
-									file_contents: self.contents.clone()
-								}, "invalid number: {err}"))
+								return Err(parser_err!(span_of(0, string.chars().count()), "invalid number: {err}"))
 							}
 						};
-						tokens.push(self.token(TokenData::Numeric(number)));
+						let tk = self.token_end_loc(TokenData::Numeric(number), loc_end);
+						self.current_loc = tk.span.loc_end.clone();
+						tokens.push(tk);
+						continue;
 
 					} else {
 						return Err(self.lex_error(format!("unexpected character '{ch}', wild!")));
@@ -1,4 +1,6 @@
 mod lexer;
+mod attributes;
+mod lints;
 mod binary_compat;
 mod converter;
 mod errors;
@@ -8,42 +10,89 @@ mod resolver;
 mod flattener;
 mod validator;
 mod codegen;
+mod dynamic;
 
-use std::{io, path::{Path}};
+use std::{collections::HashMap, io, path::{Path}};
 
 use crate::{
-	errors::PunybufError, flattener::PunybufDefinition,
+	errors::PunybufError, flattener::PunybufDefinition, lexer::Span,
 	parser::{Declaration, Parser}, resolver::LayerResolver
 };
 
 pub struct PunybufParser;
 
 pub use crate::{
-	converter::convert_full_definition,
+	converter::{convert_full_definition, parse_full_definition, pbtyperef_from_json, pbtyperef_to_json},
+	dynamic::{DynDecoder, DynValue},
+	flattener::PBTypeRef,
+	lints::{Lint, LintLevel, Lints},
 	codegen::*
 };
 
 pub struct Parsed {
 	declarations: Vec<Declaration>,
-	includes_common: bool
+	attrs: HashMap<String, Option<String>>,
+	reserved_ids: Vec<(u32, Span)>,
+	includes_common: bool,
+	lints: Lints,
+	/// Warnings from the lints checked while parsing
+	warnings: Vec<PunybufError>,
 }
 
 impl PunybufParser {
 	pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Result<Parsed, PunybufError>, io::Error> {
-		let (tokens, includes_common) = match files::tokens_from_file(path.as_ref()) {
+		Self::parse_file_with(path, false)
+	}
+	/// Like [`Self::parse_file`], but optionally allows declaring a layer lower than a
+	/// previously declared one (`--allow-nonmonotonic-layers`)
+	pub fn parse_file_with<P: AsRef<Path>>(path: P, allow_nonmonotonic_layers: bool) -> Result<Result<Parsed, PunybufError>, io::Error> {
+		let mut lints = Lints::default();
+		if allow_nonmonotonic_layers {
+			lints = lints.set(Lint::NonmonotonicLayers, LintLevel::Allow);
+		}
+		Self::parse_file_with_lints(path, lints)
+	}
+	/// Like [`Self::parse_file`], but with the levels of the lints set by `lints`.
+	/// They're also used when [resolving](Parsed::resolve) the result
+	pub fn parse_file_with_lints<P: AsRef<Path>>(path: P, lints: Lints) -> Result<Result<Parsed, PunybufError>, io::Error> {
+		let (tokens, includes_common) = match files::tokens_from_file(path.as_ref(), lexer::DEFAULT_MAX_IDENTIFIER_LENGTH, &mut vec![]) {
 			Ok(v) => match v {
 				Ok(v) => v,
 				Err(e) => return Ok(Err(e))
 			}
 			Err(e) => return Err(e)
 		};
+
+		if let Err(e) = attributes::validate_tokens(&tokens) {
+			return Ok(Err(e));
+		}
+
+		let mut problems = vec![];
+		let (mut warnings, mut denied) = (vec![], vec![]);
+		attributes::unknown_attributes(&tokens, &mut problems);
+		for problem in problems {
+			lints.report(Lint::UnknownAttribute, problem, &mut warnings, &mut denied);
+		}
+		if let Some(e) = PunybufError::chain(denied) {
+			return Ok(Err(e));
+		}
 		
-		let declarations = match Parser::new(&tokens).parse() {
+		let mut parser = Parser::new(&tokens).lints(lints);
+		let declarations = match parser.parse() {
 			Ok(v) => v,
 			Err(e) => return Ok(Err(e))
 		};
+		warnings.append(&mut parser.warnings);
 
-		Ok(Ok(Parsed { declarations, includes_common }))
+		Ok(Ok(Parsed {
+			declarations, attrs: parser.attrs, reserved_ids: parser.reserved_ids,
+			includes_common, lints, warnings
+		}))
+	}
+	/// Inlines every file `path` includes, returning one self-contained definition
+	/// and warnings about it. `include common` is kept, unless `inline_common`
+	pub fn bundle_file<P: AsRef<Path>>(path: P, inline_common: bool) -> Result<Result<(String, Vec<PunybufError>), PunybufError>, io::Error> {
+		files::bundle_file(path.as_ref(), inline_common, lexer::DEFAULT_MAX_IDENTIFIER_LENGTH)
 	}
 }
 
@@ -53,9 +102,14 @@ impl Parsed {
 	}
 	/// Resolves and validates the token tree
 	pub fn resolve(self, should_resolve_aliases: bool) -> Result<PunybufDefinition, PunybufError> {
-		let mut definition = flattener::flatten(self.declarations, self.includes_common)?;
-		definition.validate()?;
+		self.resolve_with_warnings(should_resolve_aliases).map(|(definition, _)| definition)
+	}
+	/// Resolves and validates the token tree, also returning the warnings produced by the lints
+	pub fn resolve_with_warnings(self, should_resolve_aliases: bool) -> Result<(PunybufDefinition, Vec<PunybufError>), PunybufError> {
+		let mut definition = flattener::flatten(self.declarations, self.attrs, self.reserved_ids, self.includes_common)?;
+		let mut warnings = self.warnings;
+		warnings.append(&mut definition.validate(self.lints)?);
 		LayerResolver::new(should_resolve_aliases).resolve(&mut definition);
-		Ok(definition)
+		Ok((definition, warnings))
 	}
 }
\ No newline at end of file
@@ -0,0 +1,124 @@
+//! Lints: problems that don't make a definition invalid, but are likely mistakes.
+//!
+//! Every lint has a [level](LintLevel), which decides whether it's ignored,
+//! reported as a warning, or reported as an error. `--strict` denies all of them.
+
+use std::fmt::Display;
+
+use crate::{
+	errors::{diagnostic, PunybufError},
+	lexer::Span,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lint {
+	/// A flag has a value that is always empty, like `Void` or an empty struct
+	EmptyFlagValue,
+	/// A type isn't referenced by any other type or command
+	UnusedType,
+	/// A generic parameter isn't used in the type's body
+	UnusedGeneric,
+	/// A type or variant name doesn't start with an uppercase letter, or a
+	/// field, flag or command name doesn't start with a lowercase one
+	Naming,
+	/// An attribute the compiler doesn't know, and which isn't namespaced like `@rust:ignore`
+	UnknownAttribute,
+	/// A layer is declared after a higher one in the same file
+	NonmonotonicLayers,
+	/// An enum has a single variant, which carries a value
+	SingleVariantEnum,
+	/// A type can't be reached from any command, even through other types
+	UnreachableType,
+}
+
+impl Lint {
+	pub const ALL: [Lint; 8] = [
+		Lint::EmptyFlagValue,
+		Lint::UnusedType,
+		Lint::UnusedGeneric,
+		Lint::Naming,
+		Lint::UnknownAttribute,
+		Lint::NonmonotonicLayers,
+		Lint::SingleVariantEnum,
+		Lint::UnreachableType,
+	];
+	/// The name used on the command line, like `unused-type`
+	pub fn name(self) -> &'static str {
+		match self {
+			Lint::EmptyFlagValue => "empty-flag-value",
+			Lint::UnusedType => "unused-type",
+			Lint::UnusedGeneric => "unused-generic",
+			Lint::Naming => "naming",
+			Lint::UnknownAttribute => "unknown-attribute",
+			Lint::NonmonotonicLayers => "nonmonotonic-layers",
+			Lint::SingleVariantEnum => "single-variant-enum",
+			Lint::UnreachableType => "unreachable-type",
+		}
+	}
+	pub fn from_name(name: &str) -> Option<Lint> {
+		Self::ALL.into_iter().find(|lint| lint.name() == name)
+	}
+}
+
+impl Display for Lint {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintLevel {
+	/// Not reported at all
+	Allow,
+	/// Reported as a warning
+	Warn,
+	/// Reported as an error, making the definition invalid
+	Deny,
+}
+
+/// The level of every [`Lint`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lints {
+	levels: [LintLevel; Lint::ALL.len()],
+}
+
+impl Default for Lints {
+	/// Only warns about empty flag values and single-variant enums, and denies non-monotonic layers
+	fn default() -> Self {
+		Self { levels: Lint::ALL.map(|lint| match lint {
+			Lint::EmptyFlagValue | Lint::SingleVariantEnum => LintLevel::Warn,
+			Lint::NonmonotonicLayers => LintLevel::Deny,
+			_ => LintLevel::Allow,
+		}) }
+	}
+}
+
+impl Lints {
+	/// Every lint denied (`--strict`)
+	pub fn strict() -> Self {
+		Self { levels: [LintLevel::Deny; Lint::ALL.len()] }
+	}
+	pub fn set(mut self, lint: Lint, level: LintLevel) -> Self {
+		self.levels[lint as usize] = level;
+		self
+	}
+	pub fn level(&self, lint: Lint) -> LintLevel {
+		self.levels[lint as usize]
+	}
+	/// Puts `problem` into `warnings` or `errors`, depending on the level of `lint`
+	pub(crate) fn report(
+		&self, lint: Lint, problem: PunybufError,
+		warnings: &mut Vec<PunybufError>, errors: &mut Vec<PunybufError>
+	) {
+		let (mut problem, list) = match self.level(lint) {
+			LintLevel::Allow => return,
+			LintLevel::Warn => (problem.into_warning(), warnings),
+			LintLevel::Deny => (problem, errors),
+		};
+		problem.after_error.push(diagnostic!(Info,
+			Span::impossible(),
+			format!("reported by the `{lint}` lint")
+		));
+		list.push(problem);
+	}
+}
@@ -1,9 +1,13 @@
-use clap::{arg, command, ArgAction};
+use clap::{arg, builder::PossibleValuesParser, command, value_parser, Arg, ArgAction, ArgMatches, Command};
+use clap_complete::Shell;
 use std::{
+	env,
 	fs::{self, File, read_to_string},
-	io::Write,
-	path::Path,
+	io::{self, Read, Write},
+	path::{Path, PathBuf},
 	process::exit,
+	thread::sleep,
+	time::{Duration, SystemTime},
 };
 
 mod files;
@@ -11,6 +15,11 @@ mod files;
 mod lexer;
 use lexer::Span;
 
+mod attributes;
+
+mod lints;
+use lints::{Lint, LintLevel, Lints};
+
 mod errors;
 use errors::*;
 
@@ -28,31 +37,339 @@ use resolver::LayerResolver;
 mod converter;
 
 mod codegen;
-use codegen::{RustCodegen, HTMLCodegen};
+use codegen::{Derive, RustCodegen, HTMLCodegen, MigrationCodegen, TypeScriptCodegen, Visibility};
 
 mod binary_compat;
 
-fn main() {
-	let args = command!()
+mod self_check;
+use self_check::SelfCheck;
+
+macro_rules! verboseln {
+	($verbose:expr, $($meow:expr),+) => {
+		if $verbose { eprintln!($($meow),+) }
+	};
+}
+
+/// Reads, parses, validates and resolves the definition from `file`.
+/// 
+/// `args` are the matches of the command, which has the options affecting the parsing.
+/// The paths of `file` and the files it includes are added to `read_files`, even if it fails.
+/// If `file` is `-`, the definition is read from stdin, and its includes are relative to `--include-root`.
+fn load_definition(
+	file: &str, args: &ArgMatches, resolve: bool, verbose: bool, read_files: &mut Vec<PathBuf>
+) -> Result<PunybufDefinition, String> {
+	verboseln!(verbose, "File: {file}");
+	let max_identifier_length = *args.get_one::<usize>("max-identifier-length").unwrap();
+	let tokens = if file == "-" {
+		let mut source = vec![];
+		io::stdin().read_to_end(&mut source).map_err(|e| format!("couldn't read stdin: {e}"))?;
+		let include_root = match args.get_one::<String>("include-root") {
+			Some(root) => PathBuf::from(root),
+			None => env::current_dir().map_err(|e| e.to_string())?,
+		};
+		files::tokens_from_source(source, "<stdin>", &include_root, max_identifier_length, read_files)
+	} else {
+		files::tokens_from_file(Path::new(file), max_identifier_length, read_files)
+	};
+	let (tokens, includes_common) = tokens
+		.map_err(|e| e.to_string())?
+		.map_err(|e| e.to_string())?;
+
+	verboseln!(verbose, "Tokens: {:?}", tokens);
+	attributes::validate_tokens(&tokens).map_err(|e| e.to_string())?;
+
+	let lints = lints_from_args(args);
+	let max_errors = *args.get_one::<usize>("max-errors").unwrap();
+	let mut problems = vec![];
+	let (mut warnings, mut denied) = (vec![], vec![]);
+	attributes::unknown_attributes(&tokens, &mut problems);
+	for problem in problems {
+		lints.report(Lint::UnknownAttribute, problem, &mut warnings, &mut denied);
+	}
+	if let Some(e) = PunybufError::chain(denied) {
+		return Err(e.display_at_most(max_errors));
+	}
+
+	let mut p = Parser::new(&tokens).lints(lints);
+	let decls = p.parse().map_err(|e| e.to_string())?;
+	verboseln!(verbose, "Decls: {:?}", decls);
+	warnings.append(&mut p.warnings);
+
+	let mut def: PunybufDefinition = flatten(decls, p.attrs, p.reserved_ids, includes_common).map_err(|e| e.to_string())?;
+	verboseln!(verbose, "Definition: {:?}", def);
+	warnings.append(&mut def.validate(lints).map_err(|e| e.display_at_most(max_errors))?);
+	for warning in warnings {
+		eprintln!("{YELLOW}{BOLD}warning:{NORMAL} {warning}");
+	}
+
+	let resolver = LayerResolver::new(resolve);
+	if args.get_flag("dump-resolution") {
+		eprintln!("{}", resolver.resolve_with_dump(&mut def));
+	} else {
+		resolver.resolve(&mut def);
+	}
+	Ok(def)
+}
+
+/// `pbd migrate`
+fn migrate(args: &ArgMatches) -> Result<(), String> {
+	let file = args.get_one::<String>("INPUT").unwrap();
+	let def = load_definition(file, args, true, false, &mut vec![])?;
+	let generated = MigrationCodegen::new(&def).codegen();
+
+	match args.get_one::<String>("out") {
+		Some(out_file) => {
+			let mut file = File::create(out_file).map_err(|e| e.to_string())?;
+			file.write_all(generated.as_bytes()).map_err(|e| e.to_string())?;
+			eprintln!("{GREEN}{BOLD}generated:{NORMAL} {out_file} {GRAY}(Rust migrations){NORMAL}");
+		}
+		None => print!("{generated}"),
+	}
+	Ok(())
+}
+
+/// `pbd self-check`
+fn self_check(args: &ArgMatches) -> Result<(), String> {
+	let file = args.get_one::<String>("INPUT").unwrap();
+	let def = load_definition(file, args, true, false, &mut vec![])?;
+	SelfCheck {
+		samples: *args.get_one::<usize>("samples").unwrap(),
+		seed: args.get_one::<u64>("seed").copied().unwrap_or_else(SelfCheck::random_seed),
+		common: args.get_one::<String>("common").map(PathBuf::from),
+		dir: args.get_one::<String>("dir").map(PathBuf::from),
+		offline: args.get_flag("offline"),
+	}.run(&def)
+}
+
+/// `pbd bundle`
+fn bundle(args: &ArgMatches) -> Result<(), String> {
+	let file = args.get_one::<String>("INPUT").unwrap();
+	let max_identifier_length = *args.get_one::<usize>("max-identifier-length").unwrap();
+	let (bundled, warnings) = files::bundle_file(Path::new(file), args.get_flag("inline-common"), max_identifier_length)
+		.map_err(|e| e.to_string())?
+		.map_err(|e| e.to_string())?;
+	for warning in warnings {
+		eprintln!("{YELLOW}{BOLD}warning:{NORMAL} {warning}");
+	}
+
+	match args.get_one::<String>("out") {
+		Some(out_file) => {
+			let mut file = File::create(out_file).map_err(|e| e.to_string())?;
+			file.write_all(bundled.as_bytes()).map_err(|e| e.to_string())?;
+			eprintln!("{GREEN}{BOLD}generated:{NORMAL} {out_file} {GRAY}(bundled definition){NORMAL}");
+		}
+		None => print!("{bundled}"),
+	}
+	Ok(())
+}
+
+fn max_identifier_length_arg() -> Arg {
+	arg!(--"max-identifier-length" <BYTES> "Reject identifiers and attribute names longer than this.")
+		.value_parser(value_parser!(usize))
+		// `lexer::DEFAULT_MAX_IDENTIFIER_LENGTH`
+		.default_value("1024")
+}
+
+fn include_root_arg() -> Arg {
+	arg!(--"include-root" <DIR> "The directory includes are relative to when INPUT is `-`. Defaults to the current directory.")
+}
+
+fn dump_resolution_arg() -> Arg {
+	arg!(--"dump-resolution" "Print the dependencies between types and the types generated for new layers, as JSON, into stderr.")
+}
+
+fn max_errors_arg() -> Arg {
+	arg!(--"max-errors" <N> "Show at most this many errors, 0 for no limit.")
+		.value_parser(value_parser!(usize))
+		.default_value("20")
+}
+
+/// `--strict`, and the options setting the level of a single lint
+fn lint_args() -> [Arg; 6] {
+	let lint = || PossibleValuesParser::new(Lint::ALL.map(Lint::name));
+	[
+		arg!(--"allow-nonmonotonic-layers" "Allow declaring a layer lower than a previously declared one. Same as `--allow nonmonotonic-layers`."),
+		arg!(--"warn-unreachable" "Warn about types that no command uses, even through other types. Same as `--warn unreachable-type`."),
+		arg!(--strict "Report every lint as an error. `--allow` and `--warn` still apply."),
+		arg!(--allow <LINT> "Don't report this lint. Allows multiple occurrences.").value_parser(lint()).action(ArgAction::Append),
+		arg!(--warn <LINT> "Report this lint as a warning. Allows multiple occurrences.").value_parser(lint()).action(ArgAction::Append),
+		arg!(--deny <LINT> "Report this lint as an error. Allows multiple occurrences.").value_parser(lint()).action(ArgAction::Append),
+	]
+}
+
+/// The levels of the lints, set by [`lint_args`]
+fn lints_from_args(args: &ArgMatches) -> Lints {
+	let mut lints = if args.get_flag("strict") { Lints::strict() } else { Lints::default() };
+	if args.get_flag("allow-nonmonotonic-layers") {
+		lints = lints.set(Lint::NonmonotonicLayers, LintLevel::Allow);
+	}
+	if args.get_flag("warn-unreachable") {
+		lints = lints.set(Lint::UnreachableType, LintLevel::Warn);
+	}
+	for (arg, level) in [("deny", LintLevel::Deny), ("warn", LintLevel::Warn), ("allow", LintLevel::Allow)] {
+		for name in args.get_many::<String>(arg).into_iter().flatten() {
+			lints = lints.set(Lint::from_name(name).unwrap(), level);
+		}
+	}
+	lints
+}
+
+/// Generates the contents of `out_file`, depending on its extension, and its file type
+fn generate(out_file: &str, def: &PunybufDefinition, args: &ArgMatches, docs: bool) -> Result<(String, &'static str), String> {
+	#[allow(unused_assignments)] // idk why it does that
+	let mut file_type = "unknown";
+	let generated = if out_file.ends_with(".rs") {
+		file_type = "Rust";
+		let visibility = match args.get_one::<String>("rust:visibility").unwrap().as_str() {
+			"pub(crate)" => Visibility::Crate,
+			"private" => Visibility::Private,
+			_ => Visibility::Public,
+		};
+		// `Debug` and `Clone` are always derived
+		let derives: Vec<Derive> = args.get_many::<String>("rust:derive").into_iter().flatten().filter_map(|name| match name.as_str() {
+			"PartialEq" => Some(Derive::PartialEq),
+			"Eq" => Some(Derive::Eq),
+			"Hash" => Some(Derive::Hash),
+			_ => None,
+		}).collect();
+		let codegen = RustCodegen::new(args.get_flag("rust:tokio"), docs, def)
+			.visibility(visibility)
+			.derive_copy(args.get_flag("rust:derive-copy"))
+			.derive_ord(args.get_flag("rust:derive-ord"))
+			.derive(&derives)
+			.private_fields(args.get_flag("rust:private-fields"))
+			.wire_sizes(args.get_flag("rust:wire-sizes"))
+			.inline(args.get_flag("rust:inline"))
+			.reflect(args.get_flag("rust:reflect"))
+			.metrics(args.get_flag("rust:metrics"))
+			.allow_unknown_enum(args.get_flag("rust:allow-unknown-enum"))
+			.keep_extensions(args.get_flag("rust:keep-extensions"))
+			.flatten_enums(args.get_flag("rust:flatten-enums"))
+			.layer_conversions(args.get_flag("rust:layer-conversions"));
+		if args.get_flag("rust:both") {
+			codegen.codegen_both()
+		} else {
+			codegen.codegen()
+		}
+
+	} else if out_file.ends_with(".ts") {
+		file_type = "TypeScript";
+		TypeScriptCodegen::new(def, docs).codegen().map_err(|e| format!("typescript: {e}"))?
+
+	} else if out_file.ends_with(".json") {
+		file_type = "JSON";
+		converter::convert_full_definition(def)
+
+	} else if out_file.ends_with(".htm") || out_file.ends_with(".html") {
+		file_type = "HTML";
+		let template = if let Some(template_path) = args.get_one::<String>("html:template") {
+			Some(fs::read_to_string(template_path).map_err(|e|
+				format!("html: failed to read template {template_path}: {}", e.to_string())
+			)?)
+		} else {
+			None
+		};
+		HTMLCodegen::new(def, template.as_deref()).codegen()
+		
+	} else {
+		return Err(format!(
+			"can't output a file `{out_file}` - file type not supported\n  \
+			perhaps you wanted to pipe the output from this command into another?"
+		));
+	};
+	Ok((generated, file_type))
+}
+
+/// The whole CLI, also used to generate completions
+fn cli() -> Command {
+	command!()
 		.about("Generate code or IR from a Punybuf Definition file.")
-		.arg(arg!(<INPUT> "The .pbd definition file").required(true))
+		.arg(arg!(<INPUT> "The .pbd definition file, or `-` to read it from stdin").required(true))
 		.arg(arg!(-q --quiet "Do not print JSON into stdout"))
 		.arg(arg!(-l --loud "Do print JSON into stdout, overrides -q"))
-		.arg(arg!(-o --out <OUT> "Output - only .rs, .json files supported. Implies -q. Allows multiple occurrences.").action(ArgAction::Append))
+		.arg(arg!(-o --out <OUT> "Output - only .rs, .ts, .json, .html files supported. Implies -q. Allows multiple occurrences.").action(ArgAction::Append))
 		.arg(arg!(-c --compat <JSON>
 			"Check binary compatibility with the previous version (json file). \
 			Aborts if they are not compatible."
 		))
 		.arg(arg!(-d --"dry-run" "Do not write anything to the filesystem."))
+		.arg(arg!(--"keep-going" "Write every --out file that can be generated, and report the ones that failed at the end, instead of stopping at the first failure.")
+			.alias("output-on-error"))
 		.arg(arg!(--verbose "Be verbose. Will print a lot of unnecessary things."))
+		.arg(arg!(-w --watch "Keep running, and compile again whenever the input or a file it includes changes. Errors don't stop it."))
 		.arg(arg!(--"no-resolve" "Skip `@resolve`-ing aliases."))
+		.args(lint_args())
+		.arg(include_root_arg())
+		.arg(max_identifier_length_arg())
+		.arg(max_errors_arg())
+		.arg(dump_resolution_arg())
 		.arg(arg!(--"no-docs" "Do not generate doc-comments. Doesn't affect json."))
 		.arg(arg!(--"rust:tokio" "Generate async rust code for tokio. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:both" "Generate both sync and tokio rust code, in the modules `sync` and `r#async`. Overrides --rust:tokio."))
+		.arg(arg!(--"rust:visibility" <VISIBILITY> "Visibility of the generated items, fields and methods. Affects only `.rs` files from --out.")
+			.value_parser(["pub", "pub(crate)", "private"])
+			.default_value("pub"))
+		.arg(arg!(--"rust:derive-copy" "Derive `Copy` for types that only contain `Copy` values. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:derive-ord" "Derive `PartialEq`, `Eq`, `PartialOrd` and `Ord` for types without floats or maps, comparing fields in declaration order. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:derive" <TRAITS> "Derive these traits for the generated types, commands and command errors, separated by commas. `Eq` and `Hash` are skipped for those containing floats. `Debug` and `Clone` are always derived. Affects only `.rs` files from --out.")
+			.value_parser(["Clone", "PartialEq", "Eq", "Hash"])
+			.value_delimiter(',')
+			.action(ArgAction::Append))
+		.arg(arg!(--"rust:private-fields" "Make the fields of structs private, with getters, setters and a `new` function instead. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:wire-sizes" "Mention the size of each type on the wire in its doc comment. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:inline" "Put `#[inline]` on the generated serialize and deserialize methods. Can be faster for small types, but makes the code bigger. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:reflect" "Implement `PBReflect` for the generated types. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:metrics" "Count the commands that are serialized and deserialized, and their sizes, with the `metrics` crate. Needs the `metrics` feature of punybuf_common. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:allow-unknown-enum" "Keep unknown variants of `@default` enums as `UnknownVariant`, instead of the default variant. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:keep-extensions" "Keep the flags and extensions of structs that aren't in the definition, so that re-serializing doesn't drop them. Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:flatten-enums" "Encode enums with a single variant carrying a value as just that value, without the discriminant. Changes the wire format! Affects only `.rs` files from --out."))
+		.arg(arg!(--"rust:layer-conversions" "Implement `From` or `TryFrom` for structs and commands from their previous layer, when the new one only adds flags. Affects only `.rs` files from --out."))
 		.arg(arg!(--"html:template" <PATH> "Path to the template to be used to generate `.html` files."))
-		.get_matches()
-	;
+		.args_conflicts_with_subcommands(true)
+		.subcommand_negates_reqs(true)
+		.subcommand(Command::new("migrate")
+			.about("Generate stubs of Rust functions converting types between consecutive layers.")
+			.arg(arg!(<INPUT> "The .pbd definition file, or `-` to read it from stdin").required(true))
+			.arg(arg!(-o --out <OUT> "The .rs file to write the stubs to, instead of stdout"))
+			.args(lint_args())
+			.arg(include_root_arg())
+			.arg(max_identifier_length_arg())
+			.arg(max_errors_arg())
+			.arg(dump_resolution_arg())
+		)
+		.subcommand(Command::new("bundle")
+			.about("Inline every included file into one self-contained .pbd file.")
+			.arg(arg!(<INPUT> "The .pbd definition file").required(true))
+			.arg(arg!(-o --out <OUT> "The .pbd file to write the bundle to, instead of stdout"))
+			.arg(arg!(--"inline-common" "Inline `common` too, instead of keeping `include common`."))
+			.arg(max_identifier_length_arg())
+		)
+		.subcommand(Command::new("self-check")
+			.about("Generate Rust code, compile it in a temporary crate, and check that it round-trips random values of every type and command.")
+			.arg(arg!(<INPUT> "The .pbd definition file, or `-` to read it from stdin").required(true))
+			.arg(arg!(--samples <N> "How many values of each type and command to check.")
+				.value_parser(value_parser!(usize))
+				.default_value("100"))
+			.arg(arg!(--seed <SEED> "Generate the values from this seed, to repeat an earlier check. Random by default.")
+				.value_parser(value_parser!(u64)))
+			.arg(arg!(--common <PATH> "Use the punybuf_common crate at this path, instead of the one from crates.io."))
+			.arg(arg!(--dir <DIR> "Write the crate into this directory and keep it, instead of a temporary one."))
+			.arg(arg!(--offline "Run cargo with `--offline`."))
+			.args(lint_args())
+			.arg(include_root_arg())
+			.arg(max_identifier_length_arg())
+			.arg(max_errors_arg())
+			.arg(dump_resolution_arg())
+		)
+		.subcommand(Command::new("completions")
+			.about("Print shell completions for pbd.")
+			.arg(arg!(<SHELL> "The shell to generate completions for").value_parser(value_parser!(Shell)))
+		)
+}
 
-	let file = args.get_one::<String>("INPUT").unwrap();
+/// Compiles `file` and writes the outputs, as set by `args`.
+/// The files that were read are added to `read_files`
+fn compile(file: &str, args: &ArgMatches, read_files: &mut Vec<PathBuf>) -> Result<(), String> {
 	let out = args.get_many::<String>("out").map(|x| x.collect::<Vec<_>>()).unwrap_or(vec![]);
 	let quiet = (args.get_flag("quiet") || !out.is_empty()) && !args.get_flag("loud");
 	let dry = args.get_flag("dry-run");
@@ -60,90 +377,151 @@ fn main() {
 	let resolve = !args.get_flag("no-resolve");
 	let docs = !args.get_flag("no-docs");
 	let check_binary = args.get_one::<String>("compat");
+	let keep_going = args.get_flag("keep-going");
 
-	macro_rules! verboseln {
-		($($meow:expr),+) => {
-			if verbose { eprintln!($($meow),+) }
-		};
+	let def = load_definition(file, args, resolve, verbose, read_files)?;
+
+	if let Some(compat) = check_binary {
+		let json = read_to_string(compat).map_err(|e| e.to_string())?;
+		binary_compat::BinaryCompat::new(&json, &def)?.check().map_err(|mut e| {
+			e.before_error.push(diagnostic!(Warning,
+				Span::impossible(),
+				format!("\"{file}\" is not binary compatible with \"{compat}\":")
+			));
+			e.to_string()
+		})?;
 	}
 
-	verboseln!("File: {file}");
-	let result = (|| -> Result<(), String> {
-		let (tokens, includes_common) = files::tokens_from_file(Path::new(file))
-			.map_err(|e| e.to_string())?
-			.map_err(|e| e.to_string())?;
+	let mut failures = vec![];
+	for out_file in &out {
+		let result = generate(out_file, &def, args, docs).and_then(|(generated, file_type)| {
+			if dry {
+				eprintln!("would've written to the file: {BLUE}{BOLD}{out_file}{NORMAL}, but {RED}--dry-run{NORMAL} was specified");
+				return Ok(());
+			}
+			let mut file = File::create(out_file).map_err(|e| format!("{out_file}: {e}"))?;
+			file.write_all(generated.as_bytes()).map_err(|e| format!("{out_file}: {e}"))?;
+			eprintln!("{GREEN}{BOLD}generated:{NORMAL} {out_file} {GRAY}({file_type}){NORMAL}");
+			Ok(())
+		});
+		match result {
+			Err(e) if keep_going => failures.push(e),
+			result => result?,
+		}
+	}
+	if !failures.is_empty() {
+		for e in &failures {
+			eprintln!("{RED}{BOLD}error:{NORMAL} {e}");
+		}
+		return Err(format!("failed to generate {} of the {} outputs", failures.len(), out.len()));
+	}
 
-		verboseln!("Tokens: {:?}", tokens);
+	if !quiet {
+		println!("{}", converter::convert_full_definition(&def));
+	}
 
-		let mut p = Parser::new(&tokens);
-		let decls = p.parse().map_err(|e| e.to_string())?;
-		verboseln!("Decls: {:?}", decls);
+	Ok(())
+}
 
-		let mut def: PunybufDefinition = flatten(decls, includes_common).map_err(|e| e.to_string())?;
-		verboseln!("Definition: {:?}", def);
-		def.validate().map_err(|e| e.to_string())?;
+/// How often `--watch` checks the files for changes
+const WATCH_INTERVAL: Duration = Duration::from_millis(200);
+/// How long the files have to stay the same after a change before compiling again,
+/// since saving a file can take several writes
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
 
-		LayerResolver::new(resolve).resolve(&mut def);
+/// When each file was last modified, and its size, if it exists
+fn file_states(files: &[PathBuf]) -> Vec<Option<(SystemTime, u64)>> {
+	files.iter().map(|file| {
+		let metadata = fs::metadata(file).ok()?;
+		Some((metadata.modified().ok()?, metadata.len()))
+	}).collect()
+}
 
-		if let Some(compat) = check_binary {
-			let json = read_to_string(compat).map_err(|e| e.to_string())?;
-			binary_compat::BinaryCompat::new(&json, &def)?.check().map_err(|mut e| {
-				e.before_error.push(diagnostic!(Warning,
-					Span::impossible(),
-					format!("\"{file}\" is not binary compatible with \"{compat}\":")
-				));
-				e.to_string()
-			})?;
+/// `--watch`: compiles `file` every time it or a file it includes changes, until the process is killed
+fn watch(file: &str, args: &ArgMatches) -> ! {
+	loop {
+		let mut read_files = vec![];
+		if let Err(e) = compile(file, args, &mut read_files) {
+			eprintln!("{RED}{BOLD}error:{NORMAL} {e}");
 		}
+		if read_files.is_empty() {
+			read_files.push(PathBuf::from(file));
+		}
+		eprintln!("{BLUE}{BOLD}watching:{NORMAL} {} for changes", match read_files.len() {
+			1 => file.to_string(),
+			n => format!("{file} and {} included files", n - 1),
+		});
 
-		for out_file in out {
-			#[allow(unused_assignments)] // idk why it does that
-			let mut file_type = "unknown";
-			let generated = if out_file.ends_with(".rs") {
-				file_type = "Rust";
-				RustCodegen::new(args.get_flag("rust:tokio"), docs, &def).codegen()
-
-			} else if out_file.ends_with(".json") {
-				file_type = "JSON";
-				converter::convert_full_definition(&def)
-
-			} else if out_file.ends_with(".htm") || out_file.ends_with(".html") {
-				file_type = "HTML";
-				let template = if let Some(template_path) = args.get_one::<String>("html:template") {
-					Some(fs::read_to_string(template_path).map_err(|e|
-						format!("html: failed to read template {template_path}: {}", e.to_string())
-					)?)
-				} else {
-					None
-				};
-				HTMLCodegen::new(&def, template.as_deref()).codegen()
-				
-			} else {
-				return Err(format!(
-					"can't output a file `{out_file}` - file type not supported\n  \
-					perhaps you wanted to pipe the output from this command into another?"
-				));
-			};
-
-			if dry {
-				eprintln!("would've written to the file: {BLUE}{BOLD}{out_file}{NORMAL}, but {RED}--dry-run{NORMAL} was specified");
-				continue
+		let mut states = file_states(&read_files);
+		loop {
+			sleep(WATCH_INTERVAL);
+			if file_states(&read_files) != states {
+				break;
 			}
-
-			let mut file = File::create(out_file).map_err(|e| e.to_string())?;
-			file.write_all(generated.as_bytes()).map_err(|e| e.to_string())?;
-			eprintln!("{GREEN}{BOLD}generated:{NORMAL} {out_file} {GRAY}({file_type}){NORMAL}");
 		}
-
-		if !quiet {
-			println!("{}", converter::convert_full_definition(&def));
+		loop {
+			sleep(WATCH_DEBOUNCE);
+			let now = file_states(&read_files);
+			if now == states {
+				break;
+			}
+			states = now;
 		}
+	}
+}
 
-		Ok(())
-	})();
+fn main() {
+	let args = cli().get_matches();
 
-	if let Err(e) = result {
+	match args.subcommand() {
+		Some(("migrate", sub_args)) => {
+			if let Err(e) = migrate(sub_args) {
+				eprintln!("{RED}{BOLD}error:{NORMAL} {e}");
+				exit(1)
+			}
+			return;
+		}
+		Some(("bundle", sub_args)) => {
+			if let Err(e) = bundle(sub_args) {
+				eprintln!("{RED}{BOLD}error:{NORMAL} {e}");
+				exit(1)
+			}
+			return;
+		}
+		Some(("self-check", sub_args)) => {
+			if let Err(e) = self_check(sub_args) {
+				eprintln!("{RED}{BOLD}error:{NORMAL} {e}");
+				exit(1)
+			}
+			return;
+		}
+		Some(("completions", sub_args)) => {
+			let shell = *sub_args.get_one::<Shell>("SHELL").unwrap();
+			let mut completions = Vec::new();
+			clap_complete::generate(shell, &mut cli(), "pbd", &mut completions);
+			// piping into something like `head` closes stdout early, which is fine
+			let mut stdout = io::stdout();
+			match stdout.write_all(&completions).and_then(|_| stdout.flush()) {
+				Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+					eprintln!("{RED}{BOLD}error:{NORMAL} {e}");
+					exit(1)
+				}
+				_ => return,
+			}
+		}
+		_ => {}
+	}
+
+	let file = args.get_one::<String>("INPUT").unwrap();
+	if args.get_flag("watch") {
+		if file == "-" {
+			eprintln!("{RED}{BOLD}error:{NORMAL} --watch needs a file to watch, it can't read the definition from stdin");
+			exit(1)
+		}
+		watch(file, &args);
+	}
+	if let Err(e) = compile(file, &args, &mut vec![]) {
 		eprintln!("{RED}{BOLD}error:{NORMAL} {e}");
 		exit(1)
 	}
-}
\ No newline at end of file
+}
@@ -6,6 +6,7 @@ use crate::errors::{
 };
 
 use crate::lexer::{Span, Token, TokenData};
+use crate::lints::{Lint, Lints};
 
 #[derive(Debug)]
 #[allow(unused)]
@@ -86,6 +87,8 @@ pub(crate) enum FlexibleDeclarationValue {
 		inline: bool,
 		layer: u32,
 		fields: Vec<Field>,
+		/// Optional fields outside of flag fields, only valid in `@bitmap` structs
+		optional_fields: Vec<FieldFlag>,
 	},
 	EnumDeclaration {
 		inline: bool,
@@ -151,25 +154,54 @@ pub(crate) struct Declaration {
 
 pub struct Parser<'parser> {
 	peekable: Peekable<Iter<'parser, Token>>,
+	/// Definition-level attributes, written as `@attribute;`
+	pub(crate) attrs: HashMap<String, Option<String>>,
+	/// Command IDs no command may have, written as `reserve_id 12345`
+	pub(crate) reserved_ids: Vec<(u32, Span)>,
+	/// Problems that don't stop the parsing, see [`Lints`]
+	pub(crate) warnings: Vec<PunybufError>,
+	lints: Lints,
 }
 
 impl<'parser> Parser<'parser> {
 	pub fn new(tokens: &'parser Vec<Token>) -> Self {
 		Self {
-			peekable: tokens.iter().peekable()
+			peekable: tokens.iter().peekable(),
+			attrs: HashMap::new(),
+			reserved_ids: Vec::new(),
+			warnings: Vec::new(),
+			lints: Lints::default(),
 		}
 	}
+	/// Sets the level of the lints checked while parsing
+	pub fn lints(mut self, lints: Lints) -> Self {
+		self.lints = lints;
+		self
+	}
 	pub fn parse(&mut self) -> Result<Vec<Declaration>, PunybufError> {
 		let mut decls = Vec::new();
 		let mut nextdoc: Option<(&str, &Span)> = None;
 		let mut next_attrs = HashMap::<&String, (&Option<String>, &Span)>::new();
 
 		let mut layer = 0u32;
+		// The highest layer declared so far in each file, and where it was declared
+		let mut highest_layers = HashMap::<&str, (u32, Span)>::new();
 
 		// TODO: make these errors just as pretty everywhere
 		while let Some(tk) = self.peekable.next() {
 			match &tk.data {
 				TokenData::Attribute(attr, val) => {
+					if let Some(Token { data: TokenData::Semicolon, .. }) = self.peekable.peek() {
+						// `@attribute;` applies to the whole definition
+						self.peekable.next();
+						if self.attrs.insert(attr.clone(), val.clone()).is_some() {
+							return Err(parser_err!(
+								tk.span,
+								"definition-level attribute {attr} defined twice"
+							));
+						}
+						continue;
+					}
 					if let Some((_, first_span)) = next_attrs.insert(&attr, (&val, &tk.span)) {
 						return Err(pb_err!(
 							tk.span,
@@ -206,6 +238,26 @@ impl<'parser> Parser<'parser> {
 					}
 					nextdoc = Some((doc, &tk.span));
 				}
+				TokenData::Symbol(keyword) if keyword == "reserve_id" && matches!(
+					self.peekable.peek(), Some(Token { data: TokenData::Numeric(_), .. })
+				) => {
+					let Some(Token { data: TokenData::Numeric(id), span }) = self.peekable.next() else {
+						unreachable!()
+					};
+					if let Some((attr, (_, attr_span))) = next_attrs.iter().next() {
+						return Err(parser_err!(
+							attr_span,
+							"attribute {attr} can't be applied to `reserve_id`"
+						));
+					}
+					if let Some((_, doc_span)) = nextdoc {
+						return Err(parser_err!(
+							doc_span,
+							"`reserve_id` can't be documented; use a regular comment instead"
+						));
+					}
+					self.reserved_ids.push((*id, tk.span.extend(span)));
+				}
 				TokenData::Symbol(name) => {
 					let mut equals_or_colon = self.peekable.next().ok_or(
 						parser_err!(tk.span, "unexpected EOF")
@@ -316,8 +368,15 @@ impl<'parser> Parser<'parser> {
 										)?;
 										match decl {
 											(FlexibleDeclarationValue::StructDeclaration {
-												inline: _, fields, ..
+												inline: _, fields, optional_fields, ..
 											}, _span) => {
+												if let Some(field) = optional_fields.first() {
+													return Err(parser_err!(
+														field.name_span,
+														"optional fields may only be defined on flag fields \
+														in command arguments"
+													));
+												}
 												CommandArgument::Struct { fields }
 											},
 											_ => {
@@ -446,6 +505,40 @@ impl<'parser> Parser<'parser> {
 					match self.peekable.next() {
 						Some(Token { data: TokenData::Numeric(layer_decl), span }) => {
 							layer = *layer_decl;
+							if tk.span.is_implicit_layer_definition() {
+								// The file ended, it might be included again
+								highest_layers.remove(tk.span.file_name());
+							} else if let Some((highest, highest_span)) = highest_layers.get(tk.span.file_name()) {
+								if layer < *highest {
+									let problem = pb_err!(
+										tk.span.extend(span),
+										format!("layer {layer} is declared after layer {highest}"),
+										after_error: vec![
+											diagnostic!(Info,
+												highest_span.clone(),
+												format!("layer {highest} is declared here")
+											),
+											diagnostic!(Tip,
+												Span::impossible(),
+												format!(
+													"higher layers are treated as newer, so declare them in increasing order \
+													(or pass `--allow-nonmonotonic-layers` if this is intended)"
+												)
+											),
+										]
+									);
+									let mut denied = vec![];
+									self.lints.report(Lint::NonmonotonicLayers, problem, &mut self.warnings, &mut denied);
+									if let Some(e) = denied.pop() {
+										return Err(e);
+									}
+								}
+								if layer > *highest {
+									highest_layers.insert(tk.span.file_name(), (layer, tk.span.extend(span)));
+								}
+							} else {
+								highest_layers.insert(tk.span.file_name(), (layer, tk.span.extend(span)));
+							}
 							match self.peekable.next() {
 								Some(Token { data: TokenData::Colon, span: _ }) => {},
 								Some(t) => {
@@ -549,6 +642,7 @@ impl<'parser> Parser<'parser> {
 		-> Result<FlexibleDeclarationValue, PunybufError>
 	{
 		let mut fields = vec![];
+		let mut optional_fields = vec![];
 		let mut peekable = tokens.iter().peekable();
 
 		let mut anonymous_flags_number = 0;
@@ -588,14 +682,18 @@ impl<'parser> Parser<'parser> {
 										)
 									]
 								));
-							} else {
-								return Err(parser_err!(
-									next.span, 
-									"expected a `:` after the field name, \
-									got `?`; optional fields may only be defined \
-									on flag fields"
-								));
 							}
+							// Only valid in `@bitmap` structs, which is checked by the flattener
+							let value = Parser::parse_flag_value(&mut peekable, token, field_name, layer)?;
+							optional_fields.push(FieldFlag {
+								name: field_name.to_string(),
+								name_span: token.span.clone(),
+								value,
+								attrs: next_attrs,
+								doc: next_doc.unwrap_or("").to_string()
+							});
+							next_doc = None;
+							next_attrs = HashMap::new();
 						}
 						TokenData::AngleBrackets(_) => {
 							if let Some(
@@ -723,7 +821,7 @@ impl<'parser> Parser<'parser> {
 			}
 		}
 
-		Ok(FlexibleDeclarationValue::StructDeclaration { inline: false, fields, layer })
+		Ok(FlexibleDeclarationValue::StructDeclaration { inline: false, fields, optional_fields, layer })
 	}
 
 	fn parse_enum_decl(tokens: &Vec<Token>, start_at_one: bool, layer: u32)
@@ -732,7 +830,8 @@ impl<'parser> Parser<'parser> {
 		let mut variants = vec![];
 		let mut peekable = tokens.iter().peekable();
 
-		let mut counter: u8 = if start_at_one { 1 } else { 0 };
+		// wider than a `u8`, so that running out of discriminants can be reported
+		let mut counter: u16 = if start_at_one { 1 } else { 0 };
 		let mut next_doc: Option<&str> = None;
 		let mut next_attrs = HashMap::new();
 		while let Some(tk) = peekable.next() {
@@ -759,16 +858,46 @@ impl<'parser> Parser<'parser> {
 						_ => {}
 					}
 
+					// `Red = 5`, or `ThinkingAbout: String = 3`
+					let discriminant = match peekable.peek() {
+						Some(Token { data: TokenData::Equals, span }) => {
+							peekable.next();
+							match peekable.next() {
+								Some(Token { data: TokenData::Numeric(n), span }) => {
+									u8::try_from(*n).map_err(|_| parser_err!(
+										span, "the discriminant of `{name}` must fit in a `U8`, but it's {n}"
+									))?
+								}
+								Some(other) => {
+									return Err(parser_err!(
+										other.span,
+										"expected the discriminant of `{name}` after `=`, got `{other}`"
+									));
+								}
+								None => {
+									return Err(parser_err!(
+										span,
+										"expected the discriminant of `{name}` after `=`, got nothing"
+									));
+								}
+							}
+						}
+						_ => u8::try_from(counter).map_err(|_| parser_err!(
+							tk.span, "`{name}` would have the discriminant {counter}, which doesn't fit in a `U8`"
+						))?,
+					};
+
 					variants.push(EnumVariant {
 						name: name.to_string(), name_span: tk.span.clone(),
-						discriminant: counter,
+						discriminant,
 						value,
 						attrs: next_attrs,
 						doc: next_doc.unwrap_or("").to_string()
 					});
 					next_doc = None;
 					next_attrs = HashMap::new();
-					counter += 1;
+					// the next ones continue from explicit discriminants
+					counter = discriminant as u16 + 1;
 					match peekable.next() {
 						None | Some(Token { data: TokenData::Comma, span: _ }) => {},
 						Some(Token { data: _, span }) => {
@@ -880,41 +1009,7 @@ impl<'parser> Parser<'parser> {
 						));
 					}
 
-					let mut refr = None;
-					match peekable.peek() {
-						Some(Token { data: TokenData::Colon, span }) => {
-							peekable.next(); // Consumes the colon
-
-							refr = Some(Parser::parse_reference(&mut peekable, span, layer)?);
-							match peekable.peek() {
-								Some(Token { data: TokenData::Dot, span: dot_span }) => {
-									return Err(pb_err!(
-										token.span,
-										"flags (optional fields) cannot contain flag fields".to_string(),
-										after_error: vec![
-											diagnostic!(Tip,
-												dot_span.clone(),
-												format!(
-													"try removing this period \
-													to make `{flag_name}` into a regular field"
-												)
-											),
-											diagnostic!(Tip,
-												// if this is reached, refr is always `Some(...)`
-												refr.unwrap().get_name_span().clone(),
-												format!(
-													"...or try defining `{flag_name}`'s \
-													type so that it contains a flag field"
-												)
-											),
-										]
-									));
-								}
-								_ => {}
-							}
-						}
-						_ => {}
-					}
+					let refr = Parser::parse_flag_value(&mut peekable, token, flag_name, layer)?;
 					flags.push(FieldFlag {
 						name: flag_name.to_string(),
 						name_span: token.span.clone(),
@@ -945,6 +1040,48 @@ impl<'parser> Parser<'parser> {
 		Ok(flags)
 	}
 
+	/// Parses what comes after the `?` of an optional field: either `: Type` or nothing
+	fn parse_flag_value(
+		peekable: &mut Peekable<Iter<Token>>, token: &Token, flag_name: &str, layer: u32
+	) -> Result<Option<ValueReference>, PunybufError> {
+		let mut refr = None;
+		match peekable.peek() {
+			Some(Token { data: TokenData::Colon, span }) => {
+				peekable.next(); // Consumes the colon
+
+				refr = Some(Parser::parse_reference(peekable, span, layer)?);
+				match peekable.peek() {
+					Some(Token { data: TokenData::Dot, span: dot_span }) => {
+						return Err(pb_err!(
+							token.span,
+							"flags (optional fields) cannot contain flag fields".to_string(),
+							after_error: vec![
+								diagnostic!(Tip,
+									dot_span.clone(),
+									format!(
+										"try removing this period \
+										to make `{flag_name}` into a regular field"
+									)
+								),
+								diagnostic!(Tip,
+									// if this is reached, refr is always `Some(...)`
+									refr.unwrap().get_name_span().clone(),
+									format!(
+										"...or try defining `{flag_name}`'s \
+										type so that it contains a flag field"
+									)
+								),
+							]
+						));
+					}
+					_ => {}
+				}
+			}
+			_ => {}
+		}
+		Ok(refr)
+	}
+
 	/// Consumes the next token, which is expected to be a Symbol
 	fn parse_reference(peekable: &mut Peekable<Iter<Token>>, before_sym: &Span, layer: u32)
 		-> Result<ValueReference, PunybufError>
@@ -11,18 +11,20 @@ use std::{
 	u32, vec,
 };
 
+use json::JsonValue;
+
 use crate::flattener::{
 	PBCommandArg, PBCommandDef, PBEnumVariant, PBField,
 	PBTypeDef, PBTypeRef, PunybufDefinition,
 	PB_CRC,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum DependentKind {
 	Type, Command
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 struct Dependent {
 	name: String,
 	layer: u32,
@@ -181,6 +183,9 @@ impl LayerResolver {
 		let mut new_types = vec![];
 		let mut new_commands = vec![];
 		let Some(dependents) = self.dependencies.get(changed_type.get_name().0) else { return };
+		// The order of a `HashSet` changes between runs, but the generated code shouldn't
+		let mut dependents = dependents.iter().collect::<Vec<_>>();
+		dependents.sort();
 		for dependent in dependents {
 			if &dependent.layer >= changed_type.get_layer() {
 				continue;
@@ -243,6 +248,58 @@ impl LayerResolver {
 	// TODO: refactor this so that `PunybufDefinition` is present on the struct itself
 	// (lifetimes get messy sometimes)
 	pub(crate) fn resolve(mut self, definition: &mut PunybufDefinition) {
+		self.analyze(definition);
+		self.track_all_changes(definition);
+		self.resolve_references(definition);
+	}
+	/// Like [`Self::resolve`], but also returns a JSON dump of the dependencies before and
+	/// after tracking the changes between layers, and of the types and commands generated
+	/// by tracking them (`--dump-resolution`)
+	#[allow(unused)] // only used in main.rs
+	pub(crate) fn resolve_with_dump(mut self, definition: &mut PunybufDefinition) -> String {
+		self.analyze(definition);
+		let before = self.dependencies_to_json();
+		let (type_count, command_count) = (definition.types.len(), definition.commands.len());
+
+		self.track_all_changes(definition);
+		let after = self.dependencies_to_json();
+		let generated_types = definition.types[type_count..].iter()
+			.map(|tp| json::object! { name: tp.get_name().0, layer: *tp.get_layer() });
+		let generated_commands = definition.commands[command_count..].iter()
+			.map(|cmd| json::object! { name: cmd.name.as_str(), layer: cmd.layer, id: cmd.command_id });
+		let dump = json::object! {
+			dependencies_before: before,
+			dependencies_after: after,
+			generated_types: JsonValue::from(generated_types.collect::<Vec<_>>()),
+			generated_commands: JsonValue::from(generated_commands.collect::<Vec<_>>()),
+		};
+
+		self.resolve_references(definition);
+		dump.pretty(2)
+	}
+	/// `{ "Type": [{ "name": "Dependent", "layer": 0, "kind": "type" }, ...] }`, sorted
+	#[allow(unused)]
+	fn dependencies_to_json(&self) -> JsonValue {
+		let mut names = self.dependencies.keys().collect::<Vec<_>>();
+		names.sort();
+		let mut result = JsonValue::new_object();
+		for name in names {
+			let mut dependents = self.dependencies[name].iter().collect::<Vec<_>>();
+			dependents.sort();
+			let dependents = dependents.into_iter().map(|dep| json::object! {
+				name: dep.name.as_str(),
+				layer: dep.layer,
+				kind: match dep.kind {
+					DependentKind::Type => "type",
+					DependentKind::Command => "command",
+				},
+			});
+			result[name.as_str()] = JsonValue::from(dependents.collect::<Vec<_>>());
+		}
+		result
+	}
+	/// Marks the global references and fills the dependencies in
+	fn analyze(&mut self, definition: &mut PunybufDefinition) {
 		for index in 0..definition.types.len() {
 			let tp = &mut definition.types[index];
 			match tp {
@@ -276,13 +333,15 @@ impl LayerResolver {
 		for cmd in &definition.commands {
 			self.analyze_command_dependencies(cmd);
 		}
+	}
+	/// Makes new versions of the types and commands that depend on types changed in higher layers
+	fn track_all_changes(&mut self, definition: &mut PunybufDefinition) {
+		// `track_changes` may add types, which may also need tracking
 		let mut index = 0;
 		while index < definition.types.len() {
 			self.track_changes(definition, index);
 			index += 1;
 		}
-
-		self.resolve_references(definition);
 	}
 	fn resolve_alias_generics(params: &Vec<String>, input: &Vec<PBTypeRef>, generics: &mut Vec<PBTypeRef>) {
 		for output_generic_param in generics {
@@ -0,0 +1,558 @@
+//! `pbd self-check`: generates the Rust code for a definition, compiles it in a temporary
+//! crate, and checks that it decodes and encodes random values the way the definition says.
+//!
+//! The values are generated here, already encoded, by interpreting the definition the
+//! same way `DynDecoder` does when decoding. The generated code has to decode every one of
+//! them, and serialize it back into exactly the same bytes.
+
+use std::{
+	collections::HashMap,
+	env, fs,
+	path::{Path, PathBuf},
+	process::{self, Command},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+	codegen::RustCodegen,
+	errors::{BLUE, BOLD, NORMAL, YELLOW},
+	flattener::{PBCommandArg, PBEnumVariant, PBField, PBTypeDef, PBTypeRef, PunybufDefinition},
+};
+
+/// The version of punybuf_common the crate depends on, unless `--common` is set
+const COMMON_VERSION: &str = "0.7";
+/// How deeply values are nested before only the smallest choices are made, so values
+/// of recursive types end
+const SOFT_MAX_DEPTH: usize = 8;
+/// How deeply values may be nested at all
+const MAX_DEPTH: usize = 64;
+
+/// What `pbd self-check` does, set by its options
+pub struct SelfCheck {
+	/// How many values of every type and command are checked
+	pub samples: usize,
+	pub seed: u64,
+	/// The path of punybuf_common, instead of the version from crates.io
+	pub common: Option<PathBuf>,
+	/// Where the crate is written. It's kept if set, and deleted afterwards otherwise
+	pub dir: Option<PathBuf>,
+	/// Passed on to cargo
+	pub offline: bool,
+}
+
+impl SelfCheck {
+	/// A seed for when `--seed` isn't set
+	pub fn random_seed() -> u64 {
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+		nanos as u64 ^ ((process::id() as u64) << 32)
+	}
+
+	pub fn run(&self, def: &PunybufDefinition) -> Result<(), String> {
+		let mut samples = Samples::new(def, self.seed);
+		let mut checks = vec![];
+		for tp in &def.types {
+			let attrs = tp.get_attrs();
+			let skipped = ["@builtin", "@rust:ignore", "@resolve", "@experimental"].iter().any(|a| attrs.contains_key(*a));
+			if skipped || !tp.get_generics().0.is_empty() {
+				continue;
+			}
+			let name = if tp.is_highest_layer() {
+				tp.get_name().0.to_string()
+			} else {
+				format!("{}Layer{}", tp.get_name().0, tp.get_layer())
+			};
+			let refr = PBTypeRef::new(tp.get_name().0, Some(*tp.get_layer()), vec![], tp.is_highest_layer());
+			match samples.many(self.samples, |s, out| s.gen_ref(&refr, out, 0)) {
+				Ok(values) => checks.push(Check { name: name.clone(), rust: format!("check::<{name}>"), values }),
+				Err(e) => eprintln!("{YELLOW}{BOLD}warning:{NORMAL} skipping `{name}`: {e}"),
+			}
+		}
+		for cmd in &def.commands {
+			if cmd.attrs.contains_key("@rust:ignore") || cmd.attrs.contains_key("@experimental") {
+				continue;
+			}
+			let name = if cmd.is_highest_layer {
+				cmd.name.clone()
+			} else {
+				format!("{}Layer{}", cmd.name, cmd.layer)
+			};
+			let values = samples.many(self.samples, |s, out| {
+				out.extend_from_slice(&cmd.command_id.to_be_bytes());
+				match &cmd.argument {
+					PBCommandArg::None => Ok(()),
+					PBCommandArg::Ref(refr) => s.gen_ref(refr, out, 0),
+					PBCommandArg::Struct { fields } => {
+						s.gen_fields(fields, !cmd.attrs.contains_key("@sealed"), &HashMap::new(), out, 0)
+					}
+				}
+			});
+			match values {
+				Ok(values) => checks.push(Check { name, rust: "check_command".to_string(), values }),
+				Err(e) => eprintln!("{YELLOW}{BOLD}warning:{NORMAL} skipping the command `{name}`: {e}"),
+			}
+		}
+		if checks.is_empty() {
+			return Err("there are no types or commands to check".to_string());
+		}
+
+		let dir = match &self.dir {
+			Some(dir) => dir.clone(),
+			None => env::temp_dir().join(format!("pbd-self-check-{}", process::id())),
+		};
+		eprintln!(
+			"{BLUE}{BOLD}self-check:{NORMAL} {} values of {} types and commands, with the seed {}, in {}",
+			self.samples, checks.len(), self.seed, dir.display()
+		);
+		let result = self.write_crate(&dir, def, &checks).and_then(|_| self.run_crate(&dir));
+		if self.dir.is_none() {
+			let _ = fs::remove_dir_all(&dir);
+		}
+		result
+	}
+
+	fn write_crate(&self, dir: &Path, def: &PunybufDefinition, checks: &[Check]) -> Result<(), String> {
+		let common = match &self.common {
+			Some(path) => {
+				let path = fs::canonicalize(path).map_err(|e| format!("{}: {e}", path.display()))?;
+				format!("path = {:?}", path.display().to_string())
+			}
+			None => format!("version = {COMMON_VERSION:?}"),
+		};
+		let features: Vec<_> = needed_features(def).iter().map(|f| format!("{f:?}")).collect();
+		let manifest = format!(
+			"[package]\n\
+			name = \"pbd-self-check\"\n\
+			version = \"0.0.0\"\n\
+			edition = \"2024\"\n\
+			\n\
+			[dependencies]\n\
+			punybuf_common = {{ {common}, default-features = false, features = [{}] }}\n\
+			\n\
+			# so it isn't taken for a part of a workspace it's written into\n\
+			[workspace]\n",
+			features.join(", "),
+		);
+		let generated = RustCodegen::new(false, false, def).codegen();
+
+		let src = dir.join("src");
+		fs::create_dir_all(&src).map_err(|e| format!("{}: {e}", src.display()))?;
+		for (path, contents) in [
+			(dir.join("Cargo.toml"), manifest),
+			(src.join("generated.rs"), generated),
+			(src.join("main.rs"), gen_main(checks)),
+		] {
+			fs::write(&path, contents).map_err(|e| format!("{}: {e}", path.display()))?;
+		}
+		Ok(())
+	}
+
+	fn run_crate(&self, dir: &Path) -> Result<(), String> {
+		let mut cargo = Command::new(env::var_os("CARGO").unwrap_or("cargo".into()));
+		cargo.arg("run").arg("--quiet").current_dir(dir);
+		if self.offline {
+			cargo.arg("--offline");
+		}
+		let status = cargo.status().map_err(|e| format!("couldn't run cargo: {e}"))?;
+		if !status.success() {
+			return Err("the generated code failed the self-check".to_string());
+		}
+		Ok(())
+	}
+}
+
+/// The features of punybuf_common the code generated for `def` needs
+fn needed_features(def: &PunybufDefinition) -> Vec<&'static str> {
+	let mut features = vec!["chrono"];
+	let compressed = |fields: &[PBField]| fields.iter().any(|f| f.attrs.contains_key("@compress"));
+	let compresses = def.types.iter().any(|tp| matches!(tp, PBTypeDef::Struct { fields, .. } if compressed(fields))) ||
+		def.commands.iter().any(|cmd| matches!(&cmd.argument, PBCommandArg::Struct { fields } if compressed(fields)));
+	if compresses {
+		features.push("compression");
+	}
+	features
+}
+
+/// Checks the values of commands, whose ID comes first. Only there if there are commands,
+/// since `Command` isn't generated otherwise
+const CHECK_COMMAND: &str = "
+/// The same for `Command`, whose ID comes first
+fn check_command(bytes: &'static [u8]) -> Result<(), String> {
+    let mut slice = bytes;
+    let value = Command::deserialize(&mut slice).map_err(|e| format!(\"deserialize: {e}\"))?;
+    if !slice.is_empty() {
+        return Err(format!(\"deserialize: {} trailing bytes\", slice.len()));
+    }
+    compare(bytes, {
+        let mut out = vec![];
+        PBCommand::serialize(&value, &mut out).map(|_| out)
+    })?;
+    let value = Command::deserialize_stream(&mut &bytes[..]).map_err(|e| format!(\"deserialize_stream: {e}\"))?;
+    compare(bytes, {
+        let mut out = vec![];
+        PBCommand::serialize(&value, &mut out).map(|_| out)
+    })
+}
+";
+
+/// The values of a type or a command
+struct Check {
+	name: String,
+	/// The function checking them
+	rust: String,
+	values: Vec<Vec<u8>>,
+}
+
+/// The `main.rs` checking the values of every type and command
+fn gen_main(checks: &[Check]) -> String {
+	let mut main = String::from(
+"//! Generated by `pbd self-check`.
+
+#[allow(dead_code, unused)]
+mod generated;
+
+#[allow(unused_imports)]
+use generated::*;
+#[allow(unused_imports)]
+use punybuf_common::{PBCommand, PBType};
+
+type Check = fn(&'static [u8]) -> Result<(), String>;
+
+/// Decodes a value of `T` from `bytes`, from a slice and from a stream,
+/// and checks that it's serialized into the same bytes
+fn check<T: PBType<'static>>(bytes: &'static [u8]) -> Result<(), String> {
+    let mut slice = bytes;
+    let value = T::deserialize(&mut slice).map_err(|e| format!(\"deserialize: {e}\"))?;
+    if !slice.is_empty() {
+        return Err(format!(\"deserialize: {} trailing bytes\", slice.len()));
+    }
+    compare(bytes, {
+        let mut out = vec![];
+        value.serialize(&mut out).map(|_| out)
+    })?;
+    let value = T::deserialize_stream(&mut &bytes[..]).map_err(|e| format!(\"deserialize_stream: {e}\"))?;
+    compare(bytes, {
+        let mut out = vec![];
+        value.serialize(&mut out).map(|_| out)
+    })
+}
+
+fn compare(expected: &[u8], serialized: std::io::Result<Vec<u8>>) -> Result<(), String> {
+    let serialized = serialized.map_err(|e| format!(\"serialize: {e}\"))?;
+    if serialized != expected {
+        return Err(format!(\"serialized into different bytes: {serialized:?}\"));
+    }
+    Ok(())
+}
+");
+	if checks.iter().any(|check| check.rust == "check_command") {
+		main.push_str(CHECK_COMMAND);
+	}
+	main.push_str("\nconst CHECKS: &[(&str, Check, &[&[u8]])] = &[\n");
+	for check in checks {
+		main.push_str(&format!("    ({:?}, {}, &[\n", check.name, check.rust));
+		for value in &check.values {
+			main.push_str(&format!("        &{value:?},\n"));
+		}
+		main.push_str("    ]),\n");
+	}
+	main.push_str(
+"];
+
+fn main() {
+    let mut failures = 0;
+    for (name, check, values) in CHECKS {
+        for value in *values {
+            if let Err(e) = check(value) {
+                println!(\"`{name}` failed on {value:?}: {e}\");
+                failures += 1;
+            }
+        }
+    }
+    if failures != 0 {
+        println!(\"{failures} values failed the self-check\");
+        std::process::exit(1);
+    }
+    println!(\"every value of {} types and commands round-tripped\", CHECKS.len());
+}
+");
+	main
+}
+
+/// Generates random values, already encoded
+struct Samples<'d> {
+	def: &'d PunybufDefinition,
+	/// The state of a xorshift generator, so the values only depend on the seed
+	state: u64,
+}
+
+impl<'d> Samples<'d> {
+	fn new(def: &'d PunybufDefinition, seed: u64) -> Self {
+		// xorshift gets stuck on 0
+		Self { def, state: seed | 1 }
+	}
+
+	fn next(&mut self) -> u64 {
+		self.state ^= self.state << 13;
+		self.state ^= self.state >> 7;
+		self.state ^= self.state << 17;
+		self.state
+	}
+
+	/// A number in `0..n`
+	fn below(&mut self, n: u64) -> u64 {
+		self.next() % n
+	}
+
+	fn bytes(&mut self, len: usize, out: &mut Vec<u8>) {
+		for _ in 0..len {
+			out.push(self.next() as u8);
+		}
+	}
+
+	fn many(&mut self, n: usize, mut generate: impl FnMut(&mut Self, &mut Vec<u8>) -> Result<(), String>) -> Result<Vec<Vec<u8>>, String> {
+		(0..n).map(|_| {
+			let mut out = vec![];
+			generate(self, &mut out)?;
+			Ok(out)
+		}).collect()
+	}
+
+	/// The builtin `refr` is an alias of, for the integers holding flags
+	fn builtin_of(&self, refr: &PBTypeRef) -> Result<PBTypeRef, String> {
+		match self.def.find_type(refr)? {
+			tp if tp.get_attrs().contains_key("@builtin") => Ok(refr.clone()),
+			PBTypeDef::Alias { alias, .. } => self.builtin_of(alias),
+			_ => Err(format!("`{}` isn't a number, so it can't hold flags", refr.reference)),
+		}
+	}
+
+	fn gen_ref(&mut self, refr: &PBTypeRef, out: &mut Vec<u8>, depth: usize) -> Result<(), String> {
+		if depth > MAX_DEPTH {
+			return Err("couldn't generate a value that isn't nested too deeply".to_string());
+		}
+		let depth = depth + 1;
+		let tp = self.def.find_type(refr)?;
+		if tp.get_attrs().contains_key("@builtin") {
+			return self.gen_builtin(refr, out, depth);
+		}
+		let bindings: HashMap<&str, PBTypeRef> = tp.get_generics().0.iter()
+			.map(String::as_str)
+			.zip(refr.generics.iter().cloned())
+			.collect();
+		match tp {
+			PBTypeDef::Alias { alias, .. } => self.gen_ref(&alias.substitute(&bindings)?, out, depth),
+			PBTypeDef::Struct { fields, attrs, .. } => {
+				self.gen_fields(fields, !attrs.contains_key("@sealed"), &bindings, out, depth)
+			}
+			PBTypeDef::Enum { variants, .. } => self.gen_variant(variants, &bindings, out, depth),
+		}
+	}
+
+	fn gen_uint(value: u64, out: &mut Vec<u8>) {
+		let (len, prefix, offset) = match value {
+			0..128 => (0, 0, 0),
+			128..16512 => (1, 0b1000_0000, 128),
+			16512..2113664 => (2, 0b1100_0000, 16512),
+			2113664..68721590400 => (4, 0b1110_0000, 2113664),
+			_ => (7, 0b1111_0000, 68721590400),
+		};
+		let value = value - offset;
+		out.push(prefix | (value >> (len * 8)) as u8);
+		for i in (0..len).rev() {
+			out.push((value >> (i * 8)) as u8);
+		}
+	}
+
+	/// A random `UInt`, equally likely to take any of the sizes
+	fn random_uint(&mut self) -> u64 {
+		match self.below(5) {
+			0 => self.below(128),
+			1 => 128 + self.below(16384),
+			2 => 16512 + self.below(1 << 21),
+			3 => 2113664 + self.below(1 << 36),
+			_ => 68721590400 + self.below(1 << 60),
+		}
+	}
+
+	fn gen_builtin(&mut self, refr: &PBTypeRef, out: &mut Vec<u8>, depth: usize) -> Result<(), String> {
+		let nested = depth > SOFT_MAX_DEPTH;
+		match refr.reference.as_str() {
+			"Void" => {}
+			"U8" => self.bytes(1, out),
+			"U16" => self.bytes(2, out),
+			"U32" | "I32" | "F32" => self.bytes(4, out),
+			"U64" | "I64" | "F64" => self.bytes(8, out),
+			"UInt" => {
+				let value = self.random_uint();
+				Self::gen_uint(value, out);
+			}
+			"SInt" => {
+				// small enough for the zigzag encoding to fit into a `UInt`
+				let value = self.next() as i64 >> 6;
+				Self::gen_uint(((value << 1) ^ (value >> 63)) as u64, out);
+			}
+			"Boolean" => out.push(self.below(2) as u8),
+			"Bytes" => {
+				let len = self.below(16) as usize;
+				Self::gen_uint(len as u64, out);
+				self.bytes(len, out);
+			}
+			"String" => {
+				let mut string = String::new();
+				for _ in 0..self.below(12) {
+					let chars = ['a', 'Z', '0', ' ', '\n', 'é', 'ß', 'ж', '語', '🦀'];
+					string.push(chars[self.below(chars.len() as u64) as usize]);
+				}
+				Self::gen_uint(string.len() as u64, out);
+				out.extend_from_slice(string.as_bytes());
+			}
+			"DateTime" => {
+				// within the range chrono supports
+				let secs = (self.below(200_000_000_000) as i64) - 100_000_000_000;
+				let nanos = self.below(1_000_000_000) as u32;
+				out.extend_from_slice(&secs.to_be_bytes());
+				out.extend_from_slice(&nanos.to_be_bytes());
+			}
+			"Array" => {
+				let [item] = &refr.generics[..] else {
+					return Err("`Array` takes one generic argument".to_string());
+				};
+				let len = if nested { 0 } else { self.below(4) };
+				Self::gen_uint(len, out);
+				for _ in 0..len {
+					self.gen_ref(item, out, depth)?;
+				}
+			}
+			"Optional" => {
+				let [value] = &refr.generics[..] else {
+					return Err("`Optional` takes one generic argument".to_string());
+				};
+				if nested || self.below(2) == 0 {
+					out.push(0);
+				} else {
+					out.push(1);
+					self.gen_ref(value, out, depth)?;
+				}
+			}
+			name => return Err(format!("there's no way to generate values of the builtin `{name}`")),
+		}
+		Ok(())
+	}
+
+	/// Random bits for the flags of `field`. Deeply nested, only flags without values are set
+	fn gen_bits(&mut self, field: &PBField, depth: usize) -> u64 {
+		let Some(flags) = &field.flags else { return 0 };
+		let mut bits = 0;
+		for (i, flag) in flags.iter().enumerate() {
+			if (flag.value.is_none() || depth <= SOFT_MAX_DEPTH) && self.below(2) == 0 {
+				bits |= 1 << i;
+			}
+		}
+		bits
+	}
+
+	/// Encodes `bits` as the value of `field`
+	fn gen_flags_field(&self, field: &PBField, bits: u64, bindings: &HashMap<&str, PBTypeRef>, out: &mut Vec<u8>) -> Result<(), String> {
+		let builtin = self.builtin_of(&field.value.substitute(bindings)?)?;
+		let mut bytes = match builtin.reference.as_str() {
+			"U8" => (bits as u8).to_be_bytes().to_vec(),
+			"U16" => (bits as u16).to_be_bytes().to_vec(),
+			"U32" => (bits as u32).to_be_bytes().to_vec(),
+			"U64" => bits.to_be_bytes().to_vec(),
+			"UInt" => {
+				let mut bytes = vec![];
+				Self::gen_uint(bits, &mut bytes);
+				bytes
+			}
+			_ => return Err(format!("`{}` isn't a number, so it can't hold flags", field.name)),
+		};
+		if field.attrs.contains_key("@le") {
+			bytes.reverse();
+		}
+		out.extend_from_slice(&bytes);
+		Ok(())
+	}
+
+	/// Generates the values of the flags of `field` that are set, either the ones marked `@extension` or the rest
+	fn gen_flags(
+		&mut self, field: &PBField, bits: u64, extensions: bool, bindings: &HashMap<&str, PBTypeRef>,
+		out: &mut Vec<u8>, depth: usize,
+	) -> Result<(), String> {
+		let Some(flags) = &field.flags else { return Ok(()) };
+		for (i, flag) in flags.iter().enumerate() {
+			if flag.attrs.contains_key("@extension") != extensions || bits & (1 << i) == 0 {
+				continue;
+			}
+			if let Some(refr) = &flag.value {
+				self.gen_ref(&refr.substitute(bindings)?, out, depth)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn gen_fields(
+		&mut self, fields: &[PBField], extensible: bool, bindings: &HashMap<&str, PBTypeRef>,
+		out: &mut Vec<u8>, depth: usize,
+	) -> Result<(), String> {
+		let mut bits = HashMap::new();
+		for field in fields {
+			if field.attrs.contains_key("@extension_flags") {
+				continue;
+			}
+			if field.attrs.contains_key("@compress") {
+				return Err(format!("`{}` is compressed, which isn't supported", field.name));
+			}
+			if field.flags.is_none() {
+				// random bytes are just as random in either byte order
+				self.gen_ref(&field.value.substitute(bindings)?, out, depth)?;
+				continue;
+			}
+			let field_bits = self.gen_bits(field, depth);
+			bits.insert(field.name.as_str(), field_bits);
+			self.gen_flags_field(field, field_bits, bindings, out)?;
+			self.gen_flags(field, field_bits, false, bindings, out, depth)?;
+		}
+		if !extensible {
+			return Ok(());
+		}
+		let mut extension = vec![];
+		for field in fields {
+			if let Some(&field_bits) = bits.get(field.name.as_str()) {
+				self.gen_flags(field, field_bits, true, bindings, &mut extension, depth)?;
+			}
+		}
+		if let Some(field) = fields.iter().find(|f| f.attrs.contains_key("@extension_flags")) {
+			let field_bits = self.gen_bits(field, depth);
+			self.gen_flags_field(field, field_bits, bindings, &mut extension)?;
+			self.gen_flags(field, field_bits, false, bindings, &mut extension, depth)?;
+		}
+		Self::gen_uint(extension.len() as u64, out);
+		out.append(&mut extension);
+		Ok(())
+	}
+
+	fn gen_variant(
+		&mut self, variants: &[PBEnumVariant], bindings: &HashMap<&str, PBTypeRef>,
+		out: &mut Vec<u8>, depth: usize,
+	) -> Result<(), String> {
+		if variants.is_empty() {
+			return Err("an enum without variants has no values".to_string());
+		}
+		let without_values: Vec<_> = variants.iter().filter(|v| v.value.is_none()).collect();
+		let variant = if depth > SOFT_MAX_DEPTH && !without_values.is_empty() {
+			without_values[self.below(without_values.len() as u64) as usize]
+		} else {
+			&variants[self.below(variants.len() as u64) as usize]
+		};
+		out.push(variant.discriminant);
+		let mut value = vec![];
+		if let Some(refr) = &variant.value {
+			self.gen_ref(&refr.substitute(bindings)?, &mut value, depth)?;
+		}
+		if variant.attrs.contains_key("@extension") {
+			Self::gen_uint(value.len() as u64, out);
+		}
+		out.append(&mut value);
+		Ok(())
+	}
+}
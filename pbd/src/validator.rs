@@ -1,6 +1,7 @@
-use std::{collections::HashMap, fmt::Display, u32};
+use std::{collections::{HashMap, HashSet}, fmt::Display, u32};
 
 use crate::{
+	attributes,
 	errors::{
 		diagnostic,
 		Diagnostic, ErrorInfo, PunybufError, parser_err, pb_err
@@ -10,9 +11,10 @@ use crate::{
 		PBField, PBFieldFlag, PBTypeDef, PBTypeRef, PunybufDefinition
 	},
 	lexer::Span,
+	lints::{Lint, Lints},
 };
 
-const COMMON_TYPES: [&str; 16] = [
+const COMMON_TYPES: [&str; 17] = [
 	"Void",
 	"U8",
 	"U16",
@@ -21,6 +23,7 @@ const COMMON_TYPES: [&str; 16] = [
 	"I32",
 	"I64",
 	"UInt",
+	"SInt",
 	"Array",
 	"Bytes",
 	"String",
@@ -35,11 +38,27 @@ enum FlagsAttrError<'a> {
 	NoAttribute(&'a PBTypeDef),
 	AliasGeneric {
 		typedef: &'a PBTypeDef,
-		ref_to_generic: (&'a String, &'a Span)
+		ref_to_generic: (String, Span)
 	},
 	Other(PunybufError)
 }
 
+/// A reference with its generic parameters substituted, along with the layer it's resolved in
+#[derive(Clone)]
+struct BoundRef<'a> {
+	refr: &'a PBTypeRef,
+	layer: u32,
+	generics: Vec<BoundRef<'a>>,
+}
+
+impl BoundRef<'_> {
+	/// The name, layer and generic arguments, which tell bound references apart
+	fn key(&self) -> String {
+		let generics: Vec<String> = self.generics.iter().map(Self::key).collect();
+		format!("{}@{}<{}>", self.refr.reference, self.layer, generics.join(", "))
+	}
+}
+
 enum ReferenceDefinition<'a> {
 	TopLevelDecl(&'a PBTypeDef),
 	GenericParam(Span)
@@ -100,13 +119,20 @@ impl Display for SeenNameType {
 
 pub struct PunybufValidator<'pbd> {
 	pub definition: &'pbd PunybufDefinition,
-	context_generic_params: Vec<(&'pbd str, &'pbd Span)>
+	context_generic_params: Vec<(&'pbd str, &'pbd Span)>,
+	/// Problems that don't make the definition invalid
+	pub warnings: Vec<PunybufError>,
+	lints: Lints,
+	/// Problems found by the lints set to [`LintLevel::Deny`](crate::lints::LintLevel::Deny)
+	denied: Vec<PunybufError>,
 }
 
 impl<'d> PunybufValidator<'d> {
-	/// Returns Ok(maximum amount of flags)
+	/// Returns Ok(maximum amount of flags), which is never more than the backing integer has bits
+	/// 
+	/// `args` are the generic arguments `decl` is referenced with
 	fn follow_to_flags_attr<'a>(
-		&'a self, decl: &'a PBTypeDef,
+		&'a self, decl: &'a PBTypeDef, args: &Vec<PBTypeRef>,
 		owner: &Owner, tries: usize
 	) -> Result<usize, FlagsAttrError<'a>> {
 		if tries >= 200 {
@@ -127,72 +153,70 @@ impl<'d> PunybufValidator<'d> {
 		match decl {
 			PBTypeDef::Enum { attrs, .. } |
 			PBTypeDef::Struct { attrs, ..} => {
-				let Some(n) = attrs.get(&"@flags".to_string()) else {
+				let Some(n) = attributes::number(attrs, "@flags") else {
 					return Err(FlagsAttrError::NoAttribute(decl));
 				};
-				let Some(Ok(n)) = n.as_ref().map(|x| x.trim().parse::<usize>()) else {
-					return Err(FlagsAttrError::Other(
-						pb_err!(
-							decl.get_name().1,
-							format!("the `@flags` attribute on this type doesn't put a limit on how many flags are possible"),
-							after_error: vec![
-								diagnostic!(Info,
-									owner.get_name().1.clone(),
-									format!("`{}` is mentioned here", decl.get_name().0)
-								)
-							]
-						)
-					));
-				};
-				Ok(n)
-			}
-			PBTypeDef::Alias { attrs, alias, generic_params, generic_span, .. } => {
-				if let Some(n) = attrs.get(&"@flags".to_string()) {
-					let Some(Ok(n)) = n.as_ref().map(|x| x.trim().parse::<usize>()) else {
-						return Err(FlagsAttrError::Other(
-							pb_err!(
-								decl.get_name().1,
-								format!("the `@flags` attribute on this type must put a limit on how many flags are possible"),
-								after_error: vec![
-									diagnostic!(Info,
-										owner.get_name().1.clone(),
-										format!("`{}` is mentioned here", decl.get_name().0)
-									)
-								]
-							)
-						));
-					};
-					Ok(n)
+				Ok(n as usize)
+			}
+			PBTypeDef::Alias { attrs, alias, generic_params, .. } => {
+				let n = attributes::number(attrs, "@flags");
+				if let Some(n) = n && attrs.contains_key("@builtin") {
+					Ok(n as usize)
 				} else if attrs.contains_key(&"@builtin".to_string()) {
 					return Err(FlagsAttrError::NoAttribute(decl));
 				} else {
-					let generics = generic_params.iter().map(|n| (n.as_str(), generic_span)).collect();
+					// `Alias<T> = T` referenced as `Alias<U8>` leads to `U8`. What's left
+					// after the substitution is either a type or a generic parameter of `owner`
+					let alias = Self::substitute_generics(alias, generic_params, args);
 
 					// Aliases cannot resolve to `Void` anyway
 					let def = self.validate_reference_void(
-						alias,
+						&alias,
 						owner,
-						Some(&generics)
+						None
 					).map_err(|pbe| FlagsAttrError::Other(pbe))?;
 
 					match def {
-						ReferenceDefinition::GenericParam(_) => {
-							Err(FlagsAttrError::AliasGeneric {
+						ReferenceDefinition::GenericParam(_) => match n {
+							// `@flags(n) Alias<T> = T` referenced with a generic parameter of `owner`
+							Some(n) => Ok(n as usize),
+							None => Err(FlagsAttrError::AliasGeneric {
 								typedef: &decl,
-								ref_to_generic: (&alias.reference, &alias.reference_span)
-							})
-						}
+								ref_to_generic: (alias.reference, alias.reference_span)
+							}),
+						},
+						// `@flags(n)` on an alias can only narrow what the aliased type can store.
+						// Non-generic aliases are checked in `validate_flags_capacity` too, but
+						// `@flags(40) Alias<T> = T` referenced as `Alias<U32>` only here
 						ReferenceDefinition::TopLevelDecl(decl) => {
-							self.follow_to_flags_attr(decl, owner, tries + 1)
+							let bits = self.follow_to_flags_attr(decl, &alias.generics, owner, tries + 1)?;
+							Ok(n.map_or(bits, |n| bits.min(n as usize)))
 						}
 					}
 				}
 			}
 		}
 	}
+	/// Replaces the generic parameters `params` in `refr` with `args`
+	fn substitute_generics(refr: &PBTypeRef, params: &Vec<String>, args: &Vec<PBTypeRef>) -> PBTypeRef {
+		if let Some(arg) = params.iter().position(|p| *p == refr.reference).and_then(|i| args.get(i)) {
+			return arg.clone();
+		}
+		let mut result = refr.clone();
+		result.generics = refr.generics.iter()
+			.map(|g| Self::substitute_generics(g, params, args))
+			.collect();
+		result
+	}
 	fn find_type_by_name(&self, name: &str, limit_layer: u32) -> Option<&PBTypeDef> {
 		self.definition.types.iter().rev().find(|typ| typ.get_name().0 == name && *typ.get_layer() <= limit_layer)
 	}
+	fn is_owner_experimental(&self, owner: &Owner) -> bool {
+		match owner {
+			Owner::TypeOwner(tp) => self.definition.is_experimental(tp),
+			Owner::CommandOwner(cmd) => cmd.attrs.contains_key("@experimental"),
+		}
+	}
 	fn validate_reference(&self, refr: &PBTypeRef, owner: &Owner) -> 
 		Result<ReferenceDefinition<'_>, PunybufError> 
 	{
@@ -343,7 +367,50 @@ impl<'d> PunybufValidator<'d> {
 						}
 					},
 				}
-				
+
+				if self.definition.is_experimental(decl) && !self.is_owner_experimental(owner) {
+					return Err(pb_err!(
+						refr.reference_span,
+						format!(
+							"type `{}` is experimental and cannot be referenced from `{}`",
+							refr.reference, owner.get_name().0
+						),
+						after_error: vec![
+							diagnostic!(Info,
+								decl.get_name().1.clone(),
+								format!("`{}` is declared here", refr.reference)
+							),
+							diagnostic!(Tip,
+								owner.get_name().1.clone(),
+								format!("mark `{}` as `@experimental` too", owner.get_name().0)
+							),
+						]
+					));
+				}
+
+				// the Rust codegen doesn't generate ignored types, so the owner wouldn't compile
+				if decl.get_attrs().contains_key("@rust:ignore") && !decl.get_attrs().contains_key("@builtin") &&
+					!owner.get_attrs().contains_key("@rust:ignore")
+				{
+					return Err(pb_err!(
+						refr.reference_span,
+						format!(
+							"type `{}` is `@rust:ignore`d and cannot be referenced from `{}`",
+							refr.reference, owner.get_name().0
+						),
+						after_error: vec![
+							diagnostic!(Info,
+								decl.get_name().1.clone(),
+								format!("`{}` is declared here", refr.reference)
+							),
+							diagnostic!(Tip,
+								owner.get_name().1.clone(),
+								format!("mark `{}` as `@rust:ignore` too", owner.get_name().0)
+							),
+						]
+					));
+				}
+
 				let (decl_generic_params, decl_generic_span) = decl.get_generics();
 				if decl_generic_params.len() > refr.generics.len() {
 					let not_provided = decl_generic_params.split_at(refr.generics.len()).1;
@@ -446,10 +513,14 @@ impl<'d> PunybufValidator<'d> {
 				if let Some(cmd) = self.definition.commands.iter()
 					.find(|c| c.name == refr.reference)
 				{
+					let reason = match owner {
+						Owner::TypeOwner(_) => "types cannot reference commands",
+						Owner::CommandOwner(_) => "the argument, return and errors of a command must be types",
+					};
 					return Err(pb_err!(
 						refr.reference_span,
 						format!(
-							"cannot find type `{}` in scope",
+							"`{}` is a command, not a type",
 							refr.reference
 						),
 						after_error: vec![
@@ -457,7 +528,7 @@ impl<'d> PunybufValidator<'d> {
 								cmd.name_span.clone(),
 								format!(
 									"tip: `{}` is defined here as a command, \
-									but types cannot reference commands",
+									but {reason}",
 									cmd.name
 								)
 							)
@@ -484,8 +555,35 @@ impl<'d> PunybufValidator<'d> {
 		}
 		Ok(())
 	}
+	/// Follows the aliases to see if `refr` is `Void` or a struct without any fields
+	fn is_zero_sized(&self, refr: &PBTypeRef, owner: &Owner) -> bool {
+		if refr.reference == "Void" {
+			return true;
+		}
+		let mut current = refr;
+		for _ in 0..200 {
+			let Ok(ReferenceDefinition::TopLevelDecl(decl)) = self.validate_reference_void(current, owner, None) else {
+				return false;
+			};
+			match decl {
+				PBTypeDef::Struct { fields, .. } => return fields.is_empty(),
+				PBTypeDef::Enum { .. } => return false,
+				PBTypeDef::Alias { alias, .. } => {
+					if alias.reference == "Void" {
+						return true;
+					}
+					if !alias.generics.is_empty() {
+						// generic parameters could be anything
+						return false;
+					}
+					current = alias;
+				}
+			}
+		}
+		false
+	}
 	fn validate_flags<'n, 'f: 'n>(
-		&self, owner: &Owner, flags: &'f Vec<PBFieldFlag>,
+		&mut self, owner: &Owner, flags: &'f Vec<PBFieldFlag>,
 		seen_names: &mut Vec<(&'n str, &'n Span, SeenNameType)>
 	)
 		-> Result<(), PunybufError>
@@ -494,6 +592,18 @@ impl<'d> PunybufValidator<'d> {
 		let mut extension_begin = None::<(&str, &Span)>;
 
 		for flag in flags {
+			if flag.attrs.contains_key("@compress") {
+				return Err(pb_err!(
+					flag.name_span,
+					format!("flags can't be marked `@compress`, only struct fields can")
+				));
+			}
+			if let Some(attr) = ["@le", "@be", "@lazy_array"].into_iter().find(|attr| flag.attrs.contains_key(*attr)) {
+				return Err(pb_err!(
+					flag.name_span,
+					format!("flags can't be marked `{attr}`, only struct fields can")
+				));
+			}
 			if let Some(dupe) = seen_names.iter().find(|n| *n.0 == flag.name) {
 				let mut expl = ErrorInfo::instead(vec![
 					diagnostic!(Info,
@@ -576,9 +686,24 @@ impl<'d> PunybufValidator<'d> {
 				));
 			}
 
-			if let Some(refr) = &flag.value {
+			let Some(refr) = &flag.value else { continue };
+			if refr.reference == "Void" {
+				self.validate_reference_void(refr, owner, None)?;
+			} else {
 				self.validate_reference(refr, owner)?;
 			}
+			if self.is_zero_sized(refr, owner) {
+				self.lint(Lint::EmptyFlagValue, pb_err!(
+					refr.reference_span,
+					format!("the value of the flag `{}` is always empty", flag.name),
+					after_error: vec![
+						diagnostic!(Tip,
+							flag.name_span.clone(),
+							format!("tip: remove the type to make `{}` a boolean flag", flag.name)
+						)
+					]
+				));
+			}
 		}
 		Ok(())
 	}
@@ -625,6 +750,15 @@ impl<'d> PunybufValidator<'d> {
 			seen_names.push((&field.name, &field.name_span, SeenNameType::Field));
 
 			let field_ref_def = self.validate_reference(&field.value, owner)?;
+			if let Some(compress) = field.attrs.get("@compress") {
+				Self::validate_compress(field, compress, &field_ref_def)?;
+			}
+			if field.attrs.contains_key("@le") || field.attrs.contains_key("@be") {
+				Self::validate_byte_order(field, &field_ref_def)?;
+			}
+			if field.attrs.contains_key("@lazy_array") {
+				Self::validate_lazy_array(field, &field_ref_def)?;
+			}
 			if let Some(flags) = &field.flags {
 				let field_ref_decl = match field_ref_def {
 					ReferenceDefinition::TopLevelDecl(x) => x,
@@ -647,8 +781,29 @@ impl<'d> PunybufValidator<'d> {
 					PBTypeDef::Struct { name_span, .. } |
 					PBTypeDef::Enum { name_span, .. } => name_span,
 				};
-				match self.follow_to_flags_attr(field_ref_decl, owner, 0) {
+				match self.follow_to_flags_attr(field_ref_decl, &field.value.generics, owner, 0) {
 					Ok(max_amount) => if flags.len() > max_amount {
+						let mut bound = diagnostic!(Info,
+							field.value.reference_span.clone(),
+							format!(
+								"the maximum amount of flags is bounded by type `{}`",
+								field.value.reference
+							)
+						);
+						// e.g. `@flags(40) Wide<T> = T` referenced as `Wide<U32>`
+						if let PBTypeDef::Alias { attrs, alias, generic_params, .. } = field_ref_decl &&
+							let Some(n) = attributes::number(attrs, "@flags") &&
+							n as usize > max_amount
+						{
+							let backing = Self::substitute_generics(alias, generic_params, &field.value.generics);
+							bound = diagnostic!(Info,
+								field.value.reference_span.extend(&field.value.generic_span),
+								format!(
+									"`{}` is marked `@flags({n})`, but here it's stored as `{}`, which only has room for {max_amount}",
+									field.value.reference, backing.reference
+								)
+							);
+						}
 						return Err(pb_err!(
 							field.name_span,
 							format!(
@@ -656,15 +811,7 @@ impl<'d> PunybufValidator<'d> {
 								flags.len(),
 								field.value.reference
 							),
-							after_error: vec![
-								diagnostic!(Info,
-									field.value.reference_span.clone(),
-									format!(
-										"the maximum amount of flags is bounded by type `{}`",
-										field.value.reference
-									)
-								)
-							]
+							after_error: vec![bound]
 						));
 					} else if flags.len() < max_amount {
 						can_add_extension_flags = false;
@@ -731,8 +878,8 @@ impl<'d> PunybufValidator<'d> {
 							diagnostic!(Info,
 								ref_to_generic.1.clone(),
 								format!(
-									"...and later aliases to `{}`, which cannot be constrained as `@flags`",
-									ref_to_generic.0
+									"...and resolves to `{}` here, a generic parameter of `{}` that cannot be constrained as `@flags`",
+									ref_to_generic.0, owner.get_name().0
 								)
 							)
 						);
@@ -820,6 +967,8 @@ impl<'d> PunybufValidator<'d> {
 		let mut extension_discriminant = None::<u8>;
 
 		let mut seen_names: Vec<(&str, &Span)> = vec![];
+		// two variants can't be encoded the same way
+		let mut seen_discriminants: Vec<&PBEnumVariant> = vec![];
 		for variant in variants {
 			if let Some(already_decl) = seen_names.iter().find(|n| *n.0 == variant.name) {
 				return Err(pb_err!(
@@ -839,8 +988,39 @@ impl<'d> PunybufValidator<'d> {
 			}
 			seen_names.push((&variant.name, &variant.name_span));
 
-			// TODO: validate the discriminant
-			// (right now, you can't set your own so it's fine)
+			if let Owner::CommandOwner(cmd) = owner && variant.discriminant == 0 {
+				return Err(pb_err!(
+					variant.name_span,
+					format!("the error `{}` of `{}` can't have the discriminant `0`", variant.name, cmd.name),
+					after_error: vec![
+						diagnostic!(Info,
+							cmd.name_span.clone(),
+							format!("`0` is always the unexpected error of a command, with a `String` describing it")
+						)
+					]
+				));
+			}
+
+			if let Some(same) = seen_discriminants.iter().find(|v| v.discriminant == variant.discriminant) {
+				return Err(pb_err!(
+					variant.name_span,
+					format!(
+						"enum variants `{}` and `{}` have the same discriminant `{}`",
+						same.name, variant.name, variant.discriminant
+					),
+					ErrorInfo::instead(vec![
+						diagnostic!(Info,
+							same.name_span.clone(),
+							format!("`{}` is `{}` here", same.name, same.discriminant)
+						),
+						diagnostic!(Error,
+							variant.name_span.clone(),
+							format!("`{}` is `{}` too", variant.name, variant.discriminant)
+						),
+					])
+				));
+			}
+			seen_discriminants.push(variant);
 
 			if variant.attrs.contains_key("@default") {
 				if let Some(already_default) = default_variant {
@@ -881,15 +1061,36 @@ impl<'d> PunybufValidator<'d> {
 			}
 
 			if variant.attrs.contains_key("@extension") {
-				// right now, since all enum variants are
-				// sequential, a @default key must always come before
-				// @extensions
+				// a @default key must always come before @extensions
 				if default_variant.is_none() {
 					return Err(pb_err!(
 						variant.name_span,
 						format!("an `@extension` variant cannot be defined without a `@default` variant present")
 					));
 				};
+				// with explicit discriminants, a regular variant declared
+				// earlier can still come after this one on the wire
+				if let Some(regular) = seen_discriminants.iter().find(|v| {
+					!v.attrs.contains_key("@extension") && v.discriminant > variant.discriminant
+				}) {
+					return Err(pb_err!(
+						regular.name_span,
+						format!(
+							"the regular enum variant `{}` cannot have a higher discriminant than the `@extension` variant `{}`",
+							regular.name, variant.name
+						),
+						ErrorInfo::instead(vec![
+							diagnostic!(Error,
+								regular.name_span.clone(),
+								format!("`{}` is `{}`", regular.name, regular.discriminant)
+							),
+							diagnostic!(Info,
+								variant.name_span.clone(),
+								format!("but `{}` is only `{}`", variant.name, variant.discriminant)
+							),
+						])
+					));
+				}
 				extension_discriminant = Some(variant.discriminant);
 
 			} else if let Some(extension_discriminant) = extension_discriminant {
@@ -929,6 +1130,9 @@ impl<'d> PunybufValidator<'d> {
 		match tp {
 			PBTypeDef::Alias { alias, .. } => {
 				self.validate_reference(alias, &Owner::TypeOwner(tp))?;
+				if let Some(cap) = attributes::number(attrs, "@flags") {
+					self.validate_flags_capacity(tp, alias, cap)?;
+				}
 				is_alias = true;
 			}
 			PBTypeDef::Enum { variants, .. } => {
@@ -939,6 +1143,23 @@ impl<'d> PunybufValidator<'d> {
 			}
 		}
 
+		self.validate_no_direct_recursion(tp)?;
+
+		if tp.get_attrs().contains_key("@map_convertible") {
+			self.validate_map_convertible(tp)?;
+		}
+
+		if tp.get_attrs().contains_key("@bitmap") && !matches!(tp, PBTypeDef::Struct { .. }) {
+			return Err(pb_err!(
+				tp.get_name().1,
+				format!("only structs may be marked as `@bitmap`")
+			));
+		}
+
+		if let Some(phantom) = tp.get_attrs().get("@phantom") {
+			self.validate_phantom(tp, phantom)?;
+		}
+
 		if tp.get_attrs().contains_key("@resolve") && !is_alias {
 			return Err(pb_err!(
 				tp.get_name().1,
@@ -949,6 +1170,353 @@ impl<'d> PunybufValidator<'d> {
 		self.context_generic_params = vec![];
 		Ok(())
 	}
+	/// Substitutes the generic parameters in `refr` with `env`, resolving it in `layer`
+	fn bind<'a>(refr: &'a PBTypeRef, layer: u32, env: &HashMap<&str, BoundRef<'a>>) -> BoundRef<'a> {
+		if refr.generics.is_empty() && let Some(bound) = env.get(refr.reference.as_str()) {
+			return bound.clone();
+		}
+		BoundRef {
+			refr, layer,
+			generics: refr.generics.iter().map(|g| Self::bind(g, layer, env)).collect(),
+		}
+	}
+	/// Whether a value of `bound` contains a value of `target` without any indirection.
+	/// If it does, `path` is filled with the references leading to `target`.
+	///
+	/// `visited` has the bound references that were already looked into, and `stack`
+	/// the types on the way to `bound`
+	fn directly_contains<'a>(
+		&'a self, bound: &BoundRef<'a>, target: &PBTypeDef,
+		path: &mut Vec<(&'a PBTypeRef, &'a str, &'a str)>,
+		visited: &mut HashSet<String>, stack: &mut Vec<(&'a str, u32)>,
+	) -> bool {
+		// Generic parameters that weren't substituted could be anything
+		let Some(decl) = self.find_type_by_name(&bound.refr.reference, bound.layer) else {
+			return false;
+		};
+		// `Array`, `Bytes` and friends are length-prefixed, and an `Optional` can be `None`
+		if decl.get_attrs().contains_key("@builtin") {
+			return false;
+		}
+		if decl.get_name() == target.get_name() && decl.get_layer() == target.get_layer() {
+			return true;
+		}
+		// A type that contains itself is reported on its own. Going around its loop again
+		// would never end if its generic arguments grow each time
+		let this = (decl.get_name().0, *decl.get_layer());
+		if stack.contains(&this) || !visited.insert(bound.key()) {
+			return false;
+		}
+		stack.push(this);
+
+		let layer = *decl.get_layer();
+		let env = decl.get_generics().0.iter()
+			.map(|p| p.as_str())
+			.zip(bound.generics.iter().cloned())
+			.collect::<HashMap<_, _>>();
+		let members: Vec<&PBTypeRef> = match decl {
+			PBTypeDef::Alias { alias, .. } => vec![alias],
+			// Flag values are optional
+			PBTypeDef::Struct { fields, .. } => fields.iter().map(|f| &f.value).collect(),
+			PBTypeDef::Enum { variants, .. } => variants.iter().filter_map(|v| v.value.as_ref()).collect(),
+		};
+		for member in members {
+			let bound = Self::bind(member, layer, &env);
+			// `member` may be a generic parameter of `decl`, substituted with another type
+			path.push((member, decl.get_name().0, bound.refr.reference.as_str()));
+			if self.directly_contains(&bound, target, path, visited, stack) {
+				return true;
+			}
+			path.pop();
+		}
+		stack.pop();
+		false
+	}
+	/// Makes sure the fields of a struct or the variants of an enum don't contain the type itself,
+	/// unless there's an `Array` or an `Optional` in between. Otherwise, its values would be
+	/// infinitely large
+	fn validate_no_direct_recursion(&self, tp: &'d PBTypeDef) -> Result<(), PunybufError> {
+		let members: Vec<(&PBTypeRef, &str, &Span)> = match tp {
+			PBTypeDef::Alias { .. } => return Ok(()),
+			PBTypeDef::Struct { fields, .. } => fields.iter()
+				.map(|f| (&f.value, "field", &f.name_span))
+				.collect(),
+			PBTypeDef::Enum { variants, .. } => variants.iter()
+				.filter_map(|v| Some((v.value.as_ref()?, "variant", &v.name_span)))
+				.collect(),
+		};
+		let name = tp.get_name().0;
+		for (refr, kind, member_span) in members {
+			let mut path = vec![];
+			let bound = Self::bind(refr, *tp.get_layer(), &HashMap::new());
+			if !self.directly_contains(&bound, tp, &mut path, &mut HashSet::new(), &mut vec![]) {
+				continue;
+			}
+			let mut after_error = vec![];
+			if !path.is_empty() {
+				after_error.push(diagnostic!(Info,
+					refr.reference_span.clone(),
+					format!("this {kind} is a `{}`...", refr.reference)
+				));
+				for (i, (member, owner, step)) in path.iter().enumerate() {
+					let what = if i == path.len() - 1 { format!("`{name}` itself") } else { format!("a `{step}`") };
+					after_error.push(diagnostic!(Info,
+						member.reference_span.clone(),
+						format!("...and `{owner}` contains {what} here")
+					));
+				}
+			}
+			after_error.push(diagnostic!(Tip,
+				Span::impossible(),
+				format!("put it inside an `Array` or an `Optional`, so the recursion can end")
+			));
+			return Err(pb_err!(
+				member_span,
+				format!("`{name}` contains itself through this {kind}, so its values would be infinitely large"),
+				after_error: after_error
+			));
+		}
+		Ok(())
+	}
+	/// Makes sure a `@compress`ed field is a `Bytes` or a `String` from `common`,
+	/// compressed with an algorithm the codegens know about
+	fn validate_compress(
+		field: &PBField, compress: &Option<String>, field_ref_def: &ReferenceDefinition
+	) -> Result<(), PunybufError> {
+		let algorithm = compress.as_deref().unwrap_or_default().trim();
+		if algorithm != "zstd" {
+			return Err(pb_err!(
+				field.name_span,
+				format!("unknown compression algorithm `{algorithm}`"),
+				after_error: vec![
+					diagnostic!(Tip, Span::impossible(), format!("the only supported one is `@compress(zstd)`"))
+				]
+			));
+		}
+		let is_compressible = match field_ref_def {
+			ReferenceDefinition::TopLevelDecl(decl) => {
+				matches!(decl.get_name().0, "Bytes" | "String") &&
+				decl.get_name().1.file_name() == "<common>"
+			}
+			ReferenceDefinition::GenericParam(_) => false,
+		};
+		if field.flags.is_some() || !is_compressible {
+			return Err(pb_err!(
+				field.value.reference_span,
+				format!("only `Bytes` and `String` fields can be marked `@compress`"),
+				after_error: vec![
+					diagnostic!(Info,
+						field.name_span.clone(),
+						format!("`{}` is marked `@compress` here", field.name)
+					)
+				]
+			));
+		}
+		Ok(())
+	}
+	/// Makes sure a field marked `@le` or `@be` is a number of a fixed size from `common`,
+	/// and isn't marked both
+	fn validate_byte_order(field: &PBField, field_ref_def: &ReferenceDefinition) -> Result<(), PunybufError> {
+		if field.attrs.contains_key("@le") && field.attrs.contains_key("@be") {
+			return Err(pb_err!(
+				field.name_span,
+				format!("`{}` can't be marked both `@le` and `@be`", field.name)
+			));
+		}
+		let is_fixed_width = match field_ref_def {
+			ReferenceDefinition::TopLevelDecl(decl) => {
+				matches!(decl.get_name().0, "U16" | "U32" | "U64" | "I32" | "I64" | "F32" | "F64") &&
+				decl.get_name().1.file_name() == "<common>"
+			}
+			ReferenceDefinition::GenericParam(_) => false,
+		};
+		if field.flags.is_some() || !is_fixed_width {
+			return Err(pb_err!(
+				field.value.reference_span,
+				format!("only fields of fixed-width numbers can have their byte order overridden"),
+				after_error: vec![
+					diagnostic!(Info,
+						field.name_span.clone(),
+						format!("`{}` is marked `@{}` here", field.name, if field.attrs.contains_key("@le") { "le" } else { "be" })
+					),
+					diagnostic!(Tip,
+						Span::impossible(),
+						format!("those are `U16`, `U32`, `U64`, `I32`, `I64`, `F32` and `F64`")
+					),
+				]
+			));
+		}
+		Ok(())
+	}
+	fn validate_lazy_array(field: &PBField, field_ref_def: &ReferenceDefinition) -> Result<(), PunybufError> {
+		let is_array = match field_ref_def {
+			ReferenceDefinition::TopLevelDecl(decl) => {
+				decl.get_name().0 == "Array" && decl.get_name().1.file_name() == "<common>"
+			}
+			ReferenceDefinition::GenericParam(_) => false,
+		};
+		if field.flags.is_some() || !is_array {
+			return Err(pb_err!(
+				field.value.reference_span,
+				format!("only `Array` fields can be marked `@lazy_array`, found `{}`", field.value.reference),
+				after_error: vec![
+					diagnostic!(Info,
+						field.name_span.clone(),
+						format!("`{}` is marked `@lazy_array` here", field.name)
+					),
+				]
+			));
+		}
+		Ok(())
+	}
+	/// Makes sure a `@void` type is `Void` and has nothing in it that could be encoded,
+	/// since implementations encode it as nothing at all
+	fn validate_void(tp: &'d PBTypeDef) -> Result<(), PunybufError> {
+		let name = tp.get_name();
+		if name.0 != "Void" {
+			return Err(pb_err!(
+				name.1,
+				format!("only the `Void` type may be marked as `@void`")
+			));
+		}
+		let content = match tp {
+			PBTypeDef::Alias { alias, .. } => Some(&alias.reference_span),
+			PBTypeDef::Enum { variants, .. } => variants.first().map(|v| &v.name_span),
+			PBTypeDef::Struct { fields, .. } => fields.first().map(|f| &f.name_span),
+		};
+		if let Some(span) = content {
+			return Err(pb_err!(
+				span,
+				format!("`Void` is marked as `@void`, so it must be empty"),
+				after_error: vec![
+					diagnostic!(Tip, Span::impossible(), format!("`@void` types are encoded as nothing, declare it as `Void = ()`")),
+				]
+			));
+		}
+		Ok(())
+	}
+	/// Makes sure the parameters listed in `@phantom` belong to the struct `tp`, and aren't used by its fields
+	/// Makes sure an alias marked `@flags(cap)` is an alias of a flags type with at least `cap` bits,
+	/// since the flags are stored in it
+	fn validate_flags_capacity(&self, tp: &'d PBTypeDef, alias: &PBTypeRef, cap: u64) -> Result<(), PunybufError> {
+		let (name, name_span) = tp.get_name();
+		let owner = Owner::TypeOwner(tp);
+		// an alias of a generic parameter can only be checked where it's used
+		let ReferenceDefinition::TopLevelDecl(decl) = self.validate_reference_void(alias, &owner, None)? else {
+			return Ok(());
+		};
+		let bits = match self.follow_to_flags_attr(decl, &alias.generics, &owner, 0) {
+			Ok(bits) => bits as u64,
+			Err(FlagsAttrError::NoAttribute(_)) => {
+				return Err(pb_err!(
+					name_span,
+					format!("`{name}` is marked `@flags`, but `{}` can't store flags", alias.reference),
+					after_error: vec![
+						diagnostic!(Info,
+							alias.reference_span.clone(),
+							format!("`{}` isn't marked `@flags`", alias.reference)
+						)
+					]
+				));
+			}
+			Err(FlagsAttrError::AliasGeneric { .. }) => return Ok(()),
+			Err(FlagsAttrError::Other(e)) => return Err(e),
+		};
+		if cap > bits {
+			return Err(pb_err!(
+				name_span,
+				format!("`{name}` is marked `@flags({cap})`, but it can only store {bits} flags"),
+				after_error: vec![
+					diagnostic!(Info,
+						alias.reference_span.clone(),
+						format!("`{name}` is stored as `{}`, which only has room for {bits}", alias.reference)
+					),
+					diagnostic!(Tip,
+						Span::impossible(),
+						format!("use `@flags({bits})` or less, or a wider type")
+					)
+				]
+			));
+		}
+		Ok(())
+	}
+	fn validate_phantom(&self, tp: &'d PBTypeDef, phantom: &Option<String>) -> Result<(), PunybufError> {
+		let (name, name_span) = tp.get_name();
+		if !matches!(tp, PBTypeDef::Struct { .. }) {
+			return Err(pb_err!(
+				name_span,
+				format!("only structs may have `@phantom` generic parameters")
+			));
+		}
+		let (generic_params, generic_span) = tp.get_generics();
+		let mut used = vec![];
+		for refr in Self::type_refs(tp) {
+			Self::referenced_names(refr, &mut used);
+		}
+		for param in attributes::ident_list(phantom) {
+			if !generic_params.iter().any(|p| p == param) {
+				return Err(pb_err!(
+					name_span,
+					format!("`{param}` is marked `@phantom`, but it isn't a generic parameter of `{name}`")
+				));
+			}
+			if used.contains(&param) {
+				return Err(pb_err!(
+					generic_span,
+					format!("the generic parameter `{param}` of `{name}` is marked `@phantom`, but it's used by a field"),
+					after_error: vec![
+						diagnostic!(Tip, Span::impossible(),
+							format!("`@phantom` parameters aren't encoded, remove `{param}` from `@phantom`")
+						)
+					]
+				));
+			}
+		}
+		Ok(())
+	}
+	/// Makes sure a `@map_convertible` type is (an alias of) `Array<KeyPair<K, V>>`,
+	/// since that's what the generated `HashMapConvertible` impl expects
+	fn validate_map_convertible(&self, tp: &'d PBTypeDef) -> Result<(), PunybufError> {
+		let mut current = tp;
+		for _ in 0..200 {
+			let PBTypeDef::Alias { alias, generic_params, generic_span, .. } = current else {
+				break;
+			};
+			if alias.reference == "Array" {
+				if alias.generics.first().is_some_and(|g| g.reference == "KeyPair") {
+					return Ok(());
+				}
+				break;
+			}
+			let generics = generic_params.iter().map(|n| (n.as_str(), generic_span)).collect();
+			match self.validate_reference_void(alias, &Owner::TypeOwner(current), Some(&generics))? {
+				ReferenceDefinition::TopLevelDecl(decl) => current = decl,
+				ReferenceDefinition::GenericParam(_) => break,
+			}
+		}
+		let mut after_error = vec![];
+		if let PBTypeDef::Alias { alias, .. } = current {
+			let span = if alias.generic_span == Span::impossible() {
+				alias.reference_span.clone()
+			} else {
+				alias.reference_span.extend(&alias.generic_span)
+			};
+			after_error.push(diagnostic!(Info,
+				span,
+				format!("`{}` leads here, which is not `Array<KeyPair<K, V>>`", tp.get_name().0)
+			));
+		} else if current.get_name().0 != tp.get_name().0 {
+			after_error.push(diagnostic!(Info,
+				current.get_name().1.clone(),
+				format!("`{}` leads to `{}`, which is not an alias", tp.get_name().0, current.get_name().0)
+			));
+		}
+		Err(pb_err!(
+			tp.get_name().1,
+			format!("`@map_convertible` types must be aliases of `Array<KeyPair<K, V>>`"),
+			after_error: after_error
+		))
+	}
 	pub fn validate_command(&mut self, cmd: &'d PBCommandDef) -> Result<(), PunybufError> {
 		match &cmd.argument {
 			PBCommandArg::Struct { fields } => {
@@ -974,16 +1542,230 @@ impl<'d> PunybufValidator<'d> {
 				]
 			));
 		}
+		if cmd.ret.reference == "Void" && cmd.attrs.contains_key("@stream") {
+			return Err(pb_err!(
+				cmd.ret.reference_span,
+				format!("`@stream` commands must return something other than `Void`"),
+				after_error: vec![
+					diagnostic!(Info,
+						cmd.name_span.clone(),
+						format!("`{}` is marked `@stream`, so it returns any number of values", cmd.name)
+					)
+				]
+			));
+		}
 		self.validate_enum(&Owner::CommandOwner(cmd), &cmd.err)?;
 
 		Ok(())
 	}
+	/// Reports `problem` as a warning or an error, depending on the level of `lint`
+	fn lint(&mut self, lint: Lint, problem: PunybufError) {
+		self.lints.report(lint, problem, &mut self.warnings, &mut self.denied);
+	}
+	/// Pushes the names of the types `refr` refers to, including its generic arguments
+	fn referenced_names<'a>(refr: &'a PBTypeRef, names: &mut Vec<&'a str>) {
+		names.push(&refr.reference);
+		for g in &refr.generics {
+			Self::referenced_names(g, names);
+		}
+	}
+	/// The references in the fields and flags of a struct
+	fn field_refs(fields: &Vec<PBField>) -> impl Iterator<Item = &PBTypeRef> {
+		fields.iter().flat_map(|f| {
+			let flags = f.flags.iter().flatten().filter_map(|flag| flag.value.as_ref());
+			std::iter::once(&f.value).chain(flags)
+		})
+	}
+	/// The references directly in the body of `tp`
+	fn type_refs(tp: &PBTypeDef) -> Vec<&PBTypeRef> {
+		match tp {
+			PBTypeDef::Alias { alias, .. } => vec![alias],
+			PBTypeDef::Struct { fields, .. } => Self::field_refs(fields).collect(),
+			PBTypeDef::Enum { variants, .. } => variants.iter().filter_map(|v| v.value.as_ref()).collect(),
+		}
+	}
+	/// The references in the argument, return type and errors of `cmd`
+	fn command_refs(cmd: &PBCommandDef) -> Vec<&PBTypeRef> {
+		let mut refs: Vec<&PBTypeRef> = match &cmd.argument {
+			PBCommandArg::None => vec![],
+			PBCommandArg::Ref(refr) => vec![refr],
+			PBCommandArg::Struct { fields } => Self::field_refs(fields).collect(),
+		};
+		refs.push(&cmd.ret);
+		refs.extend(cmd.err.iter().filter_map(|v| v.value.as_ref()));
+		refs
+	}
+	/// Checks the lints that are about the definition as a whole, rather than a single field
+	fn lint_declarations(&mut self) {
+		let definition = self.definition;
+		let user_types = definition.types.iter().filter(|tp| {
+			!tp.get_attrs().contains_key("@builtin") && tp.get_name().1.file_name() != "<common>"
+		});
+
+		let mut used = vec![];
+		for refr in definition.types.iter().flat_map(Self::type_refs).chain(definition.commands.iter().flat_map(Self::command_refs)) {
+			Self::referenced_names(refr, &mut used);
+		}
+
+		// the types the commands use, and the types those use, and so on
+		let mut reachable = vec![];
+		let mut queue = vec![];
+		for refr in definition.commands.iter().flat_map(Self::command_refs) {
+			Self::referenced_names(refr, &mut queue);
+		}
+		while let Some(name) = queue.pop() {
+			if reachable.contains(&name) {
+				continue;
+			}
+			reachable.push(name);
+			for refr in definition.types.iter().filter(|tp| tp.get_name().0 == name).flat_map(Self::type_refs) {
+				Self::referenced_names(refr, &mut queue);
+			}
+		}
+
+		for tp in user_types {
+			let (name, name_span) = tp.get_name();
+
+			// Without commands, there's nothing to use the types from
+			if !definition.commands.is_empty() && tp.get_inline_owner().is_none() && !used.contains(&name) {
+				self.lint(Lint::UnusedType, pb_err!(
+					name_span,
+					format!("`{name}` is never used by a command or another type")
+				));
+			}
+
+			// Types nothing uses are left to `unused-type`
+			if !definition.commands.is_empty() && tp.get_inline_owner().is_none()
+				&& used.contains(&name) && !reachable.contains(&name)
+			{
+				let users = definition.types.iter().filter(|user| {
+					let mut names = vec![];
+					for refr in Self::type_refs(user) {
+						Self::referenced_names(refr, &mut names);
+					}
+					user.get_name().0 != name && names.contains(&name)
+				});
+				let after_error = users.take(1).map(|user| diagnostic!(Info,
+					user.get_name().1.clone(),
+					format!("it's used by `{}`, which can't be reached either", user.get_name().0)
+				)).collect();
+				self.lint(Lint::UnreachableType, pb_err!(
+					name_span,
+					format!("`{name}` can't be reached from any command"),
+					after_error: after_error
+				));
+			}
+
+			let (generic_params, generic_span) = tp.get_generics();
+			let mut names = vec![];
+			for refr in Self::type_refs(tp) {
+				Self::referenced_names(refr, &mut names);
+			}
+			// `@phantom` parameters are unused on purpose
+			names.extend(tp.get_attrs().get("@phantom").map(attributes::ident_list).unwrap_or_default());
+			for param in generic_params {
+				if !names.contains(&param.as_str()) {
+					self.lint(Lint::UnusedGeneric, pb_err!(
+						generic_span,
+						format!("the generic parameter `{param}` of `{name}` is never used")
+					));
+				}
+			}
+
+			if !name.starts_with(|ch: char| ch.is_uppercase()) {
+				self.lint(Lint::Naming, pb_err!(
+					name_span,
+					format!("the name of the type `{name}` should start with an uppercase letter")
+				));
+			}
+			match tp {
+				PBTypeDef::Struct { fields, .. } => self.lint_field_names(fields),
+				PBTypeDef::Enum { variants, .. } => self.lint_variant_names(variants),
+				PBTypeDef::Alias { .. } => {}
+			}
+
+			if let Some(variant) = tp.single_variant() {
+				self.lint(Lint::SingleVariantEnum, pb_err!(
+					name_span,
+					format!("`{name}` has a single variant, so its discriminant is always the same byte"),
+					after_error: vec![
+						diagnostic!(Info,
+							variant.name_span.clone(),
+							format!("`{}` is its only variant", variant.name)
+						),
+						diagnostic!(Tip,
+							name_span.clone(),
+							format!(
+								"tip: make `{name}` an alias of the variant's type, \
+								unless more variants are going to be added"
+							)
+						)
+					]
+				));
+			}
+		}
+
+		for cmd in &definition.commands {
+			if !cmd.name.starts_with(|ch: char| ch.is_lowercase()) {
+				self.lint(Lint::Naming, pb_err!(
+					cmd.name_span,
+					format!("the name of the command `{}` should start with a lowercase letter", cmd.name)
+				));
+			}
+			if let PBCommandArg::Struct { fields } = &cmd.argument {
+				self.lint_field_names(fields);
+			}
+			self.lint_variant_names(&cmd.err);
+		}
+	}
+	fn lint_field_names(&mut self, fields: &Vec<PBField>) {
+		let flags = fields.iter().flat_map(|f| f.flags.iter().flatten().map(|flag| (&flag.name, &flag.name_span, "flag")));
+		let names: Vec<_> = fields.iter().map(|f| (&f.name, &f.name_span, "field")).chain(flags).collect();
+		for (name, span, kind) in names {
+			// Anonymous flag fields are named like `0_flags`
+			if !name.starts_with(|ch: char| ch.is_lowercase() || ch.is_ascii_digit()) {
+				self.lint(Lint::Naming, pb_err!(
+					span,
+					format!("the name of the {kind} `{name}` should start with a lowercase letter")
+				));
+			}
+		}
+	}
+	fn lint_variant_names(&mut self, variants: &Vec<PBEnumVariant>) {
+		for variant in variants {
+			if !variant.name.starts_with(|ch: char| ch.is_uppercase()) {
+				self.lint(Lint::Naming, pb_err!(
+					variant.name_span,
+					format!("the name of the variant `{}` should start with an uppercase letter", variant.name)
+				));
+			}
+		}
+	}
 	/// Validates the Punybuf definition further, catching things like
-	/// re-declarations, references to inline declarations, and stuff like that
-	/// 
-	/// Known issue: does not catch self-referential types.
-	// TODO: ^^^
+	/// re-declarations, references to inline declarations, self-referential types,
+	/// and stuff like that.
+	///
+	/// An invalid type or command doesn't stop the validation of the other ones,
+	/// so the error may have [more errors](PunybufError::more) after it.
 	pub fn validate(&mut self) -> Result<(), PunybufError> {
+		let mut errors = vec![];
+		if let Err(e) = self.validate_declarations(&mut errors) {
+			errors.push(e);
+		}
+		// The lints would only add noise to the errors
+		if errors.is_empty() {
+			self.lint_declarations();
+			errors.append(&mut self.denied);
+		}
+		match PunybufError::chain(errors) {
+			Some(error) => Err(error),
+			None => Ok(()),
+		}
+	}
+	/// Like [`Self::validate`], but errors in the types and commands themselves
+	/// are pushed to `errors`, and only stop the validation if they're about the
+	/// whole definition
+	fn validate_declarations(&mut self, errors: &mut Vec<PunybufError>) -> Result<(), PunybufError> {
 		let mut declared_things: Vec<(&str, &u32, &Span, ThingKind)> = vec![];
 		for tp in &self.definition.types {
 			if let Some(already_decl) = declared_things.iter().find(|x| x.0 == tp.get_name().0 && x.1 == tp.get_layer()) {
@@ -1010,58 +1792,58 @@ impl<'d> PunybufValidator<'d> {
 					name.1, "cannot declare a reserved type `Void`, unless the `@void` attribute is present"
 				));
 			}
+			if attrs.contains_key("@void") && let Err(e) = Self::validate_void(tp) {
+				errors.push(e);
+			}
 			declared_things.push((name.0, tp.get_layer(), name.1, ThingKind::Type));
-			if name.0 != "Void" {
-				self.validate_type(tp)?;
+			if name.0 != "Void" && let Err(e) = self.validate_type(tp) {
+				errors.push(e);
 			}
 		}
 
 		let mut seen_ids = HashMap::<u32, (&str, &u32, &Span)>::new();
 		for cmd in &self.definition.commands {
-			if let Some(already_decl) = declared_things
-				.iter()
-				.find(|x| x.0 == &cmd.name && (x.1 == &cmd.layer || x.3 != ThingKind::Command))
-			{
-				if already_decl.1 == &cmd.layer {
-					return Err(pb_err!(
-						already_decl.2,
-						format!("`{}` declared multiple times", already_decl.0),
-						ErrorInfo::instead(vec![
-							diagnostic!(Info,
-								already_decl.2.clone(),
-								format!("`{}` declared here first", already_decl.0)
-							),
-							diagnostic!(Error,
-								cmd.name_span.clone(),
-								format!("`{}` declared here again", already_decl.0)
-							),
-						])
-					));
-
-				} else if already_decl.3 != ThingKind::Command {
-					return Err(pb_err!(
-						already_decl.2,
-						format!("invalid redeclaration of `{}`; even in different layers, \
-							types can't become commands (and vice versa)", already_decl.0),
-						ErrorInfo::instead(vec![
-							diagnostic!(Error,
-								already_decl.2.clone(),
-								format!(
-									"`{}` declared here, in layer {}, as a type",
-									already_decl.0, already_decl.1
-								)
-							),
-							diagnostic!(Error,
-								cmd.name_span.clone(),
-								format!(
-									"`{}` declared here, in layer {}, as a command",
-									cmd.name, cmd.layer
-								)
-							),
-						])
-					));
-				}
-				unreachable!();
+			let mut same_name = declared_things.iter().filter(|x| x.0 == cmd.name);
+			// Commands may be redeclared in other layers, but nothing can be redeclared in the same one
+			if let Some(already_decl) = same_name.clone().find(|x| x.1 == &cmd.layer) {
+				return Err(pb_err!(
+					already_decl.2,
+					format!("`{}` declared multiple times", already_decl.0),
+					ErrorInfo::instead(vec![
+						diagnostic!(Info,
+							already_decl.2.clone(),
+							format!("`{}` declared here first", already_decl.0)
+						),
+						diagnostic!(Error,
+							cmd.name_span.clone(),
+							format!("`{}` declared here again", already_decl.0)
+						),
+					])
+				));
+			}
+			// ...and a name that belongs to a type in one layer can't belong to a command in another
+			if let Some(already_decl) = same_name.find(|x| x.3 == ThingKind::Type) {
+				return Err(pb_err!(
+					already_decl.2,
+					format!("invalid redeclaration of `{}`; even in different layers, \
+						types can't become commands (and vice versa)", already_decl.0),
+					ErrorInfo::instead(vec![
+						diagnostic!(Error,
+							already_decl.2.clone(),
+							format!(
+								"`{}` declared here, in layer {}, as a type",
+								already_decl.0, already_decl.1
+							)
+						),
+						diagnostic!(Error,
+							cmd.name_span.clone(),
+							format!(
+								"`{}` declared here, in layer {}, as a command",
+								cmd.name, cmd.layer
+							)
+						),
+					])
+				));
 			}
 			if cmd.name == "Void" {
 				return Err(parser_err!(
@@ -1069,7 +1851,10 @@ impl<'d> PunybufValidator<'d> {
 				));
 			}
 			declared_things.push((&cmd.name, &cmd.layer, &cmd.name_span, ThingKind::Command));
-			self.validate_command(cmd)?;
+			if let Err(e) = self.validate_command(cmd) {
+				errors.push(e);
+				continue;
+			}
 
 			if let Some((other_name, other_layer, other_span)) =
 				seen_ids.remove(&cmd.command_id)
@@ -1130,6 +1915,24 @@ impl<'d> PunybufValidator<'d> {
 					])
 				));
 			}
+			if let Some((_, reserved_span)) = self.definition.reserved_ids.iter()
+				.find(|(id, _)| *id == cmd.command_id)
+			{
+				return Err(pb_err!(
+					cmd.name_span,
+					format!("command `{}` of layer {} has the reserved ID {}", cmd.name, cmd.layer, cmd.command_id),
+					after_error: vec![
+						diagnostic!(Info,
+							reserved_span.clone(),
+							format!("the ID is reserved here")
+						),
+						diagnostic!(Tip,
+							Span::impossible(),
+							format!("tip: use the @id attribute to give `{}` a different ID", cmd.name)
+						)
+					]
+				));
+			}
 			seen_ids.insert(cmd.command_id, (&cmd.name, &cmd.layer, &cmd.name_span));
 		}
 		for cmd in &self.definition.commands {
@@ -1184,10 +1987,13 @@ impl<'d> PunybufValidator<'d> {
 }
 
 impl PunybufDefinition {
-	pub(crate) fn as_validator(&self) -> PunybufValidator<'_> {
-		PunybufValidator { definition: self, context_generic_params: vec![] }
+	pub(crate) fn as_validator(&self, lints: Lints) -> PunybufValidator<'_> {
+		PunybufValidator { definition: self, context_generic_params: vec![], warnings: vec![], lints, denied: vec![] }
 	}
-	pub(crate) fn validate(&self) -> Result<(), PunybufError> {
-		self.as_validator().validate()
+	/// Returns the warnings if the definition is valid
+	pub(crate) fn validate(&self, lints: Lints) -> Result<Vec<PunybufError>, PunybufError> {
+		let mut validator = self.as_validator(lints);
+		validator.validate()?;
+		Ok(validator.warnings)
 	}
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+//! Counts the allocations made while decoding, which is why it has its own test binary
+//! (the global allocator would also count the allocations of other tests).
+
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	borrow::Cow,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+use punybuf_common::{Bytes, PBType};
+
+#[allow(dead_code, unused)]
+#[path = "generated/reuse.rs"]
+mod reuse;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const MESSAGES: usize = 1000;
+
+/// Returns the amount of allocations made by `f`
+fn count_allocations(f: impl FnOnce()) -> usize {
+	let before = ALLOCATIONS.load(Ordering::Relaxed);
+	f();
+	ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn deserialize_into_allocates_less() {
+	let mut stream = vec![];
+	for id in 0..MESSAGES {
+		reuse::Message {
+			id: id as u32,
+			text: Cow::Owned(format!("message number {id}")),
+			tags: vec![Cow::Borrowed("tag"), Cow::Borrowed("another tag")],
+			payload: Bytes(Cow::Owned(vec![id as u8; 16])),
+			urgent: id % 2 == 0,
+			reply_to: Some(Cow::Borrowed("someone")),
+			edited_at: None,
+		}.serialize(&mut stream).unwrap();
+	}
+
+	let fresh = count_allocations(|| {
+		let r = &mut &stream[..];
+		for _ in 0..MESSAGES {
+			std::hint::black_box(reuse::Message::deserialize_stream(r).unwrap());
+		}
+	});
+	let reused = count_allocations(|| {
+		let r = &mut &stream[..];
+		let mut message = reuse::Message::deserialize_stream(r).unwrap();
+		for _ in 1..MESSAGES {
+			message.deserialize_into(r).unwrap();
+		}
+		std::hint::black_box(message);
+	});
+
+	eprintln!("allocations for {MESSAGES} messages: {fresh} with `deserialize_stream`, {reused} with `deserialize_into`");
+	// only the first message should allocate
+	assert!(reused < MESSAGES, "`deserialize_into` made {reused} allocations, fresh values made {fresh}");
+}
@@ -0,0 +1,397 @@
+//! Runs the `pbd` binary itself.
+
+use std::{fs, path::Path, process::{Command, Stdio}, thread::sleep, time::{Duration, Instant}};
+
+/// Runs `pbd` with `args`, returning its stdout. Panics if it fails.
+fn pbd(args: &[&str]) -> String {
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(args)
+		.output()
+		.expect("failed to run pbd");
+	assert!(output.status.success(), "pbd {args:?} failed: {}", String::from_utf8_lossy(&output.stderr));
+	String::from_utf8(output.stdout).expect("output is not UTF-8")
+}
+
+#[test]
+fn bash_completions() {
+	let completions = pbd(&["completions", "bash"]);
+	assert!(!completions.is_empty());
+	for known in ["--out", "--rust:tokio", "--rust:reflect", "--html:template", "migrate", "completions"] {
+		assert!(completions.contains(known), "`{known}` is missing from the completions");
+	}
+}
+
+#[test]
+fn completions_survive_a_closed_stdout() {
+	// like `pbd completions bash | head -0`, but the reader is gone before pbd even starts
+	let (reader, writer) = std::io::pipe().unwrap();
+	drop(reader);
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["completions", "bash"])
+		.stdout(writer)
+		.stderr(Stdio::piped())
+		.output()
+		.expect("failed to run pbd");
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn nonmonotonic_layers_can_be_allowed() {
+	let file = "test_files/nonmonotonic-layers.pbd";
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd")).args([file, "-q"]).output().expect("failed to run pbd");
+	assert!(!output.status.success());
+	assert!(String::from_utf8_lossy(&output.stderr).contains("layer 1 is declared after layer 2"));
+
+	pbd(&[file, "-q", "--allow-nonmonotonic-layers"]);
+}
+
+#[test]
+fn identifier_length_limit_is_configurable() {
+	let file = "test_files/long-identifier.pbd";
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd")).args([file, "-q"]).output().expect("failed to run pbd");
+	assert!(!output.status.success());
+	assert!(String::from_utf8_lossy(&output.stderr).contains("this identifier is longer than 1024 bytes"));
+
+	pbd(&[file, "-q", "--max-identifier-length", "2048"]);
+}
+
+#[test]
+fn error_count_is_capped() {
+	// all 30 types in the file are broken, `Missing{i}` is in the error about the i-th one
+	let reported = |extra: &[&str]| {
+		let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+			.args(["test_files/many-errors.pbd", "-q"])
+			.args(extra)
+			.output()
+			.expect("failed to run pbd");
+		assert!(!output.status.success());
+		let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+		let count = (0..30).take_while(|i| stderr.contains(&format!("`Missing{i}`"))).count();
+		assert!(!stderr.contains(&format!("`Missing{count}`")), "the errors are out of order");
+		(count, stderr)
+	};
+	let (count, stderr) = reported(&[]);
+	assert_eq!(count, 20);
+	assert!(stderr.ends_with("... and 10 more errors\n"));
+
+	let (count, stderr) = reported(&["--max-errors", "5"]);
+	assert_eq!(count, 5);
+	assert!(stderr.ends_with("... and 25 more errors\n"));
+
+	let (count, stderr) = reported(&["--max-errors", "0"]);
+	assert_eq!(count, 30);
+	assert!(!stderr.contains("more error"));
+}
+
+#[test]
+fn strict_denies_unused_types() {
+	let file = "test_files/unused-type.pbd";
+	pbd(&[file, "-q"]);
+
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd")).args([file, "-q", "--strict"]).output().expect("failed to run pbd");
+	assert!(!output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("`OldProfile` is never used by a command or another type"), "{stderr}");
+	assert!(stderr.contains("reported by the `unused-type` lint"));
+
+	pbd(&[file, "-q", "--strict", "--allow", "unused-type"]);
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd")).args([file, "-q", "--warn", "unused-type"]).output().expect("failed to run pbd");
+	assert!(output.status.success());
+	assert!(String::from_utf8_lossy(&output.stderr).contains("warning:"));
+}
+
+#[test]
+fn unreachable_types_are_reported() {
+	let file = "test_files/unreachable-types.pbd";
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd")).args([file, "-q"]).output().expect("failed to run pbd");
+	assert!(output.status.success());
+	assert!(!String::from_utf8_lossy(&output.stderr).contains("warning:"));
+
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd")).args([file, "-q", "--warn-unreachable"]).output().expect("failed to run pbd");
+	assert!(output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	for name in ["OldAvatar", "OldSize"] {
+		assert!(stderr.contains(&format!("`{name}` can't be reached from any command")), "{stderr}");
+	}
+	assert!(stderr.contains("it's used by `OldAvatar`, which can't be reached either"));
+	assert!(stderr.contains("reported by the `unreachable-type` lint"));
+	// `OldProfile` isn't used at all, which is what `unused-type` is for
+	assert!(!stderr.contains("`OldProfile` can't be reached"), "{stderr}");
+	assert!(!stderr.contains("`User`"));
+}
+
+#[test]
+fn resolution_dump() {
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["test_files/layer-bump.pbd", "-q", "--dump-resolution"])
+		.output()
+		.expect("failed to run pbd");
+	assert!(output.status.success());
+	let dump = json::parse(&String::from_utf8_lossy(&output.stderr)).expect("the dump is not JSON");
+
+	let user = json::object! { name: "User", layer: 0, kind: "type" };
+	assert!(dump["dependencies_before"]["Status"].members().any(|dep| *dep == user), "{dump:#}");
+	let get_user = json::object! { name: "getUser", layer: 1, kind: "command" };
+	assert!(dump["dependencies_after"]["User"].members().any(|dep| *dep == get_user), "{dump:#}");
+
+	let generated = dump["generated_types"].members()
+		.map(|tp| (tp["name"].as_str().unwrap(), tp["layer"].as_u32().unwrap()))
+		.collect::<Vec<_>>();
+	assert_eq!(generated, [("User", 1)]);
+	assert_eq!(dump["generated_commands"][0]["name"], "getUser");
+}
+
+#[test]
+fn keep_going_writes_the_other_outputs() {
+	let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("keep-going");
+	std::fs::create_dir_all(&dir).unwrap();
+	let json = dir.join("out.json");
+	let _ = std::fs::remove_file(&json);
+	let json = json.to_str().unwrap();
+	let unsupported = dir.join("out.txt");
+	let unsupported = unsupported.to_str().unwrap();
+
+	let run = |extra: &[&str]| Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["test_files/layer-bump.pbd", "-o", unsupported, "-o", json])
+		.args(extra)
+		.output()
+		.expect("failed to run pbd");
+
+	// stops at the first failure
+	let output = run(&[]);
+	assert!(!output.status.success());
+	assert!(!std::path::Path::new(json).exists());
+
+	let output = run(&["--keep-going", "--dry-run"]);
+	assert!(!output.status.success());
+	assert!(!std::path::Path::new(json).exists());
+
+	let output = run(&["--keep-going"]);
+	assert!(!output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("file type not supported"), "{stderr}");
+	assert!(stderr.contains("failed to generate 1 of the 2 outputs"), "{stderr}");
+	let written = std::fs::read_to_string(json).expect("the JSON output wasn't written");
+	assert!(json::parse(&written).is_ok());
+}
+
+#[test]
+fn type_becoming_a_command_points_at_both() {
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["test_files/type-becomes-command.pbd", "-q"])
+		.output()
+		.expect("failed to run pbd");
+	assert!(!output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("types can't become commands"), "{stderr}");
+	assert!(stderr.contains("type-becomes-command.pbd:8:1"), "{stderr}");
+	assert!(stderr.contains("`Foo` declared here, in layer 0, as a type"), "{stderr}");
+	assert!(stderr.contains("type-becomes-command.pbd:16:1"), "{stderr}");
+	assert!(stderr.contains("`Foo` declared here, in layer 1, as a command"), "{stderr}");
+}
+
+#[test]
+fn spans_after_comments_are_accurate() {
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["test_files/comments.pbd", "-q"])
+		.output()
+		.expect("failed to run pbd");
+	assert!(!output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("comments.pbd:14:6"), "{stderr}");
+}
+
+#[test]
+fn sealing_a_struct_is_incompatible() {
+	let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("sealing_a_struct_is_incompatible");
+	std::fs::create_dir_all(&dir).unwrap();
+	let json = dir.join("open.json");
+	let json = json.to_str().unwrap();
+	pbd(&["test_files/compat/open.pbd", "-o", json]);
+	pbd(&["test_files/compat/open.pbd", "-q", "-c", json]);
+
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["test_files/compat/sealed.pbd", "-q", "-c", json])
+		.output()
+		.expect("failed to run pbd");
+	assert!(!output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("is not binary compatible with"), "{stderr}");
+	assert!(stderr.contains("`Point` of layer 0 became `@sealed`"), "{stderr}");
+	assert!(stderr.contains("`move` of layer 0 became `@sealed`"), "{stderr}");
+}
+
+#[test]
+fn adding_variants_to_default_enums_needs_extension() {
+	let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("adding_variants_to_default_enums_needs_extension");
+	std::fs::create_dir_all(&dir).unwrap();
+	let json = dir.join("mood.json");
+	let json = json.to_str().unwrap();
+	pbd(&["test_files/compat/mood.pbd", "-o", json]);
+
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["test_files/compat/mood-added.pbd", "-q", "-c", json])
+		.output()
+		.expect("failed to run pbd");
+	assert!(!output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains("`Sad` was added to `Mood` of layer 0 without `@extension`"), "{stderr}");
+	assert!(!stderr.contains("`Scared`"), "{stderr}");
+}
+
+#[test]
+fn source_encodings() {
+	let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("source_encodings");
+	std::fs::create_dir_all(&dir).unwrap();
+	let source = "include common\n\nPoint = {\n\tx: U32\n\ty: U32\n}\n";
+	let fails_with = |name: &str, bytes: Vec<u8>, messages: &[&str]| {
+		let file = dir.join(name);
+		std::fs::write(&file, bytes).unwrap();
+		let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+			.arg(&file)
+			.output()
+			.expect("failed to run pbd");
+		assert!(!output.status.success());
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		for message in messages {
+			assert!(stderr.contains(message), "{stderr}");
+		}
+	};
+
+	let bom = dir.join("bom.pbd");
+	std::fs::write(&bom, [b"\xEF\xBB\xBF", source.as_bytes()].concat()).unwrap();
+	let json = json::parse(&pbd(&[bom.to_str().unwrap()])).unwrap();
+	assert!(json["types"].members().any(|tp| tp["name"] == "Point"));
+
+	let utf16 = [0xFF, 0xFE].into_iter().chain(source.encode_utf16().flat_map(u16::to_le_bytes)).collect();
+	fails_with("utf16.pbd", utf16, &["encoded as UTF-16LE", "iconv -f UTF-16LE -t UTF-8"]);
+
+	let mut invalid = source.as_bytes().to_vec();
+	invalid[27] = 0xC0;
+	fails_with("invalid.pbd", invalid, &["invalid byte at offset 27 (line 4)"]);
+}
+
+#[test]
+fn bundle_inlines_includes() {
+	let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("bundle");
+	std::fs::create_dir_all(&dir).unwrap();
+	let bundle = dir.join("bundled.pbd");
+	let bundle = bundle.to_str().unwrap();
+	pbd(&["bundle", "test_files/bundle/main.pbd", "-o", bundle]);
+	let source = std::fs::read_to_string(bundle).unwrap();
+	let includes: Vec<&str> = source.lines().filter(|l| l.starts_with("include")).collect();
+	// `colors.pbd` includes it too, but the bundle only needs it once, at the top
+	assert_eq!(includes, ["include common"]);
+	assert_eq!(source.lines().nth(1), Some("include common"));
+	assert_eq!(pbd(&[bundle]), pbd(&["test_files/bundle/main.pbd"]));
+
+	let inlined = pbd(&["bundle", "test_files/bundle/main.pbd", "--inline-common"]);
+	assert!(!inlined.lines().any(|l| l.starts_with("include")));
+	assert!(inlined.contains("KeyPair<K, V> = {"));
+}
+
+/// Runs `pbd` with `args` and `input` as its stdin, returning its stdout. Panics if it fails.
+fn pbd_stdin(args: &[&str], input: &str) -> String {
+	use std::io::Write;
+	let mut child = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.expect("failed to run pbd");
+	child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+	let output = child.wait_with_output().unwrap();
+	assert!(output.status.success(), "pbd {args:?} failed: {}", String::from_utf8_lossy(&output.stderr));
+	String::from_utf8(output.stdout).expect("output is not UTF-8")
+}
+
+#[test]
+fn definition_from_stdin() {
+	let output = pbd_stdin(&["-"], "include common\n\nPoint = {\n\tx: U8\n}\n");
+	assert!(output.contains(r#""name":"Point""#));
+	assert!(output.contains(r#""includes_common":true"#));
+
+	let main = fs::read_to_string("test_files/bundle/main.pbd").unwrap();
+	let output = pbd_stdin(&["-", "--include-root", "test_files/bundle"], &main);
+	assert_eq!(output, pbd(&["test_files/bundle/main.pbd"]));
+}
+
+/// Kills the process when dropped, so a failed test doesn't leave it running
+struct KillOnDrop(std::process::Child);
+
+impl Drop for KillOnDrop {
+	fn drop(&mut self) {
+		let _ = self.0.kill();
+		let _ = self.0.wait();
+	}
+}
+
+/// Waits up to 10 seconds for `path` to contain `text`
+fn wait_for_contents(path: &Path, text: &str) {
+	let start = Instant::now();
+	while !fs::read_to_string(path).is_ok_and(|contents| contents.contains(text)) {
+		assert!(start.elapsed() < Duration::from_secs(10), "{} never contained `{text}`", path.display());
+		sleep(Duration::from_millis(50));
+	}
+}
+
+#[test]
+fn watch_regenerates_on_changes() {
+	let dir = std::env::temp_dir().join(format!("pbd-watch-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let (main, included, out) = (dir.join("main.pbd"), dir.join("point.pbd"), dir.join("out.ts"));
+	fs::write(&main, "include common\ninclude point.pbd\n").unwrap();
+	fs::write(&included, "Point = {\n\tx: U8\n}\n").unwrap();
+
+	let mut child = KillOnDrop(Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.arg(&main).arg("--watch").arg("-o").arg(&out)
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.expect("failed to run pbd"));
+	wait_for_contents(&out, "x: number");
+
+	// a change to an included file is picked up too
+	fs::write(&included, "Point = {\n\ty: U8\n}\n").unwrap();
+	wait_for_contents(&out, "y: number");
+
+	// errors don't stop it
+	fs::write(&included, "Point = {\n\tz: Missing\n}\n").unwrap();
+	sleep(Duration::from_millis(600));
+	assert!(child.0.try_wait().unwrap().is_none(), "pbd exited after an error");
+	fs::write(&included, "Point = {\n\tz: U8\n}\n").unwrap();
+	wait_for_contents(&out, "z: number");
+
+	drop(child);
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Compiles the code generated for the sample definition, and round-trips random values with it
+#[test]
+fn self_check_passes() {
+	let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("self-check");
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["self-check", "test_files/self-check/sample.pbd", "--common", "../rust-punybuf_common", "--offline", "--seed", "1"])
+		.arg("--dir").arg(&dir)
+		.output()
+		.expect("failed to run pbd");
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(output.status.success(), "self-check failed: {stdout}{}", String::from_utf8_lossy(&output.stderr));
+	assert!(stdout.contains("every value of 15 types and commands round-tripped"), "{stdout}");
+}
+
+/// `@compress`ed fields aren't sampled, but the code for them still has to compile
+#[test]
+fn self_check_enables_compression() {
+	let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("self-check-compress");
+	let output = Command::new(env!("CARGO_BIN_EXE_pbd"))
+		.args(["self-check", "test_files/codegen/compress.pbd", "--common", "../rust-punybuf_common", "--offline", "--seed", "1"])
+		.arg("--dir").arg(&dir)
+		.output()
+		.expect("failed to run pbd");
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(output.status.success(), "self-check failed: {stdout}{}", String::from_utf8_lossy(&output.stderr));
+	assert!(String::from_utf8_lossy(&output.stderr).contains("skipping `Snapshot`"));
+}
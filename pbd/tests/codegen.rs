@@ -0,0 +1,317 @@
+use punybuf::{convert_full_definition, Derive, MigrationCodegen, PunybufParser, RustCodegen, TypeScriptCodegen, Visibility};
+
+/// Parses and resolves `test_files/codegen/{name}.pbd`
+macro_rules! load {
+	($name:expr) => {
+		PunybufParser::parse_file(format!("test_files/codegen/{}.pbd", $name))
+			.expect("failed to read file")
+			.expect("failed to parse file")
+			.resolve(true)
+			.expect("failed to resolve definition")
+	};
+}
+
+/// Parses `test_files/codegen/{name}.pbd` and generates synchronous Rust code for it
+fn generate(name: &str) -> String {
+	RustCodegen::new(false, true, &load!(name)).codegen()
+}
+
+/// Returns the attribute lines directly above `item`
+fn attributes_of<'a>(code: &'a str, item: &str) -> Vec<&'a str> {
+	let lines: Vec<&str> = code.lines().collect();
+	let index = lines.iter().position(|l| l.starts_with(item))
+		.unwrap_or_else(|| panic!("`{item}` not found in generated code"));
+	lines[..index].iter().rev()
+		.take_while(|l| l.starts_with("#[") || l.starts_with("///"))
+		.copied()
+		.collect()
+}
+
+#[test]
+fn must_use_on_commands() {
+	let code = generate("commands");
+	for item in ["pub enum Command", "pub struct moveCursor", "pub struct getShape"] {
+		assert!(
+			attributes_of(&code, item).iter().any(|a| a.starts_with("#[must_use")),
+			"`{item}` should be #[must_use]"
+		);
+	}
+	for item in ["pub struct Point", "pub enum Shape", "pub enum getShapeError"] {
+		assert!(
+			!attributes_of(&code, item).iter().any(|a| a.starts_with("#[must_use")),
+			"`{item}` should not be #[must_use]"
+		);
+	}
+}
+
+/// Returns the entries of every `attributes()`/`ATTRIBUTES` slice in the generated code
+fn attribute_slices(code: &str) -> Vec<Vec<&str>> {
+	let mut slices = vec![];
+	let mut lines = code.lines();
+	while let Some(line) = lines.next() {
+		if !line.contains("&'static [(&'static str, Option<&'static str>)]") || !line.ends_with('[') {
+			continue;
+		}
+		slices.push(lines.by_ref().take_while(|l| !l.trim_start().starts_with(']')).collect());
+	}
+	slices
+}
+
+#[test]
+fn attributes_are_deterministic() {
+	let first = generate("attributes");
+	let slices = attribute_slices(&first);
+	// `Point` and `moveCursor` both have 4 attributes
+	assert_eq!(slices.iter().filter(|s| s.len() == 4).count(), 2);
+	for slice in &slices {
+		assert!(slice.is_sorted(), "attributes are not sorted: {slice:#?}");
+	}
+	for _ in 0..8 {
+		assert_eq!(attribute_slices(&generate("attributes")), slices);
+	}
+}
+
+#[test]
+fn strict_strings() {
+	let code = generate("strict-strings");
+	assert!(code.contains("pub name: StrictString<'x>,"));
+	assert!(code.contains("pub tags: Vec<StrictString<'x>>,"));
+	assert!(code.contains("StrictString::<'x>::deserialize"));
+	assert!(!generate("commands").contains("StrictString"));
+}
+
+#[test]
+fn default_variant_discards_unknown_extensions() {
+	let code = generate("default-variant");
+	assert!(code.contains("discard_extension(r)?;\n                Self::Neutral"));
+	assert!(!code.contains("_ = Bytes::deserialize"));
+	// known extensions read their length before the value
+	assert!(code.contains("UInt::deserialize_stream(r)?;\n                Self::ConcernedAbout"));
+}
+
+#[test]
+fn derive_copy() {
+	let code = RustCodegen::new(false, true, &load!("derive-copy")).derive_copy(true).codegen();
+	for item in ["pub struct Point", "pub struct Pixel", "pub enum Direction"] {
+		assert!(
+			attributes_of(&code, item).contains(&"#[derive(Debug, Clone, Copy)]"),
+			"`{item}` should be Copy"
+		);
+	}
+	for item in ["pub struct Label", "pub enum Shape"] {
+		assert!(
+			attributes_of(&code, item).contains(&"#[derive(Debug, Clone)]"),
+			"`{item}` should not be Copy"
+		);
+	}
+
+	let code = generate("derive-copy");
+	assert!(!code.contains("Copy)]"), "Copy should only be derived with `derive_copy`");
+}
+
+#[test]
+fn migration_stubs() {
+	let code = MigrationCodegen::new(&load!("migrate")).codegen();
+	let expected = [
+		"pub fn migrate_status_v0_to_v1<'x>(old: StatusLayer0<'x>) -> Status<'x> {",
+		"StatusLayer0::Online => Status::Online,",
+		"StatusLayer0::Away(v) => Status::Away(v),",
+		"StatusLayer0::Offline => todo!(",
+		"pub fn migrate_profile_v0_to_v1<'x>(old: ProfileLayer0<'x>) -> Profile<'x> {",
+		"name: old.name,",
+		"age: todo!(",
+		"status: migrate_status_v0_to_v1(old.status),",
+		"verified: old.verified,",
+		"nickname: old.nickname,",
+		"bio: todo!(",
+	];
+	for line in expected {
+		assert!(code.lines().any(|l| l.trim_start().starts_with(line)), "missing `{line}` in:\n{code}");
+	}
+}
+
+#[test]
+fn derive_ord_skips_floats_and_maps() {
+	let code = RustCodegen::new(false, true, &load!("ordering")).derive_ord(true).codegen();
+	let derives_ord = |item: &str| attributes_of(&code, item).iter().any(|a| a.starts_with("#[derive") && a.contains(" Ord"));
+	for item in ["pub struct Version", "pub enum Channel", "pub struct Release"] {
+		assert!(derives_ord(item), "`{item}` should derive `Ord`");
+	}
+	for item in ["pub struct Measurement", "pub struct Tagged", "pub struct MaybeMeasured"] {
+		assert!(!derives_ord(item), "`{item}` shouldn't derive `Ord`");
+	}
+}
+
+#[test]
+fn wire_sizes_in_docs() {
+	let code = RustCodegen::new(false, true, &load!("wire-sizes")).wire_sizes(true).codegen();
+	for (item, size) in [
+		("pub enum Color", "1 byte fixed"),
+		("pub struct Pixel", "9 bytes fixed"),
+		// `String` is at least its length, then the flags and the length of the extensions
+		("pub struct Header", "≥11 bytes"),
+		("pub enum Frame", "≥1 byte"),
+	] {
+		let doc = format!("/// Wire size: {size}");
+		assert!(attributes_of(&code, item).contains(&doc.as_str()), "`{item}` should be documented as {size}");
+	}
+	// the line is added after the existing docs
+	assert_eq!(attributes_of(&code, "pub struct Pixel")[1..4], ["/// Wire size: 9 bytes fixed", "/// ", "/// A point on the screen."]);
+
+	let code = RustCodegen::new(false, true, &load!("wire-sizes")).codegen();
+	assert!(!code.contains("Wire size"));
+}
+
+#[test]
+fn inline_methods() {
+	let code = RustCodegen::new(false, true, &load!("commands")).inline(true).codegen();
+	let lines: Vec<&str> = code.lines().collect();
+	for method in ["fn serialize<", "fn deserialize_stream<", "fn deserialize<", "fn deserialize_into<", "fn serialize_self<W"] {
+		// the methods of `Command`, dispatching commands by their ID, are left alone
+		let uses = lines.iter().enumerate().filter(|(_, l)| l.trim_start().starts_with(method)).collect::<Vec<_>>();
+		assert!(!uses.is_empty(), "`{method}` not found in generated code");
+		for (i, _) in uses {
+			assert_eq!(lines[i - 1].trim(), "#[inline]", "`{method}` on line {} should be `#[inline]`", i + 1);
+		}
+	}
+
+	assert!(!generate("commands").contains("#[inline]"));
+}
+
+#[test]
+fn crate_visibility() {
+	for name in ["commands", "stream", "fixed-fields"] {
+		let code = RustCodegen::new(false, true, &load!(name)).visibility(Visibility::Crate).codegen_both();
+		assert!(code.contains("pub(crate) struct"));
+		assert!(code.contains("pub(crate) mod sync"));
+		let leaked: Vec<&str> = code.lines().filter(|l| l.contains("pub ")).collect();
+		assert!(leaked.is_empty(), "`{name}` has `pub` items: {leaked:#?}");
+	}
+
+	let code = RustCodegen::new(false, true, &load!("commands")).visibility(Visibility::Private).codegen();
+	assert!(!code.contains("pub"));
+}
+
+#[test]
+fn highest_layer() {
+	let code = generate("fixed-fields");
+	assert!(code.contains("pub const HIGHEST_LAYER: u32 = 0;"));
+
+	let definition = PunybufParser::parse_file("test_files/layer-dependency-removal.pbd")
+		.expect("failed to read file")
+		.expect("failed to parse file")
+		.resolve(true)
+		.expect("failed to resolve definition");
+	let code = RustCodegen::new(false, true, &definition).codegen();
+	assert!(code.contains("pub const HIGHEST_LAYER: u32 = 4;"));
+}
+
+#[test]
+fn type_names_of_all_layers() {
+	let definition = PunybufParser::parse_file("test_files/layer-dependency-removal.pbd")
+		.expect("failed to read file")
+		.expect("failed to parse file")
+		.resolve(true)
+		.expect("failed to resolve definition");
+	let code = RustCodegen::new(false, true, &definition).codegen();
+	let start = code.find("pub const TYPE_NAMES: &[&str] = &[\n").expect("no TYPE_NAMES");
+	let table = &code[start..start + code[start..].find("];").unwrap()];
+
+	let json = json::parse(&convert_full_definition(&definition)).unwrap();
+	for tp in json["types"].members() {
+		if tp["attrs"].has_key("@builtin") || tp["attrs"].has_key("@resolve") {
+			continue;
+		}
+		let name = match tp["is_highest_layer"].as_bool().unwrap() {
+			true => tp["name"].to_string(),
+			false => format!("{}Layer{}", tp["name"], tp["layer"]),
+		};
+		assert!(table.contains(&format!("\"{name}\",")), "`{name}` is missing from {table}");
+	}
+}
+
+#[test]
+fn void_is_never_generated() {
+	let code = generate("void");
+	assert!(!code.contains("enum Void"), "`Void` was generated:\n{code}");
+	assert!(code.contains("type Return<'a> = Void;"));
+	// without `include common`, `Void` still comes from the runtime
+	assert!(code.lines().any(|l| l == "use punybuf_common::*;"));
+}
+
+#[test]
+fn aliases_of_array_are_vecs() {
+	let code = generate("aliases");
+	for line in [
+		"pub type List<T> = Vec<T>;",
+		// aliases borrow when the items do
+		"pub type Names<'x> = Vec<Cow<'x, str>>;",
+		"pub type Blobs<'x> = List<Bytes<'x>>;",
+		"pub struct Group<'x> {",
+		"    pub ids: List<u32>,",
+		"    pub nicknames: Vec<Cow<'x, str>>,",
+	] {
+		assert!(code.lines().any(|l| l == line), "`{line}` is missing");
+	}
+}
+
+#[test]
+fn flag_counts_are_checked_at_compile_time() {
+	let assertion = |count, tp, field| format!(
+		"        const _: () = assert!({count} <= 8 * core::mem::size_of::<{tp}>(), \"not enough bits for the flags of `{field}`\");"
+	);
+	let code = generate("bitmap");
+	assert!(code.lines().any(|l| l == assertion(9, "u16", "0_bitmap")), "the assertion is missing");
+
+	// `@extension` flags need bits too
+	let code = generate("extension-flags");
+	assert_eq!(code.lines().filter(|l| *l == assertion(2, "u8", "flags")).count(), 1);
+	assert_eq!(code.lines().filter(|l| *l == assertion(4, "u8", "flags")).count(), 1);
+}
+
+#[test]
+fn derive_skips_eq_and_hash_for_floats() {
+	let code = RustCodegen::new(false, true, &load!("derives")).derive(&[Derive::Eq]).codegen();
+	for (item, derive) in [
+		("pub struct Point", "#[derive(Debug, Clone, PartialEq, Eq)]"),
+		("pub struct Palette<'x>", "#[derive(Debug, Clone, PartialEq, Eq)]"),
+		("pub struct lookup<'x>", "#[derive(Debug, Clone, PartialEq, Eq)]"),
+		// `Reading` has an `F32`, `Sample` and `record` contain a `Reading`
+		("pub struct Reading", "#[derive(Debug, Clone, PartialEq)]"),
+		("pub enum Sample", "#[derive(Debug, Clone, PartialEq)]"),
+		("pub struct record", "#[derive(Debug, Clone, PartialEq)]"),
+		("pub enum recordError<'x>", "#[derive(Debug, Clone, PartialEq)]"),
+	] {
+		assert!(attributes_of(&code, item).contains(&derive), "`{item}` should have `{derive}`");
+	}
+
+	// `derive_ord` already derives `PartialEq` and `Eq`
+	let code = RustCodegen::new(false, true, &load!("ordering"))
+		.derive_ord(true)
+		.derive(&[Derive::PartialEq, Derive::Eq, Derive::Hash])
+		.codegen();
+	assert!(attributes_of(&code, "pub struct Version").contains(&"#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]"));
+	assert!(attributes_of(&code, "pub struct Measurement").contains(&"#[derive(Debug, Clone, PartialEq)]"));
+
+	assert!(!generate("derives").contains("PartialEq"));
+}
+
+#[test]
+fn typescript() {
+	let code = TypeScriptCodegen::new(&load!("commands"), true).codegen().unwrap();
+	assert!(code.contains("export class PbReader"));
+	assert!(code.contains("export interface Point {\n\tx: number;\n\ty: number;\n}"));
+	assert!(code.contains("export type Shape =\n\t| { kind: \"Empty\" }\n\t| { kind: \"Dot\", value: Point };"));
+	assert!(code.contains("\t| { kind: \"UnexpectedError\", value: string }\n\t| { kind: \"NotFound\" };"));
+	// the command IDs are the same as in Rust
+	assert!(generate("commands").contains("(1104368450, \"getShape\")"));
+	assert!(code.contains("\tid: 1104368450,\n\tname: \"getShape\","));
+	assert!(code.contains("\t\t\tcase 1104368450:\n\t\t\t\treturn { command: \"getShape\""));
+
+	let code = TypeScriptCodegen::new(&load!("extension-flags"), true).codegen().unwrap();
+	assert!(code.contains("\t\tif (value.nickname !== undefined) pbString.serialize(ext, value.nickname);"));
+	assert!(code.contains("\t\tconst $bio = (flags0 & 8n) !== 0n ? pbString.deserialize(ext) : undefined;"));
+
+	let error = TypeScriptCodegen::new(&load!("compress"), true).codegen().unwrap_err();
+	assert!(error.contains("@compress"));
+}
@@ -0,0 +1,1759 @@
+//! Runs the code generated from the fixtures in `test_files/codegen/`.
+//! The generated files are checked in under `tests/generated/`;
+//! run the tests with `RECORD=1` to update them after changing the codegen.
+
+use std::{borrow::Cow, fs};
+
+use punybuf::{Derive, PunybufParser, RustCodegen, TypeScriptCodegen};
+
+#[allow(dead_code, unused)]
+#[path = "generated/fixed_fields.rs"]
+mod fixed_fields;
+#[allow(dead_code, unused)]
+#[path = "generated/fixed_fields_tokio.rs"]
+mod fixed_fields_tokio;
+#[allow(dead_code, unused)]
+#[path = "generated/fixed_fields_both.rs"]
+mod fixed_fields_both;
+#[allow(dead_code, unused)]
+#[path = "generated/fixed_fields_inline.rs"]
+mod fixed_fields_inline;
+#[allow(dead_code, unused)]
+#[path = "generated/empty_struct.rs"]
+mod empty_struct;
+#[allow(dead_code, unused)]
+#[path = "generated/reflect.rs"]
+mod reflect;
+#[allow(dead_code, unused)]
+#[path = "generated/reuse.rs"]
+mod reuse;
+// the `experimental` feature is never enabled here
+#[allow(dead_code, unused, unexpected_cfgs)]
+#[path = "generated/experimental.rs"]
+mod experimental;
+#[allow(dead_code, unused)]
+#[path = "generated/experimental_enabled.rs"]
+mod experimental_enabled;
+#[allow(dead_code, unused)]
+#[path = "generated/outcome.rs"]
+mod outcome;
+#[allow(dead_code, unused)]
+#[path = "generated/datetime.rs"]
+mod datetime;
+#[allow(dead_code, unused)]
+#[path = "generated/unknown_enum.rs"]
+mod unknown_enum;
+#[allow(dead_code, unused)]
+#[path = "generated/debug_paths.rs"]
+mod debug_paths;
+#[allow(dead_code, unused)]
+#[path = "generated/ordering.rs"]
+mod ordering;
+#[allow(dead_code, unused)]
+#[path = "generated/stream.rs"]
+mod stream;
+#[allow(dead_code, unused)]
+#[path = "generated/compress.rs"]
+mod compress;
+#[allow(dead_code, unused)]
+#[path = "generated/extension_flags.rs"]
+mod extension_flags;
+#[allow(dead_code, unused)]
+#[path = "generated/extension_flags_field.rs"]
+mod extension_flags_field;
+#[allow(dead_code, unused)]
+#[path = "generated/commands.rs"]
+mod commands;
+#[allow(dead_code, unused)]
+#[path = "generated/commands_tokio.rs"]
+mod commands_tokio;
+#[allow(dead_code, unused)]
+#[path = "generated/stream_tokio.rs"]
+mod stream_tokio;
+#[allow(dead_code, unused)]
+#[path = "generated/bitmap.rs"]
+mod bitmap;
+#[allow(dead_code, unused)]
+#[path = "generated/inline_layers.rs"]
+mod inline_layers;
+#[allow(dead_code, unused)]
+#[path = "generated/aliases.rs"]
+mod aliases;
+#[allow(dead_code, unused)]
+#[path = "generated/sealed.rs"]
+mod sealed;
+#[allow(dead_code, unused)]
+#[path = "generated/min_size.rs"]
+mod min_size;
+#[allow(dead_code, unused)]
+#[path = "generated/derives.rs"]
+mod derives;
+#[allow(dead_code, unused)]
+#[path = "generated/private_fields.rs"]
+mod private_fields;
+#[allow(dead_code, unused)]
+#[path = "generated/byte_order.rs"]
+mod byte_order;
+#[allow(dead_code, unused)]
+#[path = "generated/byte_order_tokio.rs"]
+mod byte_order_tokio;
+#[allow(dead_code, unused)]
+#[path = "generated/lazy_array.rs"]
+mod lazy_array;
+#[allow(dead_code, unused)]
+#[path = "generated/lazy_array_tokio.rs"]
+mod lazy_array_tokio;
+#[allow(dead_code, unused)]
+#[path = "generated/phantom.rs"]
+mod phantom;
+#[allow(dead_code, unused)]
+#[path = "generated/commands_metrics.rs"]
+mod commands_metrics;
+#[allow(dead_code, unused)]
+#[path = "generated/commands_metrics_tokio.rs"]
+mod commands_metrics_tokio;
+#[allow(dead_code, unused)]
+#[path = "generated/single_variant.rs"]
+mod single_variant;
+#[allow(dead_code, unused)]
+#[path = "generated/layer_conversions.rs"]
+mod layer_conversions;
+#[allow(dead_code, unused)]
+#[path = "generated/recursive.rs"]
+mod recursive;
+#[allow(dead_code, unused)]
+#[path = "generated/keep_extensions.rs"]
+mod keep_extensions;
+#[allow(dead_code, unused)]
+#[path = "generated/void.rs"]
+mod void;
+#[allow(dead_code, unused)]
+#[path = "generated/default_variant.rs"]
+mod default_variant;
+#[allow(dead_code, unused)]
+#[path = "generated/typescript.rs"]
+mod typescript;
+
+enum Mode {
+	Sync,
+	Tokio,
+	Both,
+	/// Sync, with `PBReflect`
+	Reflect,
+	/// Sync, as if the `experimental` feature was enabled
+	ExperimentalEnabled,
+	/// Sync, with `UnknownVariant`s
+	AllowUnknownEnum,
+	/// Sync, deriving `Ord`
+	DeriveOrd,
+	/// Sync, with `#[inline]` methods
+	Inline,
+	/// Sync, deriving `PartialEq`, `Eq` and `Hash`
+	Derive,
+	/// Sync, with getters and setters instead of public fields
+	PrivateFields,
+	/// Sync, recording metrics of the commands
+	Metrics,
+	/// Tokio, recording metrics of the commands
+	MetricsTokio,
+	/// Sync, without the discriminants of single-variant enums
+	FlattenEnums,
+	/// Sync, converting structs and commands from their previous layer
+	LayerConversions,
+	/// Sync, keeping unknown flags and extensions
+	KeepExtensions,
+}
+
+/// `(fixture, mode, generated file)`
+const GENERATED: &[(&str, Mode, &str)] = &[
+	("fixed-fields", Mode::Sync, "fixed_fields"),
+	("fixed-fields", Mode::Tokio, "fixed_fields_tokio"),
+	("fixed-fields", Mode::Both, "fixed_fields_both"),
+	("fixed-fields", Mode::Inline, "fixed_fields_inline"),
+	("empty-struct", Mode::Sync, "empty_struct"),
+	("reflect", Mode::Reflect, "reflect"),
+	("reuse", Mode::Sync, "reuse"),
+	("experimental", Mode::Sync, "experimental"),
+	("experimental", Mode::ExperimentalEnabled, "experimental_enabled"),
+	("outcome", Mode::Sync, "outcome"),
+	("datetime", Mode::Sync, "datetime"),
+	("unknown-enum", Mode::AllowUnknownEnum, "unknown_enum"),
+	("debug-paths", Mode::Sync, "debug_paths"),
+	("ordering", Mode::DeriveOrd, "ordering"),
+	("stream", Mode::Sync, "stream"),
+	("compress", Mode::Sync, "compress"),
+	("extension-flags", Mode::Sync, "extension_flags"),
+	("extension-flags-field", Mode::Sync, "extension_flags_field"),
+	("commands", Mode::Sync, "commands"),
+	("commands", Mode::Tokio, "commands_tokio"),
+	("stream", Mode::Tokio, "stream_tokio"),
+	("bitmap", Mode::Sync, "bitmap"),
+	("inline-layers", Mode::Sync, "inline_layers"),
+	("aliases", Mode::Sync, "aliases"),
+	("sealed", Mode::Sync, "sealed"),
+	("min-size", Mode::Sync, "min_size"),
+	("derives", Mode::Derive, "derives"),
+	("derives", Mode::PrivateFields, "private_fields"),
+	("byte-order", Mode::Sync, "byte_order"),
+	("byte-order", Mode::Tokio, "byte_order_tokio"),
+	("lazy-array", Mode::Sync, "lazy_array"),
+	("lazy-array", Mode::Tokio, "lazy_array_tokio"),
+	("phantom", Mode::Sync, "phantom"),
+	("commands", Mode::Metrics, "commands_metrics"),
+	("commands", Mode::MetricsTokio, "commands_metrics_tokio"),
+	("single-variant", Mode::FlattenEnums, "single_variant"),
+	("layer-conversions", Mode::LayerConversions, "layer_conversions"),
+	("recursive", Mode::Sync, "recursive"),
+	("keep-extensions", Mode::KeepExtensions, "keep_extensions"),
+	("void", Mode::Sync, "void"),
+	("default-variant", Mode::Sync, "default_variant"),
+	("typescript", Mode::Sync, "typescript"),
+];
+
+#[test]
+fn generated_code_is_up_to_date() {
+	let record = std::env::var("RECORD").is_ok();
+	for (fixture, mode, generated) in GENERATED {
+		let definition = PunybufParser::parse_file(format!("test_files/codegen/{fixture}.pbd"))
+			.expect("failed to read file")
+			.expect("failed to parse file")
+			.resolve(true)
+			.expect("failed to resolve definition");
+		let code = match mode {
+			Mode::Sync => RustCodegen::new(false, true, &definition).codegen(),
+			Mode::Tokio => RustCodegen::new(true, true, &definition).codegen(),
+			Mode::Both => RustCodegen::new(false, true, &definition).codegen_both(),
+			Mode::Reflect => RustCodegen::new(false, true, &definition).reflect(true).codegen(),
+			// `cfg(all())` is always true, and `cfg(not(all()))` always false
+			Mode::ExperimentalEnabled => RustCodegen::new(false, true, &definition).codegen()
+				.replace(r#"feature = "experimental""#, "all()"),
+			Mode::AllowUnknownEnum => RustCodegen::new(false, true, &definition).allow_unknown_enum(true).codegen(),
+			Mode::DeriveOrd => RustCodegen::new(false, true, &definition).derive_ord(true).codegen(),
+			Mode::Inline => RustCodegen::new(false, true, &definition).inline(true).codegen(),
+			Mode::Derive => RustCodegen::new(false, true, &definition)
+				.derive(&[Derive::PartialEq, Derive::Eq, Derive::Hash])
+				.codegen(),
+			Mode::PrivateFields => RustCodegen::new(false, true, &definition).private_fields(true).codegen(),
+			Mode::Metrics => RustCodegen::new(false, true, &definition).metrics(true).codegen(),
+			Mode::MetricsTokio => RustCodegen::new(true, true, &definition).metrics(true).codegen(),
+			Mode::FlattenEnums => RustCodegen::new(false, true, &definition).flatten_enums(true).codegen(),
+			Mode::LayerConversions => RustCodegen::new(false, true, &definition).layer_conversions(true).codegen(),
+			Mode::KeepExtensions => RustCodegen::new(false, true, &definition).keep_extensions(true).codegen(),
+		};
+		let path = format!("tests/generated/{generated}.rs");
+
+		if record {
+			fs::write(&path, &code).expect("failed to record generated code");
+			continue;
+		}
+		let existing = fs::read_to_string(&path).unwrap_or_default();
+		assert!(existing == code, "`{path}` is outdated, run the tests with RECORD=1 to update it");
+	}
+}
+
+#[tokio::test]
+async fn batched_fixed_fields() {
+	use punybuf_common::tokio::PBType as _;
+	use punybuf_common::PBType as _;
+
+	let sync = fixed_fields::Sample {
+		id: 0x0102030405060708,
+		kind: 9,
+		x: -10,
+		y: 11.5,
+		port: 1200,
+		name: Cow::Borrowed("sample"),
+		count: 13,
+	};
+	let tokio = fixed_fields_tokio::Sample {
+		id: sync.id,
+		kind: sync.kind,
+		x: sync.x,
+		y: sync.y,
+		port: sync.port,
+		name: sync.name.clone(),
+		count: sync.count,
+	};
+	let mut expected = vec![];
+	sync.serialize(&mut expected).unwrap();
+	let mut actual = vec![];
+	tokio.serialize(&mut actual).await.unwrap();
+	assert_eq!(actual, expected);
+
+	let decoded = fixed_fields_tokio::Sample::deserialize_stream(&mut &actual[..]).await.unwrap();
+	assert_eq!((decoded.id, decoded.x, decoded.y, decoded.name), (sync.id, sync.x, sync.y, sync.name));
+}
+
+#[tokio::test]
+async fn sync_types_over_async_io() {
+	use punybuf_common::tokio::{read_from_async, write_to_async, PBType as _};
+	use tokio::io::{AsyncReadExt, BufReader};
+
+	let sync = fixed_fields::Sample {
+		id: 1,
+		kind: 2,
+		x: -3,
+		y: 4.5,
+		port: 6,
+		name: Cow::Borrowed("bridged"),
+		count: 7,
+	};
+	let mut written = vec![];
+	write_to_async(&sync, &mut written).await.unwrap();
+	let decoded = fixed_fields_tokio::Sample::deserialize_stream(&mut &written[..]).await.unwrap();
+	assert_eq!((decoded.id, decoded.x, decoded.y, &decoded.name), (sync.id, sync.x, sync.y, &sync.name));
+
+	let mut bytes = vec![];
+	decoded.serialize(&mut bytes).await.unwrap();
+	bytes.extend_from_slice(b"next");
+	// a tiny buffer, so the value arrives in many reads
+	let mut reader = BufReader::with_capacity(3, &bytes[..]);
+	let read: fixed_fields::Sample = read_from_async(&mut reader).await.unwrap();
+	assert_eq!((read.id, read.kind, read.port, read.name, read.count), (1, 2, 6, Cow::Borrowed("bridged"), 7));
+
+	let mut rest = vec![];
+	reader.read_to_end(&mut rest).await.unwrap();
+	assert_eq!(rest, b"next");
+
+	let mut truncated = BufReader::new(&written[..written.len() - 1]);
+	let err = read_from_async::<fixed_fields::Sample, _>(&mut truncated).await.unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn both_modules() {
+	use punybuf_common::tokio::PBType as _;
+	use punybuf_common::PBType as _;
+	use fixed_fields_both::{r#async, sync};
+
+	let value = sync::Sample {
+		id: 1,
+		kind: 2,
+		x: 3,
+		y: 4.0,
+		port: 5,
+		name: Cow::Borrowed("both"),
+		count: 6,
+	};
+	let mut expected = vec![];
+	value.serialize(&mut expected).unwrap();
+
+	let decoded = r#async::Sample::deserialize_stream(&mut &expected[..]).await.unwrap();
+	let mut actual = vec![];
+	decoded.serialize(&mut actual).await.unwrap();
+	assert_eq!(actual, expected);
+}
+
+/// Compares how fast `Sample`s decode with and without `--rust:inline`.
+/// Only meaningful in release mode: `cargo test --release --test generated -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn inline_decode_throughput() {
+	use punybuf_common::PBType;
+	use std::hint::black_box;
+	use std::time::{Duration, Instant};
+
+	const ROUNDS: usize = 2_000_000;
+
+	fn measure<'x, T: PBType<'x>>(bytes: &'x [u8]) -> Duration {
+		let start = Instant::now();
+		for _ in 0..ROUNDS {
+			let mut r = black_box(bytes);
+			black_box(T::deserialize(&mut r).unwrap());
+		}
+		start.elapsed()
+	}
+
+	let value = fixed_fields::Sample {
+		id: 1,
+		kind: 2,
+		x: -3,
+		y: 4.5,
+		port: 6,
+		name: Cow::Borrowed("throughput"),
+		count: 7,
+	};
+	let mut bytes = vec![];
+	value.serialize(&mut bytes).unwrap();
+	let mut inlined = vec![];
+	fixed_fields_inline::Sample::deserialize(&mut &bytes[..]).unwrap().serialize(&mut inlined).unwrap();
+	assert_eq!(inlined, bytes);
+
+	// warm up, so the first measurement isn't penalized
+	measure::<fixed_fields::Sample>(&bytes);
+	let plain = measure::<fixed_fields::Sample>(&bytes);
+	let inline = measure::<fixed_fields_inline::Sample>(&bytes);
+	let per_second = |elapsed: Duration| ROUNDS as f64 / elapsed.as_secs_f64();
+	println!("without #[inline]: {:.0} values/s", per_second(plain));
+	println!("with #[inline]:    {:.0} values/s", per_second(inline));
+}
+
+#[test]
+fn empty_structs() {
+	use punybuf_common::PBType;
+	use empty_struct::{Empty, Holder, SealedEmpty};
+
+	assert_eq!(size_of::<Empty>(), 0);
+
+	let mut bytes = vec![];
+	SealedEmpty.serialize(&mut bytes).unwrap();
+	assert!(bytes.is_empty());
+	SealedEmpty::deserialize(&mut &bytes[..]).unwrap();
+
+	// without `@sealed`, there's still an (empty) extension section
+	Empty.serialize(&mut bytes).unwrap();
+	assert_eq!(bytes, [0]);
+	Empty::deserialize(&mut &bytes[..]).unwrap();
+
+	let holder = Holder { before: 1, empty: Empty, sealed: SealedEmpty, after: 2 };
+	let mut bytes = vec![];
+	holder.serialize(&mut bytes).unwrap();
+	assert_eq!(bytes, [1, 0, 2, 0]);
+	let decoded = Holder::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!((decoded.before, decoded.after), (1, 2));
+}
+
+#[test]
+fn sealed_structs_have_no_extension_length() {
+	use punybuf_common::{PBCommand, PBType};
+	use sealed::{rename, Open, Sealed};
+
+	let mut open = vec![];
+	Open { id: 7, name: Cow::Borrowed("x") }.serialize(&mut open).unwrap();
+	let mut sealed = vec![];
+	Sealed { id: 7, name: Cow::Borrowed("x") }.serialize(&mut sealed).unwrap();
+	assert_eq!(sealed, [0, 0, 0, 7, 1, b'x']);
+	// the same fields, then the length of the extensions
+	assert_eq!(open, [&sealed[..], &[0]].concat());
+
+	let mut command = vec![];
+	rename { id: 7, name: Cow::Borrowed("x") }.serialize_self(&mut command).unwrap();
+	assert_eq!(command, sealed);
+
+	// reading one as the other doesn't fail, it leaves a byte behind or takes one too many
+	let mut rest = &open[..];
+	Sealed::deserialize(&mut rest).unwrap();
+	assert_eq!(rest, [0]);
+	assert!(Open::deserialize(&mut &sealed[..]).is_err());
+}
+
+#[test]
+fn reflection() {
+	use punybuf_common::{FieldValue, PBReflect, UInt};
+	use reflect::{Color, KeyPair, Profile};
+
+	let profile = Profile {
+		id: 7,
+		name: Cow::Borrowed("reflected"),
+		scores: vec![1, -2],
+		color: Color::Custom(0xff00ff),
+		tags: vec![KeyPair { key: Cow::Borrowed("level"), value: UInt(3) }],
+		verified: true,
+		nickname: None,
+		avatar: punybuf_common::Bytes(Cow::Borrowed(&[1, 2, 3])),
+		public: false,
+	};
+	assert_eq!(profile.type_name(), "Profile");
+
+	let fields = profile.fields();
+	let names: Vec<_> = fields.iter().map(|(name, _)| *name).collect();
+	assert_eq!(names, ["id", "name", "scores", "color", "tags", "verified", "nickname", "avatar", "public"]);
+
+	assert!(matches!(fields[0].1, FieldValue::U64(7)));
+	assert!(matches!(fields[1].1, FieldValue::String("reflected")));
+	let FieldValue::Array(scores) = &fields[2].1 else { panic!("scores is not an array") };
+	assert!(matches!(scores[..], [FieldValue::I32(1), FieldValue::I32(-2)]));
+	let FieldValue::Reflect(color) = &fields[3].1 else { panic!("color is not reflected") };
+	assert_eq!(color.type_name(), "Color");
+	assert!(matches!(color.fields()[..], [("Custom", FieldValue::U32(0xff00ff))]));
+	let FieldValue::Array(tags) = &fields[4].1 else { panic!("tags is not an array") };
+	let FieldValue::Reflect(pair) = &tags[0] else { panic!("a key pair is not reflected") };
+	assert!(matches!(pair.fields()[..], [("key", FieldValue::String("level")), ("value", FieldValue::UInt(3))]));
+	assert!(matches!(fields[5].1, FieldValue::Bool(true)));
+	assert!(matches!(fields[6].1, FieldValue::Option(None)));
+	assert!(matches!(fields[7].1, FieldValue::Bytes(&[1, 2, 3])));
+	assert!(matches!(fields[8].1, FieldValue::Bool(false)));
+}
+
+#[test]
+fn deserialize_into_reuses_allocations() {
+	use punybuf_common::{Bytes, PBType};
+	use reuse::Message;
+
+	let first = Message {
+		id: 1,
+		text: Cow::Owned("the first message".into()),
+		tags: vec![Cow::Owned("a".into()), Cow::Owned("b".into())],
+		payload: Bytes(Cow::Owned(vec![1, 2, 3, 4])),
+		urgent: true,
+		reply_to: Some(Cow::Owned("someone".into())),
+		edited_at: None,
+	};
+	let second = Message {
+		id: 2,
+		text: Cow::Borrowed("second"),
+		tags: vec![Cow::Borrowed("c")],
+		payload: Bytes(Cow::Borrowed(&[5])),
+		urgent: false,
+		reply_to: None,
+		edited_at: Some(1200),
+	};
+	let mut bytes = vec![];
+	first.serialize(&mut bytes).unwrap();
+	second.serialize(&mut bytes).unwrap();
+	let r = &mut &bytes[..];
+
+	let mut message = Message::deserialize_stream(r).unwrap();
+	let Cow::Owned(text) = &message.text else { panic!("text is not owned") };
+	let text_ptr = text.as_ptr();
+	let tags_ptr = message.tags.as_ptr();
+	let payload_ptr = message.payload.0.as_ptr();
+
+	message.deserialize_into(r).unwrap();
+	assert!(r.is_empty());
+	assert_eq!((message.id, &*message.text, &message.tags[..]), (2, "second", &[Cow::Borrowed("c")][..]));
+	assert_eq!(&message.payload.0[..], [5]);
+	assert_eq!((message.urgent, &message.reply_to, message.edited_at), (false, &None, Some(1200)));
+
+	assert_eq!(message.text.as_ptr(), text_ptr, "the string was reallocated");
+	assert_eq!(message.tags.as_ptr(), tags_ptr, "the array was reallocated");
+	assert_eq!(message.payload.0.as_ptr(), payload_ptr, "the bytes were reallocated");
+}
+
+#[test]
+fn experimental_commands() {
+	use punybuf_common::{PBCommand, UInt};
+
+	let note = experimental::getNote { id: UInt(1) };
+	let mut bytes = vec![];
+	note.serialize(&mut bytes).unwrap();
+	assert!(matches!(experimental::Command::deserialize(&mut &bytes[..]), Ok(experimental::Command::getNote(_))));
+
+	let react = experimental_enabled::react {
+		note: UInt(1),
+		reaction: experimental_enabled::Reaction { emoji: Cow::Borrowed("+1"), count: UInt(2) },
+	};
+	let mut bytes = vec![];
+	react.serialize(&mut bytes).unwrap();
+	let Ok(experimental_enabled::Command::react(decoded)) = experimental_enabled::Command::deserialize(&mut &bytes[..]) else {
+		panic!("the experimental command wasn't decoded with the feature enabled");
+	};
+	assert_eq!((&*decoded.reaction.emoji, decoded.reaction.count.0), ("+1", 2));
+
+	// without the feature, the command doesn't exist at all
+	let error = experimental::Command::deserialize(&mut &bytes[..]).unwrap_err();
+	assert_eq!(error.to_string(), "Invalid or unsupported command ID");
+}
+
+#[test]
+fn command_outcomes() {
+	use punybuf_common::{CommandOutcome, PBCommandExt, PBType};
+	use outcome::{getNote, getNoteError, Note};
+
+	let command = getNote { title: Cow::Borrowed("todo") };
+	let mut bytes = vec![];
+	Note { text: Cow::Borrowed("found it") }.serialize(&mut bytes).unwrap();
+	let CommandOutcome::Ok(note) = command.deserialize_outcome(&mut &bytes[..], false).unwrap() else {
+		panic!("expected the return value");
+	};
+	assert_eq!(note.text, "found it");
+
+	let mut bytes = vec![];
+	getNoteError::Hidden(Cow::Borrowed("by the author")).serialize(&mut bytes).unwrap();
+	let outcome = command.deserialize_outcome(&mut &bytes[..], true).unwrap();
+	assert!(matches!(outcome.into_result(), Err(getNoteError::Hidden(reason)) if reason == "by the author"));
+}
+
+#[test]
+fn date_times() {
+	use punybuf_common::{DateTime, PBType};
+	use datetime::Event;
+
+	let at = DateTime::from_timestamp(1_700_000_000, 250_000_001).unwrap();
+	let event = Event {
+		name: Cow::Borrowed("launch"),
+		at,
+		ends_at: Some(DateTime::from_timestamp(1_700_000_000, 250_000_002).unwrap()),
+		reminders: vec![DateTime::from_timestamp(1_699_996_400, 0).unwrap()],
+	};
+	let mut bytes = vec![];
+	event.serialize(&mut bytes).unwrap();
+	let decoded = Event::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!(decoded.at, event.at);
+	assert_eq!(decoded.ends_at, event.ends_at);
+	assert_eq!(decoded.reminders, event.reminders);
+}
+
+#[test]
+fn unknown_enum_variants() {
+	use punybuf_common::PBType;
+	use unknown_enum::{Mood, NewerMood, Sealed, Status};
+
+	for newer in [NewerMood::ConfusedAbout(Cow::Borrowed("the request")), NewerMood::Hungry] {
+		let mut bytes = vec![];
+		newer.serialize(&mut bytes).unwrap();
+
+		let older = Mood::deserialize(&mut &bytes[..]).unwrap();
+		assert!(matches!(older, Mood::UnknownVariant(3 | 4, _)), "got {older:?}");
+		let mut reencoded = vec![];
+		older.serialize(&mut reencoded).unwrap();
+		assert_eq!(reencoded, bytes);
+
+		// known variants keep working, unknown ones are skipped entirely
+		let status = Status { mood: older, sealed: Sealed::No };
+		let mut bytes = vec![];
+		status.serialize(&mut bytes).unwrap();
+		let decoded = Status::deserialize(&mut &bytes[..]).unwrap();
+		assert!(matches!(decoded.sealed, Sealed::No));
+	}
+
+	let mut bytes = vec![];
+	NewerMood::ThinkingAbout(Cow::Borrowed("lunch")).serialize(&mut bytes).unwrap();
+	let Mood::ThinkingAbout(about) = Mood::deserialize(&mut &bytes[..]).unwrap() else {
+		panic!("a known variant was decoded as unknown");
+	};
+	assert_eq!(about, "lunch");
+
+	assert!(Sealed::deserialize(&mut &[2u8][..]).is_err());
+}
+
+#[test]
+fn single_variant_enums_are_flattened() {
+	use punybuf_common::PBType;
+	use single_variant::{Choice, Name, Person, Wrapped};
+
+	let person = Person {
+		name: Name::Some(Cow::Borrowed("someone")),
+		age: Wrapped::Value(30),
+		choice: Choice::Second,
+	};
+	let mut bytes = vec![];
+	person.serialize(&mut bytes).unwrap();
+	// the name and the age without discriminants, then `Choice` with one,
+	// and the length of the extensions of `Person`
+	let mut expected = vec![7];
+	expected.extend(b"someone");
+	expected.extend([30, 1, 0]);
+	assert_eq!(bytes, expected);
+
+	let decoded = Person::deserialize(&mut &bytes[..]).unwrap();
+	assert!(matches!(decoded.name, Name::Some(ref name) if name == "someone"));
+	assert!(matches!(decoded.age, Wrapped::Value(30)));
+	assert_eq!(<Wrapped<u8>>::MIN_SIZE, 1);
+	assert_eq!(Choice::MIN_SIZE, 1);
+}
+
+#[test]
+fn layers_are_converted() {
+	use layer_conversions::{
+		Address, AddressLayer0, Profile, ProfileLayer0, rename, renameLayer0,
+	};
+
+	let address = Address::from(AddressLayer0 { street: Cow::Borrowed("Main St.") });
+	assert_eq!((address.street.as_ref(), address.zip), ("Main St.", None));
+
+	let old = || ProfileLayer0 {
+		name: Cow::Borrowed("someone"),
+		address: AddressLayer0 { street: Cow::Borrowed("Main St.") },
+		verified: true,
+		nickname: None,
+	};
+	let err = Profile::try_from(old()).unwrap_err();
+	assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+	assert!(err.to_string().contains("`nickname`"));
+
+	let profile = Profile::try_from(ProfileLayer0 { nickname: Some(Cow::Borrowed("some")), ..old() }).unwrap();
+	assert_eq!((profile.name.as_ref(), profile.nickname.as_ref(), profile.verified), ("someone", "some", true));
+	assert_eq!(profile.address.zip, None);
+
+	let renamed: rename = renameLayer0 { name: Cow::Borrowed("new name") }.into();
+	assert!(!renamed.notify);
+
+	// `value` went from `U8` to `U16`, which isn't a new flag
+	let definition = PunybufParser::parse_file("test_files/codegen/layer-conversions.pbd")
+		.expect("failed to read file")
+		.expect("failed to parse file")
+		.resolve(true)
+		.expect("failed to resolve definition");
+	let code = RustCodegen::new(false, true, &definition).layer_conversions(true).codegen();
+	assert!(!code.contains("From<CounterLayer0>"));
+	assert!(!RustCodegen::new(false, true, &definition).codegen().contains("From<AddressLayer0"));
+}
+
+#[test]
+fn debug_paths() {
+	use punybuf_common::{DecodePathError, PBType};
+	use debug_paths::{Address, Contact, Message, User};
+
+	let address = || Address { street: Cow::Borrowed("Main St."), zip: 12345 };
+	let message = Message {
+		user: User { name: Cow::Borrowed("someone"), address: address() },
+		contact: Contact::Post(address()),
+	};
+	let mut bytes = vec![];
+	message.serialize(&mut bytes).unwrap();
+
+	// cut off inside `contact` (before the extension lengths of `Address` and `Message`),
+	// then inside `user`
+	for (len, path) in [(bytes.len() - 3, ".contact.Post.zip"), (20, ".user.address.zip")] {
+		let err = Message::deserialize(&mut &bytes[..len]).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+		assert!(err.to_string().starts_with(&format!("failed at {path}: ")), "got {err}");
+		let inner = err.get_ref().and_then(|e| e.downcast_ref::<DecodePathError>()).unwrap();
+		assert_eq!(inner.path().collect::<Vec<_>>().join("."), path[1..]);
+	}
+}
+
+#[test]
+fn ordered_types() {
+	use ordering::{Channel, Release, Version};
+
+	let version = |major, minor, patch, tag| Version { major, minor, patch, tag: Cow::Borrowed(tag) };
+	let mut versions = vec![
+		version(1, 10, 0, ""),
+		version(1, 2, 3, "rc1"),
+		version(0, 9, 9, ""),
+		version(1, 2, 3, ""),
+	];
+	versions.sort();
+	assert_eq!(versions, [
+		version(0, 9, 9, ""),
+		version(1, 2, 3, ""),
+		version(1, 2, 3, "rc1"),
+		version(1, 10, 0, ""),
+	]);
+
+	// fields are compared in the order of declaration, so `channel` goes first
+	let release = |channel, version| Release { channel, version, checksums: vec![], yanked: false, replaced_by: None };
+	let mut releases = vec![
+		release(Channel::Stable, version(1, 0, 0, "")),
+		release(Channel::Nightly(20), version(2, 0, 0, "")),
+		release(Channel::Beta, version(3, 0, 0, "")),
+		release(Channel::Nightly(10), version(4, 0, 0, "")),
+	];
+	releases.sort();
+	let majors: Vec<_> = releases.iter().map(|r| r.version.major).collect();
+	assert_eq!(majors, [4, 2, 3, 1]);
+}
+
+#[test]
+fn streaming_commands() {
+	use punybuf_common::{PBCommand, PBCommandExt};
+	use stream::{Command, CommandReturn, LogLine, getLine, tailLog};
+
+	const _: () = assert!(tailLog::IS_STREAM && !getLine::IS_STREAM);
+	let command = tailLog { source: Cow::Borrowed("kernel") };
+	assert!(command.is_stream());
+	assert!(Command::tailLog(command.clone()).is_stream());
+
+	// the server writes every line as a frame, then ends the stream
+	let lines = ["booting", "mounting", "ready"];
+	let mut bytes = vec![];
+	for (level, text) in lines.iter().enumerate() {
+		let frame = CommandReturn::tailLog(Some(LogLine { level: level as u8, text: Cow::Borrowed(text) }));
+		frame.serialize(&mut bytes).unwrap();
+	}
+	CommandReturn::tailLog(None).serialize(&mut bytes).unwrap();
+	bytes.push(0xff); // whatever comes after the stream
+
+	let mut r = &bytes[..];
+	let mut items = command.deserialize_return_items(&mut r);
+	let received: Vec<_> = items.by_ref().map(|line| line.unwrap()).collect();
+	assert!(items.is_ended());
+	assert_eq!(received.iter().map(|l| &l.text[..]).collect::<Vec<_>>(), lines);
+	assert_eq!(received.iter().map(|l| l.level).collect::<Vec<_>>(), [0, 1, 2]);
+	assert_eq!(r, [0xff]);
+
+	let CommandReturn::tailLog(Some(first)) = CommandReturn::deserialize_return(tailLog::ID, &mut &bytes[..]).unwrap() else {
+		panic!("expected the first line");
+	};
+	assert_eq!(first.text, "booting");
+
+	// a stream that got cut off fails, and ends
+	let mut r = &bytes[..bytes.len() / 2];
+	let mut items = command.deserialize_return_items(&mut r);
+	assert!(items.by_ref().any(|line| line.is_err()));
+	assert!(items.next().is_none());
+}
+
+#[test]
+fn compressed_fields() {
+	use punybuf_common::PBType;
+	use compress::Snapshot;
+
+	let data: Vec<u8> = b"punybuf ".iter().copied().cycle().take(64 * 1024).collect();
+	let log = "everything is fine\n".repeat(1000);
+	let snapshot = Snapshot {
+		id: 7,
+		data: punybuf_common::Bytes(Cow::Borrowed(&data)),
+		log: Cow::Borrowed(&log),
+		checksum: 0xdeadbeef,
+	};
+	let mut bytes = vec![];
+	snapshot.serialize(&mut bytes).unwrap();
+	assert!(bytes.len() < (data.len() + log.len()) / 10, "{} bytes weren't compressed", bytes.len());
+
+	let decoded = Snapshot::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!((decoded.id, decoded.checksum), (7, 0xdeadbeef));
+	assert!(decoded.data.0 == data && decoded.log == log);
+
+	let mut reused = Snapshot::deserialize(&mut &bytes[..]).unwrap();
+	reused.deserialize_into(&mut &bytes[..]).unwrap();
+	assert!(reused.data.0 == data && reused.log == log);
+
+	// the uncompressed length has to match what's actually there
+	let mut short = vec![];
+	punybuf_common::compression::serialize_zstd(&Cow::Borrowed("hello"), &mut short).unwrap();
+	assert_eq!(short[0], 5);
+	short[0] = 4;
+	assert!(punybuf_common::compression::deserialize_zstd::<Cow<str>, _>(&mut &short[..]).is_err());
+}
+
+#[test]
+fn boolean_extension_flags() {
+	use punybuf_common::PBType;
+	use extension_flags::{New, Old};
+
+	// a boolean extension is only a bit, so the EL stays 0
+	let verified = New { name: None, nickname: None, verified: true, bio: None };
+	let mut bytes = vec![];
+	verified.serialize(&mut bytes).unwrap();
+	assert_eq!(bytes, [0b0100, 0]);
+	assert!(New::deserialize(&mut &bytes[..]).unwrap().verified);
+
+	let new = New {
+		name: Some(Cow::Borrowed("Ada")),
+		nickname: Some(Cow::Borrowed("ada")),
+		verified: true,
+		bio: Some(Cow::Borrowed("hi")),
+	};
+	let mut bytes = vec![];
+	new.serialize(&mut bytes).unwrap();
+	let decoded = New::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!((decoded.name, decoded.nickname, decoded.verified, decoded.bio), (new.name, new.nickname, true, new.bio));
+
+	// an outdated deserializer ignores the bit, and skips `bio`
+	let mut r = &bytes[..];
+	let old = Old::deserialize(&mut r).unwrap();
+	assert_eq!((old.name.as_deref(), old.nickname.as_deref()), (Some("Ada"), Some("ada")));
+	assert!(r.is_empty());
+
+	let mut bytes = vec![];
+	old.serialize(&mut bytes).unwrap();
+	let upgraded = New::deserialize(&mut &bytes[..]).unwrap();
+	assert!(!upgraded.verified && upgraded.bio.is_none());
+}
+
+#[test]
+fn commands_implement_both_traits() {
+	use punybuf_common::{PBCommand, PBCommandExt, PBType, UInt};
+	use commands::{Command, CommandReturn, Point, Shape, getShape, moveCursor};
+
+	/// Only compiles if `C` implements both traits
+	fn check_id<'x, C: PBCommand + PBCommandExt<'x>>(command: &C) {
+		assert_eq!(command.id(), C::ID);
+		assert_eq!(command.is_void(), C::IS_VOID);
+	}
+	let move_cursor = moveCursor(Point { x: -1, y: 2 });
+	let get_shape = getShape { id: UInt(3) };
+	check_id(&move_cursor);
+	check_id(&get_shape);
+
+	let mut bytes = vec![];
+	Command::moveCursor(move_cursor).serialize(&mut bytes).unwrap();
+	let Command::moveCursor(moveCursor(point)) = Command::deserialize(&mut &bytes[..]).unwrap() else {
+		panic!("expected moveCursor");
+	};
+	assert_eq!((point.x, point.y), (-1, 2));
+	let mut r = &bytes[4..];
+	let decoded = moveCursor::deserialize_stream(&mut r).unwrap();
+	assert_eq!((decoded.0.x, decoded.0.y), (-1, 2));
+	assert!(r.is_empty());
+
+	let mut bytes = vec![];
+	get_shape.serialize(&mut bytes).unwrap();
+	let Command::getShape(decoded) = Command::deserialize_stream(&mut &bytes[..]).unwrap() else {
+		panic!("expected getShape");
+	};
+	assert_eq!(decoded.id.0, 3);
+
+	let mut bytes = vec![];
+	Shape::Dot(Point { x: 5, y: 6 }).serialize(&mut bytes).unwrap();
+	let CommandReturn::getShape(Shape::Dot(dot)) = CommandReturn::deserialize_return(getShape::ID, &mut &bytes[..]).unwrap() else {
+		panic!("expected a dot");
+	};
+	assert_eq!((dot.x, dot.y), (5, 6));
+}
+
+#[tokio::test]
+async fn tokio_commands_implement_both_traits() {
+	use punybuf_common::tokio::{PBCommand, PBCommandExt, PBType};
+	use commands_tokio::{Command, Point, moveCursor};
+	use stream_tokio::{LogLine, tailLog};
+
+	/// Only compiles if `C` implements both traits
+	fn check_id<'x, C: PBCommand + PBCommandExt<'x>>(command: &C) {
+		assert_eq!(command.id(), C::ID);
+		assert_eq!(command.is_stream(), C::IS_STREAM);
+	}
+	let move_cursor = moveCursor(Point { x: 7, y: -8 });
+	let tail_log = tailLog { source: Cow::Borrowed("kernel") };
+	check_id(&move_cursor);
+	check_id(&tail_log);
+
+	let mut bytes = vec![];
+	Command::moveCursor(move_cursor).serialize(&mut bytes).await.unwrap();
+	let Command::moveCursor(moveCursor(point)) = Command::deserialize_stream(&mut &bytes[..]).await.unwrap() else {
+		panic!("expected moveCursor");
+	};
+	assert_eq!((point.x, point.y), (7, -8));
+
+	let mut bytes = vec![];
+	tail_log.serialize(&mut bytes).await.unwrap();
+	let stream_tokio::Command::tailLog(decoded) = stream_tokio::Command::deserialize_stream(&mut &bytes[..]).await.unwrap() else {
+		panic!("expected tailLog");
+	};
+	assert_eq!(decoded.source, "kernel");
+
+	let mut bytes = vec![];
+	LogLine { level: 1, text: Cow::Borrowed("ready") }.serialize(&mut bytes).await.unwrap();
+	let line = decoded.deserialize_return_stream(&mut &bytes[..]).await.unwrap();
+	assert_eq!(line.text, "ready");
+}
+
+#[tokio::test]
+async fn client_calls_commands() {
+	use punybuf_common::tokio::{PBType, UInt};
+	use commands_tokio::{Client, Command, Point, Shape, getShape, getShapeError, moveCursor};
+
+	let (client, mut server) = tokio::io::duplex(64);
+	let mut client = Client::new(client);
+
+	let calls = async {
+		client.call(moveCursor(Point { x: 1, y: 2 })).await.unwrap().unwrap();
+		let Shape::Dot(dot) = client.call(getShape { id: UInt(1) }).await.unwrap().unwrap() else {
+			panic!("expected a dot");
+		};
+		assert_eq!((dot.x, dot.y), (1, 2));
+		let err = client.call(getShape { id: UInt(2) }).await.unwrap().unwrap_err();
+		assert!(matches!(err, getShapeError::NotFound));
+		let err = client.call(getShape { id: UInt(3) }).await.unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+	};
+	let responses = async {
+		let Command::moveCursor(moveCursor(cursor)) = Command::deserialize_stream(&mut server).await.unwrap() else {
+			panic!("expected moveCursor");
+		};
+		for discriminator in [1u8, 0, 2] {
+			let Command::getShape(_) = Command::deserialize_stream(&mut server).await.unwrap() else {
+				panic!("expected getShape");
+			};
+			discriminator.serialize(&mut server).await.unwrap();
+			match discriminator {
+				1 => Shape::Dot(Point { x: cursor.x, y: cursor.y }).serialize(&mut server).await.unwrap(),
+				0 => getShapeError::NotFound.serialize(&mut server).await.unwrap(),
+				_ => {}
+			}
+		}
+	};
+	tokio::join!(calls, responses);
+}
+
+#[test]
+fn bitmap_roundtrip() {
+	use punybuf_common::{Bytes, PBType};
+	use bitmap::Profile;
+
+	let profile = |nickname, age, is_admin, avatar, score, is_bot, bio| Profile {
+		id: 7, username: Cow::Borrowed("ada"),
+		nickname, age, is_admin, avatar, location: None, score, theme: None, is_bot, bio,
+	};
+	let profiles = [
+		profile(None, None, false, None, None, false, None),
+		profile(Some(Cow::Borrowed("Ada")), None, true, None, Some(u64::MAX), false, Some(Cow::Borrowed("hi"))),
+		profile(None, Some(36), false, Some(Bytes(Cow::Borrowed(&[1, 2, 3]))), None, true, None),
+	];
+	for profile in profiles {
+		let mut bytes = vec![];
+		profile.serialize(&mut bytes).unwrap();
+
+		// 9 optional fields need a `U16` bitmap, which comes first
+		let bitmap = u16::from_be_bytes([bytes[0], bytes[1]]);
+		let present = [
+			profile.nickname.is_some(), profile.age.is_some(), profile.is_admin, profile.avatar.is_some(),
+			profile.location.is_some(), profile.score.is_some(), profile.theme.is_some(), profile.is_bot,
+			profile.bio.is_some(),
+		];
+		for (bit, present) in present.into_iter().enumerate() {
+			assert_eq!(bitmap & (1 << bit) != 0, present, "bit {bit} of {bitmap:#b}");
+		}
+
+		let decoded = Profile::deserialize(&mut &bytes[..]).unwrap();
+		assert_eq!(
+			(decoded.nickname, decoded.age, decoded.is_admin, decoded.avatar.map(|b| b.0), decoded.score, decoded.is_bot, decoded.bio),
+			(profile.nickname, profile.age, profile.is_admin, profile.avatar.map(|b| b.0), profile.score, profile.is_bot, profile.bio)
+		);
+		assert_eq!((decoded.id, decoded.username), (7, Cow::Borrowed("ada")));
+		assert_eq!(Profile::deserialize_stream(&mut &bytes[..]).unwrap().score, decoded.score);
+	}
+}
+
+#[test]
+fn io_errors_become_unexpected_errors() {
+	use punybuf_common::PBType;
+	use commands::{Shape, getShapeError};
+
+	fn handle(request: &[u8]) -> Result<Shape, getShapeError<'static>> {
+		let id = u64::deserialize(&mut &request[..])?;
+		if id != 1 {
+			return Err(getShapeError::NotFound);
+		}
+		Ok(Shape::Empty)
+	}
+
+	assert!(matches!(handle(&1u64.to_be_bytes()), Ok(Shape::Empty)));
+	assert!(matches!(handle(&2u64.to_be_bytes()), Err(getShapeError::NotFound)));
+	let Err(getShapeError::UnexpectedError(message)) = handle(&[1, 2]) else {
+		panic!("expected an unexpected error");
+	};
+	assert_eq!(message, u64::deserialize(&mut &[1, 2][..]).unwrap_err().to_string());
+}
+
+#[test]
+fn name_tables() {
+	use punybuf_common::PBCommandExt;
+	use commands::{getShape, moveCursor, COMMAND_IDS_TO_NAMES, TYPE_NAMES};
+
+	for name in ["Point", "Shape", "Map"] {
+		assert!(TYPE_NAMES.contains(&name), "`{name}` is missing from {TYPE_NAMES:?}");
+	}
+	// builtins don't become types
+	assert!(!TYPE_NAMES.contains(&"U8"));
+	assert!(!TYPE_NAMES.contains(&"Optional"));
+	assert_eq!(COMMAND_IDS_TO_NAMES, [(moveCursor::ID, "moveCursor"), (getShape::ID, "getShape")]);
+}
+
+#[test]
+fn type_name_is_the_declared_name() {
+	use punybuf_common::PBType;
+	use inline_layers::{User, UserLayer0};
+
+	assert_eq!(<commands::Point>::TYPE_NAME, "Point");
+	assert_eq!(<Option<commands::Point>>::TYPE_NAME, "Optional");
+	// the layer isn't part of the name
+	assert_eq!(User::TYPE_NAME, "User");
+	assert_eq!(UserLayer0::TYPE_NAME, "User");
+}
+
+#[test]
+fn max_size_of_bounded_types() {
+	use punybuf_common::PBType;
+	use ordering::{Channel, KeyPair, MaybeMeasured, Measurement};
+
+	let size_of = |value: &dyn Fn(&mut Vec<u8>)| {
+		let mut bytes = vec![];
+		value(&mut bytes);
+		Some(bytes.len())
+	};
+	assert_eq!(Measurement::MAX_SIZE, size_of(&|w| Measurement { value: 1.5 }.serialize(w).unwrap()));
+	assert_eq!(MaybeMeasured::MAX_SIZE, size_of(&|w| MaybeMeasured { value: Some(1.5) }.serialize(w).unwrap()));
+	assert_eq!(Channel::MAX_SIZE, size_of(&|w| Channel::Nightly(7).serialize(w).unwrap()));
+	// `Empty` still writes the length of its extensions
+	assert_eq!(empty_struct::Holder::MAX_SIZE, Some(4));
+
+	assert_eq!(fixed_fields::Sample::MAX_SIZE, None);
+	assert_eq!(ordering::Release::MAX_SIZE, None);
+	// the arguments of generic types are unknown
+	assert_eq!(KeyPair::<u32, u32>::MAX_SIZE, None);
+}
+
+#[test]
+fn min_size_is_the_smallest_value() {
+	use punybuf_common::{Bytes, PBType};
+	use min_size::{readSensorError, Either, Pair, Reading};
+
+	let smallest = Reading {
+		sensor: 1,
+		value: Either::Right(Pair { first: 2, second: 3 }),
+		calibrated: false,
+		offset: None,
+	};
+	let mut bytes = vec![];
+	smallest.serialize(&mut bytes).unwrap();
+	assert_eq!(Reading::MIN_SIZE, bytes.len());
+	assert!(Reading::deserialize(&mut &bytes[..Reading::MIN_SIZE - 1]).is_err());
+
+	// generic parameters contribute their own `MIN_SIZE`
+	assert_eq!(Pair::<u8, u32>::MIN_SIZE, 5);
+	assert_eq!(Either::<u32, Bytes>::MIN_SIZE, 2);
+	assert_eq!(Either::<u32, ()>::MIN_SIZE, 1);
+	assert_eq!(readSensorError::MIN_SIZE, 2);
+}
+
+#[test]
+fn derived_equality_and_hashing() {
+	use std::collections::HashSet;
+	use punybuf_common::PBType;
+	use derives::{Labeled, Palette, Point, Reading, Sample};
+
+	let palette = |dark| Palette {
+		colors: vec![Labeled { label: Cow::Borrowed("red"), value: 0xff0000 }],
+		dark,
+		accent: Some(Point { x: 1, y: 2 }),
+	};
+	let set = HashSet::from([palette(false), palette(true), palette(false)]);
+	assert_eq!(set.len(), 2);
+
+	// types with floats are only `PartialEq`
+	let sample = Sample::Measured(Reading { at: Point { x: 3, y: 4 }, celsius: 21.5 });
+	let mut bytes = vec![];
+	sample.serialize(&mut bytes).unwrap();
+	assert_eq!(Sample::deserialize(&mut &bytes[..]).unwrap(), sample);
+	assert_ne!(Sample::Empty, sample);
+}
+
+#[test]
+fn flags_default_to_unset() {
+	use punybuf_common::PBType;
+	use bitmap::Profile;
+
+	let profile = Profile::new(7, Cow::Borrowed("someone"))
+		.with_age(30)
+		.with_is_bot(true);
+	assert_eq!((profile.id, &*profile.username), (7, "someone"));
+	assert_eq!((profile.age, profile.is_bot), (Some(30), true));
+	assert!(profile.nickname.is_none() && profile.bio.is_none() && !profile.is_admin);
+
+	let mut bytes = vec![];
+	profile.serialize(&mut bytes).unwrap();
+	let decoded = Profile::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!((decoded.age, decoded.is_bot, decoded.score), (Some(30), true, None));
+}
+
+#[test]
+fn extension_flags_field_roundtrip() {
+	use punybuf_common::PBType;
+	use extension_flags_field::Settings;
+
+	let settings = Settings::new()
+		.with_dark_mode(true)
+		.with_font_size(12)
+		.with_nickname(Cow::Borrowed("ada"))
+		.with_pinned(true)
+		.with_bio(Cow::Borrowed("hi"))
+		.with_location(Cow::Borrowed("here"))
+		.with_verified(true);
+	let mut bytes = vec![];
+	settings.serialize(&mut bytes).unwrap();
+	// the flags, `font_size`, and then the nickname, `more` and its values after the EL
+	assert_eq!(bytes, [
+		0b1100_0101, 12, 13,
+		3, b'a', b'd', b'a',
+		0b111, 2, b'h', b'i', 4, b'h', b'e', b'r', b'e',
+	]);
+
+	let mut r = &bytes[..];
+	let decoded = Settings::deserialize(&mut r).unwrap();
+	assert!(r.is_empty());
+	assert!(decoded.dark_mode && decoded.pinned && decoded.verified);
+	assert_eq!(decoded.font_size, Some(12));
+	assert_eq!(decoded.nickname.as_deref(), Some("ada"));
+	assert_eq!((decoded.bio.as_deref(), decoded.location.as_deref()), (Some("hi"), Some("here")));
+
+	let mut again = vec![];
+	decoded.serialize(&mut again).unwrap();
+	assert_eq!(again, bytes);
+}
+
+#[test]
+fn debug_shows_flags_by_name() {
+	use extension_flags::New;
+
+	let user = New::new().with_name(Cow::Borrowed("someone")).with_verified(true);
+	// the integers the flags are encoded in aren't fields
+	assert_eq!(format!("{user:?}"), r#"New { name: Some("someone"), nickname: None, verified: true, bio: None }"#);
+}
+
+#[test]
+fn private_fields_have_getters() {
+	use punybuf_common::PBType;
+	use private_fields::{Labeled, Palette, Point};
+
+	let mut bytes = vec![];
+	Palette::new(vec![Labeled::new(Cow::Borrowed("red"), 0xff0000)])
+		.with_dark(true)
+		.with_accent(Point::new(1, 2))
+		.serialize(&mut bytes)
+		.unwrap();
+	let mut palette = Palette::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!(palette.colors()[0].label(), "red");
+	assert_eq!(*palette.colors()[0].value(), 0xff0000);
+	assert!(*palette.dark());
+	assert_eq!(*palette.accent().as_ref().unwrap().y(), 2);
+
+	palette.set_dark(false);
+	palette.set_accent(None);
+	assert!(!*palette.dark());
+	assert!(palette.accent().is_none());
+}
+
+#[test]
+fn inline_types_across_layers() {
+	use punybuf_common::PBType;
+	use inline_layers::{
+		Color, ColorLayer0, Settings, SettingsLayer0, SettingsLayer1, Status, StatusLayer0, StatusLayer1,
+		User, UserLayer0, UserLayer1,
+	};
+
+	// layer 1 only exists because `Color` changed, and uses the new `Color`
+	let user = UserLayer1 {
+		name: Cow::Borrowed("ada"),
+		status: StatusLayer1::Away(Color::Blue),
+		settings: SettingsLayer1 { theme: Color::Blue },
+	};
+	let mut bytes = vec![];
+	user.serialize(&mut bytes).unwrap();
+	let decoded = UserLayer1::deserialize(&mut &bytes[..]).unwrap();
+	assert!(matches!(decoded.status, StatusLayer1::Away(Color::Blue)));
+
+	let user = UserLayer0 {
+		name: Cow::Borrowed("ada"),
+		status: StatusLayer0::Away(ColorLayer0::Green),
+		settings: SettingsLayer0 { theme: ColorLayer0::Green },
+	};
+	let mut bytes = vec![];
+	user.serialize(&mut bytes).unwrap();
+	assert!(matches!(UserLayer0::deserialize(&mut &bytes[..]).unwrap().status, StatusLayer0::Away(ColorLayer0::Green)));
+
+	let user = User { name: Cow::Borrowed("ada"), status: Status::Busy, settings: Settings { theme: Color::Red } };
+	let mut bytes = vec![];
+	user.serialize(&mut bytes).unwrap();
+	assert!(matches!(User::deserialize(&mut &bytes[..]).unwrap().status, Status::Busy));
+}
+
+#[test]
+fn dyn_decoder_matches_generated_code() {
+	use punybuf::{DynDecoder, DynValue};
+	use punybuf_common::{PBCommand, PBType, UInt};
+	use commands::{Point, Shape, getShape, moveCursor};
+
+	let load = |fixture: &str| PunybufParser::parse_file(format!("test_files/codegen/{fixture}.pbd"))
+		.expect("failed to read file")
+		.expect("failed to parse file")
+		.resolve(true)
+		.expect("failed to resolve definition");
+	let string = |s: &str| DynValue::Optional(Some(Box::new(DynValue::String(s.to_string()))));
+
+	let definition = load("commands");
+	let decoder = DynDecoder::new(&definition);
+
+	let mut bytes = vec![];
+	getShape { id: UInt(300) }.serialize_self(&mut bytes).unwrap();
+	assert_eq!(
+		decoder.decode_command("getShape", &bytes).unwrap(),
+		DynValue::Struct(vec![("id".to_string(), DynValue::UInt(300))])
+	);
+
+	let point = DynValue::Struct(vec![
+		("x".to_string(), DynValue::I32(-1)),
+		("y".to_string(), DynValue::I32(2)),
+	]);
+	let mut bytes = vec![];
+	moveCursor(Point { x: -1, y: 2 }).serialize_self(&mut bytes).unwrap();
+	assert_eq!(decoder.decode_command("moveCursor", &bytes).unwrap(), point);
+
+	let mut bytes = vec![];
+	Shape::Dot(Point { x: -1, y: 2 }).serialize(&mut bytes).unwrap();
+	assert_eq!(
+		decoder.decode_type("Shape", &bytes).unwrap(),
+		DynValue::Enum { variant: "Dot".to_string(), value: Some(Box::new(point)) }
+	);
+	bytes.push(0);
+	assert_eq!(decoder.decode_type("Shape", &bytes).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+	assert_eq!(decoder.decode_command("nope", &[]).unwrap_err().kind(), std::io::ErrorKind::NotFound);
+
+	// flags, and extensions after the EL
+	let definition = load("extension-flags");
+	let decoder = DynDecoder::new(&definition);
+	let new = extension_flags::New {
+		name: Some(Cow::Borrowed("Ada")),
+		nickname: None,
+		verified: true,
+		bio: Some(Cow::Borrowed("hi")),
+	};
+	let mut bytes = vec![];
+	new.serialize(&mut bytes).unwrap();
+	assert_eq!(decoder.decode_type("New", &bytes).unwrap(), DynValue::Struct(vec![
+		("name".to_string(), string("Ada")),
+		("nickname".to_string(), DynValue::Optional(None)),
+		("verified".to_string(), DynValue::Bool(true)),
+		("bio".to_string(), string("hi")),
+	]));
+	// unknown extensions are skipped
+	assert_eq!(decoder.decode_type("Old", &bytes).unwrap(), DynValue::Struct(vec![
+		("name".to_string(), string("Ada")),
+		("nickname".to_string(), DynValue::Optional(None)),
+	]));
+}
+
+#[test]
+fn aliased_arrays_roundtrip() {
+	use punybuf_common::{Bytes, PBType};
+	use aliases::Group;
+
+	let group = Group {
+		ids: vec![1, 2],
+		names: vec![Cow::Borrowed("Ada")],
+		blobs: vec![Bytes(Cow::Borrowed(&[7]))],
+		nicknames: vec![],
+		motto: None,
+	};
+	let mut bytes = vec![];
+	group.serialize(&mut bytes).unwrap();
+	let decoded = Group::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!((decoded.ids, decoded.names), (group.ids, group.names));
+	assert_eq!(decoded.blobs[0].0, group.blobs[0].0);
+}
+
+#[tokio::test]
+async fn byte_order_overrides() {
+	use punybuf::{DynDecoder, DynValue};
+	use punybuf_common::PBType;
+
+	let header = byte_order::Header {
+		length: 0x01020304,
+		checksum: 0x01020304,
+		offset: -2,
+		version: 0x0506,
+		name: Cow::Borrowed("a"),
+	};
+	let mut bytes = vec![];
+	header.serialize(&mut bytes).unwrap();
+	assert_eq!(bytes, [
+		4, 3, 2, 1,
+		1, 2, 3, 4,
+		0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+		5, 6,
+		1, b'a',
+		0,
+	]);
+	let decoded = byte_order::Header::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!((decoded.length, decoded.checksum, decoded.offset), (header.length, header.checksum, header.offset));
+	let mut reused = byte_order::Header::deserialize(&mut &bytes[..]).unwrap();
+	reused.deserialize_into(&mut &bytes[..]).unwrap();
+	assert_eq!((reused.length, reused.offset), (header.length, header.offset));
+
+	let tokio = byte_order_tokio::Header {
+		length: header.length,
+		checksum: header.checksum,
+		offset: header.offset,
+		version: header.version,
+		name: header.name.clone(),
+	};
+	let mut tokio_bytes = vec![];
+	punybuf_common::tokio::PBType::serialize(&tokio, &mut tokio_bytes).await.unwrap();
+	assert_eq!(tokio_bytes, bytes);
+	let decoded = <byte_order_tokio::Header as punybuf_common::tokio::PBType>::deserialize_stream(&mut &bytes[..]).await.unwrap();
+	assert_eq!((decoded.length, decoded.offset), (header.length, header.offset));
+
+	let definition = PunybufParser::parse_file("test_files/codegen/byte-order.pbd")
+		.expect("failed to read file")
+		.expect("failed to parse file")
+		.resolve(true)
+		.expect("failed to resolve definition");
+	let decoded = DynDecoder::new(&definition).decode_type("Header", &bytes).unwrap();
+	let DynValue::Struct(fields) = decoded else { panic!("not a struct: {decoded:?}") };
+	assert_eq!(fields[0], ("length".to_string(), DynValue::U32(0x01020304)));
+	assert_eq!(fields[2], ("offset".to_string(), DynValue::I64(-2)));
+}
+
+#[tokio::test]
+async fn lazy_arrays_decode_items_on_demand() {
+	use punybuf_common::{LazyArray, PBType, UInt};
+
+	let points: Vec<_> = (0..100).map(|i| lazy_array::Point { x: i, y: -i }).collect();
+	let path = lazy_array::Path {
+		id: 7,
+		points: LazyArray::from_items(&points).unwrap(),
+		gaps: LazyArray::from_items(&[UInt(10), UInt(1000), UInt(100000)]).unwrap(),
+	};
+	let mut bytes = vec![];
+	path.serialize(&mut bytes).unwrap();
+	let mut as_vecs = vec![];
+	7u64.serialize(&mut as_vecs).unwrap();
+	points.serialize(&mut as_vecs).unwrap();
+	vec![UInt(10), UInt(1000), UInt(100000)].serialize(&mut as_vecs).unwrap();
+	0u8.serialize(&mut as_vecs).unwrap();
+	// the encoding is the same as with regular arrays
+	assert_eq!(bytes, as_vecs);
+
+	// from a slice, and from a reader that isn't one
+	let from_slice = lazy_array::Path::deserialize(&mut &bytes[..]).unwrap();
+	let from_stream = lazy_array::Path::deserialize_stream(&mut std::io::Cursor::new(&bytes)).unwrap();
+	for decoded in [&from_slice, &from_stream] {
+		assert_eq!(decoded.points.len(), 100);
+		let mut sum = 0;
+		for point in decoded.points_iter() {
+			let point = point.unwrap();
+			assert_eq!(point.x, -point.y);
+			sum += point.x;
+		}
+		assert_eq!(sum, 4950);
+		let gaps: Vec<_> = decoded.gaps_iter().map(Result::unwrap).collect();
+		assert_eq!(gaps, [10, 1000, 100000]);
+	}
+
+	let decoded = <lazy_array_tokio::Path as punybuf_common::tokio::PBType>::deserialize_stream(&mut &bytes[..]).await.unwrap();
+	assert_eq!(decoded.points_iter().nth(99).unwrap().unwrap().x, 99);
+	assert_eq!(decoded.gaps_iter().last().unwrap().unwrap(), 100000);
+
+	// a truncated item is an error, and then the iterator ends
+	let broken: LazyArray<lazy_array::Point> = LazyArray::from_encoded(2, vec![0; 12]);
+	let mut items = broken.iter();
+	assert!(items.next().unwrap().is_ok());
+	assert!(items.next().unwrap().is_err());
+	assert!(items.next().is_none());
+}
+
+#[tokio::test]
+async fn lazy_arrays_keep_extensions_of_items() {
+	use punybuf_common::PBType;
+
+	// `Point` isn't `@sealed`, so a newer version can send it with extensions
+	assert_eq!(<lazy_array::Point as PBType>::FIXED_SIZE, None);
+	let mut bytes = vec![];
+	7u64.serialize(&mut bytes).unwrap();
+	bytes.push(2);
+	bytes.extend([0, 0, 0, 1, 0, 0, 0, 2, 0]);
+	// one byte of extensions
+	bytes.extend([0, 0, 0, 3, 0, 0, 0, 4, 1, 0xab]);
+	// no gaps, and no extensions of the path
+	bytes.extend([0, 0]);
+
+	let from_slice = lazy_array::Path::deserialize(&mut &bytes[..]).unwrap();
+	let from_stream = lazy_array::Path::deserialize_stream(&mut std::io::Cursor::new(&bytes)).unwrap();
+	let from_tokio = <lazy_array_tokio::Path as punybuf_common::tokio::PBType>::deserialize_stream(&mut &bytes[..]).await.unwrap();
+	for points in [from_slice.points_iter(), from_stream.points_iter()] {
+		let points: Vec<_> = points.map(|p| p.map(|p| (p.x, p.y)).unwrap()).collect();
+		assert_eq!(points, [(1, 2), (3, 4)]);
+	}
+	let points: Vec<_> = from_tokio.points_iter().map(|p| p.map(|p| (p.x, p.y)).unwrap()).collect();
+	assert_eq!(points, [(1, 2), (3, 4)]);
+	assert_eq!(from_stream.gaps.len(), 0);
+}
+
+#[test]
+fn phantom_generics_arent_encoded() {
+	use punybuf_common::PBType;
+	use phantom::{Handle, Id, Session, User};
+
+	let mut bytes = vec![];
+	Id::<User>::new(7).serialize(&mut bytes).unwrap();
+	assert_eq!(bytes, 7u64.to_be_bytes());
+	assert_eq!(Id::<User>::MAX_SIZE, Some(8));
+
+	let session = Session { user: Id::new(7), handle: Handle::new(3).with_pinned(true) };
+	let mut bytes = vec![];
+	session.serialize(&mut bytes).unwrap();
+	// the ID, the slot, the flags and the lengths of the extensions
+	assert_eq!(bytes.len(), 8 + 4 + 1 + 1 + 1);
+	let decoded = Session::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!((decoded.user.value, decoded.handle.slot, decoded.handle.pinned), (7, 3, true));
+}
+
+#[test]
+fn recursive_lists_roundtrip() {
+	use punybuf_common::PBType;
+	use recursive::{FlagList, List};
+
+	let third = List { value: 3, next: None };
+	let second = List { value: 2, next: Some(Box::new(third)) };
+	let list = List { value: 1, next: Some(Box::new(second)) };
+	let mut bytes = vec![];
+	list.serialize(&mut bytes).unwrap();
+	// every node is its value, the `Optional` and the length of its extensions,
+	// which comes after the nodes inside it
+	assert_eq!(bytes, [
+		0, 0, 0, 1, 1,
+		0, 0, 0, 2, 1,
+		0, 0, 0, 3, 0, 0,
+		0,
+		0,
+	]);
+	for decoded in [List::deserialize(&mut &bytes[..]).unwrap(), List::deserialize_stream(&mut &bytes[..]).unwrap()] {
+		let second = decoded.next.unwrap();
+		let third = second.next.unwrap();
+		assert_eq!((decoded.value, second.value, third.value), (1, 2, 3));
+		assert!(third.next.is_none());
+	}
+
+	let list = FlagList::new(1).with_next(Box::new(FlagList::new(2).with_next(Box::new(FlagList::new(3)))));
+	let mut bytes = vec![];
+	list.serialize(&mut bytes).unwrap();
+	let decoded = FlagList::deserialize(&mut &bytes[..]).unwrap();
+	let third = decoded.next.as_ref().and_then(|second| second.next.as_ref()).unwrap();
+	assert_eq!((decoded.value, third.value), (1, 3));
+	assert!(third.next.is_none());
+}
+
+#[test]
+fn unknown_extensions_are_serialized_again() {
+	use std::borrow::Cow;
+	use punybuf_common::PBType;
+	use keep_extensions::{New, Old, Point};
+
+	let new = New::new(7)
+		.with_name(Cow::Borrowed("ann"))
+		.with_nickname(Cow::Borrowed("annie"))
+		.with_verified(true)
+		.with_bio(Cow::Borrowed("hi"));
+	let mut bytes = vec![];
+	new.serialize(&mut bytes).unwrap();
+
+	let old = Old::deserialize(&mut &bytes[..]).unwrap();
+	assert_eq!(old.nickname.as_deref(), Some("annie"));
+	// `verified` and `bio`, and the `bio` after `nickname` in the extensions
+	assert_eq!(old._unknown_flags, 0b1100);
+	assert_eq!(old._unknown_extensions, [2, b'h', b'i']);
+	let mut again = vec![];
+	old.serialize(&mut again).unwrap();
+	assert_eq!(again, bytes);
+
+	let mut reused = Old::new(0).with_name(Cow::Borrowed("bob"));
+	reused._unknown_extensions = vec![1, 2, 3];
+	reused.deserialize_into(&mut &bytes[..]).unwrap();
+	let mut again = vec![];
+	reused.serialize(&mut again).unwrap();
+	assert_eq!(again, bytes);
+
+	let decoded = New::deserialize(&mut &again[..]).unwrap();
+	assert!(decoded.verified);
+	assert_eq!(decoded.bio.as_deref(), Some("hi"));
+	assert!(decoded._unknown_extensions.is_empty());
+
+	// a newer `Point` with extensions of its own
+	let bytes = [0, 0, 0, 1, 0, 0, 0, 2, 3, 9, 9, 9];
+	let point = Point::deserialize_stream(&mut &bytes[..]).unwrap();
+	assert_eq!((point.x, point.y, &point._unknown_extensions[..]), (1, 2, &[9, 9, 9][..]));
+	let mut again = vec![];
+	point.serialize(&mut again).unwrap();
+	assert_eq!(again, bytes);
+}
+
+#[test]
+fn metrics_are_recorded_per_command() {
+	use punybuf_common::{PBCommand, PBCommandExt, UInt};
+	use commands_metrics::{Command, Point, getShape, moveCursor};
+
+	let definition = PunybufParser::parse_file("test_files/codegen/commands.pbd")
+		.expect("failed to read file")
+		.expect("failed to parse file")
+		.resolve(true)
+		.expect("failed to resolve definition");
+	for tokio in [false, true] {
+		let code = RustCodegen::new(tokio, true, &definition).metrics(true).codegen();
+		for command in ["moveCursor", "getShape"] {
+			for metric in ["punybuf_decode_total", "punybuf_encode_total"] {
+				let counter = format!(r#"counter!("{metric}", "command" => "{command}")"#);
+				assert!(code.contains(&counter), "`{counter}` is missing");
+			}
+		}
+	}
+	assert!(!RustCodegen::new(false, true, &definition).codegen().contains("metrics"));
+
+	// without a recorder, the metrics go nowhere, and the commands are encoded as usual
+	let mut bytes = vec![];
+	Command::moveCursor(moveCursor(Point { x: -1, y: 2 })).serialize(&mut bytes).unwrap();
+	let mut plain = vec![];
+	commands::Command::moveCursor(commands::moveCursor(commands::Point { x: -1, y: 2 })).serialize(&mut plain).unwrap();
+	assert_eq!(bytes, plain);
+	let Command::moveCursor(moveCursor(point)) = Command::deserialize_stream(&mut &bytes[..]).unwrap() else {
+		panic!("expected moveCursor");
+	};
+	assert_eq!((point.x, point.y), (-1, 2));
+
+	let mut bytes = vec![];
+	getShape { id: UInt(3) }.serialize_self(&mut bytes).unwrap();
+	assert_eq!(getShape::deserialize(&mut &bytes[..]).unwrap().id.0, 3);
+	assert!(getShape::deserialize(&mut &bytes[..1]).is_err());
+}
+
+#[test]
+fn void_without_common_is_unit() {
+	use punybuf_common::{PBCommand, PBCommandExt};
+
+	// `void.pbd` declares its own `Void` instead of including `common`
+	let _: <void::ping as PBCommandExt>::Return<'static> = ();
+	let command = void::Command::ping(void::ping(void::Ping));
+	assert!(command.is_void());
+	let mut bytes = vec![];
+	command.serialize_self(&mut bytes).unwrap();
+	assert_eq!(bytes, [0]);
+}
+
+#[test]
+fn default_variant_skips_newer_extensions() {
+	use punybuf_common::PBType;
+	use default_variant::Mood;
+
+	// a newer version added `@extension Confused: String` after `ConcernedAbout`
+	let mut bytes = vec![6];
+	String::from("why").serialize(&mut bytes).unwrap();
+	// whatever comes next has to be read from the right place
+	bytes.push(2);
+	let mut r = &bytes[..];
+	assert!(matches!(Mood::deserialize(&mut r).unwrap(), Mood::Neutral));
+	assert!(matches!(Mood::deserialize(&mut r).unwrap(), Mood::Sad));
+	assert!(r.is_empty());
+	let mut r = std::io::Cursor::new(&bytes);
+	assert!(matches!(Mood::deserialize_stream(&mut r).unwrap(), Mood::Neutral));
+	assert!(matches!(Mood::deserialize_stream(&mut r).unwrap(), Mood::Sad));
+	assert_eq!(r.position() as usize, bytes.len());
+
+	// known extensions are read as usual
+	let mut bytes = vec![];
+	Mood::ConcernedAbout("rain".into()).serialize(&mut bytes).unwrap();
+	Mood::Scared.serialize(&mut bytes).unwrap();
+	assert_eq!(bytes, [5, 5, 4, b'r', b'a', b'i', b'n', 4, 0]);
+	let mut r = &bytes[..];
+	assert!(matches!(Mood::deserialize(&mut r).unwrap(), Mood::ConcernedAbout(about) if about == "rain"));
+	assert!(matches!(Mood::deserialize(&mut r).unwrap(), Mood::Scared));
+	assert!(r.is_empty());
+}
+
+/// Decodes each line of the file in the first argument, `<codec> <hex>`, with the generated
+/// TypeScript code, and prints the values as JSON, followed by the hex of them encoded again
+const TYPESCRIPT_DECODER: &str = r#"import * as pb from "./generated.ts";
+import { readFileSync } from "node:fs";
+
+const hex = (bytes) => Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+for (const line of readFileSync(process.argv[2], "utf8").trim().split("\n")) {
+	const [codec, input] = line.split(" ");
+	const r = new pb.PbReader(Uint8Array.from(input.match(/../g) ?? [], (b) => parseInt(b, 16)));
+	const w = new pb.PbWriter();
+	const values = [];
+	while (r.remaining > 0) {
+		const value = pb[codec].deserialize(r);
+		values.push(value);
+		pb[codec].serialize(w, value);
+	}
+	console.log(JSON.stringify(values, (_, v) => typeof v === "bigint" ? `${v}n` : v) + " " + hex(w.finish()));
+}
+"#;
+
+/// Needs a `node` that can strip the types of TypeScript files, i.e. 22.6 or newer:
+/// `cargo test --test generated -- --ignored typescript`
+#[test]
+#[ignore]
+fn typescript_decodes_what_rust_encodes() {
+	use std::process::Command;
+	use punybuf_common::{PBType, SInt, UInt};
+	use typescript::{Mood, Numbers, Presence, Profile};
+
+	let node = |args: &[&str]| Command::new("node").arg("--experimental-strip-types").args(args).output();
+	assert!(
+		node(&["-e", ""]).is_ok_and(|out| out.status.success()),
+		"`node` isn't there or can't run TypeScript",
+	);
+	let encode = |values: &[&dyn Fn(&mut Vec<u8>)]| {
+		let mut bytes = vec![];
+		for value in values {
+			value(&mut bytes);
+		}
+		bytes
+	};
+	let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+
+	// the boundaries of each `UInt` size, up to the largest safe integer of JavaScript
+	let uints = [0, 127, 128, 16511, 16512, 2113663, 2113664, 68721590399, 68721590400, (1 << 53) - 1];
+	let numbers = Numbers {
+		uints: uints.iter().map(|&u| UInt(u)).collect(),
+		sints: [0, -1, 1, -64, 64, -(1 << 40)].iter().map(|&s| SInt(s)).collect(),
+		big: u64::MAX,
+		small: -2,
+	};
+	let profile = Profile::new(7, numbers.clone())
+		.with_admin(true)
+		.with_nickname("nick".into())
+		.with_bio("héllo".into())
+		.with_pinned(true);
+	let plain = Profile::new(8, Numbers { uints: vec![], sints: vec![], big: 0, small: 0 });
+	let nones = Presence::new(Some(()), Some(None)).with_nothing(()).with_maybe(None);
+	let somes = Presence::new(None, Some(Some(5))).with_maybe(Some(7));
+	let numbers_json = concat!(
+		r#"{"uints":[0,127,128,16511,16512,2113663,2113664,68721590399,68721590400,9007199254740991],"#,
+		r#""sints":[0,-1,1,-64,64,-1099511627776],"big":"18446744073709551615n","small":-2}"#,
+	);
+	let profiles_json = format!(
+		r#"[{{"id":7,"admin":true,"nickname":"nick","bio":"héllo","pinned":true,"numbers":{numbers_json}}},{}]"#,
+		r#"{"id":8,"admin":false,"pinned":false,"numbers":{"uints":[],"sints":[],"big":"0n","small":0}}"#,
+	);
+	// (codec, bytes, the values as JSON, the values encoded by TypeScript)
+	let cases = [
+		(
+			"Numbers",
+			encode(&[&|w| numbers.serialize(w).unwrap()]),
+			format!("[{numbers_json}]"),
+			None,
+		),
+		(
+			"Profile",
+			encode(&[&|w| profile.serialize(w).unwrap(), &|w| plain.serialize(w).unwrap()]),
+			profiles_json,
+			None,
+		),
+		(
+			// `Void` is `null`, and `Some(None)` is `{ value: undefined }`, which JSON leaves empty
+			"Presence",
+			encode(&[
+				&|w| nones.serialize(w).unwrap(),
+				&|w| somes.serialize(w).unwrap(),
+				&|w| Presence::new(None, None).serialize(w).unwrap(),
+			]),
+			r#"[{"empty":null,"nested":{},"nothing":null,"maybe":{}},{"nested":{"value":5},"maybe":{"value":7}},{}]"#.to_string(),
+			None,
+		),
+		(
+			"Mood",
+			encode(&[&|w| Mood::Scared.serialize(w).unwrap(), &|w| Mood::ConcernedAbout("rain".into()).serialize(w).unwrap()]),
+			r#"[{"kind":"Scared"},{"kind":"ConcernedAbout","value":"rain"}]"#.to_string(),
+			None,
+		),
+		(
+			// the older version decodes the newer extensions as its `@default` variant
+			"MoodLayer0",
+			encode(&[
+				&|w| Mood::ConcernedAbout("spiders".into()).serialize(w).unwrap(),
+				&|w| Mood::Scared.serialize(w).unwrap(),
+				&|w| Mood::Happy.serialize(w).unwrap(),
+			]),
+			r#"[{"kind":"Neutral"},{"kind":"Neutral"},{"kind":"Happy"}]"#.to_string(),
+			Some(vec![0, 0, 1]),
+		),
+	];
+
+	let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("typescript");
+	fs::create_dir_all(&dir).unwrap();
+	let definition = PunybufParser::parse_file("test_files/codegen/typescript.pbd")
+		.expect("failed to read file")
+		.expect("failed to parse file")
+		.resolve(true)
+		.expect("failed to resolve definition");
+	fs::write(dir.join("generated.ts"), TypeScriptCodegen::new(&definition, false).codegen().unwrap()).unwrap();
+	fs::write(dir.join("decode.ts"), TYPESCRIPT_DECODER).unwrap();
+	fs::write(dir.join("package.json"), r#"{ "type": "module" }"#).unwrap();
+	let input: String = cases.iter().map(|(codec, bytes, ..)| format!("{codec} {}\n", hex(bytes))).collect();
+	fs::write(dir.join("cases.txt"), input).unwrap();
+
+	let dir = dir.to_str().unwrap();
+	let output = node(&[&format!("{dir}/decode.ts"), &format!("{dir}/cases.txt")]).expect("failed to run node");
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	let stdout = String::from_utf8(output.stdout).unwrap();
+	let lines: Vec<_> = stdout.lines().collect();
+	assert_eq!(lines.len(), cases.len(), "{stdout}");
+	for ((codec, bytes, json, reencoded), line) in cases.iter().zip(lines) {
+		let (decoded, encoded) = line.rsplit_once(' ').unwrap();
+		assert_eq!(decoded, *json, "decoding `{codec}`");
+		assert_eq!(encoded, hex(reencoded.as_deref().unwrap_or(bytes)), "encoding `{codec}` again");
+	}
+}
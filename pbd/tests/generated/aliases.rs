@@ -0,0 +1,489 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "List",
+    "Names",
+    "Blobs",
+    "Group",
+];
+
+/// The IDs of all commands in this file, with their names
+pub const COMMAND_IDS_TO_NAMES: &[(u32, &str)] = &[
+    (1341337796, "getNames"),
+    (621188344, "getBlobs"),
+];
+
+/// This enum contains all possible commands in the RPC definition.
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub enum Command<'x> {
+    getNames(getNames),
+    getBlobs(getBlobs<'x>),
+}
+impl<'x> PBCommand for Command<'x> {
+    fn id(&self) -> u32 {
+        match self {
+            Self::getNames(_) => 1341337796,
+            Self::getBlobs(_) => 621188344,
+        }
+    }
+    fn is_void(&self) -> bool {
+        match self {
+            Self::getNames(_) => false,
+            Self::getBlobs(_) => false,
+        }
+    }
+    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Self::getNames(_) => getNames::ATTRIBUTES,
+            Self::getBlobs(_) => getBlobs::ATTRIBUTES,
+        }
+    }
+    fn required_capability(&self) -> Option<&'static str> {
+        match self {
+            Self::getNames(_) => getNames::REQUIRED_CAPABILITY,
+            Self::getBlobs(_) => getBlobs::REQUIRED_CAPABILITY,
+        }
+    }
+    fn serialize_self<R: io::Write>(&self, r: &mut R) -> Result<(), io::Error> {
+        match self {
+            Self::getNames(c) => c.serialize_self(r),
+            Self::getBlobs(c) => c.serialize_self(r),
+        }
+    }
+}
+
+impl<'x> Command<'x> {
+
+    /// Reads both the ID of the command and its value
+    pub fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let mut id = [0; 4];
+        r.read_exact(&mut id)?;
+        let id = u32::from_be_bytes(id);
+        Ok(match id {
+            1341337796 => Self::getNames(getNames::deserialize_stream(r)?),
+            621188344 => Self::getBlobs(getBlobs::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let (a, b) = r.split_at_checked(4)
+            .ok_or(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"))?;
+        let arr = a.try_into().unwrap(); // has to be 4 bytes
+        let id = u32::from_be_bytes(arr);
+        *r = b;
+        Ok(match id {
+            1341337796 => Self::getNames(getNames::deserialize_stream(r)?),
+            621188344 => Self::getBlobs(getBlobs::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command return types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandReturn<'x> {
+    getNames(Names<'x>),
+    getBlobs(Vec<Bytes<'x>>),
+}
+impl<'x> CommandReturn<'x> {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::getNames(c) => c.serialize(w)?,
+            Self::getBlobs(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_return_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            1341337796 => Self::getNames(Names::<'x>::deserialize_stream(r)?),
+            621188344 => Self::getBlobs(Vec::<Bytes::<'x>>::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_return<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            1341337796 => Self::getNames(Names::<'x>::deserialize(r)?),
+            621188344 => Self::getBlobs(Vec::<Bytes::<'x>>::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command error types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandError<'x> {
+    getNames(getNamesError<'x>),
+    getBlobs(getBlobsError<'x>),
+}
+impl<'x> CommandError<'x> {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::getNames(c) => c.serialize(w)?,
+            Self::getBlobs(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_error_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            1341337796 => Self::getNames(getNamesError::deserialize_stream(r)?),
+            621188344 => Self::getBlobs(getBlobsError::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_error<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            1341337796 => Self::getNames(getNamesError::deserialize(r)?),
+            621188344 => Self::getBlobs(getBlobsError::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct getNames(pub List<u32>);
+impl<'x> PBCommandExt<'x> for getNames {
+    type Error<'a> = getNamesError<'a>;
+    type Return<'a> = Names<'a>;
+    const ID: u32 = 1341337796;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self(List::<u32>::deserialize_stream(r)?))
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self(List::<u32>::deserialize(r)?))
+    }
+}
+impl PBCommand for getNames {
+    fn id(&self) -> u32 { 1341337796 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.0.serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum getNamesError<'x> {
+    UnexpectedError(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for getNamesError<'x> {
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for getNamesError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct getBlobs<'x>(pub Option<Bytes<'x>>);
+impl<'x> PBCommandExt<'x> for getBlobs<'x> {
+    type Error<'a> = getBlobsError<'a>;
+    type Return<'a> = Vec<Bytes<'a>>;
+    const ID: u32 = 621188344;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self(Option::<Bytes::<'x>>::deserialize_stream(r)?))
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self(Option::<Bytes::<'x>>::deserialize(r)?))
+    }
+}
+impl<'x> PBCommand for getBlobs<'x> {
+    fn id(&self) -> u32 { 621188344 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.0.serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum getBlobsError<'x> {
+    UnexpectedError(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for getBlobsError<'x> {
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for getBlobsError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub type List<T> = Vec<T>;
+pub type Names<'x> = Vec<Cow<'x, str>>;
+pub type Blobs<'x> = List<Bytes<'x>>;
+#[derive(Debug, Clone)]
+pub struct Group<'x> {
+    pub ids: List<u32>,
+    pub names: Names<'x>,
+    pub blobs: Blobs<'x>,
+    pub nicknames: Vec<Cow<'x, str>>,
+    pub motto: Option<Option<Cow<'x, str>>>, // Flag of `flags`
+}
+impl<'x> Group<'x> {
+    pub fn new(ids: List<u32>, names: Names<'x>, blobs: Blobs<'x>, nicknames: Vec<Cow<'x, str>>) -> Self {
+        Self { ids, names, blobs, nicknames, motto: None }
+    }
+    pub fn with_motto(mut self, motto: Option<Cow<'x, str>>) -> Self {
+        self.motto = Some(motto);
+        self
+    }
+}
+impl<'x> PBType<'x> for Group<'x> {
+    const TYPE_NAME: &'static str = "Group";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 6;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.ids.serialize(w)?;
+        self.names.serialize(w)?;
+        self.blobs.serialize(w)?;
+        self.nicknames.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(1 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.motto.is_some() { flags |= 1 << 0 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.motto {
+            v.serialize(w)?;
+        }
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_ids = List::<u32>::deserialize_stream(r).at_path("ids")?;
+        let field_names = Names::<'x>::deserialize_stream(r).at_path("names")?;
+        let field_blobs = Blobs::<'x>::deserialize_stream(r).at_path("blobs")?;
+        let field_nicknames = Vec::<Cow::<'x, str>>::deserialize_stream(r).at_path("nicknames")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_motto = if (field_flags & (1 << 0)) != 0 {
+            Some(Option::<Cow::<'x, str>>::deserialize_stream(r).at_path("motto")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            ids: field_ids,
+            names: field_names,
+            blobs: field_blobs,
+            nicknames: field_nicknames,
+            motto: flag_motto,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_ids = List::<u32>::deserialize(r).at_path("ids")?;
+        let field_names = Names::<'x>::deserialize(r).at_path("names")?;
+        let field_blobs = Blobs::<'x>::deserialize(r).at_path("blobs")?;
+        let field_nicknames = Vec::<Cow::<'x, str>>::deserialize(r).at_path("nicknames")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_motto = if (field_flags & (1 << 0)) != 0 {
+            Some(Option::<Cow::<'x, str>>::deserialize(r).at_path("motto")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            ids: field_ids,
+            names: field_names,
+            blobs: field_blobs,
+            nicknames: field_nicknames,
+            motto: flag_motto,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.ids.deserialize_into(r).at_path("ids")?;
+        self.names.deserialize_into(r).at_path("names")?;
+        self.blobs.deserialize_into(r).at_path("blobs")?;
+        self.nicknames.deserialize_into(r).at_path("nicknames")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        deserialize_option_into(&mut self.motto, (field_flags & (1 << 0)) != 0, r).at_path("motto")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
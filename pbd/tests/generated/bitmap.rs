@@ -0,0 +1,351 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Profile",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Profile<'x> {
+    pub nickname: Option<Cow<'x, str>>, // Flag of `0_bitmap`
+    pub age: Option<u8>, // Flag of `0_bitmap`
+    pub is_admin: bool, // Flag of `0_bitmap`
+    pub avatar: Option<Bytes<'x>>, // Flag of `0_bitmap`
+    pub location: Option<Cow<'x, str>>, // Flag of `0_bitmap`
+    pub score: Option<u64>, // Flag of `0_bitmap`
+    pub theme: Option<u8>, // Flag of `0_bitmap`
+    pub is_bot: bool, // Flag of `0_bitmap`
+    pub bio: Option<Cow<'x, str>>, // Flag of `0_bitmap`
+    pub id: u32,
+    pub username: Cow<'x, str>,
+}
+impl<'x> Profile<'x> {
+    pub fn new(id: u32, username: Cow<'x, str>) -> Self {
+        Self { nickname: None, age: None, is_admin: false, avatar: None, location: None, score: None, theme: None, is_bot: false, bio: None, id, username }
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+    pub fn with_age(mut self, age: u8) -> Self {
+        self.age = Some(age);
+        self
+    }
+    pub fn with_is_admin(mut self, is_admin: bool) -> Self {
+        self.is_admin = is_admin;
+        self
+    }
+    pub fn with_avatar(mut self, avatar: Bytes<'x>) -> Self {
+        self.avatar = Some(avatar);
+        self
+    }
+    pub fn with_location(mut self, location: Cow<'x, str>) -> Self {
+        self.location = Some(location);
+        self
+    }
+    pub fn with_score(mut self, score: u64) -> Self {
+        self.score = Some(score);
+        self
+    }
+    pub fn with_theme(mut self, theme: u8) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+    pub fn with_is_bot(mut self, is_bot: bool) -> Self {
+        self.is_bot = is_bot;
+        self
+    }
+    pub fn with_bio(mut self, bio: Cow<'x, str>) -> Self {
+        self.bio = Some(bio);
+        self
+    }
+}
+impl<'x> PBType<'x> for Profile<'x> {
+    const TYPE_NAME: &'static str = "Profile";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 8;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@bitmap", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u16 = 0.try_into().unwrap();
+        const _: () = assert!(9 <= 8 * core::mem::size_of::<u16>(), "not enough bits for the flags of `0_bitmap`");
+        if self.nickname.is_some() { flags |= 1 << 0 }
+        if self.age.is_some() { flags |= 1 << 1 }
+        if self.is_admin { flags |= 1 << 2 }
+        if self.avatar.is_some() { flags |= 1 << 3 }
+        if self.location.is_some() { flags |= 1 << 4 }
+        if self.score.is_some() { flags |= 1 << 5 }
+        if self.theme.is_some() { flags |= 1 << 6 }
+        if self.is_bot { flags |= 1 << 7 }
+        if self.bio.is_some() { flags |= 1 << 8 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.age {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.avatar {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.location {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.score {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.theme {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.bio {
+            v.serialize(w)?;
+        }
+        self.id.serialize(w)?;
+        self.username.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_0_bitmap = u16::deserialize_stream(r).at_path("0_bitmap")?;
+        let flag_nickname = if (field_0_bitmap & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("nickname")?)
+        } else { None };
+        let flag_age = if (field_0_bitmap & (1 << 1)) != 0 {
+            Some(u8::deserialize_stream(r).at_path("age")?)
+        } else { None };
+        let flag_is_admin = (field_0_bitmap & (1 << 2)) != 0;
+        let flag_avatar = if (field_0_bitmap & (1 << 3)) != 0 {
+            Some(Bytes::<'x>::deserialize_stream(r).at_path("avatar")?)
+        } else { None };
+        let flag_location = if (field_0_bitmap & (1 << 4)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("location")?)
+        } else { None };
+        let flag_score = if (field_0_bitmap & (1 << 5)) != 0 {
+            Some(u64::deserialize_stream(r).at_path("score")?)
+        } else { None };
+        let flag_theme = if (field_0_bitmap & (1 << 6)) != 0 {
+            Some(u8::deserialize_stream(r).at_path("theme")?)
+        } else { None };
+        let flag_is_bot = (field_0_bitmap & (1 << 7)) != 0;
+        let flag_bio = if (field_0_bitmap & (1 << 8)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("bio")?)
+        } else { None };
+        let field_id = u32::deserialize_stream(r).at_path("id")?;
+        let field_username = Cow::<'x, str>::deserialize_stream(r).at_path("username")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            nickname: flag_nickname,
+            age: flag_age,
+            is_admin: flag_is_admin,
+            avatar: flag_avatar,
+            location: flag_location,
+            score: flag_score,
+            theme: flag_theme,
+            is_bot: flag_is_bot,
+            bio: flag_bio,
+            id: field_id,
+            username: field_username,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_0_bitmap = u16::deserialize(r).at_path("0_bitmap")?;
+        let flag_nickname = if (field_0_bitmap & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("nickname")?)
+        } else { None };
+        let flag_age = if (field_0_bitmap & (1 << 1)) != 0 {
+            Some(u8::deserialize(r).at_path("age")?)
+        } else { None };
+        let flag_is_admin = (field_0_bitmap & (1 << 2)) != 0;
+        let flag_avatar = if (field_0_bitmap & (1 << 3)) != 0 {
+            Some(Bytes::<'x>::deserialize(r).at_path("avatar")?)
+        } else { None };
+        let flag_location = if (field_0_bitmap & (1 << 4)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("location")?)
+        } else { None };
+        let flag_score = if (field_0_bitmap & (1 << 5)) != 0 {
+            Some(u64::deserialize(r).at_path("score")?)
+        } else { None };
+        let flag_theme = if (field_0_bitmap & (1 << 6)) != 0 {
+            Some(u8::deserialize(r).at_path("theme")?)
+        } else { None };
+        let flag_is_bot = (field_0_bitmap & (1 << 7)) != 0;
+        let flag_bio = if (field_0_bitmap & (1 << 8)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("bio")?)
+        } else { None };
+        let field_id = u32::deserialize(r).at_path("id")?;
+        let field_username = Cow::<'x, str>::deserialize(r).at_path("username")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            nickname: flag_nickname,
+            age: flag_age,
+            is_admin: flag_is_admin,
+            avatar: flag_avatar,
+            location: flag_location,
+            score: flag_score,
+            theme: flag_theme,
+            is_bot: flag_is_bot,
+            bio: flag_bio,
+            id: field_id,
+            username: field_username,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        let field_0_bitmap = u16::deserialize_stream(r).at_path("0_bitmap")?;
+        deserialize_option_into(&mut self.nickname, (field_0_bitmap & (1 << 0)) != 0, r).at_path("nickname")?;
+        deserialize_option_into(&mut self.age, (field_0_bitmap & (1 << 1)) != 0, r).at_path("age")?;
+        self.is_admin = (field_0_bitmap & (1 << 2)) != 0;
+        deserialize_option_into(&mut self.avatar, (field_0_bitmap & (1 << 3)) != 0, r).at_path("avatar")?;
+        deserialize_option_into(&mut self.location, (field_0_bitmap & (1 << 4)) != 0, r).at_path("location")?;
+        deserialize_option_into(&mut self.score, (field_0_bitmap & (1 << 5)) != 0, r).at_path("score")?;
+        deserialize_option_into(&mut self.theme, (field_0_bitmap & (1 << 6)) != 0, r).at_path("theme")?;
+        self.is_bot = (field_0_bitmap & (1 << 7)) != 0;
+        deserialize_option_into(&mut self.bio, (field_0_bitmap & (1 << 8)) != 0, r).at_path("bio")?;
+        self.id.deserialize_into(r).at_path("id")?;
+        self.username.deserialize_into(r).at_path("username")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,239 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Header",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Header<'x> {
+    pub length: u32,
+    pub checksum: u32,
+    pub offset: i64,
+    pub version: u16,
+    pub name: Cow<'x, str>,
+}
+impl<'x> PBType<'x> for Header<'x> {
+    const TYPE_NAME: &'static str = "Header";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 20;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.length.to_le_bytes())?;
+        w.write_all(&self.checksum.to_be_bytes())?;
+        w.write_all(&self.offset.to_le_bytes())?;
+        self.version.serialize(w)?;
+        self.name.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_length = u32::from_le_bytes({
+            let mut bytes = [0; 4];
+            io::Read::read_exact(r, &mut bytes).at_path("length")?;
+            bytes
+        });
+        let field_checksum = u32::from_be_bytes({
+            let mut bytes = [0; 4];
+            io::Read::read_exact(r, &mut bytes).at_path("checksum")?;
+            bytes
+        });
+        let field_offset = i64::from_le_bytes({
+            let mut bytes = [0; 8];
+            io::Read::read_exact(r, &mut bytes).at_path("offset")?;
+            bytes
+        });
+        let field_version = u16::deserialize_stream(r).at_path("version")?;
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            length: field_length,
+            checksum: field_checksum,
+            offset: field_offset,
+            version: field_version,
+            name: field_name,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_length = u32::from_le_bytes({
+            let mut bytes = [0; 4];
+            io::Read::read_exact(r, &mut bytes).at_path("length")?;
+            bytes
+        });
+        let field_checksum = u32::from_be_bytes({
+            let mut bytes = [0; 4];
+            io::Read::read_exact(r, &mut bytes).at_path("checksum")?;
+            bytes
+        });
+        let field_offset = i64::from_le_bytes({
+            let mut bytes = [0; 8];
+            io::Read::read_exact(r, &mut bytes).at_path("offset")?;
+            bytes
+        });
+        let field_version = u16::deserialize(r).at_path("version")?;
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            length: field_length,
+            checksum: field_checksum,
+            offset: field_offset,
+            version: field_version,
+            name: field_name,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        let mut bytes = [0; 4];
+        io::Read::read_exact(r, &mut bytes).at_path("length")?;
+        self.length = u32::from_le_bytes(bytes);
+        let mut bytes = [0; 4];
+        io::Read::read_exact(r, &mut bytes).at_path("checksum")?;
+        self.checksum = u32::from_be_bytes(bytes);
+        let mut bytes = [0; 8];
+        io::Read::read_exact(r, &mut bytes).at_path("offset")?;
+        self.offset = i64::from_le_bytes(bytes);
+        self.version.deserialize_into(r).at_path("version")?;
+        self.name.deserialize_into(r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
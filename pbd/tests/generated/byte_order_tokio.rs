@@ -0,0 +1,204 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+// if you get an error: tokio's "io" feature must be enabled.
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+// if you get an error: punybuf_common's "tokio" feature must be enabled.
+use punybuf_common::tokio::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Header",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w).await?;
+        self.value.serialize(w).await?;
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).await.at_path("key")?;
+        let field_value = V::deserialize_stream(r).await.at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Header<'x> {
+    pub length: u32,
+    pub checksum: u32,
+    pub offset: i64,
+    pub version: u16,
+    pub name: Cow<'x, str>,
+}
+impl<'x> PBType<'x> for Header<'x> {
+    const TYPE_NAME: &'static str = "Header";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 20;
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        let mut fixed = [0u8; 18];
+        fixed[0..4].copy_from_slice(&self.length.to_le_bytes());
+        fixed[4..8].copy_from_slice(&self.checksum.to_be_bytes());
+        fixed[8..16].copy_from_slice(&self.offset.to_le_bytes());
+        fixed[16..18].copy_from_slice(&self.version.to_be_bytes());
+        w.write_all(&fixed).await?;
+        self.name.serialize(w).await?;
+        UInt(0).serialize(w).await?;
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let field_length = u32::from_le_bytes({
+            let mut bytes = [0; 4];
+            r.read_exact(&mut bytes).await.at_path("length")?;
+            bytes
+        });
+        let field_checksum = u32::from_be_bytes({
+            let mut bytes = [0; 4];
+            r.read_exact(&mut bytes).await.at_path("checksum")?;
+            bytes
+        });
+        let field_offset = i64::from_le_bytes({
+            let mut bytes = [0; 8];
+            r.read_exact(&mut bytes).await.at_path("offset")?;
+            bytes
+        });
+        let field_version = u16::deserialize_stream(r).await.at_path("version")?;
+        let field_name = Cow::<'x, str>::deserialize_stream(r).await.at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r).await?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            length: field_length,
+            checksum: field_checksum,
+            offset: field_offset,
+            version: field_version,
+            name: field_name,
+        })
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
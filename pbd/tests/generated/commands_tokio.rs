@@ -0,0 +1,482 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+// if you get an error: tokio's "io" feature must be enabled.
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+// if you get an error: punybuf_common's "tokio" feature must be enabled.
+use punybuf_common::tokio::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Point",
+    "Shape",
+];
+
+/// The IDs of all commands in this file, with their names
+pub const COMMAND_IDS_TO_NAMES: &[(u32, &str)] = &[
+    (1672831569, "moveCursor"),
+    (1104368450, "getShape"),
+];
+
+/// This enum contains all possible commands in the RPC definition.
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub enum Command {
+    moveCursor(moveCursor),
+    getShape(getShape),
+}
+impl PBCommand for Command {
+    fn id(&self) -> u32 {
+        match self {
+            Self::moveCursor(_) => 1672831569,
+            Self::getShape(_) => 1104368450,
+        }
+    }
+    fn is_void(&self) -> bool {
+        match self {
+            Self::moveCursor(_) => true,
+            Self::getShape(_) => false,
+        }
+    }
+    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Self::moveCursor(_) => moveCursor::ATTRIBUTES,
+            Self::getShape(_) => getShape::ATTRIBUTES,
+        }
+    }
+    fn required_capability(&self) -> Option<&'static str> {
+        match self {
+            Self::moveCursor(_) => moveCursor::REQUIRED_CAPABILITY,
+            Self::getShape(_) => getShape::REQUIRED_CAPABILITY,
+        }
+    }
+    async fn serialize_self<R: AsyncWriteExt + Unpin + Send>(&self, r: &mut R) -> Result<(), io::Error> {
+        match self {
+            Self::moveCursor(c) => c.serialize_self(r).await,
+            Self::getShape(c) => c.serialize_self(r).await,
+        }
+    }
+}
+
+impl Command {
+
+    /// Reads both the ID of the command and its value
+    pub async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let mut id = [0; 4];
+        r.read_exact(&mut id).await?;
+        let id = u32::from_be_bytes(id);
+        Ok(match id {
+            1672831569 => Self::moveCursor(moveCursor::deserialize_stream(r).await?),
+            1104368450 => Self::getShape(getShape::deserialize_stream(r).await?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command return types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandReturn {
+    moveCursor(Void),
+    getShape(Shape),
+}
+impl CommandReturn {
+    pub async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::moveCursor(c) => c.serialize(w).await?,
+            Self::getShape(c) => c.serialize(w).await?,
+        }
+        Ok(())
+    }
+    pub async fn deserialize_return_stream<R: AsyncReadExt + Unpin + Send>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            1672831569 => Self::moveCursor(Void::deserialize_stream(r).await?),
+            1104368450 => Self::getShape(Shape::deserialize_stream(r).await?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command error types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandError<'x> {
+    moveCursor(moveCursorError<'x>),
+    getShape(getShapeError<'x>),
+}
+impl<'x> CommandError<'x> {
+    pub async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::moveCursor(c) => c.serialize(w).await?,
+            Self::getShape(c) => c.serialize(w).await?,
+        }
+        Ok(())
+    }
+    pub async fn deserialize_error_stream<R: AsyncReadExt + Unpin + Send>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            1672831569 => Self::moveCursor(moveCursorError::deserialize_stream(r).await?),
+            1104368450 => Self::getShape(getShapeError::deserialize_stream(r).await?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// Moves the cursor
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct moveCursor(pub Point);
+impl<'x> PBCommandExt<'x> for moveCursor {
+    type Error<'a> = moveCursorError<'a>;
+    type Return<'a> = Void;
+    const ID: u32 = 1672831569;
+    const IS_VOID: bool = true;
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        Ok(Self(Point::deserialize_stream(r).await?))
+    }
+}
+impl PBCommand for moveCursor {
+    fn id(&self) -> u32 { 1672831569 }
+    fn is_void(&self) -> bool { true }
+    async fn serialize_self<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        self.0.serialize(w).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum moveCursorError<'x> {
+    UnexpectedError(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for moveCursorError<'x> {
+    const MIN_SIZE: usize = 1;
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w).await?; x.serialize(w).await?; }
+        }
+        Ok(())
+    }
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r).await?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r).await?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for moveCursorError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct getShape {
+    pub id: UInt,
+}
+impl<'x> PBCommandExt<'x> for getShape {
+    type Error<'a> = getShapeError<'a>;
+    type Return<'a> = Shape;
+    const ID: u32 = 1104368450;
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let field_id = UInt::deserialize_stream(r).await.at_path("id")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r).await?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+        })
+    }
+}
+impl PBCommand for getShape {
+    fn id(&self) -> u32 { 1104368450 }
+    async fn serialize_self<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w).await?;
+        UInt(0).serialize(w).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum getShapeError<'x> {
+    UnexpectedError(Cow<'x, str>),
+    NotFound,
+}
+impl<'x> PBType<'x> for getShapeError<'x> {
+    const MIN_SIZE: usize = 1;
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w).await?; x.serialize(w).await?; }
+            Self::NotFound => {
+                1u8.serialize(w).await?;
+            }
+        }
+        Ok(())
+    }
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r).await?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r).await?) }
+            1 => {
+                Self::NotFound
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for getShapeError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+/// Invokes commands over a stream, one at a time.
+///
+/// A command is written with its ID, and answered with a byte, `0` if it failed
+/// and `1` if it returned, followed by the error or the return value.
+/// Commands returning `Void` aren't answered.
+#[derive(Debug)]
+pub struct Client<RW> {
+    pub stream: RW,
+}
+impl<RW: AsyncReadExt + AsyncWriteExt + Unpin + Send> Client<RW> {
+    pub fn new(stream: RW) -> Self {
+        Self { stream }
+    }
+    pub fn into_inner(self) -> RW {
+        self.stream
+    }
+    /// Sends `cmd`, and waits for its error or return value.
+    /// `@stream` commands aren't supported, since they return any number of values.
+    pub async fn call<'x, C: PBCommand + PBCommandExt<'x>>(&mut self, cmd: C) -> io::Result<Result<C::Return<'static>, C::Error<'static>>> {
+        if C::IS_STREAM {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "`@stream` commands can't be called with `Client::call`"));
+        }
+        cmd.serialize(&mut self.stream).await?;
+        self.stream.flush().await?;
+        if C::IS_VOID {
+            // `Void` takes up no bytes
+            return Ok(Ok(cmd.deserialize_return_stream(&mut &[][..]).await?));
+        }
+        let is_error = match u8::deserialize_stream(&mut self.stream).await? {
+            0 => true,
+            1 => false,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "the response is neither an error nor a return value")),
+        };
+        Ok(cmd.deserialize_outcome(&mut self.stream, is_error).await?.into_result())
+    }
+}
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w).await?;
+        self.value.serialize(w).await?;
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).await.at_path("key")?;
+        let field_value = V::deserialize_stream(r).await.at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+impl<'x> PBType<'x> for Point {
+    const TYPE_NAME: &'static str = "Point";
+    const MAX_SIZE: Option<usize> = Some(9);
+    const MIN_SIZE: usize = 9;
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        let mut fixed = [0u8; 8];
+        fixed[0..4].copy_from_slice(&self.x.to_be_bytes());
+        fixed[4..8].copy_from_slice(&self.y.to_be_bytes());
+        w.write_all(&fixed).await?;
+        UInt(0).serialize(w).await?;
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let field_x = i32::deserialize_stream(r).await.at_path("x")?;
+        let field_y = i32::deserialize_stream(r).await.at_path("y")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r).await?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            x: field_x,
+            y: field_y,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Empty,
+    Dot(Point),
+}
+impl<'x> PBType<'x> for Shape {
+    const TYPE_NAME: &'static str = "Shape";
+    const MAX_SIZE: Option<usize> = Some(10);
+    const MIN_SIZE: usize = 1;
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Empty => {
+                0u8.serialize(w).await?;
+            }
+            Self::Dot(value) => {
+                1u8.serialize(w).await?;
+                value.serialize(w).await?;
+            }
+        }
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r).await?;
+        Ok(match discriminant {
+            0 => {
+                Self::Empty
+            }
+            1 => {
+                Self::Dot(Point::deserialize_stream(r).await.at_path("Dot")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
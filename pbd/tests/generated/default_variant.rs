@@ -0,0 +1,249 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Mood",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Mood<'x> {
+    Neutral,
+    Happy,
+    Sad,
+    ThinkingAbout(Cow<'x, str>),
+    Scared,
+    ConcernedAbout(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for Mood<'x> {
+    const TYPE_NAME: &'static str = "Mood";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Neutral => {
+                0u8.serialize(w)?;
+            }
+            Self::Happy => {
+                1u8.serialize(w)?;
+            }
+            Self::Sad => {
+                2u8.serialize(w)?;
+            }
+            Self::ThinkingAbout(value) => {
+                3u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+            Self::Scared => {
+                4u8.serialize(w)?;
+                // Skipped extension:
+                UInt(0).serialize(w)?;
+            }
+            Self::ConcernedAbout(value) => {
+                5u8.serialize(w)?;
+                // Extension:
+                let real_w = w;
+                let mut bytes = Bytes(Cow::Owned(Vec::new()));
+                let w = bytes.0.to_mut();
+                value.serialize(w)?;
+                bytes.serialize(real_w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                Self::Sad
+            }
+            3 => {
+                Self::ThinkingAbout(Cow::<'x, str>::deserialize_stream(r).at_path("ThinkingAbout")?)
+            }
+            4 => {
+                UInt::deserialize_stream(r)?;
+                Self::Scared
+            }
+            5 => {
+                UInt::deserialize_stream(r)?;
+                Self::ConcernedAbout(Cow::<'x, str>::deserialize_stream(r).at_path("ConcernedAbout")?)
+            }
+            _ => {
+                discard_extension(r)?;
+                Self::Neutral
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                Self::Sad
+            }
+            3 => {
+                Self::ThinkingAbout(Cow::<'x, str>::deserialize(r).at_path("ThinkingAbout")?)
+            }
+            4 => {
+                UInt::deserialize(r)?;
+                Self::Scared
+            }
+            5 => {
+                UInt::deserialize(r)?;
+                Self::ConcernedAbout(Cow::<'x, str>::deserialize(r).at_path("ConcernedAbout")?)
+            }
+            _ => {
+                discard_extension(r)?;
+                Self::Neutral
+            }
+        })
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,556 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Note",
+    "Reaction",
+];
+
+/// The IDs of all commands in this file, with their names
+pub const COMMAND_IDS_TO_NAMES: &[(u32, &str)] = &[
+    (2798979288, "getNote"),
+    (3476429980, "react"),
+];
+
+/// This enum contains all possible commands in the RPC definition.
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub enum Command<'x> {
+    getNote(getNote),
+    #[cfg(all())]
+    react(react<'x>),
+    #[cfg(not(all()))]
+    #[doc(hidden)]
+    __Experimental(core::marker::PhantomData<&'x ()>, core::convert::Infallible),
+}
+impl<'x> PBCommand for Command<'x> {
+    fn id(&self) -> u32 {
+        match self {
+            Self::getNote(_) => 2798979288,
+            #[cfg(all())]
+            Self::react(_) => 3476429980,
+            #[cfg(not(all()))]
+            Self::__Experimental(..) => unreachable!(),
+        }
+    }
+    fn is_void(&self) -> bool {
+        match self {
+            Self::getNote(_) => false,
+            #[cfg(all())]
+            Self::react(_) => false,
+            #[cfg(not(all()))]
+            Self::__Experimental(..) => unreachable!(),
+        }
+    }
+    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Self::getNote(_) => getNote::ATTRIBUTES,
+            #[cfg(all())]
+            Self::react(_) => react::ATTRIBUTES,
+            #[cfg(not(all()))]
+            Self::__Experimental(..) => unreachable!(),
+        }
+    }
+    fn required_capability(&self) -> Option<&'static str> {
+        match self {
+            Self::getNote(_) => getNote::REQUIRED_CAPABILITY,
+            #[cfg(all())]
+            Self::react(_) => react::REQUIRED_CAPABILITY,
+            #[cfg(not(all()))]
+            Self::__Experimental(..) => unreachable!(),
+        }
+    }
+    fn serialize_self<R: io::Write>(&self, r: &mut R) -> Result<(), io::Error> {
+        match self {
+            Self::getNote(c) => c.serialize_self(r),
+            #[cfg(all())]
+            Self::react(c) => c.serialize_self(r),
+            #[cfg(not(all()))]
+            Self::__Experimental(..) => unreachable!(),
+        }
+    }
+}
+
+impl<'x> Command<'x> {
+
+    /// Reads both the ID of the command and its value
+    pub fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let mut id = [0; 4];
+        r.read_exact(&mut id)?;
+        let id = u32::from_be_bytes(id);
+        Ok(match id {
+            2798979288 => Self::getNote(getNote::deserialize_stream(r)?),
+            #[cfg(all())]
+            3476429980 => Self::react(react::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let (a, b) = r.split_at_checked(4)
+            .ok_or(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"))?;
+        let arr = a.try_into().unwrap(); // has to be 4 bytes
+        let id = u32::from_be_bytes(arr);
+        *r = b;
+        Ok(match id {
+            2798979288 => Self::getNote(getNote::deserialize_stream(r)?),
+            #[cfg(all())]
+            3476429980 => Self::react(react::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command return types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandReturn<'x> {
+    getNote(Note<'x>),
+    #[cfg(all())]
+    react(Note<'x>),
+}
+impl<'x> CommandReturn<'x> {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::getNote(c) => c.serialize(w)?,
+            #[cfg(all())]
+            Self::react(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_return_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            2798979288 => Self::getNote(Note::<'x>::deserialize_stream(r)?),
+            #[cfg(all())]
+            3476429980 => Self::react(Note::<'x>::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_return<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            2798979288 => Self::getNote(Note::<'x>::deserialize(r)?),
+            #[cfg(all())]
+            3476429980 => Self::react(Note::<'x>::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command error types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandError<'x> {
+    getNote(getNoteError<'x>),
+    #[cfg(all())]
+    react(reactError<'x>),
+}
+impl<'x> CommandError<'x> {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::getNote(c) => c.serialize(w)?,
+            #[cfg(all())]
+            Self::react(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_error_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            2798979288 => Self::getNote(getNoteError::deserialize_stream(r)?),
+            #[cfg(all())]
+            3476429980 => Self::react(reactError::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_error<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            2798979288 => Self::getNote(getNoteError::deserialize(r)?),
+            #[cfg(all())]
+            3476429980 => Self::react(reactError::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct getNote {
+    pub id: UInt,
+}
+impl<'x> PBCommandExt<'x> for getNote {
+    type Error<'a> = getNoteError<'a>;
+    type Return<'a> = Note<'a>;
+    const ID: u32 = 2798979288;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_id = UInt::deserialize_stream(r).at_path("id")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_id = UInt::deserialize(r).at_path("id")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+        })
+    }
+}
+impl PBCommand for getNote {
+    fn id(&self) -> u32 { 2798979288 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum getNoteError<'x> {
+    UnexpectedError(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for getNoteError<'x> {
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for getNoteError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+#[cfg(all())]
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct react<'x> {
+    pub note: UInt,
+    pub reaction: Reaction<'x>,
+}
+#[cfg(all())]
+impl<'x> PBCommandExt<'x> for react<'x> {
+    type Error<'a> = reactError<'a>;
+    type Return<'a> = Note<'a>;
+    const ID: u32 = 3476429980;
+    const ATTRIBUTES: &'static [(&'static str, Option<&'static str>)] = &[
+        ("@experimental", None),
+    ];
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_note = UInt::deserialize_stream(r).at_path("note")?;
+        let field_reaction = Reaction::<'x>::deserialize_stream(r).at_path("reaction")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            note: field_note,
+            reaction: field_reaction,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_note = UInt::deserialize(r).at_path("note")?;
+        let field_reaction = Reaction::<'x>::deserialize(r).at_path("reaction")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            note: field_note,
+            reaction: field_reaction,
+        })
+    }
+}
+#[cfg(all())]
+impl<'x> PBCommand for react<'x> {
+    fn id(&self) -> u32 { 3476429980 }
+    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] { 
+        Self::ATTRIBUTES
+    }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.note.serialize(w)?;
+        self.reaction.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+}
+
+#[cfg(all())]
+#[derive(Debug, Clone)]
+pub enum reactError<'x> {
+    UnexpectedError(Cow<'x, str>),
+}
+#[cfg(all())]
+impl<'x> PBType<'x> for reactError<'x> {
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+#[cfg(all())]
+impl<'x> From<io::Error> for reactError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Note<'x> {
+    pub text: Cow<'x, str>,
+}
+impl<'x> PBType<'x> for Note<'x> {
+    const TYPE_NAME: &'static str = "Note";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.text.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_text = Cow::<'x, str>::deserialize_stream(r).at_path("text")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            text: field_text,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_text = Cow::<'x, str>::deserialize(r).at_path("text")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            text: field_text,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.text.deserialize_into(r).at_path("text")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+/// Might be replaced by something more general
+#[cfg(all())]
+#[derive(Debug, Clone)]
+pub struct Reaction<'x> {
+    pub emoji: Cow<'x, str>,
+    pub count: UInt,
+}
+#[cfg(all())]
+impl<'x> PBType<'x> for Reaction<'x> {
+    const TYPE_NAME: &'static str = "Reaction";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 3;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@experimental", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.emoji.serialize(w)?;
+        self.count.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_emoji = Cow::<'x, str>::deserialize_stream(r).at_path("emoji")?;
+        let field_count = UInt::deserialize_stream(r).at_path("count")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            emoji: field_emoji,
+            count: field_count,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_emoji = Cow::<'x, str>::deserialize(r).at_path("emoji")?;
+        let field_count = UInt::deserialize(r).at_path("count")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            emoji: field_emoji,
+            count: field_count,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.emoji.deserialize_into(r).at_path("emoji")?;
+        self.count.deserialize_into(r).at_path("count")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
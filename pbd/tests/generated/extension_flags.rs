@@ -0,0 +1,336 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Old",
+    "New",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Old<'x> {
+    pub name: Option<Cow<'x, str>>, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+}
+impl<'x> Old<'x> {
+    pub fn new() -> Self {
+        Self { name: None, nickname: None }
+    }
+    pub fn with_name(mut self, name: Cow<'x, str>) -> Self {
+        self.name = Some(name);
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+}
+impl<'x> PBType<'x> for Old<'x> {
+    const TYPE_NAME: &'static str = "Old";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.name.is_some() { flags |= 1 << 0 }
+        if self.nickname.is_some() { flags |= 1 << 1 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.name {
+            v.serialize(w)?;
+        }
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        Ok(Self {
+            name: flag_name,
+            nickname: flag_nickname,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        Ok(Self {
+            name: flag_name,
+            nickname: flag_nickname,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        deserialize_option_into(&mut self.name, (field_flags & (1 << 0)) != 0, r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 1)) != 0, _extension_reader).at_path("nickname")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct New<'x> {
+    pub name: Option<Cow<'x, str>>, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+    pub verified: bool, // Flag of `flags`
+    pub bio: Option<Cow<'x, str>>, // Flag of `flags`
+}
+impl<'x> New<'x> {
+    pub fn new() -> Self {
+        Self { name: None, nickname: None, verified: false, bio: None }
+    }
+    pub fn with_name(mut self, name: Cow<'x, str>) -> Self {
+        self.name = Some(name);
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
+    pub fn with_bio(mut self, bio: Cow<'x, str>) -> Self {
+        self.bio = Some(bio);
+        self
+    }
+}
+impl<'x> PBType<'x> for New<'x> {
+    const TYPE_NAME: &'static str = "New";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(4 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.name.is_some() { flags |= 1 << 0 }
+        if self.nickname.is_some() { flags |= 1 << 1 }
+        if self.verified { flags |= 1 << 2 }
+        if self.bio.is_some() { flags |= 1 << 3 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.name {
+            v.serialize(w)?;
+        }
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.bio {
+            v.serialize(w)?;
+        }
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        let flag_verified = (field_flags & (1 << 2)) != 0;
+        let flag_bio = if (field_flags & (1 << 3)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        Ok(Self {
+            name: flag_name,
+            nickname: flag_nickname,
+            verified: flag_verified,
+            bio: flag_bio,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        let flag_verified = (field_flags & (1 << 2)) != 0;
+        let flag_bio = if (field_flags & (1 << 3)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        Ok(Self {
+            name: flag_name,
+            nickname: flag_nickname,
+            verified: flag_verified,
+            bio: flag_bio,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        deserialize_option_into(&mut self.name, (field_flags & (1 << 0)) != 0, r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 1)) != 0, _extension_reader).at_path("nickname")?;
+        self.verified = (field_flags & (1 << 2)) != 0;
+        deserialize_option_into(&mut self.bio, (field_flags & (1 << 3)) != 0, _extension_reader).at_path("bio")?;
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,407 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Settings",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings<'x> {
+    pub dark_mode: bool, // Flag of `flags`
+    pub compact: bool, // Flag of `flags`
+    pub font_size: Option<u8>, // Flag of `flags`
+    pub accent: Option<u32>, // Flag of `flags`
+    pub muted: bool, // Flag of `flags`
+    pub beta: bool, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+    pub pinned: bool, // Flag of `flags`
+    pub bio: Option<Cow<'x, str>>, // Flag of `more`
+    pub location: Option<Cow<'x, str>>, // Flag of `more`
+    pub verified: bool, // Flag of `more`
+    pub a: bool, // Flag of `more`
+    pub b: bool, // Flag of `more`
+    pub c: bool, // Flag of `more`
+    pub d: bool, // Flag of `more`
+    pub e: bool, // Flag of `more`
+}
+impl<'x> Settings<'x> {
+    pub fn new() -> Self {
+        Self { dark_mode: false, compact: false, font_size: None, accent: None, muted: false, beta: false, nickname: None, pinned: false, bio: None, location: None, verified: false, a: false, b: false, c: false, d: false, e: false }
+    }
+    pub fn with_dark_mode(mut self, dark_mode: bool) -> Self {
+        self.dark_mode = dark_mode;
+        self
+    }
+    pub fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+    pub fn with_font_size(mut self, font_size: u8) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+    pub fn with_accent(mut self, accent: u32) -> Self {
+        self.accent = Some(accent);
+        self
+    }
+    pub fn with_muted(mut self, muted: bool) -> Self {
+        self.muted = muted;
+        self
+    }
+    pub fn with_beta(mut self, beta: bool) -> Self {
+        self.beta = beta;
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+    pub fn with_pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+    pub fn with_bio(mut self, bio: Cow<'x, str>) -> Self {
+        self.bio = Some(bio);
+        self
+    }
+    pub fn with_location(mut self, location: Cow<'x, str>) -> Self {
+        self.location = Some(location);
+        self
+    }
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
+    pub fn with_a(mut self, a: bool) -> Self {
+        self.a = a;
+        self
+    }
+    pub fn with_b(mut self, b: bool) -> Self {
+        self.b = b;
+        self
+    }
+    pub fn with_c(mut self, c: bool) -> Self {
+        self.c = c;
+        self
+    }
+    pub fn with_d(mut self, d: bool) -> Self {
+        self.d = d;
+        self
+    }
+    pub fn with_e(mut self, e: bool) -> Self {
+        self.e = e;
+        self
+    }
+}
+impl<'x> PBType<'x> for Settings<'x> {
+    const TYPE_NAME: &'static str = "Settings";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(8 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.dark_mode { flags |= 1 << 0 }
+        if self.compact { flags |= 1 << 1 }
+        if self.font_size.is_some() { flags |= 1 << 2 }
+        if self.accent.is_some() { flags |= 1 << 3 }
+        if self.muted { flags |= 1 << 4 }
+        if self.beta { flags |= 1 << 5 }
+        if self.nickname.is_some() { flags |= 1 << 6 }
+        if self.pinned { flags |= 1 << 7 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.font_size {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.accent {
+            v.serialize(w)?;
+        }
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(8 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `more`");
+        if self.bio.is_some() { flags |= 1 << 0 }
+        if self.location.is_some() { flags |= 1 << 1 }
+        if self.verified { flags |= 1 << 2 }
+        if self.a { flags |= 1 << 3 }
+        if self.b { flags |= 1 << 4 }
+        if self.c { flags |= 1 << 5 }
+        if self.d { flags |= 1 << 6 }
+        if self.e { flags |= 1 << 7 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.bio {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.location {
+            v.serialize(w)?;
+        }
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_dark_mode = (field_flags & (1 << 0)) != 0;
+        let flag_compact = (field_flags & (1 << 1)) != 0;
+        let flag_font_size = if (field_flags & (1 << 2)) != 0 {
+            Some(u8::deserialize_stream(r).at_path("font_size")?)
+        } else { None };
+        let flag_accent = if (field_flags & (1 << 3)) != 0 {
+            Some(u32::deserialize_stream(r).at_path("accent")?)
+        } else { None };
+        let flag_muted = (field_flags & (1 << 4)) != 0;
+        let flag_beta = (field_flags & (1 << 5)) != 0;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 6)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        let flag_pinned = (field_flags & (1 << 7)) != 0;
+        let field_more = u8::deserialize_stream(_extension_reader).at_path("more")?;
+        let flag_bio = if (field_more & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        let flag_location = if (field_more & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("location")?)
+        } else { None };
+        let flag_verified = (field_more & (1 << 2)) != 0;
+        let flag_a = (field_more & (1 << 3)) != 0;
+        let flag_b = (field_more & (1 << 4)) != 0;
+        let flag_c = (field_more & (1 << 5)) != 0;
+        let flag_d = (field_more & (1 << 6)) != 0;
+        let flag_e = (field_more & (1 << 7)) != 0;
+        Ok(Self {
+            dark_mode: flag_dark_mode,
+            compact: flag_compact,
+            font_size: flag_font_size,
+            accent: flag_accent,
+            muted: flag_muted,
+            beta: flag_beta,
+            nickname: flag_nickname,
+            pinned: flag_pinned,
+            bio: flag_bio,
+            location: flag_location,
+            verified: flag_verified,
+            a: flag_a,
+            b: flag_b,
+            c: flag_c,
+            d: flag_d,
+            e: flag_e,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_dark_mode = (field_flags & (1 << 0)) != 0;
+        let flag_compact = (field_flags & (1 << 1)) != 0;
+        let flag_font_size = if (field_flags & (1 << 2)) != 0 {
+            Some(u8::deserialize(r).at_path("font_size")?)
+        } else { None };
+        let flag_accent = if (field_flags & (1 << 3)) != 0 {
+            Some(u32::deserialize(r).at_path("accent")?)
+        } else { None };
+        let flag_muted = (field_flags & (1 << 4)) != 0;
+        let flag_beta = (field_flags & (1 << 5)) != 0;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 6)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        let flag_pinned = (field_flags & (1 << 7)) != 0;
+        let field_more = u8::deserialize_stream(_extension_reader).at_path("more")?;
+        let flag_bio = if (field_more & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        let flag_location = if (field_more & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("location")?)
+        } else { None };
+        let flag_verified = (field_more & (1 << 2)) != 0;
+        let flag_a = (field_more & (1 << 3)) != 0;
+        let flag_b = (field_more & (1 << 4)) != 0;
+        let flag_c = (field_more & (1 << 5)) != 0;
+        let flag_d = (field_more & (1 << 6)) != 0;
+        let flag_e = (field_more & (1 << 7)) != 0;
+        Ok(Self {
+            dark_mode: flag_dark_mode,
+            compact: flag_compact,
+            font_size: flag_font_size,
+            accent: flag_accent,
+            muted: flag_muted,
+            beta: flag_beta,
+            nickname: flag_nickname,
+            pinned: flag_pinned,
+            bio: flag_bio,
+            location: flag_location,
+            verified: flag_verified,
+            a: flag_a,
+            b: flag_b,
+            c: flag_c,
+            d: flag_d,
+            e: flag_e,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.dark_mode = (field_flags & (1 << 0)) != 0;
+        self.compact = (field_flags & (1 << 1)) != 0;
+        deserialize_option_into(&mut self.font_size, (field_flags & (1 << 2)) != 0, r).at_path("font_size")?;
+        deserialize_option_into(&mut self.accent, (field_flags & (1 << 3)) != 0, r).at_path("accent")?;
+        self.muted = (field_flags & (1 << 4)) != 0;
+        self.beta = (field_flags & (1 << 5)) != 0;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 6)) != 0, _extension_reader).at_path("nickname")?;
+        self.pinned = (field_flags & (1 << 7)) != 0;
+        let field_more = u8::deserialize_stream(_extension_reader).at_path("more")?;
+        deserialize_option_into(&mut self.bio, (field_more & (1 << 0)) != 0, _extension_reader).at_path("bio")?;
+        deserialize_option_into(&mut self.location, (field_more & (1 << 1)) != 0, _extension_reader).at_path("location")?;
+        self.verified = (field_more & (1 << 2)) != 0;
+        self.a = (field_more & (1 << 3)) != 0;
+        self.b = (field_more & (1 << 4)) != 0;
+        self.c = (field_more & (1 << 5)) != 0;
+        self.d = (field_more & (1 << 6)) != 0;
+        self.e = (field_more & (1 << 7)) != 0;
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,432 @@
+///! This file was automatically generated by Punybuf.
+///! It contains both the synchronous and the tokio code.
+
+pub mod sync {
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Sample",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sample<'x> {
+    pub id: u64,
+    pub kind: u8,
+    pub x: i32,
+    pub y: f64,
+    pub port: u16,
+    pub name: Cow<'x, str>,
+    pub count: u32,
+}
+impl<'x> PBType<'x> for Sample<'x> {
+    const TYPE_NAME: &'static str = "Sample";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 29;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w)?;
+        self.kind.serialize(w)?;
+        self.x.serialize(w)?;
+        self.y.serialize(w)?;
+        self.port.serialize(w)?;
+        self.name.serialize(w)?;
+        self.count.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_id = u64::deserialize_stream(r).at_path("id")?;
+        let field_kind = u8::deserialize_stream(r).at_path("kind")?;
+        let field_x = i32::deserialize_stream(r).at_path("x")?;
+        let field_y = f64::deserialize_stream(r).at_path("y")?;
+        let field_port = u16::deserialize_stream(r).at_path("port")?;
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let field_count = u32::deserialize_stream(r).at_path("count")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+            kind: field_kind,
+            x: field_x,
+            y: field_y,
+            port: field_port,
+            name: field_name,
+            count: field_count,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_id = u64::deserialize(r).at_path("id")?;
+        let field_kind = u8::deserialize(r).at_path("kind")?;
+        let field_x = i32::deserialize(r).at_path("x")?;
+        let field_y = f64::deserialize(r).at_path("y")?;
+        let field_port = u16::deserialize(r).at_path("port")?;
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let field_count = u32::deserialize(r).at_path("count")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+            kind: field_kind,
+            x: field_x,
+            y: field_y,
+            port: field_port,
+            name: field_name,
+            count: field_count,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.id.deserialize_into(r).at_path("id")?;
+        self.kind.deserialize_into(r).at_path("kind")?;
+        self.x.deserialize_into(r).at_path("x")?;
+        self.y.deserialize_into(r).at_path("y")?;
+        self.port.deserialize_into(r).at_path("port")?;
+        self.name.deserialize_into(r).at_path("name")?;
+        self.count.deserialize_into(r).at_path("count")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
+} // mod sync
+
+pub mod r#async {
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+// if you get an error: tokio's "io" feature must be enabled.
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+// if you get an error: punybuf_common's "tokio" feature must be enabled.
+use punybuf_common::tokio::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Sample",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w).await?;
+        self.value.serialize(w).await?;
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).await.at_path("key")?;
+        let field_value = V::deserialize_stream(r).await.at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sample<'x> {
+    pub id: u64,
+    pub kind: u8,
+    pub x: i32,
+    pub y: f64,
+    pub port: u16,
+    pub name: Cow<'x, str>,
+    pub count: u32,
+}
+impl<'x> PBType<'x> for Sample<'x> {
+    const TYPE_NAME: &'static str = "Sample";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 29;
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+        let mut fixed = [0u8; 23];
+        fixed[0..8].copy_from_slice(&self.id.to_be_bytes());
+        fixed[8..9].copy_from_slice(&self.kind.to_be_bytes());
+        fixed[9..13].copy_from_slice(&self.x.to_be_bytes());
+        fixed[13..21].copy_from_slice(&self.y.to_be_bytes());
+        fixed[21..23].copy_from_slice(&self.port.to_be_bytes());
+        w.write_all(&fixed).await?;
+        self.name.serialize(w).await?;
+        self.count.serialize(w).await?;
+        UInt(0).serialize(w).await?;
+        Ok(())
+    }
+    // If you get an compile time error here saying
+    // "cycle detected when computing type of...,"
+    // that's because when using async, currently
+    // no cyclic types are supported at all. Sorry!
+    async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+        let field_id = u64::deserialize_stream(r).await.at_path("id")?;
+        let field_kind = u8::deserialize_stream(r).await.at_path("kind")?;
+        let field_x = i32::deserialize_stream(r).await.at_path("x")?;
+        let field_y = f64::deserialize_stream(r).await.at_path("y")?;
+        let field_port = u16::deserialize_stream(r).await.at_path("port")?;
+        let field_name = Cow::<'x, str>::deserialize_stream(r).await.at_path("name")?;
+        let field_count = u32::deserialize_stream(r).await.at_path("count")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r).await?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+            kind: field_kind,
+            x: field_x,
+            y: field_y,
+            port: field_port,
+            name: field_name,
+            count: field_count,
+        })
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
+} // mod r#async
+
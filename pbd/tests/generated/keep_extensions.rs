@@ -0,0 +1,566 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Old",
+    "New",
+    "Point",
+    "Size",
+    "Settings",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Old<'x> {
+    pub id: u32,
+    pub name: Option<Cow<'x, str>>, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+    pub _unknown_flags: u8, // Unknown flags of `flags`
+    pub _unknown_extensions: Vec<u8>,
+}
+impl<'x> Old<'x> {
+    pub fn new(id: u32) -> Self {
+        Self { id, name: None, nickname: None, _unknown_flags: Default::default(), _unknown_extensions: Vec::new() }
+    }
+    pub fn with_name(mut self, name: Cow<'x, str>) -> Self {
+        self.name = Some(name);
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+}
+impl<'x> PBType<'x> for Old<'x> {
+    const TYPE_NAME: &'static str = "Old";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 6;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.name.is_some() { flags |= 1 << 0 }
+        if self.nickname.is_some() { flags |= 1 << 1 }
+        flags |= self._unknown_flags;
+        flags.serialize(w)?;
+        if let Some(ref v) = self.name {
+            v.serialize(w)?;
+        }
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        w.extend_from_slice(&self._unknown_extensions);
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_id = u32::deserialize_stream(r).at_path("id")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        Ok(Self {
+            id: field_id,
+            name: flag_name,
+            nickname: flag_nickname,
+            _unknown_flags: field_flags & !0x3,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_id = u32::deserialize(r).at_path("id")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        Ok(Self {
+            id: field_id,
+            name: flag_name,
+            nickname: flag_nickname,
+            _unknown_flags: field_flags & !0x3,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.id.deserialize_into(r).at_path("id")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        deserialize_option_into(&mut self.name, (field_flags & (1 << 0)) != 0, r).at_path("name")?;
+        self._unknown_flags = field_flags & !0x3;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 1)) != 0, _extension_reader).at_path("nickname")?;
+        self._unknown_extensions.clear();
+        self._unknown_extensions.extend_from_slice(_extension_reader);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct New<'x> {
+    pub id: u32,
+    pub name: Option<Cow<'x, str>>, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+    pub verified: bool, // Flag of `flags`
+    pub bio: Option<Cow<'x, str>>, // Flag of `flags`
+    pub _unknown_flags: u8, // Unknown flags of `flags`
+    pub _unknown_extensions: Vec<u8>,
+}
+impl<'x> New<'x> {
+    pub fn new(id: u32) -> Self {
+        Self { id, name: None, nickname: None, verified: false, bio: None, _unknown_flags: Default::default(), _unknown_extensions: Vec::new() }
+    }
+    pub fn with_name(mut self, name: Cow<'x, str>) -> Self {
+        self.name = Some(name);
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
+    pub fn with_bio(mut self, bio: Cow<'x, str>) -> Self {
+        self.bio = Some(bio);
+        self
+    }
+}
+impl<'x> PBType<'x> for New<'x> {
+    const TYPE_NAME: &'static str = "New";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 6;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(4 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.name.is_some() { flags |= 1 << 0 }
+        if self.nickname.is_some() { flags |= 1 << 1 }
+        if self.verified { flags |= 1 << 2 }
+        if self.bio.is_some() { flags |= 1 << 3 }
+        flags |= self._unknown_flags;
+        flags.serialize(w)?;
+        if let Some(ref v) = self.name {
+            v.serialize(w)?;
+        }
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.bio {
+            v.serialize(w)?;
+        }
+        w.extend_from_slice(&self._unknown_extensions);
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_id = u32::deserialize_stream(r).at_path("id")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        let flag_verified = (field_flags & (1 << 2)) != 0;
+        let flag_bio = if (field_flags & (1 << 3)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        Ok(Self {
+            id: field_id,
+            name: flag_name,
+            nickname: flag_nickname,
+            verified: flag_verified,
+            bio: flag_bio,
+            _unknown_flags: field_flags & !0xf,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_id = u32::deserialize(r).at_path("id")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_name = if (field_flags & (1 << 0)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("name")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("nickname")?)
+        } else { None };
+        let flag_verified = (field_flags & (1 << 2)) != 0;
+        let flag_bio = if (field_flags & (1 << 3)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        Ok(Self {
+            id: field_id,
+            name: flag_name,
+            nickname: flag_nickname,
+            verified: flag_verified,
+            bio: flag_bio,
+            _unknown_flags: field_flags & !0xf,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.id.deserialize_into(r).at_path("id")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        deserialize_option_into(&mut self.name, (field_flags & (1 << 0)) != 0, r).at_path("name")?;
+        self._unknown_flags = field_flags & !0xf;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 1)) != 0, _extension_reader).at_path("nickname")?;
+        self.verified = (field_flags & (1 << 2)) != 0;
+        deserialize_option_into(&mut self.bio, (field_flags & (1 << 3)) != 0, _extension_reader).at_path("bio")?;
+        self._unknown_extensions.clear();
+        self._unknown_extensions.extend_from_slice(_extension_reader);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+    pub _unknown_extensions: Vec<u8>,
+}
+impl Point {
+    pub fn new(x: u32, y: u32) -> Self {
+        Self { x, y, _unknown_extensions: Vec::new() }
+    }
+}
+impl<'x> PBType<'x> for Point {
+    const TYPE_NAME: &'static str = "Point";
+    const MAX_SIZE: Option<usize> = Some(9);
+    const MIN_SIZE: usize = 9;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.x.serialize(w)?;
+        self.y.serialize(w)?;
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        w.extend_from_slice(&self._unknown_extensions);
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_x = u32::deserialize_stream(r).at_path("x")?;
+        let field_y = u32::deserialize_stream(r).at_path("y")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            x: field_x,
+            y: field_y,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_x = u32::deserialize(r).at_path("x")?;
+        let field_y = u32::deserialize(r).at_path("y")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            x: field_x,
+            y: field_y,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.x.deserialize_into(r).at_path("x")?;
+        self.y.deserialize_into(r).at_path("y")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        self._unknown_extensions.clear();
+        self._unknown_extensions.extend_from_slice(_extension_reader);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+impl<'x> PBType<'x> for Size {
+    const TYPE_NAME: &'static str = "Size";
+    const MAX_SIZE: Option<usize> = Some(8);
+    const MIN_SIZE: usize = 8;
+    const FIXED_SIZE: Option<usize> = Some(8);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.width.serialize(w)?;
+        self.height.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_width = u32::deserialize_stream(r).at_path("width")?;
+        let field_height = u32::deserialize_stream(r).at_path("height")?;
+        Ok(Self {
+            width: field_width,
+            height: field_height,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_width = u32::deserialize(r).at_path("width")?;
+        let field_height = u32::deserialize(r).at_path("height")?;
+        Ok(Self {
+            width: field_width,
+            height: field_height,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.width.deserialize_into(r).at_path("width")?;
+        self.height.deserialize_into(r).at_path("height")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub theme: u8,
+    pub dark: bool, // Flag of `more`
+    pub scale: Option<u32>, // Flag of `more`
+    pub _unknown_more: UInt, // Unknown flags of `more`
+    pub _unknown_extensions: Vec<u8>,
+}
+impl Settings {
+    pub fn new(theme: u8) -> Self {
+        Self { theme, dark: false, scale: None, _unknown_more: Default::default(), _unknown_extensions: Vec::new() }
+    }
+    pub fn with_dark(mut self, dark: bool) -> Self {
+        self.dark = dark;
+        self
+    }
+    pub fn with_scale(mut self, scale: u32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+}
+impl<'x> PBType<'x> for Settings {
+    const TYPE_NAME: &'static str = "Settings";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 3;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.theme.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: UInt = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<UInt>(), "not enough bits for the flags of `more`");
+        if self.dark { flags |= 1 << 0 }
+        if self.scale.is_some() { flags |= 1 << 1 }
+        flags |= self._unknown_more;
+        flags.serialize(w)?;
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.scale {
+            v.serialize(w)?;
+        }
+        w.extend_from_slice(&self._unknown_extensions);
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_theme = u8::deserialize_stream(r).at_path("theme")?;
+        let field_more = UInt::deserialize_stream(r).at_path("more")?;
+        let flag_dark = (field_more & (1 << 0)) != 0;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_scale = if (field_more & (1 << 1)) != 0 {
+            Some(u32::deserialize_stream(_extension_reader).at_path("scale")?)
+        } else { None };
+        Ok(Self {
+            theme: field_theme,
+            dark: flag_dark,
+            scale: flag_scale,
+            _unknown_more: field_more & !0x3,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_theme = u8::deserialize(r).at_path("theme")?;
+        let field_more = UInt::deserialize(r).at_path("more")?;
+        let flag_dark = (field_more & (1 << 0)) != 0;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_scale = if (field_more & (1 << 1)) != 0 {
+            Some(u32::deserialize_stream(_extension_reader).at_path("scale")?)
+        } else { None };
+        Ok(Self {
+            theme: field_theme,
+            dark: flag_dark,
+            scale: flag_scale,
+            _unknown_more: field_more & !0x3,
+            _unknown_extensions: _extension_reader.to_vec(),
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.theme.deserialize_into(r).at_path("theme")?;
+        let field_more = UInt::deserialize_stream(r).at_path("more")?;
+        self.dark = (field_more & (1 << 0)) != 0;
+        self._unknown_more = field_more & !0x3;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.scale, (field_more & (1 << 1)) != 0, _extension_reader).at_path("scale")?;
+        self._unknown_extensions.clear();
+        self._unknown_extensions.extend_from_slice(_extension_reader);
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
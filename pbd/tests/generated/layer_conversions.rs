@@ -0,0 +1,814 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 1;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "AddressLayer0",
+    "ProfileLayer0",
+    "CounterLayer0",
+    "Address",
+    "Profile",
+    "Counter",
+];
+
+/// The IDs of all commands in this file, with their names
+pub const COMMAND_IDS_TO_NAMES: &[(u32, &str)] = &[
+    (3947225797, "renameLayer0"),
+    (4018470770, "rename"),
+];
+
+/// This enum contains all possible commands in the RPC definition.
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub enum Command<'x> {
+    renameLayer0(renameLayer0<'x>),
+    rename(rename<'x>),
+}
+impl<'x> PBCommand for Command<'x> {
+    fn id(&self) -> u32 {
+        match self {
+            Self::renameLayer0(_) => 3947225797,
+            Self::rename(_) => 4018470770,
+        }
+    }
+    fn is_void(&self) -> bool {
+        match self {
+            Self::renameLayer0(_) => false,
+            Self::rename(_) => false,
+        }
+    }
+    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Self::renameLayer0(_) => renameLayer0::ATTRIBUTES,
+            Self::rename(_) => rename::ATTRIBUTES,
+        }
+    }
+    fn required_capability(&self) -> Option<&'static str> {
+        match self {
+            Self::renameLayer0(_) => renameLayer0::REQUIRED_CAPABILITY,
+            Self::rename(_) => rename::REQUIRED_CAPABILITY,
+        }
+    }
+    fn serialize_self<R: io::Write>(&self, r: &mut R) -> Result<(), io::Error> {
+        match self {
+            Self::renameLayer0(c) => c.serialize_self(r),
+            Self::rename(c) => c.serialize_self(r),
+        }
+    }
+}
+
+impl<'x> Command<'x> {
+
+    /// Reads both the ID of the command and its value
+    pub fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let mut id = [0; 4];
+        r.read_exact(&mut id)?;
+        let id = u32::from_be_bytes(id);
+        Ok(match id {
+            3947225797 => Self::renameLayer0(renameLayer0::deserialize_stream(r)?),
+            4018470770 => Self::rename(rename::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let (a, b) = r.split_at_checked(4)
+            .ok_or(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"))?;
+        let arr = a.try_into().unwrap(); // has to be 4 bytes
+        let id = u32::from_be_bytes(arr);
+        *r = b;
+        Ok(match id {
+            3947225797 => Self::renameLayer0(renameLayer0::deserialize_stream(r)?),
+            4018470770 => Self::rename(rename::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command return types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandReturn {
+    renameLayer0(Done),
+    rename(Done),
+}
+impl CommandReturn {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::renameLayer0(c) => c.serialize(w)?,
+            Self::rename(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_return_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            3947225797 => Self::renameLayer0(Done::deserialize_stream(r)?),
+            4018470770 => Self::rename(Done::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_return<'a>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            3947225797 => Self::renameLayer0(Done::deserialize(r)?),
+            4018470770 => Self::rename(Done::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command error types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandError<'x> {
+    renameLayer0(renameLayer0Error<'x>),
+    rename(renameError<'x>),
+}
+impl<'x> CommandError<'x> {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::renameLayer0(c) => c.serialize(w)?,
+            Self::rename(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_error_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            3947225797 => Self::renameLayer0(renameLayer0Error::deserialize_stream(r)?),
+            4018470770 => Self::rename(renameError::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_error<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            3947225797 => Self::renameLayer0(renameLayer0Error::deserialize(r)?),
+            4018470770 => Self::rename(renameError::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct renameLayer0<'x> {
+    pub name: Cow<'x, str>,
+}
+impl<'x> PBCommandExt<'x> for renameLayer0<'x> {
+    type Error<'a> = renameLayer0Error<'a>;
+    type Return<'a> = Done;
+    const ID: u32 = 3947225797;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+        })
+    }
+}
+impl<'x> PBCommand for renameLayer0<'x> {
+    fn id(&self) -> u32 { 3947225797 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.name.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum renameLayer0Error<'x> {
+    UnexpectedError(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for renameLayer0Error<'x> {
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for renameLayer0Error<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct rename<'x> {
+    pub name: Cow<'x, str>,
+    pub notify: bool, // Flag of `flags`
+}
+impl<'x> rename<'x> {
+    pub fn new(name: Cow<'x, str>) -> Self {
+        Self { name, notify: false }
+    }
+    pub fn with_notify(mut self, notify: bool) -> Self {
+        self.notify = notify;
+        self
+    }
+}
+impl<'x> PBCommandExt<'x> for rename<'x> {
+    type Error<'a> = renameError<'a>;
+    type Return<'a> = Done;
+    const ID: u32 = 4018470770;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_notify = (field_flags & (1 << 0)) != 0;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+            notify: flag_notify,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_notify = (field_flags & (1 << 0)) != 0;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+            notify: flag_notify,
+        })
+    }
+}
+impl<'x> PBCommand for rename<'x> {
+    fn id(&self) -> u32 { 4018470770 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.name.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(1 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.notify { flags |= 1 << 0 }
+        flags.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum renameError<'x> {
+    UnexpectedError(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for renameError<'x> {
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for renameError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddressLayer0<'x> {
+    pub street: Cow<'x, str>,
+}
+impl<'x> PBType<'x> for AddressLayer0<'x> {
+    const TYPE_NAME: &'static str = "Address";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.street.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_street = Cow::<'x, str>::deserialize_stream(r).at_path("street")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            street: field_street,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_street = Cow::<'x, str>::deserialize(r).at_path("street")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            street: field_street,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.street.deserialize_into(r).at_path("street")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfileLayer0<'x> {
+    pub name: Cow<'x, str>,
+    pub address: AddressLayer0<'x>,
+    pub verified: bool, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+}
+impl<'x> ProfileLayer0<'x> {
+    pub fn new(name: Cow<'x, str>, address: AddressLayer0<'x>) -> Self {
+        Self { name, address, verified: false, nickname: None }
+    }
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+}
+impl<'x> PBType<'x> for ProfileLayer0<'x> {
+    const TYPE_NAME: &'static str = "Profile";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 5;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.name.serialize(w)?;
+        self.address.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.verified { flags |= 1 << 0 }
+        if self.nickname.is_some() { flags |= 1 << 1 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let field_address = AddressLayer0::<'x>::deserialize_stream(r).at_path("address")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_verified = (field_flags & (1 << 0)) != 0;
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("nickname")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+            address: field_address,
+            verified: flag_verified,
+            nickname: flag_nickname,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let field_address = AddressLayer0::<'x>::deserialize(r).at_path("address")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_verified = (field_flags & (1 << 0)) != 0;
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("nickname")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+            address: field_address,
+            verified: flag_verified,
+            nickname: flag_nickname,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.name.deserialize_into(r).at_path("name")?;
+        self.address.deserialize_into(r).at_path("address")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.verified = (field_flags & (1 << 0)) != 0;
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 1)) != 0, r).at_path("nickname")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CounterLayer0 {
+    pub value: u8,
+}
+impl<'x> PBType<'x> for CounterLayer0 {
+    const TYPE_NAME: &'static str = "Counter";
+    const MAX_SIZE: Option<usize> = Some(2);
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.value.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_value = u8::deserialize_stream(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_value = u8::deserialize(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.value.deserialize_into(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Address<'x> {
+    pub street: Cow<'x, str>,
+    pub zip: Option<u32>, // Flag of `flags`
+}
+impl<'x> Address<'x> {
+    pub fn new(street: Cow<'x, str>) -> Self {
+        Self { street, zip: None }
+    }
+    pub fn with_zip(mut self, zip: u32) -> Self {
+        self.zip = Some(zip);
+        self
+    }
+}
+impl<'x> PBType<'x> for Address<'x> {
+    const TYPE_NAME: &'static str = "Address";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 3;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.street.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(1 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.zip.is_some() { flags |= 1 << 0 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.zip {
+            v.serialize(w)?;
+        }
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_street = Cow::<'x, str>::deserialize_stream(r).at_path("street")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_zip = if (field_flags & (1 << 0)) != 0 {
+            Some(u32::deserialize_stream(r).at_path("zip")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            street: field_street,
+            zip: flag_zip,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_street = Cow::<'x, str>::deserialize(r).at_path("street")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_zip = if (field_flags & (1 << 0)) != 0 {
+            Some(u32::deserialize(r).at_path("zip")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            street: field_street,
+            zip: flag_zip,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.street.deserialize_into(r).at_path("street")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        deserialize_option_into(&mut self.zip, (field_flags & (1 << 0)) != 0, r).at_path("zip")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Profile<'x> {
+    pub name: Cow<'x, str>,
+    pub address: Address<'x>,
+    pub nickname: Cow<'x, str>,
+    pub verified: bool, // Flag of `flags`
+}
+impl<'x> Profile<'x> {
+    pub fn new(name: Cow<'x, str>, address: Address<'x>, nickname: Cow<'x, str>) -> Self {
+        Self { name, address, nickname, verified: false }
+    }
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
+}
+impl<'x> PBType<'x> for Profile<'x> {
+    const TYPE_NAME: &'static str = "Profile";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 7;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.name.serialize(w)?;
+        self.address.serialize(w)?;
+        self.nickname.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(1 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.verified { flags |= 1 << 0 }
+        flags.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let field_address = Address::<'x>::deserialize_stream(r).at_path("address")?;
+        let field_nickname = Cow::<'x, str>::deserialize_stream(r).at_path("nickname")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_verified = (field_flags & (1 << 0)) != 0;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+            address: field_address,
+            nickname: field_nickname,
+            verified: flag_verified,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let field_address = Address::<'x>::deserialize(r).at_path("address")?;
+        let field_nickname = Cow::<'x, str>::deserialize(r).at_path("nickname")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_verified = (field_flags & (1 << 0)) != 0;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+            address: field_address,
+            nickname: field_nickname,
+            verified: flag_verified,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.name.deserialize_into(r).at_path("name")?;
+        self.address.deserialize_into(r).at_path("address")?;
+        self.nickname.deserialize_into(r).at_path("nickname")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.verified = (field_flags & (1 << 0)) != 0;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Counter {
+    pub value: u16,
+}
+impl<'x> PBType<'x> for Counter {
+    const TYPE_NAME: &'static str = "Counter";
+    const MAX_SIZE: Option<usize> = Some(3);
+    const MIN_SIZE: usize = 3;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.value.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_value = u16::deserialize_stream(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_value = u16::deserialize(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.value.deserialize_into(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
+impl<'x> From<AddressLayer0<'x>> for Address<'x> {
+    fn from(old: AddressLayer0<'x>) -> Self {
+        Self {
+            street: old.street,
+            zip: None,
+        }
+    }
+}
+
+impl<'x> TryFrom<ProfileLayer0<'x>> for Profile<'x> {
+    type Error = io::Error;
+    fn try_from(old: ProfileLayer0<'x>) -> io::Result<Self> {
+        Ok(Self {
+            name: old.name,
+            address: old.address.into(),
+            nickname: old.nickname.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "`nickname` is required by layer 1 of `Profile`"))?,
+            verified: old.verified,
+        })
+    }
+}
+
+impl<'x> From<renameLayer0<'x>> for rename<'x> {
+    fn from(old: renameLayer0<'x>) -> Self {
+        Self {
+            name: old.name,
+            notify: false,
+        }
+    }
+}
+
@@ -0,0 +1,520 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Either",
+    "Pair",
+    "Reading",
+];
+
+/// The IDs of all commands in this file, with their names
+pub const COMMAND_IDS_TO_NAMES: &[(u32, &str)] = &[
+    (195976287, "readSensor"),
+];
+
+/// This enum contains all possible commands in the RPC definition.
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub enum Command {
+    readSensor(readSensor),
+}
+impl PBCommand for Command {
+    fn id(&self) -> u32 {
+        match self {
+            Self::readSensor(_) => 195976287,
+        }
+    }
+    fn is_void(&self) -> bool {
+        match self {
+            Self::readSensor(_) => false,
+        }
+    }
+    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Self::readSensor(_) => readSensor::ATTRIBUTES,
+        }
+    }
+    fn required_capability(&self) -> Option<&'static str> {
+        match self {
+            Self::readSensor(_) => readSensor::REQUIRED_CAPABILITY,
+        }
+    }
+    fn serialize_self<R: io::Write>(&self, r: &mut R) -> Result<(), io::Error> {
+        match self {
+            Self::readSensor(c) => c.serialize_self(r),
+        }
+    }
+}
+
+impl Command {
+
+    /// Reads both the ID of the command and its value
+    pub fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let mut id = [0; 4];
+        r.read_exact(&mut id)?;
+        let id = u32::from_be_bytes(id);
+        Ok(match id {
+            195976287 => Self::readSensor(readSensor::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize<'a>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let (a, b) = r.split_at_checked(4)
+            .ok_or(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"))?;
+        let arr = a.try_into().unwrap(); // has to be 4 bytes
+        let id = u32::from_be_bytes(arr);
+        *r = b;
+        Ok(match id {
+            195976287 => Self::readSensor(readSensor::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command return types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandReturn {
+    readSensor(Reading),
+}
+impl CommandReturn {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::readSensor(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_return_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            195976287 => Self::readSensor(Reading::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_return<'a>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            195976287 => Self::readSensor(Reading::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command error types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandError<'x> {
+    readSensor(readSensorError<'x>),
+}
+impl<'x> CommandError<'x> {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::readSensor(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_error_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            195976287 => Self::readSensor(readSensorError::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_error<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            195976287 => Self::readSensor(readSensorError::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct readSensor(pub u16);
+impl<'x> PBCommandExt<'x> for readSensor {
+    type Error<'a> = readSensorError<'a>;
+    type Return<'a> = Reading;
+    const ID: u32 = 195976287;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self(u16::deserialize_stream(r)?))
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self(u16::deserialize(r)?))
+    }
+}
+impl PBCommand for readSensor {
+    fn id(&self) -> u32 { 195976287 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.0.serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum readSensorError<'x> {
+    UnexpectedError(Cow<'x, str>),
+    Unplugged(u8),
+    Broken(u32),
+}
+impl<'x> PBType<'x> for readSensorError<'x> {
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+            Self::Unplugged(value) => {
+                1u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+            Self::Broken(value) => {
+                2u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            1 => {
+                Self::Unplugged(u8::deserialize_stream(r).at_path("Unplugged")?)
+            }
+            2 => {
+                Self::Broken(u32::deserialize_stream(r).at_path("Broken")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            1 => {
+                Self::Unplugged(u8::deserialize(r).at_path("Unplugged")?)
+            }
+            2 => {
+                Self::Broken(u32::deserialize(r).at_path("Broken")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for readSensorError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Either<X, Y> {
+    Left(X),
+    Right(Y),
+}
+impl<'x, X: PBType<'x>, Y: PBType<'x>> PBType<'x> for Either<X, Y> {
+    const TYPE_NAME: &'static str = "Either";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 1 + min_size_of(&[X::MIN_SIZE, Y::MIN_SIZE]);
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Left(value) => {
+                0u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+            Self::Right(value) => {
+                1u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Left(X::deserialize_stream(r).at_path("Left")?)
+            }
+            1 => {
+                Self::Right(Y::deserialize_stream(r).at_path("Right")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Left(X::deserialize(r).at_path("Left")?)
+            }
+            1 => {
+                Self::Right(Y::deserialize(r).at_path("Right")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Pair<A, B> {
+    pub first: A,
+    pub second: B,
+}
+impl<'x, A: PBType<'x>, B: PBType<'x>> PBType<'x> for Pair<A, B> {
+    const TYPE_NAME: &'static str = "Pair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = A::MIN_SIZE + B::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.first.serialize(w)?;
+        self.second.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_first = A::deserialize_stream(r).at_path("first")?;
+        let field_second = B::deserialize_stream(r).at_path("second")?;
+        Ok(Self {
+            first: field_first,
+            second: field_second,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_first = A::deserialize(r).at_path("first")?;
+        let field_second = B::deserialize(r).at_path("second")?;
+        Ok(Self {
+            first: field_first,
+            second: field_second,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.first.deserialize_into(r).at_path("first")?;
+        self.second.deserialize_into(r).at_path("second")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Reading {
+    pub sensor: u16,
+    pub value: Either<u32, Pair<u8, u8>>,
+    pub calibrated: bool, // Flag of `flags`
+    pub offset: Option<i32>, // Flag of `flags`
+}
+impl Reading {
+    pub fn new(sensor: u16, value: Either<u32, Pair<u8, u8>>) -> Self {
+        Self { sensor, value, calibrated: false, offset: None }
+    }
+    pub fn with_calibrated(mut self, calibrated: bool) -> Self {
+        self.calibrated = calibrated;
+        self
+    }
+    pub fn with_offset(mut self, offset: i32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+impl<'x> PBType<'x> for Reading {
+    const TYPE_NAME: &'static str = "Reading";
+    const MAX_SIZE: Option<usize> = Some(13);
+    const MIN_SIZE: usize = 7;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.sensor.serialize(w)?;
+        self.value.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.calibrated { flags |= 1 << 0 }
+        if self.offset.is_some() { flags |= 1 << 1 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.offset {
+            v.serialize(w)?;
+        }
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_sensor = u16::deserialize_stream(r).at_path("sensor")?;
+        let field_value = Either::<u32, Pair::<u8, u8>>::deserialize_stream(r).at_path("value")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_calibrated = (field_flags & (1 << 0)) != 0;
+        let flag_offset = if (field_flags & (1 << 1)) != 0 {
+            Some(i32::deserialize_stream(r).at_path("offset")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            sensor: field_sensor,
+            value: field_value,
+            calibrated: flag_calibrated,
+            offset: flag_offset,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_sensor = u16::deserialize(r).at_path("sensor")?;
+        let field_value = Either::<u32, Pair::<u8, u8>>::deserialize(r).at_path("value")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_calibrated = (field_flags & (1 << 0)) != 0;
+        let flag_offset = if (field_flags & (1 << 1)) != 0 {
+            Some(i32::deserialize(r).at_path("offset")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            sensor: field_sensor,
+            value: field_value,
+            calibrated: flag_calibrated,
+            offset: flag_offset,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.sensor.deserialize_into(r).at_path("sensor")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.calibrated = (field_flags & (1 << 0)) != 0;
+        deserialize_option_into(&mut self.offset, (field_flags & (1 << 1)) != 0, r).at_path("offset")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,471 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Version",
+    "Channel",
+    "Release",
+    "Measurement",
+    "Tagged",
+    "MaybeMeasured",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version<'x> {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    pub tag: Cow<'x, str>,
+}
+impl<'x> PBType<'x> for Version<'x> {
+    const TYPE_NAME: &'static str = "Version";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 8;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.major.serialize(w)?;
+        self.minor.serialize(w)?;
+        self.patch.serialize(w)?;
+        self.tag.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_major = u16::deserialize_stream(r).at_path("major")?;
+        let field_minor = u16::deserialize_stream(r).at_path("minor")?;
+        let field_patch = u16::deserialize_stream(r).at_path("patch")?;
+        let field_tag = Cow::<'x, str>::deserialize_stream(r).at_path("tag")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            major: field_major,
+            minor: field_minor,
+            patch: field_patch,
+            tag: field_tag,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_major = u16::deserialize(r).at_path("major")?;
+        let field_minor = u16::deserialize(r).at_path("minor")?;
+        let field_patch = u16::deserialize(r).at_path("patch")?;
+        let field_tag = Cow::<'x, str>::deserialize(r).at_path("tag")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            major: field_major,
+            minor: field_minor,
+            patch: field_patch,
+            tag: field_tag,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.major.deserialize_into(r).at_path("major")?;
+        self.minor.deserialize_into(r).at_path("minor")?;
+        self.patch.deserialize_into(r).at_path("patch")?;
+        self.tag.deserialize_into(r).at_path("tag")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Channel {
+    Nightly(u32),
+    Beta,
+    Stable,
+}
+impl<'x> PBType<'x> for Channel {
+    const TYPE_NAME: &'static str = "Channel";
+    const MAX_SIZE: Option<usize> = Some(5);
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Nightly(value) => {
+                0u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+            Self::Beta => {
+                1u8.serialize(w)?;
+            }
+            Self::Stable => {
+                2u8.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Nightly(u32::deserialize_stream(r).at_path("Nightly")?)
+            }
+            1 => {
+                Self::Beta
+            }
+            2 => {
+                Self::Stable
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Nightly(u32::deserialize(r).at_path("Nightly")?)
+            }
+            1 => {
+                Self::Beta
+            }
+            2 => {
+                Self::Stable
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Release<'x> {
+    pub channel: Channel,
+    pub version: Version<'x>,
+    pub checksums: Vec<Bytes<'x>>,
+    pub yanked: bool, // Flag of `flags`
+    pub replaced_by: Option<Version<'x>>, // Flag of `flags`
+}
+impl<'x> Release<'x> {
+    pub fn new(channel: Channel, version: Version<'x>, checksums: Vec<Bytes<'x>>) -> Self {
+        Self { channel, version, checksums, yanked: false, replaced_by: None }
+    }
+    pub fn with_yanked(mut self, yanked: bool) -> Self {
+        self.yanked = yanked;
+        self
+    }
+    pub fn with_replaced_by(mut self, replaced_by: Version<'x>) -> Self {
+        self.replaced_by = Some(replaced_by);
+        self
+    }
+}
+impl<'x> PBType<'x> for Release<'x> {
+    const TYPE_NAME: &'static str = "Release";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 12;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.channel.serialize(w)?;
+        self.version.serialize(w)?;
+        self.checksums.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.yanked { flags |= 1 << 0 }
+        if self.replaced_by.is_some() { flags |= 1 << 1 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.replaced_by {
+            v.serialize(w)?;
+        }
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_channel = Channel::deserialize_stream(r).at_path("channel")?;
+        let field_version = Version::<'x>::deserialize_stream(r).at_path("version")?;
+        let field_checksums = Vec::<Bytes::<'x>>::deserialize_stream(r).at_path("checksums")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_yanked = (field_flags & (1 << 0)) != 0;
+        let flag_replaced_by = if (field_flags & (1 << 1)) != 0 {
+            Some(Version::<'x>::deserialize_stream(r).at_path("replaced_by")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            channel: field_channel,
+            version: field_version,
+            checksums: field_checksums,
+            yanked: flag_yanked,
+            replaced_by: flag_replaced_by,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_channel = Channel::deserialize(r).at_path("channel")?;
+        let field_version = Version::<'x>::deserialize(r).at_path("version")?;
+        let field_checksums = Vec::<Bytes::<'x>>::deserialize(r).at_path("checksums")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_yanked = (field_flags & (1 << 0)) != 0;
+        let flag_replaced_by = if (field_flags & (1 << 1)) != 0 {
+            Some(Version::<'x>::deserialize(r).at_path("replaced_by")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            channel: field_channel,
+            version: field_version,
+            checksums: field_checksums,
+            yanked: flag_yanked,
+            replaced_by: flag_replaced_by,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.channel.deserialize_into(r).at_path("channel")?;
+        self.version.deserialize_into(r).at_path("version")?;
+        self.checksums.deserialize_into(r).at_path("checksums")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.yanked = (field_flags & (1 << 0)) != 0;
+        deserialize_option_into(&mut self.replaced_by, (field_flags & (1 << 1)) != 0, r).at_path("replaced_by")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    pub value: f64,
+}
+impl<'x> PBType<'x> for Measurement {
+    const TYPE_NAME: &'static str = "Measurement";
+    const MAX_SIZE: Option<usize> = Some(9);
+    const MIN_SIZE: usize = 9;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.value.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_value = f64::deserialize_stream(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_value = f64::deserialize(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.value.deserialize_into(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Tagged {
+    pub tags: Map<u32, u32>,
+}
+impl<'x> PBType<'x> for Tagged {
+    const TYPE_NAME: &'static str = "Tagged";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.tags.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_tags = Map::<u32, u32>::deserialize_stream(r).at_path("tags")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            tags: field_tags,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_tags = Map::<u32, u32>::deserialize(r).at_path("tags")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            tags: field_tags,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.tags.deserialize_into(r).at_path("tags")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MaybeMeasured {
+    pub value: Option<f64>,
+}
+impl<'x> PBType<'x> for MaybeMeasured {
+    const TYPE_NAME: &'static str = "MaybeMeasured";
+    const MAX_SIZE: Option<usize> = Some(10);
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.value.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_value = Option::<f64>::deserialize_stream(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_value = Option::<f64>::deserialize(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.value.deserialize_into(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
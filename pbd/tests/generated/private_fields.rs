@@ -0,0 +1,791 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Point",
+    "Reading",
+    "Sample",
+    "Labeled",
+    "Palette",
+];
+
+/// The IDs of all commands in this file, with their names
+pub const COMMAND_IDS_TO_NAMES: &[(u32, &str)] = &[
+    (2140553945, "lookup"),
+    (2771262791, "record"),
+];
+
+/// This enum contains all possible commands in the RPC definition.
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub enum Command<'x> {
+    lookup(lookup<'x>),
+    record(record),
+}
+impl<'x> PBCommand for Command<'x> {
+    fn id(&self) -> u32 {
+        match self {
+            Self::lookup(_) => 2140553945,
+            Self::record(_) => 2771262791,
+        }
+    }
+    fn is_void(&self) -> bool {
+        match self {
+            Self::lookup(_) => false,
+            Self::record(_) => false,
+        }
+    }
+    fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Self::lookup(_) => lookup::ATTRIBUTES,
+            Self::record(_) => record::ATTRIBUTES,
+        }
+    }
+    fn required_capability(&self) -> Option<&'static str> {
+        match self {
+            Self::lookup(_) => lookup::REQUIRED_CAPABILITY,
+            Self::record(_) => record::REQUIRED_CAPABILITY,
+        }
+    }
+    fn serialize_self<R: io::Write>(&self, r: &mut R) -> Result<(), io::Error> {
+        match self {
+            Self::lookup(c) => c.serialize_self(r),
+            Self::record(c) => c.serialize_self(r),
+        }
+    }
+}
+
+impl<'x> Command<'x> {
+
+    /// Reads both the ID of the command and its value
+    pub fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let mut id = [0; 4];
+        r.read_exact(&mut id)?;
+        let id = u32::from_be_bytes(id);
+        Ok(match id {
+            2140553945 => Self::lookup(lookup::deserialize_stream(r)?),
+            2771262791 => Self::record(record::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let (a, b) = r.split_at_checked(4)
+            .ok_or(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"))?;
+        let arr = a.try_into().unwrap(); // has to be 4 bytes
+        let id = u32::from_be_bytes(arr);
+        *r = b;
+        Ok(match id {
+            2140553945 => Self::lookup(lookup::deserialize_stream(r)?),
+            2771262791 => Self::record(record::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command return types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandReturn {
+    lookup(Point),
+    record(Done),
+}
+impl CommandReturn {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::lookup(c) => c.serialize(w)?,
+            Self::record(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_return_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            2140553945 => Self::lookup(Point::deserialize_stream(r)?),
+            2771262791 => Self::record(Done::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_return<'a>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            2140553945 => Self::lookup(Point::deserialize(r)?),
+            2771262791 => Self::record(Done::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+/// This enum contains all possible command error types in the RPC definition.
+#[derive(Debug, Clone)]
+pub enum CommandError<'x> {
+    lookup(lookupError<'x>),
+    record(recordError<'x>),
+}
+impl<'x> CommandError<'x> {
+    pub fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::lookup(c) => c.serialize(w)?,
+            Self::record(c) => c.serialize(w)?,
+        }
+        Ok(())
+    }
+    pub fn deserialize_error_stream<R: io::Read>(id: u32, r: &mut R) -> io::Result<Self> {
+        Ok(match id {
+            2140553945 => Self::lookup(lookupError::deserialize_stream(r)?),
+            2771262791 => Self::record(recordError::deserialize_stream(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+    pub fn deserialize_error<'a: 'x>(id: u32, r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(match id {
+            2140553945 => Self::lookup(lookupError::deserialize(r)?),
+            2771262791 => Self::record(recordError::deserialize(r)?),
+            _ => Err(io::Error::other("Invalid or unsupported command ID"))?
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct lookup<'x> {
+    name: Cow<'x, str>,
+}
+impl<'x> lookup<'x> {
+    pub fn new(name: Cow<'x, str>) -> Self {
+        Self { name }
+    }
+    pub fn name(&self) -> &Cow<'x, str> {
+        &self.name
+    }
+    pub fn set_name(&mut self, name: Cow<'x, str>) {
+        self.name = name;
+    }
+}
+impl<'x> PBCommandExt<'x> for lookup<'x> {
+    type Error<'a> = lookupError<'a>;
+    type Return<'a> = Point;
+    const ID: u32 = 2140553945;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            name: field_name,
+        })
+    }
+}
+impl<'x> PBCommand for lookup<'x> {
+    fn id(&self) -> u32 { 2140553945 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.name.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum lookupError<'x> {
+    UnexpectedError(Cow<'x, str>),
+    NotFound,
+}
+impl<'x> PBType<'x> for lookupError<'x> {
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+            Self::NotFound => {
+                1u8.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            1 => {
+                Self::NotFound
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            1 => {
+                Self::NotFound
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for lookupError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[must_use = "commands must be serialized or dispatched"]
+pub struct record(pub Reading);
+impl<'x> PBCommandExt<'x> for record {
+    type Error<'a> = recordError<'a>;
+    type Return<'a> = Done;
+    const ID: u32 = 2771262791;
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self(Reading::deserialize_stream(r)?))
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self(Reading::deserialize(r)?))
+    }
+}
+impl PBCommand for record {
+    fn id(&self) -> u32 { 2771262791 }
+    fn serialize_self<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.0.serialize(w)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum recordError<'x> {
+    UnexpectedError(Cow<'x, str>),
+    TooCold(f32),
+}
+impl<'x> PBType<'x> for recordError<'x> {
+    const MIN_SIZE: usize = 2;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::UnexpectedError(x) => { 0u8.serialize(w)?; x.serialize(w)?; }
+            Self::TooCold(value) => {
+                1u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize_stream(r)?) }
+            1 => {
+                Self::TooCold(f32::deserialize_stream(r).at_path("TooCold")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => { Self::UnexpectedError(Cow::deserialize(r)?) }
+            1 => {
+                Self::TooCold(f32::deserialize(r).at_path("TooCold")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+impl<'x> From<io::Error> for recordError<'x> {
+    fn from(err: io::Error) -> Self {
+        Self::UnexpectedError(Cow::Owned(err.to_string()))
+    }
+}
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    key: K,
+    value: V,
+}
+impl<K, V> KeyPair<K, V> {
+    pub fn new(key: K, value: V) -> Self {
+        Self { key, value }
+    }
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+    pub fn set_key(&mut self, key: K) {
+        self.key = key;
+    }
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+    pub fn set_value(&mut self, value: V) {
+        self.value = value;
+    }
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+    pub fn x(&self) -> &i32 {
+        &self.x
+    }
+    pub fn set_x(&mut self, x: i32) {
+        self.x = x;
+    }
+    pub fn y(&self) -> &i32 {
+        &self.y
+    }
+    pub fn set_y(&mut self, y: i32) {
+        self.y = y;
+    }
+}
+impl<'x> PBType<'x> for Point {
+    const TYPE_NAME: &'static str = "Point";
+    const MAX_SIZE: Option<usize> = Some(9);
+    const MIN_SIZE: usize = 9;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.x.serialize(w)?;
+        self.y.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_x = i32::deserialize_stream(r).at_path("x")?;
+        let field_y = i32::deserialize_stream(r).at_path("y")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            x: field_x,
+            y: field_y,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_x = i32::deserialize(r).at_path("x")?;
+        let field_y = i32::deserialize(r).at_path("y")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            x: field_x,
+            y: field_y,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.x.deserialize_into(r).at_path("x")?;
+        self.y.deserialize_into(r).at_path("y")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Reading {
+    at: Point,
+    celsius: f32,
+}
+impl Reading {
+    pub fn new(at: Point, celsius: f32) -> Self {
+        Self { at, celsius }
+    }
+    pub fn at(&self) -> &Point {
+        &self.at
+    }
+    pub fn set_at(&mut self, at: Point) {
+        self.at = at;
+    }
+    pub fn celsius(&self) -> &f32 {
+        &self.celsius
+    }
+    pub fn set_celsius(&mut self, celsius: f32) {
+        self.celsius = celsius;
+    }
+}
+impl<'x> PBType<'x> for Reading {
+    const TYPE_NAME: &'static str = "Reading";
+    const MAX_SIZE: Option<usize> = Some(14);
+    const MIN_SIZE: usize = 14;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.at.serialize(w)?;
+        self.celsius.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_at = Point::deserialize_stream(r).at_path("at")?;
+        let field_celsius = f32::deserialize_stream(r).at_path("celsius")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            at: field_at,
+            celsius: field_celsius,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_at = Point::deserialize(r).at_path("at")?;
+        let field_celsius = f32::deserialize(r).at_path("celsius")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            at: field_at,
+            celsius: field_celsius,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.at.deserialize_into(r).at_path("at")?;
+        self.celsius.deserialize_into(r).at_path("celsius")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Sample {
+    Empty,
+    Measured(Reading),
+}
+impl<'x> PBType<'x> for Sample {
+    const TYPE_NAME: &'static str = "Sample";
+    const MAX_SIZE: Option<usize> = Some(15);
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Empty => {
+                0u8.serialize(w)?;
+            }
+            Self::Measured(value) => {
+                1u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Empty
+            }
+            1 => {
+                Self::Measured(Reading::deserialize_stream(r).at_path("Measured")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Empty
+            }
+            1 => {
+                Self::Measured(Reading::deserialize(r).at_path("Measured")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Labeled<'x, T> {
+    label: Cow<'x, str>,
+    value: T,
+}
+impl<'x, T> Labeled<'x, T> {
+    pub fn new(label: Cow<'x, str>, value: T) -> Self {
+        Self { label, value }
+    }
+    pub fn label(&self) -> &Cow<'x, str> {
+        &self.label
+    }
+    pub fn set_label(&mut self, label: Cow<'x, str>) {
+        self.label = label;
+    }
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+    pub fn set_value(&mut self, value: T) {
+        self.value = value;
+    }
+}
+impl<'x, T: PBType<'x>> PBType<'x> for Labeled<'x, T> {
+    const TYPE_NAME: &'static str = "Labeled";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 2 + T::MIN_SIZE;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.label.serialize(w)?;
+        self.value.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_label = Cow::<'x, str>::deserialize_stream(r).at_path("label")?;
+        let field_value = T::deserialize_stream(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            label: field_label,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_label = Cow::<'x, str>::deserialize(r).at_path("label")?;
+        let field_value = T::deserialize(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            label: field_label,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.label.deserialize_into(r).at_path("label")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Palette<'x> {
+    colors: Vec<Labeled<'x, u32>>,
+    dark: bool, // Flag of `flags`
+    accent: Option<Point>, // Flag of `flags`
+}
+impl<'x> Palette<'x> {
+    pub fn new(colors: Vec<Labeled<'x, u32>>) -> Self {
+        Self { colors, dark: false, accent: None }
+    }
+    pub fn with_dark(mut self, dark: bool) -> Self {
+        self.dark = dark;
+        self
+    }
+    pub fn with_accent(mut self, accent: Point) -> Self {
+        self.accent = Some(accent);
+        self
+    }
+    pub fn colors(&self) -> &Vec<Labeled<'x, u32>> {
+        &self.colors
+    }
+    pub fn set_colors(&mut self, colors: Vec<Labeled<'x, u32>>) {
+        self.colors = colors;
+    }
+    pub fn dark(&self) -> &bool {
+        &self.dark
+    }
+    pub fn set_dark(&mut self, dark: bool) {
+        self.dark = dark;
+    }
+    pub fn accent(&self) -> &Option<Point> {
+        &self.accent
+    }
+    pub fn set_accent(&mut self, accent: Option<Point>) {
+        self.accent = accent;
+    }
+}
+impl<'x> PBType<'x> for Palette<'x> {
+    const TYPE_NAME: &'static str = "Palette";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 3;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.colors.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.dark { flags |= 1 << 0 }
+        if self.accent.is_some() { flags |= 1 << 1 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.accent {
+            v.serialize(w)?;
+        }
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_colors = Vec::<Labeled::<'x, u32>>::deserialize_stream(r).at_path("colors")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_dark = (field_flags & (1 << 0)) != 0;
+        let flag_accent = if (field_flags & (1 << 1)) != 0 {
+            Some(Point::deserialize_stream(r).at_path("accent")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            colors: field_colors,
+            dark: flag_dark,
+            accent: flag_accent,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_colors = Vec::<Labeled::<'x, u32>>::deserialize(r).at_path("colors")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_dark = (field_flags & (1 << 0)) != 0;
+        let flag_accent = if (field_flags & (1 << 1)) != 0 {
+            Some(Point::deserialize(r).at_path("accent")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            colors: field_colors,
+            dark: flag_dark,
+            accent: flag_accent,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.colors.deserialize_into(r).at_path("colors")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.dark = (field_flags & (1 << 0)) != 0;
+        deserialize_option_into(&mut self.accent, (field_flags & (1 << 1)) != 0, r).at_path("accent")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,384 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Color",
+    "Profile",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+impl<K: AsFieldValue + core::fmt::Debug + Send + Sync, V: AsFieldValue + core::fmt::Debug + Send + Sync> PBReflect for KeyPair<K, V> {
+    fn type_name(&self) -> &'static str { "KeyPair" }
+    fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)> {
+        vec![
+            ("key", self.key.as_field_value()),
+            ("value", self.value.as_field_value()),
+        ]
+    }
+}
+impl<K: AsFieldValue + core::fmt::Debug + Send + Sync, V: AsFieldValue + core::fmt::Debug + Send + Sync> AsFieldValue for KeyPair<K, V> {
+    fn as_field_value(&self) -> FieldValue<'_> { FieldValue::Reflect(self) }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl PBReflect for Done {
+    fn type_name(&self) -> &'static str { "Done" }
+    fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)> {
+        vec![
+        ]
+    }
+}
+impl AsFieldValue for Done {
+    fn as_field_value(&self) -> FieldValue<'_> { FieldValue::Reflect(self) }
+}
+
+#[derive(Debug, Clone)]
+pub enum Color {
+    Red,
+    Green,
+    Custom(u32),
+}
+impl<'x> PBType<'x> for Color {
+    const TYPE_NAME: &'static str = "Color";
+    const MAX_SIZE: Option<usize> = Some(5);
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Red => {
+                0u8.serialize(w)?;
+            }
+            Self::Green => {
+                1u8.serialize(w)?;
+            }
+            Self::Custom(value) => {
+                2u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Red
+            }
+            1 => {
+                Self::Green
+            }
+            2 => {
+                Self::Custom(u32::deserialize_stream(r).at_path("Custom")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Red
+            }
+            1 => {
+                Self::Green
+            }
+            2 => {
+                Self::Custom(u32::deserialize(r).at_path("Custom")?)
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+
+impl PBReflect for Color {
+    fn type_name(&self) -> &'static str { "Color" }
+    fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)> {
+        match self {
+            Self::Red => vec![("Red", FieldValue::Unit)],
+            Self::Green => vec![("Green", FieldValue::Unit)],
+            Self::Custom(v) => vec![("Custom", v.as_field_value())],
+        }
+    }
+}
+impl AsFieldValue for Color {
+    fn as_field_value(&self) -> FieldValue<'_> { FieldValue::Reflect(self) }
+}
+
+#[derive(Debug, Clone)]
+pub struct Profile<'x> {
+    pub id: u64,
+    pub name: Cow<'x, str>,
+    pub scores: Vec<i32>,
+    pub color: Color,
+    pub tags: Map<Cow<'x, str>, UInt>,
+    pub verified: bool, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+    pub avatar: Bytes<'x>,
+    pub public: bool,
+}
+impl<'x> Profile<'x> {
+    pub fn new(id: u64, name: Cow<'x, str>, scores: Vec<i32>, color: Color, tags: Map<Cow<'x, str>, UInt>, avatar: Bytes<'x>, public: bool) -> Self {
+        Self { id, name, scores, color, tags, verified: false, nickname: None, avatar, public }
+    }
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+}
+impl<'x> PBType<'x> for Profile<'x> {
+    const TYPE_NAME: &'static str = "Profile";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 16;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w)?;
+        self.name.serialize(w)?;
+        self.scores.serialize(w)?;
+        self.color.serialize(w)?;
+        self.tags.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.verified { flags |= 1 << 0 }
+        if self.nickname.is_some() { flags |= 1 << 1 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        self.avatar.serialize(w)?;
+        self.public.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_id = u64::deserialize_stream(r).at_path("id")?;
+        let field_name = Cow::<'x, str>::deserialize_stream(r).at_path("name")?;
+        let field_scores = Vec::<i32>::deserialize_stream(r).at_path("scores")?;
+        let field_color = Color::deserialize_stream(r).at_path("color")?;
+        let field_tags = Map::<Cow::<'x, str>, UInt>::deserialize_stream(r).at_path("tags")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_verified = (field_flags & (1 << 0)) != 0;
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("nickname")?)
+        } else { None };
+        let field_avatar = Bytes::<'x>::deserialize_stream(r).at_path("avatar")?;
+        let field_public = bool::deserialize_stream(r).at_path("public")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+            name: field_name,
+            scores: field_scores,
+            color: field_color,
+            tags: field_tags,
+            verified: flag_verified,
+            nickname: flag_nickname,
+            avatar: field_avatar,
+            public: field_public,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_id = u64::deserialize(r).at_path("id")?;
+        let field_name = Cow::<'x, str>::deserialize(r).at_path("name")?;
+        let field_scores = Vec::<i32>::deserialize(r).at_path("scores")?;
+        let field_color = Color::deserialize(r).at_path("color")?;
+        let field_tags = Map::<Cow::<'x, str>, UInt>::deserialize(r).at_path("tags")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_verified = (field_flags & (1 << 0)) != 0;
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("nickname")?)
+        } else { None };
+        let field_avatar = Bytes::<'x>::deserialize(r).at_path("avatar")?;
+        let field_public = bool::deserialize(r).at_path("public")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            id: field_id,
+            name: field_name,
+            scores: field_scores,
+            color: field_color,
+            tags: field_tags,
+            verified: flag_verified,
+            nickname: flag_nickname,
+            avatar: field_avatar,
+            public: field_public,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.id.deserialize_into(r).at_path("id")?;
+        self.name.deserialize_into(r).at_path("name")?;
+        self.scores.deserialize_into(r).at_path("scores")?;
+        self.color.deserialize_into(r).at_path("color")?;
+        self.tags.deserialize_into(r).at_path("tags")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.verified = (field_flags & (1 << 0)) != 0;
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 1)) != 0, r).at_path("nickname")?;
+        self.avatar.deserialize_into(r).at_path("avatar")?;
+        self.public.deserialize_into(r).at_path("public")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+impl<'x> PBReflect for Profile<'x> {
+    fn type_name(&self) -> &'static str { "Profile" }
+    fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)> {
+        vec![
+            ("id", self.id.as_field_value()),
+            ("name", self.name.as_field_value()),
+            ("scores", self.scores.as_field_value()),
+            ("color", self.color.as_field_value()),
+            ("tags", self.tags.as_field_value()),
+            ("verified", self.verified.as_field_value()),
+            ("nickname", self.nickname.as_field_value()),
+            ("avatar", self.avatar.as_field_value()),
+            ("public", self.public.as_field_value()),
+        ]
+    }
+}
+impl<'x> AsFieldValue for Profile<'x> {
+    fn as_field_value(&self) -> FieldValue<'_> { FieldValue::Reflect(self) }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,264 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Message",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Message<'x> {
+    pub id: u32,
+    pub text: Cow<'x, str>,
+    pub tags: Vec<Cow<'x, str>>,
+    pub payload: Bytes<'x>,
+    pub urgent: bool, // Flag of `flags`
+    pub reply_to: Option<Cow<'x, str>>, // Flag of `flags`
+    pub edited_at: Option<u64>, // Flag of `flags`
+}
+impl<'x> Message<'x> {
+    pub fn new(id: u32, text: Cow<'x, str>, tags: Vec<Cow<'x, str>>, payload: Bytes<'x>) -> Self {
+        Self { id, text, tags, payload, urgent: false, reply_to: None, edited_at: None }
+    }
+    pub fn with_urgent(mut self, urgent: bool) -> Self {
+        self.urgent = urgent;
+        self
+    }
+    pub fn with_reply_to(mut self, reply_to: Cow<'x, str>) -> Self {
+        self.reply_to = Some(reply_to);
+        self
+    }
+    pub fn with_edited_at(mut self, edited_at: u64) -> Self {
+        self.edited_at = Some(edited_at);
+        self
+    }
+}
+impl<'x> PBType<'x> for Message<'x> {
+    const TYPE_NAME: &'static str = "Message";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 9;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w)?;
+        self.text.serialize(w)?;
+        self.tags.serialize(w)?;
+        self.payload.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(3 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.urgent { flags |= 1 << 0 }
+        if self.reply_to.is_some() { flags |= 1 << 1 }
+        if self.edited_at.is_some() { flags |= 1 << 2 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.reply_to {
+            v.serialize(w)?;
+        }
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.edited_at {
+            v.serialize(w)?;
+        }
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_id = u32::deserialize_stream(r).at_path("id")?;
+        let field_text = Cow::<'x, str>::deserialize_stream(r).at_path("text")?;
+        let field_tags = Vec::<Cow::<'x, str>>::deserialize_stream(r).at_path("tags")?;
+        let field_payload = Bytes::<'x>::deserialize_stream(r).at_path("payload")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_urgent = (field_flags & (1 << 0)) != 0;
+        let flag_reply_to = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("reply_to")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_edited_at = if (field_flags & (1 << 2)) != 0 {
+            Some(u64::deserialize_stream(_extension_reader).at_path("edited_at")?)
+        } else { None };
+        Ok(Self {
+            id: field_id,
+            text: field_text,
+            tags: field_tags,
+            payload: field_payload,
+            urgent: flag_urgent,
+            reply_to: flag_reply_to,
+            edited_at: flag_edited_at,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_id = u32::deserialize(r).at_path("id")?;
+        let field_text = Cow::<'x, str>::deserialize(r).at_path("text")?;
+        let field_tags = Vec::<Cow::<'x, str>>::deserialize(r).at_path("tags")?;
+        let field_payload = Bytes::<'x>::deserialize(r).at_path("payload")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_urgent = (field_flags & (1 << 0)) != 0;
+        let flag_reply_to = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("reply_to")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_edited_at = if (field_flags & (1 << 2)) != 0 {
+            Some(u64::deserialize_stream(_extension_reader).at_path("edited_at")?)
+        } else { None };
+        Ok(Self {
+            id: field_id,
+            text: field_text,
+            tags: field_tags,
+            payload: field_payload,
+            urgent: flag_urgent,
+            reply_to: flag_reply_to,
+            edited_at: flag_edited_at,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.id.deserialize_into(r).at_path("id")?;
+        self.text.deserialize_into(r).at_path("text")?;
+        self.tags.deserialize_into(r).at_path("tags")?;
+        self.payload.deserialize_into(r).at_path("payload")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.urgent = (field_flags & (1 << 0)) != 0;
+        deserialize_option_into(&mut self.reply_to, (field_flags & (1 << 1)) != 0, r).at_path("reply_to")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.edited_at, (field_flags & (1 << 2)) != 0, _extension_reader).at_path("edited_at")?;
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
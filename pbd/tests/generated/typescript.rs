@@ -0,0 +1,561 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 1;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "MoodLayer0",
+    "Numbers",
+    "Profile",
+    "Presence",
+    "Mood",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MoodLayer0 {
+    Neutral,
+    Happy,
+}
+impl<'x> PBType<'x> for MoodLayer0 {
+    const TYPE_NAME: &'static str = "Mood";
+    const MAX_SIZE: Option<usize> = Some(1);
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Neutral => {
+                0u8.serialize(w)?;
+            }
+            Self::Happy => {
+                1u8.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            _ => {
+                discard_extension(r)?;
+                Self::Neutral
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            _ => {
+                discard_extension(r)?;
+                Self::Neutral
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Numbers {
+    pub uints: Vec<UInt>,
+    pub sints: Vec<SInt>,
+    pub big: u64,
+    pub small: i32,
+}
+impl<'x> PBType<'x> for Numbers {
+    const TYPE_NAME: &'static str = "Numbers";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 15;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.uints.serialize(w)?;
+        self.sints.serialize(w)?;
+        self.big.serialize(w)?;
+        w.write_all(&self.small.to_le_bytes())?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_uints = Vec::<UInt>::deserialize_stream(r).at_path("uints")?;
+        let field_sints = Vec::<SInt>::deserialize_stream(r).at_path("sints")?;
+        let field_big = u64::deserialize_stream(r).at_path("big")?;
+        let field_small = i32::from_le_bytes({
+            let mut bytes = [0; 4];
+            io::Read::read_exact(r, &mut bytes).at_path("small")?;
+            bytes
+        });
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            uints: field_uints,
+            sints: field_sints,
+            big: field_big,
+            small: field_small,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_uints = Vec::<UInt>::deserialize(r).at_path("uints")?;
+        let field_sints = Vec::<SInt>::deserialize(r).at_path("sints")?;
+        let field_big = u64::deserialize(r).at_path("big")?;
+        let field_small = i32::from_le_bytes({
+            let mut bytes = [0; 4];
+            io::Read::read_exact(r, &mut bytes).at_path("small")?;
+            bytes
+        });
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            uints: field_uints,
+            sints: field_sints,
+            big: field_big,
+            small: field_small,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.uints.deserialize_into(r).at_path("uints")?;
+        self.sints.deserialize_into(r).at_path("sints")?;
+        self.big.deserialize_into(r).at_path("big")?;
+        let mut bytes = [0; 4];
+        io::Read::read_exact(r, &mut bytes).at_path("small")?;
+        self.small = i32::from_le_bytes(bytes);
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Profile<'x> {
+    pub id: u32,
+    pub admin: bool, // Flag of `flags`
+    pub nickname: Option<Cow<'x, str>>, // Flag of `flags`
+    pub bio: Option<Cow<'x, str>>, // Flag of `flags`
+    pub pinned: bool, // Flag of `flags`
+    pub numbers: Numbers,
+}
+impl<'x> Profile<'x> {
+    pub fn new(id: u32, numbers: Numbers) -> Self {
+        Self { id, admin: false, nickname: None, bio: None, pinned: false, numbers }
+    }
+    pub fn with_admin(mut self, admin: bool) -> Self {
+        self.admin = admin;
+        self
+    }
+    pub fn with_nickname(mut self, nickname: Cow<'x, str>) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+    pub fn with_bio(mut self, bio: Cow<'x, str>) -> Self {
+        self.bio = Some(bio);
+        self
+    }
+    pub fn with_pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+}
+impl<'x> PBType<'x> for Profile<'x> {
+    const TYPE_NAME: &'static str = "Profile";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 21;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.id.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(4 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.admin { flags |= 1 << 0 }
+        if self.nickname.is_some() { flags |= 1 << 1 }
+        if self.bio.is_some() { flags |= 1 << 2 }
+        if self.pinned { flags |= 1 << 3 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.nickname {
+            v.serialize(w)?;
+        }
+        self.numbers.serialize(w)?;
+        let real_w = w;
+        let mut bytes = Bytes(Cow::Owned(Vec::new()));
+        let w = bytes.0.to_mut();
+        if let Some(ref v) = self.bio {
+            v.serialize(w)?;
+        }
+        bytes.serialize(real_w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_id = u32::deserialize_stream(r).at_path("id")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_admin = (field_flags & (1 << 0)) != 0;
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(r).at_path("nickname")?)
+        } else { None };
+        let field_numbers = Numbers::deserialize_stream(r).at_path("numbers")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_bio = if (field_flags & (1 << 2)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        let flag_pinned = (field_flags & (1 << 3)) != 0;
+        Ok(Self {
+            id: field_id,
+            admin: flag_admin,
+            nickname: flag_nickname,
+            bio: flag_bio,
+            pinned: flag_pinned,
+            numbers: field_numbers,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_id = u32::deserialize(r).at_path("id")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_admin = (field_flags & (1 << 0)) != 0;
+        let flag_nickname = if (field_flags & (1 << 1)) != 0 {
+            Some(Cow::<'x, str>::deserialize(r).at_path("nickname")?)
+        } else { None };
+        let field_numbers = Numbers::deserialize(r).at_path("numbers")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        let flag_bio = if (field_flags & (1 << 2)) != 0 {
+            Some(Cow::<'x, str>::deserialize_stream(_extension_reader).at_path("bio")?)
+        } else { None };
+        let flag_pinned = (field_flags & (1 << 3)) != 0;
+        Ok(Self {
+            id: field_id,
+            admin: flag_admin,
+            nickname: flag_nickname,
+            bio: flag_bio,
+            pinned: flag_pinned,
+            numbers: field_numbers,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.id.deserialize_into(r).at_path("id")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        self.admin = (field_flags & (1 << 0)) != 0;
+        deserialize_option_into(&mut self.nickname, (field_flags & (1 << 1)) != 0, r).at_path("nickname")?;
+        self.numbers.deserialize_into(r).at_path("numbers")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        deserialize_option_into(&mut self.bio, (field_flags & (1 << 2)) != 0, _extension_reader).at_path("bio")?;
+        self.pinned = (field_flags & (1 << 3)) != 0;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Presence {
+    pub empty: Option<Void>,
+    pub nested: Option<Option<u8>>,
+    pub nothing: Option<Void>, // Flag of `flags`
+    pub maybe: Option<Option<u8>>, // Flag of `flags`
+}
+impl Presence {
+    pub fn new(empty: Option<Void>, nested: Option<Option<u8>>) -> Self {
+        Self { empty, nested, nothing: None, maybe: None }
+    }
+    pub fn with_nothing(mut self, nothing: Void) -> Self {
+        self.nothing = Some(nothing);
+        self
+    }
+    pub fn with_maybe(mut self, maybe: Option<u8>) -> Self {
+        self.maybe = Some(maybe);
+        self
+    }
+}
+impl<'x> PBType<'x> for Presence {
+    const TYPE_NAME: &'static str = "Presence";
+    const MAX_SIZE: Option<usize> = Some(8);
+    const MIN_SIZE: usize = 4;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.empty.serialize(w)?;
+        self.nested.serialize(w)?;
+        // If you get an error here, this type doesn't support flags.
+        let mut flags: u8 = 0.try_into().unwrap();
+        const _: () = assert!(2 <= 8 * core::mem::size_of::<u8>(), "not enough bits for the flags of `flags`");
+        if self.nothing.is_some() { flags |= 1 << 0 }
+        if self.maybe.is_some() { flags |= 1 << 1 }
+        flags.serialize(w)?;
+        if let Some(ref v) = self.nothing {
+            v.serialize(w)?;
+        }
+        if let Some(ref v) = self.maybe {
+            v.serialize(w)?;
+        }
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_empty = Option::<Void>::deserialize_stream(r).at_path("empty")?;
+        let field_nested = Option::<Option::<u8>>::deserialize_stream(r).at_path("nested")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        let flag_nothing = if (field_flags & (1 << 0)) != 0 {
+            Some(Void::deserialize_stream(r).at_path("nothing")?)
+        } else { None };
+        let flag_maybe = if (field_flags & (1 << 1)) != 0 {
+            Some(Option::<u8>::deserialize_stream(r).at_path("maybe")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            empty: field_empty,
+            nested: field_nested,
+            nothing: flag_nothing,
+            maybe: flag_maybe,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_empty = Option::<Void>::deserialize(r).at_path("empty")?;
+        let field_nested = Option::<Option::<u8>>::deserialize(r).at_path("nested")?;
+        let field_flags = u8::deserialize(r).at_path("flags")?;
+        let flag_nothing = if (field_flags & (1 << 0)) != 0 {
+            Some(Void::deserialize(r).at_path("nothing")?)
+        } else { None };
+        let flag_maybe = if (field_flags & (1 << 1)) != 0 {
+            Some(Option::<u8>::deserialize(r).at_path("maybe")?)
+        } else { None };
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            empty: field_empty,
+            nested: field_nested,
+            nothing: flag_nothing,
+            maybe: flag_maybe,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.empty.deserialize_into(r).at_path("empty")?;
+        self.nested.deserialize_into(r).at_path("nested")?;
+        let field_flags = u8::deserialize_stream(r).at_path("flags")?;
+        deserialize_option_into(&mut self.nothing, (field_flags & (1 << 0)) != 0, r).at_path("nothing")?;
+        deserialize_option_into(&mut self.maybe, (field_flags & (1 << 1)) != 0, r).at_path("maybe")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Mood<'x> {
+    Neutral,
+    Happy,
+    Scared,
+    ConcernedAbout(Cow<'x, str>),
+}
+impl<'x> PBType<'x> for Mood<'x> {
+    const TYPE_NAME: &'static str = "Mood";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Neutral => {
+                0u8.serialize(w)?;
+            }
+            Self::Happy => {
+                1u8.serialize(w)?;
+            }
+            Self::Scared => {
+                2u8.serialize(w)?;
+                // Skipped extension:
+                UInt(0).serialize(w)?;
+            }
+            Self::ConcernedAbout(value) => {
+                3u8.serialize(w)?;
+                // Extension:
+                let real_w = w;
+                let mut bytes = Bytes(Cow::Owned(Vec::new()));
+                let w = bytes.0.to_mut();
+                value.serialize(w)?;
+                bytes.serialize(real_w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                UInt::deserialize_stream(r)?;
+                Self::Scared
+            }
+            3 => {
+                UInt::deserialize_stream(r)?;
+                Self::ConcernedAbout(Cow::<'x, str>::deserialize_stream(r).at_path("ConcernedAbout")?)
+            }
+            _ => {
+                discard_extension(r)?;
+                Self::Neutral
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                UInt::deserialize(r)?;
+                Self::Scared
+            }
+            3 => {
+                UInt::deserialize(r)?;
+                Self::ConcernedAbout(Cow::<'x, str>::deserialize(r).at_path("ConcernedAbout")?)
+            }
+            _ => {
+                discard_extension(r)?;
+                Self::Neutral
+            }
+        })
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
@@ -0,0 +1,408 @@
+#![allow(nonstandard_style)]
+///! This file was automatically generated by Punybuf.
+///! It's best you don't change anything.
+
+extern crate alloc;
+
+#[allow(unused_imports)]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use punybuf_common::io;
+use punybuf_common::*;
+
+/// The highest layer of the definition, i.e. the newest version of the protocol this code supports
+pub const HIGHEST_LAYER: u32 = 0;
+
+/// The names of all types in this file
+pub const TYPE_NAMES: &[&str] = &[
+    "Map",
+    "KeyPair",
+    "Done",
+    "Mood",
+    "NewerMood",
+    "Sealed",
+    "Status",
+];
+
+impl<'x, K: PBType<'x> + punybuf_common::MapKey, V: PBType<'x>> HashMapConvertible<K, V> for Map<K, V> {
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool) {
+        let mut hm = punybuf_common::ConvertibleMap::new();
+        let mut duplicates = false;
+        for pair in self {
+            if hm.insert(pair.key, pair.value).is_some() {
+                duplicates = true;
+            }
+        }
+        (hm, duplicates)
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self {
+        let mut this = Self::new();
+        for (key, value) in map.into_iter() {
+            this.push(KeyPair { key, value });
+        }
+        this
+    }
+}
+/// A map type. This isn't marked `@builtin`, but implementations may, for their
+/// own convinience, allow to convert this type to their own `HashMap`
+/// implementation. This conversion may fail, as this type enforces no rules
+/// on the uniquness of the keys.
+/// 
+/// In the case that one of the keys is not unique, the implementation SHOULD NOT
+/// reject a frame or fail the deserialization completely, but should react to this error
+/// in some other way, like telling the user or throwing a more specific error.
+pub type Map<K, V> = Vec<KeyPair<K, V>>;
+#[derive(Debug, Clone)]
+pub struct KeyPair<K, V> {
+    pub key: K,
+    pub value: V,
+}
+impl<'x, K: PBType<'x>, V: PBType<'x>> PBType<'x> for KeyPair<K, V> {
+    const TYPE_NAME: &'static str = "KeyPair";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = K::MIN_SIZE + V::MIN_SIZE;
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.key.serialize(w)?;
+        self.value.serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_key = K::deserialize_stream(r).at_path("key")?;
+        let field_value = V::deserialize_stream(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_key = K::deserialize(r).at_path("key")?;
+        let field_value = V::deserialize(r).at_path("value")?;
+        Ok(Self {
+            key: field_key,
+            value: field_value,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.key.deserialize_into(r).at_path("key")?;
+        self.value.deserialize_into(r).at_path("value")?;
+        Ok(())
+    }
+}
+
+/// An empty type, used as a return type for a command that doesn't need to return
+/// anything, but needs to indicate that it's been recieved or that the requested
+/// operation finished processing.
+/// 
+/// Note that this is very different from the [`Void`](Void) type that means that
+/// the reciever will never return any acknoledgement to the sender.
+#[derive(Debug, Clone)]
+pub struct Done;
+impl<'x> PBType<'x> for Done {
+    const TYPE_NAME: &'static str = "Done";
+    const MAX_SIZE: Option<usize> = Some(0);
+    const MIN_SIZE: usize = 0;
+    const FIXED_SIZE: Option<usize> = Some(0);
+    fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[
+        ("@sealed", None),
+    ] }
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        Ok(Self {
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Mood<'x> {
+    Neutral,
+    Happy,
+    ThinkingAbout(Cow<'x, str>),
+    /// A variant added in a later version, with its discriminant and encoded value
+    UnknownVariant(u8, Bytes<'x>),
+}
+impl<'x> PBType<'x> for Mood<'x> {
+    const TYPE_NAME: &'static str = "Mood";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Neutral => {
+                0u8.serialize(w)?;
+            }
+            Self::Happy => {
+                1u8.serialize(w)?;
+            }
+            Self::ThinkingAbout(value) => {
+                2u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+            Self::UnknownVariant(discriminant, bytes) => {
+                discriminant.serialize(w)?;
+                bytes.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                Self::ThinkingAbout(Cow::<'x, str>::deserialize_stream(r).at_path("ThinkingAbout")?)
+            }
+            discriminant => {
+                Self::UnknownVariant(discriminant, Bytes::deserialize_stream(r)?)
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                Self::ThinkingAbout(Cow::<'x, str>::deserialize(r).at_path("ThinkingAbout")?)
+            }
+            discriminant => {
+                Self::UnknownVariant(discriminant, Bytes::deserialize(r)?)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum NewerMood<'x> {
+    Neutral,
+    Happy,
+    ThinkingAbout(Cow<'x, str>),
+    ConfusedAbout(Cow<'x, str>),
+    Hungry,
+    /// A variant added in a later version, with its discriminant and encoded value
+    UnknownVariant(u8, Bytes<'x>),
+}
+impl<'x> PBType<'x> for NewerMood<'x> {
+    const TYPE_NAME: &'static str = "NewerMood";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 1;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Neutral => {
+                0u8.serialize(w)?;
+            }
+            Self::Happy => {
+                1u8.serialize(w)?;
+            }
+            Self::ThinkingAbout(value) => {
+                2u8.serialize(w)?;
+                value.serialize(w)?;
+            }
+            Self::ConfusedAbout(value) => {
+                3u8.serialize(w)?;
+                // Extension:
+                let real_w = w;
+                let mut bytes = Bytes(Cow::Owned(Vec::new()));
+                let w = bytes.0.to_mut();
+                value.serialize(w)?;
+                bytes.serialize(real_w)?;
+            }
+            Self::Hungry => {
+                4u8.serialize(w)?;
+                // Skipped extension:
+                UInt(0).serialize(w)?;
+            }
+            Self::UnknownVariant(discriminant, bytes) => {
+                discriminant.serialize(w)?;
+                bytes.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                Self::ThinkingAbout(Cow::<'x, str>::deserialize_stream(r).at_path("ThinkingAbout")?)
+            }
+            3 => {
+                UInt::deserialize_stream(r)?;
+                Self::ConfusedAbout(Cow::<'x, str>::deserialize_stream(r).at_path("ConfusedAbout")?)
+            }
+            4 => {
+                UInt::deserialize_stream(r)?;
+                Self::Hungry
+            }
+            discriminant => {
+                Self::UnknownVariant(discriminant, Bytes::deserialize_stream(r)?)
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Neutral
+            }
+            1 => {
+                Self::Happy
+            }
+            2 => {
+                Self::ThinkingAbout(Cow::<'x, str>::deserialize(r).at_path("ThinkingAbout")?)
+            }
+            3 => {
+                UInt::deserialize(r)?;
+                Self::ConfusedAbout(Cow::<'x, str>::deserialize(r).at_path("ConfusedAbout")?)
+            }
+            4 => {
+                UInt::deserialize(r)?;
+                Self::Hungry
+            }
+            discriminant => {
+                Self::UnknownVariant(discriminant, Bytes::deserialize(r)?)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Sealed {
+    Yes,
+    No,
+}
+impl<'x> PBType<'x> for Sealed {
+    const TYPE_NAME: &'static str = "Sealed";
+    const MAX_SIZE: Option<usize> = Some(1);
+    const MIN_SIZE: usize = 1;
+    const FIXED_SIZE: Option<usize> = Some(1);
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Yes => {
+                0u8.serialize(w)?;
+            }
+            Self::No => {
+                1u8.serialize(w)?;
+            }
+        }
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let discriminant = u8::deserialize_stream(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Yes
+            }
+            1 => {
+                Self::No
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let discriminant = u8::deserialize(r)?;
+        Ok(match discriminant {
+            0 => {
+                Self::Yes
+            }
+            1 => {
+                Self::No
+            }
+            _ => {
+                Err(io::Error::other("Unknown enum discriminant; enum is not extensible"))?
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Status<'x> {
+    pub mood: Mood<'x>,
+    pub sealed: Sealed,
+}
+impl<'x> PBType<'x> for Status<'x> {
+    const TYPE_NAME: &'static str = "Status";
+    const MAX_SIZE: Option<usize> = None;
+    const MIN_SIZE: usize = 3;
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.mood.serialize(w)?;
+        self.sealed.serialize(w)?;
+        UInt(0).serialize(w)?;
+        Ok(())
+    }
+    fn deserialize_stream<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let field_mood = Mood::<'x>::deserialize_stream(r).at_path("mood")?;
+        let field_sealed = Sealed::deserialize_stream(r).at_path("sealed")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            mood: field_mood,
+            sealed: field_sealed,
+        })
+    }
+    fn deserialize<'a: 'x>(r: &mut &'a [u8]) -> io::Result<Self> {
+        let field_mood = Mood::<'x>::deserialize(r).at_path("mood")?;
+        let field_sealed = Sealed::deserialize(r).at_path("sealed")?;
+        let mut _extension_bytes = Bytes::deserialize(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(Self {
+            mood: field_mood,
+            sealed: field_sealed,
+        })
+    }
+    fn deserialize_into<R: io::Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.mood.deserialize_into(r).at_path("mood")?;
+        self.sealed.deserialize_into(r).at_path("sealed")?;
+        let mut _extension_bytes = Bytes::deserialize_stream(r)?;
+        let _extension_reader = &mut &_extension_bytes.0[..];
+        Ok(())
+    }
+}
+
+
+// Because of Rust's orphan rules, we can't put this in the punybuf_common crate.
+
+pub struct DuplicateKeysFound;
+pub trait HashMapConvertible<K, V>: Sized {
+    /// Converts the value to a `HashMap`, overriding duplicate keys.  
+    /// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
+    fn to_map_allow_duplicates(self) -> (punybuf_common::ConvertibleMap<K, V>, bool);
+
+    /// Returns an error if there were any duplicate keys in the Map
+    fn try_to_map(self) -> Result<punybuf_common::ConvertibleMap<K, V>, DuplicateKeysFound> {
+        let (map, duplicates_found) = self.to_map_allow_duplicates();
+        if !duplicates_found {
+            Ok(map)
+        } else {
+            Err(DuplicateKeysFound)
+        }
+    }
+    fn from_map(map: punybuf_common::ConvertibleMap<K, V>) -> Self;
+}
+
+
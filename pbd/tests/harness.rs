@@ -163,7 +163,7 @@ fn run_test(file: impl AsRef<Path>, expected: Option<String>) -> Result<Option<S
 			));
 		}
 	};
-	let definiton = match parsed.resolve(true) {
+	let (definiton, warnings) = match parsed.resolve_with_warnings(true) {
 		Ok(x) => x,
 		Err(err) => {
 			if let Some(expected) = expected {
@@ -195,6 +195,35 @@ fn run_test(file: impl AsRef<Path>, expected: Option<String>) -> Result<Option<S
 			));
 		}
 	};
+	if let Some(warning) = warnings.first() {
+		if let Some(expected) = expected {
+			let mut lines = expected.lines();
+			let expected_status = lines.next().expect("invalid test result file");
+			if expected_status != "!warning/validator" {
+				panic!(
+					"invalid status: expected {expected_status:?}, \
+					got \"!warning/validator\" with this warning:\n\
+					{warning}"
+				);
+			}
+			let expected_warning = lines.next().unwrap_or("<no warning>");
+			if expected_warning != warning.error.content {
+				return Ok(Some(
+					format!(
+						"did not match the exact warning: got `{}`, expected `{}`",
+						warning.error.content, expected_warning
+					)
+				));
+			}
+			return Ok(None);
+		}
+		return Err(format!(
+			"!warning/validator\n\
+			{}\n\
+			# This file was auto-generated by harness.rs",
+			warning.error.content
+		));
+	}
 	let json_result = convert_full_definition(&definiton);
 	if let Some(mut expected) = expected {
 		let mut lines = expected.lines();
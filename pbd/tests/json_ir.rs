@@ -0,0 +1,121 @@
+//! Using `pbd` as a library to work with the JSON IR.
+
+use json::{array, JsonValue};
+use punybuf::{
+	pbtyperef_from_json, pbtyperef_to_json, convert_full_definition, parse_full_definition,
+	PBTypeRef, PunybufParser, RustCodegen
+};
+
+#[test]
+fn refs_round_trip() {
+	let refs = [
+		array!["U8", 0, [], true],
+		array!["T", null, [], false],
+		array!["Point", 3, [], false],
+		array!["Map", 0, [["String", 0, [], true], ["Array", 0, [["T", null, [], false]], true]], true],
+		array!["Wrapper", 2, [["Optional", 0, [["Point", 1, [], true]], true]], true],
+	];
+	for json in refs {
+		let refr = pbtyperef_from_json(&json).expect("failed to read the ref");
+		assert_eq!(pbtyperef_to_json(&refr), json);
+	}
+}
+
+#[test]
+fn refs_from_json() {
+	let refr = pbtyperef_from_json(
+		&array!["Map", 0, [["String", 0, [], true], ["V", null, [], false]], true]
+	).unwrap();
+	assert_eq!(refr.name(), "Map");
+	assert_eq!(refr.layer(), Some(0));
+	assert!(refr.is_highest_layer());
+
+	let [key, value] = refr.generics() else {
+		panic!("expected 2 generics, got {:?}", refr.generics());
+	};
+	assert_eq!((key.name(), key.layer()), ("String", Some(0)));
+	assert_eq!((value.name(), value.layer()), ("V", None));
+
+	let built = PBTypeRef::new("Map", Some(0), vec![
+		PBTypeRef::new("String", Some(0), vec![], true),
+		PBTypeRef::new("V", None, vec![], false),
+	], true);
+	assert_eq!(pbtyperef_to_json(&built), pbtyperef_to_json(&refr));
+
+	for invalid in [array![], array![1, 0, [], true], array!["U8", "0", [], true], array!["U8", 0]] {
+		assert!(pbtyperef_from_json(&invalid).is_err(), "{invalid} should be rejected");
+	}
+}
+
+/// Finds all refs in the JSON IR of a definition
+fn collect_refs<'a>(value: &'a JsonValue, refs: &mut Vec<&'a JsonValue>) {
+	match value {
+		JsonValue::Object(obj) => for (key, value) in obj.iter() {
+			if matches!(key, "alias" | "value" | "ret" | "ref") && value.is_array() {
+				refs.push(value);
+			} else {
+				collect_refs(value, refs);
+			}
+		},
+		JsonValue::Array(values) => for value in values {
+			collect_refs(value, refs);
+		},
+		_ => {}
+	}
+}
+
+#[test]
+fn definition_refs_round_trip() {
+	let def = PunybufParser::parse_file("test_files/layer-resolution.pbd")
+		.expect("failed to read the file").expect("failed to parse")
+		.resolve(true).expect("failed to resolve");
+	let ir = json::parse(&convert_full_definition(&def)).unwrap();
+
+	let mut refs = vec![];
+	collect_refs(&ir, &mut refs);
+	assert!(refs.iter().any(|refr| refr[1].as_u32().is_some_and(|layer| layer > 0)));
+	for json in refs {
+		let refr = pbtyperef_from_json(json).expect("failed to read the ref");
+		assert_eq!(&pbtyperef_to_json(&refr), json);
+	}
+}
+
+#[test]
+fn definitions_round_trip() {
+	for file in ["layer-resolution", "codegen/commands", "codegen/extension-flags", "codegen/stream"] {
+		let def = PunybufParser::parse_file(format!("test_files/{file}.pbd"))
+			.expect("failed to read the file").expect("failed to parse")
+			.resolve(true).expect("failed to resolve");
+		let ir = convert_full_definition(&def);
+		let parsed = parse_full_definition(&ir).unwrap_or_else(|e| panic!("failed to read the IR of {file}: {e}"));
+		assert_eq!(convert_full_definition(&parsed), ir, "{file} changed after a round trip");
+		// the IR has everything the codegen needs
+		assert_eq!(
+			RustCodegen::new(false, true, &parsed).codegen(),
+			RustCodegen::new(false, true, &def).codegen(),
+		);
+	}
+}
+
+#[test]
+fn definitions_with_missing_fields_are_rejected() {
+	let def = PunybufParser::parse_file("test_files/codegen/commands.pbd")
+		.expect("failed to read the file").expect("failed to parse")
+		.resolve(true).expect("failed to resolve");
+	let ir = json::parse(&convert_full_definition(&def)).unwrap();
+
+	let broken: [(&dyn Fn(&mut JsonValue), &str); 5] = [
+		(&|ir| { ir.remove("types"); }, "`types` should be an array"),
+		(&|ir| { ir["types"][0].remove("layer"); }, "invalid type #0: `Void`: `layer` should be a number"),
+		(&|ir| { ir["types"][0]["is"] = "union".into(); }, "`is` should be"),
+		(&|ir| { ir["commands"][0].remove("id"); }, "`moveCursor`: `id` should be a number"),
+		(&|ir| { ir["commands"][0]["ret"] = JsonValue::Null; }, "invalid reference"),
+	];
+	for (breakage, error) in broken {
+		let mut ir = ir.clone();
+		breakage(&mut ir);
+		let result = parse_full_definition(&ir.dump());
+		assert!(result.as_ref().is_err_and(|e| e.contains(error)), "expected `{error}`, got {:?}", result.err());
+	}
+	assert!(parse_full_definition("{").is_err());
+}
@@ -0,0 +1,48 @@
+//! Builds generated code against `punybuf_common` without its `std` feature
+
+use std::{fs, path::Path, process::Command};
+
+/// Generated files that don't need any optional features of `punybuf_common`
+const GENERATED: &[&str] = &[
+	"fixed_fields", "fixed_fields_inline", "empty_struct", "reflect", "reuse", "outcome", "unknown_enum",
+	"ordering", "stream", "extension_flags", "extension_flags_field", "commands", "bitmap", "inline_layers",
+	"aliases", "sealed", "min_size", "derives", "private_fields", "byte_order", "lazy_array", "phantom",
+	"single_variant", "layer_conversions", "recursive", "keep_extensions",
+];
+
+#[test]
+fn generated_code_builds_without_std() {
+	let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("no-std");
+	let src = dir.join("src");
+	fs::create_dir_all(&src).expect("failed to create the crate");
+
+	let common = fs::canonicalize("../rust-punybuf_common").expect("punybuf_common is missing");
+	let manifest = format!(
+		"[package]\n\
+		name = \"pbd-no-std\"\n\
+		version = \"0.0.0\"\n\
+		edition = \"2024\"\n\
+		\n\
+		[dependencies]\n\
+		punybuf_common = {{ path = {:?}, default-features = false }}\n\
+		\n\
+		[workspace]\n",
+		common.display().to_string(),
+	);
+	fs::write(dir.join("Cargo.toml"), manifest).expect("failed to write the manifest");
+
+	let mut lib = String::from("#![no_std]\n");
+	for generated in GENERATED {
+		let code = fs::read_to_string(format!("tests/generated/{generated}.rs")).expect("failed to read generated code");
+		fs::write(src.join(format!("{generated}.rs")), code).expect("failed to write generated code");
+		lib += &format!("#[allow(dead_code, unused)]\nmod {generated};\n");
+	}
+	fs::write(src.join("lib.rs"), lib).expect("failed to write lib.rs");
+
+	let output = Command::new(env!("CARGO"))
+		.args(["build", "--offline", "--quiet"])
+		.current_dir(&dir)
+		.output()
+		.expect("failed to run cargo");
+	assert!(output.status.success(), "the generated code doesn't build without std:\n{}", String::from_utf8_lossy(&output.stderr));
+}
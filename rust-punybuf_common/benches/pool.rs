@@ -0,0 +1,49 @@
+//! Compares reading frames into new buffers and decoding owned values with reading them
+//! into pooled buffers and decoding values that borrow from them.
+//! Run with `cargo bench --bench pool`.
+
+use std::{borrow::Cow, hint::black_box, time::{Duration, Instant}};
+
+use punybuf_common::{pool::{BufferPool, PooledReader}, read_framed_raw, write_framed_raw, Bytes, PBType};
+
+/// The best time out of a few runs of `f` over the whole `stream`
+fn measure(stream: &[u8], mut f: impl FnMut(&mut &[u8])) -> Duration {
+	(0..40).map(|_| {
+		let mut r = stream;
+		let start = Instant::now();
+		while !r.is_empty() {
+			f(&mut r);
+		}
+		start.elapsed()
+	}).min().unwrap()
+}
+
+fn main() {
+	// frames of a few names and an avatar, like a server would get them
+	let mut stream = vec![];
+	for i in 0..20_000 {
+		let mut body = vec![];
+		let names: Vec<Cow<str>> = (0..8).map(|n| Cow::Owned(format!("user {i} name {n}"))).collect();
+		names.serialize(&mut body).unwrap();
+		Bytes(Cow::Owned(vec![i as u8; 256])).serialize(&mut body).unwrap();
+		write_framed_raw(&mut stream, &body).unwrap();
+	}
+
+	let allocating = measure(&stream, |r| {
+		let frame = read_framed_raw(r).unwrap();
+		let slice = &mut &frame[..];
+		let names = Vec::<Cow<str>>::deserialize_stream(slice).unwrap();
+		let avatar = Bytes::deserialize_stream(slice).unwrap();
+		black_box((names, avatar));
+	});
+	let pool = BufferPool::new(1);
+	let pooled = measure(&stream, |r| {
+		let frame = PooledReader::new(r, pool.clone()).read_frame().unwrap();
+		let slice = &mut &frame[..];
+		let names = Vec::<Cow<str>>::deserialize(slice).unwrap();
+		let avatar = Bytes::deserialize(slice).unwrap();
+		black_box((names, avatar));
+	});
+	println!("allocating: {allocating:?}");
+	println!("pooled:     {pooled:?}");
+}
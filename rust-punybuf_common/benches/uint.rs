@@ -0,0 +1,68 @@
+//! Compares `UInt::serialize` with how it used to be written, one range at a time.
+//! Run with `cargo bench --bench uint`.
+
+use std::{hint::black_box, io::{self, Write}, time::{Duration, Instant}};
+
+use punybuf_common::{PBType, UInt};
+
+/// The previous `UInt::serialize`
+fn serialize_previous<W: Write>(mut uint: u64, w: &mut W) -> io::Result<()> {
+	if uint < 128 {
+		w.write_all(&uint.to_be_bytes()[7..8])?;
+	} else if uint < 16512 {
+		uint -= 128;
+		let bytes = &mut uint.to_be_bytes()[6..8];
+		bytes[0] |= 0b10_000000;
+		w.write_all(bytes)?;
+	} else if uint < 2113664 {
+		uint -= 16512;
+		let bytes = &mut uint.to_be_bytes()[5..8];
+		bytes[0] |= 0b110_00000;
+		w.write_all(bytes)?;
+	} else if uint < 68721590400 {
+		uint -= 2113664;
+		let bytes = &mut uint.to_be_bytes()[3..8];
+		bytes[0] |= 0b1110_0000;
+		w.write_all(bytes)?;
+	} else if uint < 1152921573328437376 {
+		uint -= 68721590400;
+		let bytes = &mut uint.to_be_bytes()[0..8];
+		bytes[0] |= 0b1111_0000;
+		w.write_all(bytes)?;
+	} else {
+		Err(io::Error::other("number too big (max 1152921573328437375)"))?;
+	}
+	Ok(())
+}
+
+/// The best time out of a few runs of `f` over all the `numbers`
+fn measure(numbers: &[u64], mut f: impl FnMut(u64, &mut Vec<u8>)) -> Duration {
+	let mut buf = Vec::with_capacity(numbers.len() * 8);
+	(0..40).map(|_| {
+		buf.clear();
+		let start = Instant::now();
+		for n in numbers {
+			f(black_box(*n), &mut buf);
+		}
+		black_box(&buf);
+		start.elapsed()
+	}).min().unwrap()
+}
+
+fn main() {
+	// every length, with small numbers being the most common
+	let mut numbers = vec![];
+	let mut state = 0x2545F4914F6CDD1Du64;
+	for _ in 0..1_000_000 {
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+		let max = [128, 128, 128, 128, 16512, 16512, 2113664, 68721590400, 1152921573328437376][(state % 9) as usize];
+		numbers.push(state % max);
+	}
+
+	let previous = measure(&numbers, |n, buf| serialize_previous(n, buf).unwrap());
+	let current = measure(&numbers, |n, buf| UInt(n).serialize(buf).unwrap());
+	println!("previous: {previous:?}");
+	println!("current:  {current:?}");
+}
@@ -0,0 +1,110 @@
+//! Compression for `Bytes` and `String` fields marked `@compress(zstd)`.
+//!
+//! A compressed value is encoded as the length of the uncompressed data (`UInt`),
+//! followed by the compressed data (`Bytes`). The generated code calls
+//! [`serialize_zstd`] and [`deserialize_zstd`] for these fields, so they keep
+//! their usual types.
+
+use std::{borrow::Cow, io::{self, Error, Read, Write}};
+
+use crate::{from_utf8_lossy_owned, invalid_utf8, Bytes, PBType, StrictString, UInt, MAX_BYTES_LENGTH};
+
+/// Types that may be stored compressed. Implemented for the types generated for `Bytes` and `String`.
+pub trait Compressible: Sized {
+	/// The bytes to compress
+	fn uncompressed(&self) -> &[u8];
+	/// Creates the value from the decompressed bytes
+	fn from_uncompressed(bytes: Vec<u8>) -> io::Result<Self>;
+}
+
+impl Compressible for Bytes<'_> {
+	fn uncompressed(&self) -> &[u8] {
+		&self.0
+	}
+	fn from_uncompressed(bytes: Vec<u8>) -> io::Result<Self> {
+		Ok(Self(Cow::Owned(bytes)))
+	}
+}
+
+impl Compressible for Cow<'_, str> {
+	fn uncompressed(&self) -> &[u8] {
+		self.as_bytes()
+	}
+	fn from_uncompressed(bytes: Vec<u8>) -> io::Result<Self> {
+		Ok(Cow::Owned(from_utf8_lossy_owned(bytes)))
+	}
+}
+
+impl Compressible for String {
+	fn uncompressed(&self) -> &[u8] {
+		self.as_bytes()
+	}
+	fn from_uncompressed(bytes: Vec<u8>) -> io::Result<Self> {
+		Ok(from_utf8_lossy_owned(bytes))
+	}
+}
+
+impl Compressible for StrictString<'_> {
+	fn uncompressed(&self) -> &[u8] {
+		self.0.as_bytes()
+	}
+	fn from_uncompressed(bytes: Vec<u8>) -> io::Result<Self> {
+		let string = String::from_utf8(bytes).map_err(|e| invalid_utf8(e.utf8_error()))?;
+		Ok(Self(Cow::Owned(string)))
+	}
+}
+
+fn compress(uncompressed: &[u8]) -> io::Result<Vec<u8>> {
+	zstd::bulk::compress(uncompressed, zstd::DEFAULT_COMPRESSION_LEVEL)
+}
+
+fn decompress<T: Compressible>(len: UInt, compressed: &[u8]) -> io::Result<T> {
+	let len: usize = len.into();
+	if len > MAX_BYTES_LENGTH {
+		return Err(Error::other("Bytes length too large"));
+	}
+	let bytes = zstd::bulk::decompress(compressed, len)?;
+	if bytes.len() != len {
+		return Err(Error::new(io::ErrorKind::InvalidData, "Decompressed length doesn't match"));
+	}
+	T::from_uncompressed(bytes)
+}
+
+/// Writes the length of `value`, followed by `value` compressed with zstd
+pub fn serialize_zstd<T: Compressible, W: Write>(value: &T, w: &mut W) -> io::Result<()> {
+	let uncompressed = value.uncompressed();
+	UInt(uncompressed.len() as u64).serialize(w)?;
+	Bytes(Cow::Owned(compress(uncompressed)?)).serialize(w)
+}
+
+/// Reads a value written by [`serialize_zstd`]
+pub fn deserialize_zstd<T: Compressible, R: Read>(r: &mut R) -> io::Result<T> {
+	let len = UInt::deserialize_stream(r)?;
+	let compressed = Bytes::deserialize_stream(r)?;
+	decompress(len, &compressed.0)
+}
+
+#[cfg(feature = "tokio")]
+pub mod tokio {
+	//! The same functions, for the code generated with `--rust:tokio`.
+
+	use std::{borrow::Cow, io};
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+	use crate::{tokio::PBType, Bytes, UInt};
+	pub use super::Compressible;
+
+	/// Writes the length of `value`, followed by `value` compressed with zstd
+	pub async fn serialize_zstd<T: Compressible + Sync, W: AsyncWriteExt + Unpin + Send>(value: &T, w: &mut W) -> io::Result<()> {
+		let uncompressed = value.uncompressed();
+		UInt(uncompressed.len() as u64).serialize(w).await?;
+		Bytes(Cow::Owned(super::compress(uncompressed)?)).serialize(w).await
+	}
+
+	/// Reads a value written by [`serialize_zstd`]
+	pub async fn deserialize_zstd<T: Compressible, R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<T> {
+		let len = UInt::deserialize_stream(r).await?;
+		let compressed = Bytes::deserialize_stream(r).await?;
+		super::decompress(len, &compressed.0)
+	}
+}
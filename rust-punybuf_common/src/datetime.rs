@@ -0,0 +1,55 @@
+//! `PBType` for `chrono::DateTime<Utc>`, used for the `DateTime` builtin.
+//!
+//! A `DateTime` is encoded as the seconds since the Unix epoch (`I64`), followed by
+//! the nanoseconds since the last whole second (`U32`). The time zone isn't part
+//! of the encoding, all timestamps are UTC.
+//!
+//! Like in `chrono`, a leap second is represented with nanoseconds in the range
+//! `1_000_000_000..2_000_000_000` on the last second of a minute. Any other
+//! nanosecond value that's out of the usual range fails the deserialization.
+
+use std::io::{self, Read, Write};
+
+pub use chrono::{DateTime, Utc};
+
+use crate::PBType;
+
+fn from_parts(secs: i64, nanos: u32) -> io::Result<DateTime<Utc>> {
+	DateTime::from_timestamp(secs, nanos)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "timestamp out of range"))
+}
+
+impl<'x> PBType<'x> for DateTime<Utc> {
+	const TYPE_NAME: &'static str = "DateTime";
+	const MIN_SIZE: usize = 12;
+	const MAX_SIZE: Option<usize> = Some(12);
+	const FIXED_SIZE: Option<usize> = Some(12);
+	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+		self.timestamp().serialize(w)?;
+		self.timestamp_subsec_nanos().serialize(w)
+	}
+	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+		let secs = i64::deserialize_stream(r)?;
+		let nanos = u32::deserialize_stream(r)?;
+		from_parts(secs, nanos)
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl<'x> crate::tokio::PBType<'x> for DateTime<Utc> {
+	const TYPE_NAME: &'static str = "DateTime";
+	const MIN_SIZE: usize = 12;
+	const MAX_SIZE: Option<usize> = Some(12);
+	const FIXED_SIZE: Option<usize> = Some(12);
+	async fn serialize<W: tokio::io::AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+		let mut buf = [0; 12];
+		buf[..8].copy_from_slice(&self.timestamp().to_be_bytes());
+		buf[8..].copy_from_slice(&self.timestamp_subsec_nanos().to_be_bytes());
+		w.write_all(&buf).await
+	}
+	async fn deserialize_stream<R: tokio::io::AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+		let secs = r.read_i64().await?;
+		let nanos = r.read_u32().await?;
+		from_parts(secs, nanos)
+	}
+}
@@ -0,0 +1,87 @@
+//! Breadcrumbs for deserialization errors.
+//!
+//! The generated code calls [`DecodePath::at_path`] with the name of every field and
+//! variant it deserializes. With the `debug-paths` feature enabled, a failure is wrapped
+//! in a [`DecodePathError`], so the error reads `failed at .user.address.zip: ...`
+//! instead of only saying what went wrong. Without the feature, `at_path` does nothing.
+
+use alloc::vec::Vec;
+use core::{error::Error, fmt::{self, Debug, Display}};
+
+use crate::io;
+
+/// The error that failed deserialization, along with the path to the value that caused it.
+///
+/// Only created with the `debug-paths` feature. The [`io::Error`] containing it keeps the
+/// [`io::ErrorKind`] of the original error.
+pub struct DecodePathError {
+	/// Field and variant names, innermost first, since they're pushed as the error bubbles up.
+	path: Vec<&'static str>,
+	source: io::Error,
+}
+
+impl DecodePathError {
+	/// The names of the fields and variants leading to the failed value, outermost first.
+	pub fn path(&self) -> impl Iterator<Item = &'static str> + '_ {
+		self.path.iter().rev().copied()
+	}
+	/// The original error.
+	pub fn error(&self) -> &io::Error {
+		&self.source
+	}
+}
+
+impl Display for DecodePathError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "failed at ")?;
+		for name in self.path() {
+			write!(f, ".{name}")?;
+		}
+		write!(f, ": {}", self.source)
+	}
+}
+
+impl Debug for DecodePathError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		Display::fmt(self, f)
+	}
+}
+
+impl Error for DecodePathError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		Some(&self.source)
+	}
+}
+
+pub trait DecodePath {
+	/// Records `name` as part of the path to the value that failed to deserialize.
+	/// Does nothing unless the `debug-paths` feature is enabled.
+	fn at_path(self, name: &'static str) -> Self;
+}
+
+impl<T> DecodePath for io::Result<T> {
+	#[inline(always)]
+	fn at_path(self, name: &'static str) -> Self {
+		#[cfg(feature = "debug-paths")]
+		return self.map_err(|err| push_path(err, name));
+		#[cfg(not(feature = "debug-paths"))]
+		{
+			let _ = name;
+			self
+		}
+	}
+}
+
+#[cfg(feature = "debug-paths")]
+#[cold]
+fn push_path(err: io::Error, name: &'static str) -> io::Error {
+	let kind = err.kind();
+	if err.get_ref().is_some_and(|inner| inner.is::<DecodePathError>()) {
+		let mut inner = err.into_inner()
+			.and_then(|inner| inner.downcast::<DecodePathError>().ok())
+			.expect("checked above");
+		inner.path.push(name);
+		return io::Error::new(kind, *inner);
+	}
+	io::Error::new(kind, DecodePathError { path: vec![name], source: err })
+}
@@ -0,0 +1,66 @@
+//! Hashing the encoded form of a value while it's written.
+//!
+//! Every value has exactly one encoding, so equal values always produce equal
+//! hashes, which makes the hash usable for deduplication and content addressing.
+
+use core::hash::Hasher;
+
+use crate::io::{self, Write};
+
+/// Writes everything into `W`, and feeds it to the hasher `H` at the same time.
+///
+/// To only compute the hash without keeping the bytes, write into [`io::sink()`].
+pub struct HashingWriter<W, H> {
+	inner: W,
+	hasher: H,
+}
+
+impl<W, H: Hasher> HashingWriter<W, H> {
+	pub fn new(inner: W, hasher: H) -> Self {
+		Self { inner, hasher }
+	}
+	/// The hash of everything written so far
+	pub fn finish(&self) -> u64 {
+		self.hasher.finish()
+	}
+	pub fn hasher(&self) -> &H {
+		&self.hasher
+	}
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+	pub fn into_inner(self) -> (W, H) {
+		(self.inner, self.hasher)
+	}
+}
+
+impl<W: Write, H: Hasher> Write for HashingWriter<W, H> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let written = self.inner.write(buf)?;
+		self.hasher.write(&buf[..written]);
+		Ok(written)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin, H: Hasher + Unpin> tokio::io::AsyncWrite for HashingWriter<W, H> {
+	fn poll_write(
+		self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>, buf: &[u8]
+	) -> std::task::Poll<io::Result<usize>> {
+		let this = self.get_mut();
+		let result = std::pin::Pin::new(&mut this.inner).poll_write(cx, buf);
+		if let std::task::Poll::Ready(Ok(written)) = result {
+			this.hasher.write(&buf[..written]);
+		}
+		result
+	}
+	fn poll_flush(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<io::Result<()>> {
+		std::pin::Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+	}
+	fn poll_shutdown(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<io::Result<()>> {
+		std::pin::Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+	}
+}
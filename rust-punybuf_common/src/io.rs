@@ -0,0 +1,239 @@
+//! The parts of `std::io` that Punybuf needs, for `no_std` builds.
+//!
+//! With `std`, this module is `std::io` itself. Without it, [`Read`] and [`Write`] are
+//! implemented for `&[u8]`, `&mut [u8]` and `Vec<u8>`, and for anything else they can be
+//! implemented by hand, e.g. on top of a UART or a DMA buffer.
+
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::fmt::{self, Debug, Display};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// What went wrong, the same as the `std::io::ErrorKind`s Punybuf uses
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+	InvalidInput,
+	InvalidData,
+	UnexpectedEof,
+	WriteZero,
+	Interrupted,
+	Unsupported,
+	Other,
+}
+
+impl ErrorKind {
+	fn as_str(&self) -> &'static str {
+		match self {
+			ErrorKind::InvalidInput => "invalid input parameter",
+			ErrorKind::InvalidData => "invalid data",
+			ErrorKind::UnexpectedEof => "unexpected end of file",
+			ErrorKind::WriteZero => "write zero",
+			ErrorKind::Interrupted => "operation interrupted",
+			ErrorKind::Unsupported => "unsupported",
+			ErrorKind::Other => "other error",
+		}
+	}
+}
+
+/// An error with a kind and a message
+pub struct Error {
+	kind: ErrorKind,
+	message: String,
+}
+
+impl Error {
+	pub fn new<M: Display>(kind: ErrorKind, message: M) -> Self {
+		Self { kind, message: message.to_string() }
+	}
+	pub fn other<M: Display>(message: M) -> Self {
+		Self::new(ErrorKind::Other, message)
+	}
+	pub fn kind(&self) -> ErrorKind {
+		self.kind
+	}
+}
+
+impl From<ErrorKind> for Error {
+	fn from(kind: ErrorKind) -> Self {
+		Self::new(kind, kind.as_str())
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+
+impl Debug for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Error").field("kind", &self.kind).field("message", &self.message).finish()
+	}
+}
+
+impl core::error::Error for Error {}
+
+pub trait Read {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+	fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+		while !buf.is_empty() {
+			match self.read(buf) {
+				Ok(0) => return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer")),
+				Ok(n) => buf = &mut buf[n..],
+				Err(e) if e.kind() == ErrorKind::Interrupted => {}
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(())
+	}
+	/// Reads until the end, appending to `buf`, and returns how many bytes were read
+	fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+		let start = buf.len();
+		let mut chunk = [0u8; 256];
+		loop {
+			match self.read(&mut chunk) {
+				Ok(0) => return Ok(buf.len() - start),
+				Ok(n) => buf.extend_from_slice(&chunk[..n]),
+				Err(e) if e.kind() == ErrorKind::Interrupted => {}
+				Err(e) => return Err(e),
+			}
+		}
+	}
+	fn take(self, limit: u64) -> Take<Self> where Self: Sized {
+		Take { inner: self, limit }
+	}
+	fn by_ref(&mut self) -> &mut Self where Self: Sized {
+		self
+	}
+}
+
+pub trait Write {
+	fn write(&mut self, buf: &[u8]) -> Result<usize>;
+	fn flush(&mut self) -> Result<()>;
+
+	fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+		while !buf.is_empty() {
+			match self.write(buf) {
+				Ok(0) => return Err(Error::new(ErrorKind::WriteZero, "failed to write whole buffer")),
+				Ok(n) => buf = &buf[n..],
+				Err(e) if e.kind() == ErrorKind::Interrupted => {}
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(())
+	}
+	fn by_ref(&mut self) -> &mut Self where Self: Sized {
+		self
+	}
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+		(**self).read(buf)
+	}
+}
+
+impl Read for &[u8] {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+		let n = buf.len().min(self.len());
+		let (read, rest) = self.split_at(n);
+		buf[..n].copy_from_slice(read);
+		*self = rest;
+		Ok(n)
+	}
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+		(**self).write(buf)
+	}
+	fn flush(&mut self) -> Result<()> {
+		(**self).flush()
+	}
+}
+
+impl Write for Vec<u8> {
+	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+		self.extend_from_slice(buf);
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
+
+impl Write for &mut [u8] {
+	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+		let n = buf.len().min(self.len());
+		let (written, rest) = core::mem::take(self).split_at_mut(n);
+		written.copy_from_slice(&buf[..n]);
+		*self = rest;
+		Ok(n)
+	}
+	fn flush(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
+
+/// Reads at most `limit` bytes from `inner`
+#[derive(Debug)]
+pub struct Take<R> {
+	inner: R,
+	limit: u64,
+}
+
+impl<R> Take<R> {
+	pub fn limit(&self) -> u64 {
+		self.limit
+	}
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: Read> Read for Take<R> {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+		if self.limit == 0 {
+			return Ok(0);
+		}
+		let max = buf.len().min(self.limit.try_into().unwrap_or(usize::MAX));
+		let n = self.inner.read(&mut buf[..max])?;
+		self.limit -= n as u64;
+		Ok(n)
+	}
+}
+
+/// A writer that throws everything away
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Sink;
+
+pub fn sink() -> Sink {
+	Sink
+}
+
+impl Write for Sink {
+	fn write(&mut self, buf: &[u8]) -> Result<usize> {
+		Ok(buf.len())
+	}
+	fn flush(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
+
+/// Copies everything from `r` into `w`, and returns how many bytes that was
+pub fn copy<R: Read + ?Sized, W: Write + ?Sized>(r: &mut R, w: &mut W) -> Result<u64> {
+	let mut chunk = [0u8; 256];
+	let mut copied = 0;
+	loop {
+		let n = match r.read(&mut chunk) {
+			Ok(0) => return Ok(copied),
+			Ok(n) => n,
+			Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+		w.write_all(&chunk[..n])?;
+		copied += n as u64;
+	}
+}
@@ -0,0 +1,219 @@
+//! Arrays that stay encoded until they're iterated, used for fields marked `@lazy_array`.
+//!
+//! Decoding a [`LazyArray`] still reads through all of its items to find where it ends,
+//! but it only keeps their bytes. The generated `<field>_iter` methods then decode
+//! the items one at a time, so they never have to be in memory all at once.
+
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+
+use crate::io::{self, Error, Read};
+
+use crate::{PBType, UInt, MAX_ARRAY_LENGTH};
+
+/// An `Array<T>`, kept as the encoded items, one after another.
+pub struct LazyArray<T> {
+	len: usize,
+	bytes: Vec<u8>,
+	items: PhantomData<fn() -> T>,
+}
+
+impl<T> LazyArray<T> {
+	/// Creates the array from `len` items that are already encoded in `bytes`.
+	///
+	/// The bytes aren't checked, so if they don't contain exactly `len` items,
+	/// the array is encoded incorrectly, and iterating over it returns errors.
+	pub fn from_encoded(len: usize, bytes: Vec<u8>) -> Self {
+		Self { len, bytes, items: PhantomData }
+	}
+	/// The amount of items
+	pub fn len(&self) -> usize {
+		self.len
+	}
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+	/// The encoded items, without the length before them
+	pub fn encoded(&self) -> &[u8] {
+		&self.bytes
+	}
+	/// Decodes the items one at a time with `decode`, which reads an item from the start of the slice.
+	/// Stops after the first error.
+	pub fn iter_with<'s, F>(&'s self, mut decode: F) -> impl Iterator<Item = io::Result<T>> + 's
+	where F: FnMut(&mut &'s [u8]) -> io::Result<T> + 's {
+		let mut r = &self.bytes[..];
+		let mut remaining = self.len;
+		core::iter::from_fn(move || {
+			if remaining == 0 {
+				return None;
+			}
+			remaining -= 1;
+			let item = decode(&mut r);
+			if item.is_err() {
+				remaining = 0;
+			}
+			Some(item)
+		})
+	}
+}
+
+impl<'x, T: PBType<'x>> LazyArray<T> {
+	/// Encodes `items` into a new array
+	pub fn from_items(items: &[T]) -> io::Result<Self> {
+		let mut bytes = vec![];
+		T::serialize_slice(items, &mut bytes)?;
+		Ok(Self::from_encoded(items.len(), bytes))
+	}
+	/// Decodes the items one at a time. Stops after the first error.
+	pub fn iter(&self) -> impl Iterator<Item = io::Result<T>> + '_ {
+		self.iter_with(|r| T::deserialize_stream(r))
+	}
+}
+
+impl<T> Default for LazyArray<T> {
+	fn default() -> Self {
+		Self::from_encoded(0, vec![])
+	}
+}
+
+impl<T> Clone for LazyArray<T> {
+	fn clone(&self) -> Self {
+		Self::from_encoded(self.len, self.bytes.clone())
+	}
+}
+
+impl<T> Debug for LazyArray<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("LazyArray").field("len", &self.len).field("bytes", &self.bytes).finish()
+	}
+}
+
+// every value has exactly one encoding, so comparing the bytes compares the items
+impl<T> PartialEq for LazyArray<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.len == other.len && self.bytes == other.bytes
+	}
+}
+
+impl<T> Eq for LazyArray<T> {}
+
+impl<T> Hash for LazyArray<T> {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+		self.len.hash(state);
+		self.bytes.hash(state);
+	}
+}
+
+/// Copies everything read from `R` into `bytes`
+struct Recorder<'r, R> {
+	inner: &'r mut R,
+	bytes: Vec<u8>,
+}
+
+impl<R: Read> Read for Recorder<'_, R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = self.inner.read(buf)?;
+		self.bytes.extend_from_slice(&buf[..read]);
+		Ok(read)
+	}
+}
+
+fn read_len(len: UInt) -> io::Result<usize> {
+	let len = len.into();
+	if len > MAX_ARRAY_LENGTH {
+		return Err(Error::other("Array length too large"));
+	}
+	Ok(len)
+}
+
+impl<'x, T: PBType<'x>> PBType<'x> for LazyArray<T> {
+	const TYPE_NAME: &'static str = "Array";
+	const MIN_SIZE: usize = 1;
+	fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+		UInt(self.len as u64).serialize(w)?;
+		w.write_all(&self.bytes)
+	}
+	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+		let len = read_len(UInt::deserialize_stream(r)?)?;
+		if let Some(size) = T::FIXED_SIZE {
+			// the length could be wrong, so the buffer grows as the bytes arrive
+			let mut bytes = vec![];
+			let expected = (len * size) as u64;
+			if r.take(expected).read_to_end(&mut bytes)? as u64 != expected {
+				return Err(Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"));
+			}
+			return Ok(Self::from_encoded(len, bytes));
+		}
+		let mut recorder = Recorder { inner: r, bytes: vec![] };
+		for _ in 0..len {
+			T::deserialize_stream(&mut recorder)?;
+		}
+		Ok(Self::from_encoded(len, recorder.bytes))
+	}
+	fn deserialize<'a: 'x>(slice: &mut &'a [u8]) -> io::Result<Self> {
+		let len = read_len(UInt::deserialize(slice)?)?;
+		let start = *slice;
+		for _ in 0..len {
+			T::deserialize(slice)?;
+		}
+		let read = start.len() - slice.len();
+		Ok(Self::from_encoded(len, start[..read].to_vec()))
+	}
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_impl {
+	use std::{io, pin::Pin, task::{Context, Poll}};
+	use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadBuf};
+
+	use super::{read_len, LazyArray};
+	use crate::{tokio::PBType, UInt};
+
+	/// Copies everything read from `R` into `bytes`
+	struct Recorder<'r, R> {
+		inner: &'r mut R,
+		bytes: Vec<u8>,
+	}
+
+	impl<R: AsyncRead + Unpin> AsyncRead for Recorder<'_, R> {
+		fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+			let this = self.get_mut();
+			let before = buf.filled().len();
+			let result = Pin::new(&mut *this.inner).poll_read(cx, buf);
+			if let Poll::Ready(Ok(())) = result {
+				this.bytes.extend_from_slice(&buf.filled()[before..]);
+			}
+			result
+		}
+	}
+
+	impl<'x, T: PBType<'x>> PBType<'x> for LazyArray<T> {
+		const TYPE_NAME: &'static str = "Array";
+		const MIN_SIZE: usize = 1;
+		async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+			UInt(self.len as u64).serialize(w).await?;
+			w.write_all(&self.bytes).await
+		}
+		async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+			let len = read_len(UInt::deserialize_stream(r).await?)?;
+			if let Some(size) = T::FIXED_SIZE {
+				// the length could be wrong, so the buffer grows as the bytes arrive
+				let mut bytes = vec![];
+				let expected = (len * size) as u64;
+				if r.take(expected).read_to_end(&mut bytes).await? as u64 != expected {
+					return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"));
+				}
+				return Ok(Self::from_encoded(len, bytes));
+			}
+			let mut recorder = Recorder { inner: r, bytes: vec![] };
+			for _ in 0..len {
+				// without the box, proving that the future is `Send` hits a compiler limitation
+				// (rust-lang/rust#100013) in the code generated for structs containing this
+				let item: Pin<Box<dyn Future<Output = io::Result<T>> + Send + '_>> =
+					Box::pin(T::deserialize_stream(&mut recorder));
+				item.await?;
+			}
+			Ok(Self::from_encoded(len, recorder.bytes))
+		}
+	}
+}
@@ -1,10 +1,48 @@
-use std::{collections::HashMap, fmt::{Debug, Display}, io::{self, Error, Read, Write}, ops::*};
+#![cfg_attr(not(feature = "std"), no_std)]
 
-pub use std::borrow::Cow;
+extern crate alloc;
+
+use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec, vec::Vec};
+use core::{fmt::{Debug, Display}, ops::*};
+
+pub use alloc::borrow::Cow;
+
+#[cfg(feature = "std")]
+pub use std::io;
+#[cfg(not(feature = "std"))]
+pub mod io;
+use io::{Error, Read, Write};
 
 mod const_macro;
+mod reflect;
+pub use reflect::*;
+mod debug_path;
+pub use debug_path::*;
+mod hashing;
+pub use hashing::*;
+mod lazy_array;
+pub use lazy_array::*;
+#[cfg(feature = "compression")]
+pub mod compression;
+#[cfg(feature = "std")]
+pub mod pool;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "chrono")]
+mod datetime;
+#[cfg(feature = "chrono")]
+pub use datetime::*;
 const MAX_BYTES_LENGTH: usize = const_unwrap!(usize::from_str_radix(env!("PUNYBUF_MAX_BYTES_LENGTH"), 10));
 const MAX_ARRAY_LENGTH: usize = const_unwrap!(usize::from_str_radix(env!("PUNYBUF_MAX_ARRAY_LENGTH"), 10));
+/// The most bytes allocated up front for a length-prefixed value read from a stream.
+/// The length comes from the input, so the rest is only allocated as the items arrive.
+const MAX_PREALLOCATION: usize = 64 * 1024;
+
+/// How many `T`s to allocate up front for `len` of them read from a stream
+pub(crate) const fn initial_capacity<T>(len: usize) -> usize {
+	let max = MAX_PREALLOCATION / if size_of::<T>() == 0 { 1 } else { size_of::<T>() };
+	if len < max { len } else { max }
+}
 
 #[cfg(feature = "tokio")]
 pub mod tokio;
@@ -14,19 +52,92 @@ macro_rules! buffer_too_small {
 		io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small")
 	}
 }
+/// The smallest of `sizes`, or `0` if there are none.
+///
+/// Generated code uses it for the `MIN_SIZE` of generic enums, since `Ord::min` can't be used in constants.
+pub const fn min_size_of(sizes: &[usize]) -> usize {
+	if sizes.is_empty() {
+		return 0;
+	}
+	let mut min = sizes[0];
+	let mut i = 1;
+	while i < sizes.len() {
+		if sizes[i] < min {
+			min = sizes[i];
+		}
+		i += 1;
+	}
+	min
+}
+
 /// All Punybuf types implement this trait.
 pub trait PBType<'x> {
+	/// The name of the type in the definition, like `"UInt"` or `"Bytes"`, for error messages.
+	///
+	/// Generated types use their name from the definition, without the layer.
+	/// Types that don't set it are called `"unknown"`.
+	const TYPE_NAME: &'static str = "unknown";
+	/// The most bytes `serialize` can write, or `None` if there's no limit, e.g. because
+	/// the type contains an `Array`, `Bytes` or a `String`. Useful for allocating buffers
+	/// for messages of a fixed shape.
+	const MAX_SIZE: Option<usize> = None;
+	/// The fewest bytes a value of the type takes, so that shorter inputs can be rejected
+	/// before decoding them.
+	const MIN_SIZE: usize = 0;
+	/// How many bytes every value takes, if it's always the same. Unlike `MIN_SIZE` and `MAX_SIZE`,
+	/// this also covers the values it can decode, so it's `None` for structs that aren't `@sealed`
+	/// and enums with a `@default` variant, since newer versions of them can be longer.
+	const FIXED_SIZE: Option<usize> = None;
 	fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[] }
 	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()>;
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> where Self: Sized;
 	fn deserialize<'a: 'x>(slice: &mut &'a [u8]) -> io::Result<Self> where Self: Sized {
 		Self::deserialize_stream(slice)
 	}
+	/// Deserializes into an existing value, overwriting it. `Vec`s, owned strings and
+	/// owned bytes reuse their allocations instead of allocating new ones.
+	fn deserialize_into<R: Read>(&mut self, r: &mut R) -> io::Result<()> where Self: Sized {
+		*self = Self::deserialize_stream(r)?;
+		Ok(())
+	}
+	/// Serializes the elements of an array, without the length.
+	///
+	/// `Vec<T>` calls this instead of serializing its elements one by one, so
+	/// `u8` can write all of them at once, making `Vec<u8>` as fast as [`Bytes`].
+	fn serialize_slice<W: Write>(items: &[Self], w: &mut W) -> io::Result<()> where Self: Sized {
+		for item in items {
+			item.serialize(w)?;
+		}
+		Ok(())
+	}
+
+	/// Serializes the value into a new [`bytes::Bytes`] buffer.
+	#[cfg(feature = "bytes")]
+	fn serialize_to_bytes(&self) -> io::Result<bytes::Bytes> {
+		use bytes::BufMut;
+		let mut w = bytes::BytesMut::new().writer();
+		self.serialize(&mut w)?;
+		Ok(w.into_inner().freeze())
+	}
+	/// Deserializes the value from the start of `buf`, advancing it past the bytes that were read.
+	#[cfg(feature = "bytes")]
+	fn deserialize_from_bytes(buf: &mut bytes::Bytes) -> io::Result<Self> where Self: Sized {
+		use bytes::Buf;
+		let mut slice = &buf[..];
+		let this = Self::deserialize_stream(&mut slice)?;
+		let read = buf.len() - slice.len();
+		buf.advance(read);
+		Ok(this)
+	}
 }
 
 pub type Void = ();
 
 impl<'x> PBType<'x> for Void {
+	const TYPE_NAME: &'static str = "Void";
+	const MIN_SIZE: usize = 0;
+	const MAX_SIZE: Option<usize> = Some(0);
+	const FIXED_SIZE: Option<usize> = Some(0);
 	fn serialize<W: Write>(&self, _: &mut W) -> io::Result<()> {
 		Ok(())
 	}
@@ -35,14 +146,31 @@ impl<'x> PBType<'x> for Void {
 	}
 }
 
+/// The map [`HashMapConvertible`] converts to and from: a `HashMap` with the `std` feature,
+/// and a `BTreeMap` without it, since `alloc` has no `HashMap`
+#[cfg(feature = "std")]
+pub type ConvertibleMap<K, V> = std::collections::HashMap<K, V>;
+#[cfg(not(feature = "std"))]
+pub type ConvertibleMap<K, V> = alloc::collections::BTreeMap<K, V>;
+
+/// What the keys of a [`ConvertibleMap`] need: `Hash` and `Eq` with the `std` feature, and `Ord` without it
+#[cfg(feature = "std")]
+pub trait MapKey: core::hash::Hash + Eq {}
+#[cfg(feature = "std")]
+impl<T: core::hash::Hash + Eq> MapKey for T {}
+#[cfg(not(feature = "std"))]
+pub trait MapKey: Ord {}
+#[cfg(not(feature = "std"))]
+impl<T: Ord> MapKey for T {}
+
 pub struct DuplicateKeysFound;
 pub trait HashMapConvertible<K, V>: Sized {
 	/// Converts the value to a `HashMap`, overriding duplicate keys.  
 	/// Returns the resulting hashmap and a boolean indicating whether any duplicate keys were found
-	fn to_map_allow_duplicates(self) -> (HashMap<K, V>, bool);
+	fn to_map_allow_duplicates(self) -> (ConvertibleMap<K, V>, bool);
 
 	/// Returns an error if there were any duplicate keys in the Map
-	fn try_to_map(self) -> Result<HashMap<K, V>, DuplicateKeysFound> {
+	fn try_to_map(self) -> Result<ConvertibleMap<K, V>, DuplicateKeysFound> {
 		let (map, duplicates_found) = self.to_map_allow_duplicates();
 		if !duplicates_found {
 			Ok(map)
@@ -50,7 +178,7 @@ pub trait HashMapConvertible<K, V>: Sized {
 			Err(DuplicateKeysFound)
 		}
 	}
-	fn from_map(map: std::collections::HashMap<K, V>) -> Self;
+	fn from_map(map: ConvertibleMap<K, V>) -> Self;
 }
 
 /// An empty type, used as a return type for a command that doesn't need to return
@@ -60,6 +188,10 @@ pub trait HashMapConvertible<K, V>: Sized {
 pub struct Done {}
 
 impl<'x> PBType<'x> for Done {
+	const TYPE_NAME: &'static str = "Done";
+	const MIN_SIZE: usize = 0;
+	const MAX_SIZE: Option<usize> = Some(0);
+	const FIXED_SIZE: Option<usize> = Some(0);
 	fn deserialize_stream<R: Read>(_r: &mut R) -> io::Result<Self> {
 		Ok(Done {})
 	}
@@ -69,7 +201,7 @@ impl<'x> PBType<'x> for Done {
 }
 
 /// A variable-length integer. The greatest supported value is 1152921573328437375.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct UInt(pub u64);
 impl Into<u64> for UInt {
 	fn into(self) -> u64 {
@@ -98,13 +230,13 @@ impl From<i32> for UInt {
 }
 
 impl Debug for UInt {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		write!(f, "{}", self.0)
 	}
 }
 
 impl Display for UInt {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		write!(f, "{}", self.0)
 	}
 }
@@ -122,6 +254,19 @@ impl BitOrAssign<u64> for UInt {
 	}
 }
 
+impl BitOr for UInt {
+	type Output = UInt;
+	fn bitor(self, rhs: UInt) -> Self::Output {
+		Self(self.0 | rhs.0)
+	}
+}
+
+impl BitOrAssign for UInt {
+	fn bitor_assign(&mut self, rhs: UInt) {
+		self.0 |= rhs.0
+	}
+}
+
 impl BitAnd<u64> for UInt {
 	type Output = UInt;
 	fn bitand(self, rhs: u64) -> Self::Output {
@@ -142,46 +287,58 @@ impl PartialEq<u64> for UInt {
 }
 
 impl PartialOrd<u64> for UInt {
-	fn partial_cmp(&self, other: &u64) -> Option<std::cmp::Ordering> {
+	fn partial_cmp(&self, other: &u64) -> Option<core::cmp::Ordering> {
 		self.0.partial_cmp(other)
 	}
 }
 
+impl UInt {
+	/// How many bytes `serialize` writes for this number: 1, 2, 3, 5 or 8.
+	/// Numbers too big to be encoded count as 8, but fail to serialize.
+	pub const fn encoded_len(self) -> usize {
+		match self.0 {
+			0..128 => 1,
+			128..16512 => 2,
+			16512..2113664 => 3,
+			2113664..68721590400 => 5,
+			_ => 8,
+		}
+	}
+	/// The encoded number.
+	///
+	/// Panics if it's bigger than 1152921573328437375, the greatest number a `UInt` can encode.
+	pub fn encode_to_vec(self) -> Vec<u8> {
+		let mut buf = Vec::with_capacity(self.encoded_len());
+		self.serialize(&mut buf).expect("number too big (max 1152921573328437375)");
+		buf
+	}
+	/// Decodes a number from the start of `buf`, returning it and how many bytes it took,
+	/// e.g. to peek at a length without a reader.
+	pub fn decode_slice(buf: &[u8]) -> io::Result<(UInt, usize)> {
+		let mut slice = buf;
+		let uint = Self::deserialize_stream(&mut slice)?;
+		Ok((uint, buf.len() - slice.len()))
+	}
+}
+
 
 impl<'x> PBType<'x> for UInt {
+	const TYPE_NAME: &'static str = "UInt";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(8);
 	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
-		let mut uint = self.0;
-		if uint < 128 {
-			w.write_all(&uint.to_be_bytes()[7..8])?;
-
-			} else if uint < 16512 {
-				uint -= 128;
-				let bytes = &mut uint.to_be_bytes()[6..8];
-				bytes[0] |= 0b10_000000;
-				w.write_all(bytes)?;
-
-			} else if uint < 2113664 {
-				uint -= 16512;
-				let bytes = &mut uint.to_be_bytes()[5..8];
-				bytes[0] |= 0b110_00000;
-				w.write_all(bytes)?;
-
-			} else if uint < 68721590400 {
-				uint -= 2113664;
-				let bytes = &mut uint.to_be_bytes()[3..8];
-				bytes[0] |= 0b1110_0000;
-				w.write_all(bytes)?;
-
-			} else if uint < 1152921573328437376 {
-				uint -= 68721590400;
-				let bytes = &mut uint.to_be_bytes()[0..8];
-				bytes[0] |= 0b1111_0000;
-				w.write_all(bytes)?;
-
-			} else {
-				Err(io::Error::other("number too big (max 1152921573328437375)"))?;
-			}
-			Ok(())
+		// The length bits are part of the number, so each length is written straight from
+		// its big endian bytes. Writing a fixed amount of bytes in each branch benchmarks
+		// faster than finding the length first (`cargo bench --bench uint`)
+		let uint = self.0;
+		match uint {
+			0..128 => w.write_all(&[uint as u8]),
+			128..16512 => w.write_all(&((uint - 128) as u16 | 0b10 << 14).to_be_bytes()),
+			16512..2113664 => w.write_all(&((uint - 16512) as u32 | 0b110 << 21).to_be_bytes()[1..]),
+			2113664..68721590400 => w.write_all(&((uint - 2113664) | 0b1110 << 36).to_be_bytes()[3..]),
+			68721590400..1152921573328437376 => w.write_all(&((uint - 68721590400) | 0b1111 << 60).to_be_bytes()),
+			_ => Err(io::Error::other("number too big (max 1152921573328437375)")),
+		}
 	}
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut first_byte = [0; 1];
@@ -227,7 +384,101 @@ impl<'x> PBType<'x> for UInt {
 	// is very fast anyways
 }
 
+/// A variable-length signed integer. It's ZigZag-encoded into a [`UInt`], so that
+/// numbers close to zero take few bytes, whether they're positive or negative.
+///
+/// The supported values are -576460786664218688 to 576460786664218687.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SInt(pub i64);
+impl From<SInt> for i64 {
+	fn from(value: SInt) -> Self {
+		value.0
+	}
+}
+impl From<i64> for SInt {
+	fn from(value: i64) -> Self {
+		Self(value)
+	}
+}
+
+impl SInt {
+	/// Maps 0, -1, 1, -2, 2... to 0, 1, 2, 3, 4...
+	pub(crate) fn to_zigzag(self) -> UInt {
+		UInt(((self.0 << 1) ^ (self.0 >> 63)) as u64)
+	}
+	pub(crate) fn from_zigzag(uint: UInt) -> Self {
+		Self((uint.0 >> 1) as i64 ^ -((uint.0 & 1) as i64))
+	}
+}
+
+impl Debug for SInt {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl Display for SInt {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl PartialEq<i64> for SInt {
+	fn eq(&self, other: &i64) -> bool {
+		&self.0 == other
+	}
+}
+
+impl PartialOrd<i64> for SInt {
+	fn partial_cmp(&self, other: &i64) -> Option<core::cmp::Ordering> {
+		self.0.partial_cmp(other)
+	}
+}
+
+impl<'x> PBType<'x> for SInt {
+	const TYPE_NAME: &'static str = "SInt";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(8);
+	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+		if self.to_zigzag().0 > 1152921573328437375 {
+			return Err(io::Error::other("number out of range (min -576460786664218688, max 576460786664218687)"));
+		}
+		self.to_zigzag().serialize(w)
+	}
+	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+		Ok(Self::from_zigzag(UInt::deserialize_stream(r)?))
+	}
+}
+
+/// The `Boolean` type from `common`. It's an enum `[True, False]`, so `true` is `0` and `false` is `1`.
+impl<'x> PBType<'x> for bool {
+	const TYPE_NAME: &'static str = "Boolean";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(1);
+	const FIXED_SIZE: Option<usize> = Some(1);
+	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+		let mut buf = [0; 1];
+		r.read_exact(&mut buf)?;
+		bool_from_byte(buf[0])
+	}
+	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+		w.write_all(&[if *self { 0 } else { 1 }])
+	}
+}
+
+pub(crate) fn bool_from_byte(byte: u8) -> io::Result<bool> {
+	match byte {
+		0 => Ok(true),
+		1 => Ok(false),
+		_ => Err(io::Error::new(io::ErrorKind::InvalidData, format!("invalid Boolean {byte}, expected 0 or 1"))),
+	}
+}
+
 impl<'x> PBType<'x> for u8 {
+	const TYPE_NAME: &'static str = "U8";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(1);
+	const FIXED_SIZE: Option<usize> = Some(1);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 1];
 		r.read_exact(&mut buf)?;
@@ -236,8 +487,15 @@ impl<'x> PBType<'x> for u8 {
 	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
 		w.write_all(&[*self])
 	}
+	fn serialize_slice<W: Write>(items: &[Self], w: &mut W) -> io::Result<()> {
+		w.write_all(items)
+	}
 }
 impl<'x> PBType<'x> for u16 {
+	const TYPE_NAME: &'static str = "U16";
+	const MIN_SIZE: usize = 2;
+	const MAX_SIZE: Option<usize> = Some(2);
+	const FIXED_SIZE: Option<usize> = Some(2);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 2];
 		r.read_exact(&mut buf)?;
@@ -248,6 +506,10 @@ impl<'x> PBType<'x> for u16 {
 	}
 }
 impl<'x> PBType<'x> for u32 {
+	const TYPE_NAME: &'static str = "U32";
+	const MIN_SIZE: usize = 4;
+	const MAX_SIZE: Option<usize> = Some(4);
+	const FIXED_SIZE: Option<usize> = Some(4);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 4];
 		r.read_exact(&mut buf)?;
@@ -258,6 +520,10 @@ impl<'x> PBType<'x> for u32 {
 	}
 }
 impl<'x> PBType<'x> for u64 {
+	const TYPE_NAME: &'static str = "U64";
+	const MIN_SIZE: usize = 8;
+	const MAX_SIZE: Option<usize> = Some(8);
+	const FIXED_SIZE: Option<usize> = Some(8);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 8];
 		r.read_exact(&mut buf)?;
@@ -268,6 +534,10 @@ impl<'x> PBType<'x> for u64 {
 	}
 }
 impl<'x> PBType<'x> for i32 {
+	const TYPE_NAME: &'static str = "I32";
+	const MIN_SIZE: usize = 4;
+	const MAX_SIZE: Option<usize> = Some(4);
+	const FIXED_SIZE: Option<usize> = Some(4);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 4];
 		r.read_exact(&mut buf)?;
@@ -278,6 +548,10 @@ impl<'x> PBType<'x> for i32 {
 	}
 }
 impl<'x> PBType<'x> for i64 {
+	const TYPE_NAME: &'static str = "I64";
+	const MIN_SIZE: usize = 8;
+	const MAX_SIZE: Option<usize> = Some(8);
+	const FIXED_SIZE: Option<usize> = Some(8);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 8];
 		r.read_exact(&mut buf)?;
@@ -288,6 +562,10 @@ impl<'x> PBType<'x> for i64 {
 	}
 }
 impl<'x> PBType<'x> for f32 {
+	const TYPE_NAME: &'static str = "F32";
+	const MIN_SIZE: usize = 4;
+	const MAX_SIZE: Option<usize> = Some(4);
+	const FIXED_SIZE: Option<usize> = Some(4);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 4];
 		r.read_exact(&mut buf)?;
@@ -298,6 +576,10 @@ impl<'x> PBType<'x> for f32 {
 	}
 }
 impl<'x> PBType<'x> for f64 {
+	const TYPE_NAME: &'static str = "F64";
+	const MIN_SIZE: usize = 8;
+	const MAX_SIZE: Option<usize> = Some(8);
+	const FIXED_SIZE: Option<usize> = Some(8);
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 8];
 		r.read_exact(&mut buf)?;
@@ -309,20 +591,19 @@ impl<'x> PBType<'x> for f64 {
 }
 
 impl<'x, T: PBType<'x>> PBType<'x> for Vec<T> {
+	const TYPE_NAME: &'static str = "Array";
+	const MIN_SIZE: usize = 1;
 	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
 		let len = self.len() as u64;
 		UInt(len).serialize(w)?;
-		for item in self {
-			item.serialize(w)?;
-		}
-		Ok(())
+		T::serialize_slice(self, w)
 	}
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let len = UInt::deserialize_stream(r)?.into();
 		if len > MAX_ARRAY_LENGTH {
 			return Err(Error::other("Array length too large"));
 		}
-		let mut this = Vec::with_capacity(len);
+		let mut this = Vec::with_capacity(initial_capacity::<T>(len));
 
 		for _ in 0..len {
 			this.push(T::deserialize_stream(r)?);
@@ -331,11 +612,15 @@ impl<'x, T: PBType<'x>> PBType<'x> for Vec<T> {
 		Ok(this)
 	}
 	fn deserialize<'a: 'x>(slice: &mut &'a [u8]) -> io::Result<Self> where Self: Sized {
-		let len = UInt::deserialize(slice)?.into();
+		let len: usize = UInt::deserialize(slice)?.into();
 		if len > MAX_ARRAY_LENGTH {
 			return Err(Error::other("Array length too large"));
 		}
-		let mut this = Vec::with_capacity(len);
+		// every item takes at least `MIN_SIZE` bytes, so the slice has to have room for them
+		if len.saturating_mul(T::MIN_SIZE) > slice.len() {
+			Err(buffer_too_small!())?;
+		}
+		let mut this = Vec::with_capacity(initial_capacity::<T>(len));
 
 		for _ in 0..len {
 			this.push(T::deserialize(slice)?);
@@ -343,13 +628,165 @@ impl<'x, T: PBType<'x>> PBType<'x> for Vec<T> {
 
 		Ok(this)
 	}
+	fn deserialize_into<R: Read>(&mut self, r: &mut R) -> io::Result<()> {
+		let len = UInt::deserialize_stream(r)?.into();
+		if len > MAX_ARRAY_LENGTH {
+			return Err(Error::other("Array length too large"));
+		}
+		self.truncate(len);
+		for item in self.iter_mut() {
+			item.deserialize_into(r)?;
+		}
+		self.reserve(initial_capacity::<T>(len - self.len()));
+		while self.len() < len {
+			self.push(T::deserialize_stream(r)?);
+		}
+		Ok(())
+	}
+}
+
+/// Encoded like `Optional<T>`: a `0` octet for `None`, or a `1` octet followed by the value.
+///
+/// Generated code uses this for `Optional` fields and for the frames returned by `@stream` commands.
+impl<'x, T: PBType<'x>> PBType<'x> for Option<T> {
+	const TYPE_NAME: &'static str = "Optional";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = match T::MAX_SIZE {
+		Some(size) => Some(1 + size),
+		None => None,
+	};
+	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+		match self {
+			None => 0u8.serialize(w),
+			Some(value) => {
+				1u8.serialize(w)?;
+				value.serialize(w)
+			}
+		}
+	}
+	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+		match u8::deserialize_stream(r)? {
+			0 => Ok(None),
+			1 => Ok(Some(T::deserialize_stream(r)?)),
+			_ => Err(Error::other("Invalid Optional discriminant")),
+		}
+	}
+	fn deserialize<'a: 'x>(slice: &mut &'a [u8]) -> io::Result<Self> where Self: Sized {
+		match u8::deserialize(slice)? {
+			0 => Ok(None),
+			1 => Ok(Some(T::deserialize(slice)?)),
+			_ => Err(Error::other("Invalid Optional discriminant")),
+		}
+	}
+}
+
+/// Encoded the same as `T`. The generated code boxes the values of `Optional`s and flags
+/// that contain the type they're in, since the type would be infinitely large otherwise.
+impl<'x, T: PBType<'x>> PBType<'x> for Box<T> {
+	const TYPE_NAME: &'static str = T::TYPE_NAME;
+	const MIN_SIZE: usize = T::MIN_SIZE;
+	// Not `T::MAX_SIZE`, which would depend on itself. A type containing itself has no maximum anyway.
+	const MAX_SIZE: Option<usize> = None;
+	fn attributes() -> &'static [(&'static str, Option<&'static str>)] {
+		T::attributes()
+	}
+	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+		(**self).serialize(w)
+	}
+	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+		Ok(Box::new(T::deserialize_stream(r)?))
+	}
+	fn deserialize<'a: 'x>(slice: &mut &'a [u8]) -> io::Result<Self> where Self: Sized {
+		Ok(Box::new(T::deserialize(slice)?))
+	}
+	fn deserialize_into<R: Read>(&mut self, r: &mut R) -> io::Result<()> {
+		(**self).deserialize_into(r)
+	}
+}
+
+/// Reads the values returned by a `@stream` command, see [`PBCommandExt::deserialize_return_items`].
+///
+/// Every value is a frame encoded as `Optional<Return>`, and the first `None` ends the stream.
+/// After the end of the stream or an error, the iterator only returns `None`.
+pub struct ReturnStream<'r, R, T> {
+	r: &'r mut R,
+	ended: bool,
+	_item: core::marker::PhantomData<T>,
+}
+
+impl<'r, R, T> ReturnStream<'r, R, T> {
+	pub fn new(r: &'r mut R) -> Self {
+		Self { r, ended: false, _item: core::marker::PhantomData }
+	}
+	/// Whether the end of the stream was reached, or it failed
+	pub fn is_ended(&self) -> bool {
+		self.ended
+	}
+}
+
+impl<'r, R: Read, T: PBType<'static>> Iterator for ReturnStream<'r, R, T> {
+	type Item = io::Result<T>;
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.ended {
+			return None;
+		}
+		match Option::<T>::deserialize_stream(self.r) {
+			Ok(Some(value)) => Some(Ok(value)),
+			Ok(None) => {
+				self.ended = true;
+				None
+			}
+			Err(err) => {
+				self.ended = true;
+				Some(Err(err))
+			}
+		}
+	}
+}
+
+/// Reads the length-prefixed bytes into `buf`, replacing its contents
+fn read_bytes_into<R: Read>(buf: &mut Vec<u8>, r: &mut R, too_large: &str) -> io::Result<()> {
+	let len = UInt::deserialize_stream(r)?.into();
+	if len > MAX_BYTES_LENGTH {
+		return Err(Error::other(too_large));
+	}
+	buf.clear();
+	buf.reserve(initial_capacity::<u8>(len));
+	r.take(len as u64).read_to_end(buf)?;
+	if buf.len() < len {
+		Err(buffer_too_small!())?;
+	}
+	Ok(())
+}
+
+/// Takes the owned buffer out of `cow`, or an empty one if it's borrowed
+fn take_owned<B: ToOwned + ?Sized>(cow: &mut Cow<'_, B>) -> B::Owned where B::Owned: Default {
+	match cow {
+		Cow::Owned(owned) => core::mem::take(owned),
+		Cow::Borrowed(_) => B::Owned::default(),
+	}
+}
+
+/// Used by generated `deserialize_into` implementations for flags with values.
+/// Reuses the existing value if there is one.
+pub fn deserialize_option_into<'x, T: PBType<'x>, R: Read>(
+	value: &mut Option<T>, present: bool, r: &mut R
+) -> io::Result<()> {
+	match value {
+		Some(v) if present => v.deserialize_into(r)?,
+		_ if present => *value = Some(T::deserialize_stream(r)?),
+		_ => *value = None,
+	}
+	Ok(())
 }
 
 /// A convenience type wrapping a `Vec<u8>`, for more efficient (de)serialization.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Bytes<'a>(pub Cow<'a, [u8]>);
 
 impl<'x> PBType<'x> for Bytes<'x> {
+	const TYPE_NAME: &'static str = "Bytes";
+	const MIN_SIZE: usize = 1;
 	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
 		let len = self.0.len() as u64;
 		UInt(len).serialize(w)?;
@@ -361,7 +798,7 @@ impl<'x> PBType<'x> for Bytes<'x> {
 		if len > MAX_BYTES_LENGTH {
 			return Err(Error::other("Bytes length too large"));
 		}
-		let mut this = Vec::with_capacity(len);
+		let mut this = Vec::with_capacity(initial_capacity::<u8>(len));
 		let mut taken = r.take(len as u64);
 
 		taken.read_to_end(&mut this)?;
@@ -369,17 +806,15 @@ impl<'x> PBType<'x> for Bytes<'x> {
 		Ok(Self(this.into()))
 	}
 	fn deserialize<'a: 'x>(slice: &mut &'a [u8]) -> io::Result<Self> {
-		let len = UInt::deserialize(slice)?.into();
-		if len > MAX_BYTES_LENGTH {
-			return Err(Error::other("Bytes length too large"));
-		}
-		if slice.len() < len {
-			Err(buffer_too_small!())?;
-		}
-		let result = &slice.get(0..len).unwrap();
-		let new_slice = &slice.get(len..slice.len()).unwrap();
-		*slice = new_slice;
-		Ok(Self(Cow::Borrowed(result)))
+		let (bytes, rest) = BytesRef::deserialize_from_slice(slice)?;
+		*slice = rest;
+		Ok(bytes.into())
+	}
+	fn deserialize_into<R: Read>(&mut self, r: &mut R) -> io::Result<()> {
+		let mut buf = take_owned(&mut self.0);
+		read_bytes_into(&mut buf, r, "Bytes length too large")?;
+		self.0 = Cow::Owned(buf);
+		Ok(())
 	}
 }
 
@@ -407,6 +842,90 @@ impl<'a> From<Cow<'a, [u8]>> for Bytes<'a> {
 	}
 }
 
+/// `Bytes` borrowed from the slice they were decoded from, without copying them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BytesRef<'a>(pub &'a [u8]);
+
+impl<'a> BytesRef<'a> {
+	/// Decodes `Bytes` from the start of `buf`, returning them and the rest of `buf`.
+	pub fn deserialize_from_slice(buf: &'a [u8]) -> io::Result<(Self, &'a [u8])> {
+		let mut slice = buf;
+		let len = UInt::deserialize(&mut slice)?.into();
+		if len > MAX_BYTES_LENGTH {
+			return Err(Error::other("Bytes length too large"));
+		}
+		let (bytes, rest) = slice.split_at_checked(len).ok_or(buffer_too_small!())?;
+		Ok((Self(bytes), rest))
+	}
+	/// Encodes them the same way as [`Bytes`]
+	pub fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+		UInt(self.0.len() as u64).serialize(w)?;
+		w.write_all(self.0)
+	}
+}
+
+impl<'a> From<BytesRef<'a>> for Bytes<'a> {
+	fn from(value: BytesRef<'a>) -> Self {
+		Self(Cow::Borrowed(value.0))
+	}
+}
+
+/// Reads the extension length (a `UInt`) and discards exactly that many bytes.
+///
+/// Used to skip the value of an unknown `@extension` enum variant.
+pub fn discard_extension<R: Read>(r: &mut R) -> io::Result<()> {
+	let len = UInt::deserialize_stream(r)?.0;
+	let discarded = io::copy(&mut r.take(len), &mut io::sink())?;
+	if discarded < len {
+		Err(buffer_too_small!())?;
+	}
+	Ok(())
+}
+
+/// Deserializes a value and makes sure nothing is left in the reader afterwards.
+///
+/// Use this for decoding a complete buffer, where extra data means that the
+/// buffer is corrupted or was encoded with a different schema.
+pub fn deserialize_exact<'x, T: PBType<'x>, R: Read>(r: &mut R) -> io::Result<T> {
+	let value = T::deserialize_stream(r)?;
+	let mut byte = [0u8];
+	loop {
+		match r.read(&mut byte) {
+			Ok(0) => return Ok(value),
+			Ok(_) => return Err(trailing_bytes()),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		}
+	}
+}
+
+/// Writes `body` prefixed by its length, as a `UInt`, the way `Bytes` are.
+/// Read it with [`read_framed_raw`].
+pub fn write_framed_raw<W: Write>(w: &mut W, body: &[u8]) -> io::Result<()> {
+	if body.len() > MAX_BYTES_LENGTH {
+		return Err(Error::other("Frame length too large"));
+	}
+	UInt(body.len() as u64).serialize(w)?;
+	w.write_all(body)
+}
+
+/// Reads a frame written with [`write_framed_raw`] or `tokio::write_framed`, without decoding it,
+/// so that it can be passed along as it is.
+pub fn read_framed_raw<R: Read>(r: &mut R) -> io::Result<Vec<u8>> {
+	let mut frame = vec![];
+	read_bytes_into(&mut frame, r, "Frame length too large")?;
+	Ok(frame)
+}
+
+/// Adds the name of `T` to `err`, so it reads `while decoding Bytes: ...`, keeping its kind.
+pub fn while_decoding<'x, T: PBType<'x>>(err: io::Error) -> io::Error {
+	io::Error::new(err.kind(), format!("while decoding {}: {err}", T::TYPE_NAME))
+}
+
+pub(crate) fn trailing_bytes() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "trailing bytes")
+}
+
 pub(crate) fn from_utf8_lossy_owned(v: Vec<u8>) -> String {
 	if let Cow::Owned(string) = String::from_utf8_lossy(&v) {
 		string
@@ -422,6 +941,8 @@ pub(crate) fn from_utf8_lossy_owned(v: Vec<u8>) -> String {
 
 
 impl<'x> PBType<'x> for Cow<'x, str> {
+	const TYPE_NAME: &'static str = "String";
+	const MIN_SIZE: usize = 1;
 	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
 		let len = self.len() as u64;
 		UInt(len).serialize(w)?;
@@ -445,17 +966,25 @@ impl<'x> PBType<'x> for Cow<'x, str> {
 		*slice = new_slice;
 		Ok(String::from_utf8_lossy(result))
 	}
+	fn deserialize_into<R: Read>(&mut self, r: &mut R) -> io::Result<()> {
+		let mut buf = take_owned(self).into_bytes();
+		read_bytes_into(&mut buf, r, "String length too large")?;
+		*self = Cow::Owned(from_utf8_lossy_owned(buf));
+		Ok(())
+	}
 }
 
 // Legacy code
 impl<'x> PBType<'x> for String {
+	const TYPE_NAME: &'static str = "String";
+	const MIN_SIZE: usize = 1;
 	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
 		let len = UInt::deserialize_stream(r)?.into();
 		if len > MAX_BYTES_LENGTH {
 			return Err(Error::other("String length too large"));
 		}
 
-		let mut this = Vec::with_capacity(len);
+		let mut this = Vec::with_capacity(initial_capacity::<u8>(len));
 		let mut taken = r.take(len as u64);
 
 		taken.read_to_end(&mut this)?;
@@ -470,6 +999,80 @@ impl<'x> PBType<'x> for String {
 	}
 }
 
+/// A string that, unlike `Cow<str>`, fails to deserialize if it isn't valid UTF-8.
+///
+/// Generated code uses this type for `String` when the definition is marked `@strict_strings;`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StrictString<'a>(pub Cow<'a, str>);
+
+impl Deref for StrictString<'_> {
+	type Target = str;
+	fn deref(&self) -> &str {
+		&self.0
+	}
+}
+
+impl Display for StrictString<'_> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl<'a> From<&'a str> for StrictString<'a> {
+	fn from(value: &'a str) -> Self {
+		Self(Cow::Borrowed(value))
+	}
+}
+
+impl From<String> for StrictString<'_> {
+	fn from(value: String) -> Self {
+		Self(Cow::Owned(value))
+	}
+}
+
+impl<'a> From<Cow<'a, str>> for StrictString<'a> {
+	fn from(value: Cow<'a, str>) -> Self {
+		Self(value)
+	}
+}
+
+impl<'a> From<StrictString<'a>> for Cow<'a, str> {
+	fn from(value: StrictString<'a>) -> Self {
+		value.0
+	}
+}
+
+pub(crate) fn invalid_utf8(error: core::str::Utf8Error) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+impl<'x> PBType<'x> for StrictString<'x> {
+	const TYPE_NAME: &'static str = "String";
+	const MIN_SIZE: usize = 1;
+	fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+		self.0.serialize(w)
+	}
+	fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+		let bytes: Vec<u8> = Bytes::deserialize_stream(r)?.into();
+		let string = String::from_utf8(bytes).map_err(|e| invalid_utf8(e.utf8_error()))?;
+		Ok(Self(Cow::Owned(string)))
+	}
+	fn deserialize<'a: 'x>(slice: &mut &'a [u8]) -> io::Result<Self> {
+		let Bytes(bytes) = Bytes::deserialize(slice)?;
+		Ok(Self(match bytes {
+			Cow::Borrowed(b) => Cow::Borrowed(core::str::from_utf8(b).map_err(invalid_utf8)?),
+			Cow::Owned(b) => Cow::Owned(String::from_utf8(b).map_err(|e| invalid_utf8(e.utf8_error()))?),
+		}))
+	}
+	fn deserialize_into<R: Read>(&mut self, r: &mut R) -> io::Result<()> {
+		let mut buf = take_owned(&mut self.0).into_bytes();
+		read_bytes_into(&mut buf, r, "String length too large")?;
+		let string = String::from_utf8(buf).map_err(|e| invalid_utf8(e.utf8_error()))?;
+		self.0 = Cow::Owned(string);
+		Ok(())
+	}
+}
+
 /// A trait that all individual commands implement. The enum of all commands *does not* implement this trait.
 pub trait PBCommandExt<'x> {
 	type Error<'a>: PBType<'a>;
@@ -479,6 +1082,8 @@ pub trait PBCommandExt<'x> {
 	const ID: u32;
 	/// Whether the `Return` type is `Void`.
 	const IS_VOID: bool = false;
+	/// Whether this is a `@stream` command, returning any number of `Return` values.
+	const IS_STREAM: bool = false;
 
 	const ATTRIBUTES: &'static [(&'static str, Option<&'static str>)] = &[];
 	const REQUIRED_CAPABILITY: Option<&'static str> = None;
@@ -489,12 +1094,25 @@ pub trait PBCommandExt<'x> {
 	fn deserialize_return<'a>(slice: &mut &'a[u8]) -> io::Result<Self::Return<'a>> {
 		Self::Return::deserialize(slice)
 	}
+	/// Reads the values returned by a `@stream` command, until the end of the stream.
+	fn deserialize_return_items<'r, R: Read>(&self, r: &'r mut R) -> ReturnStream<'r, R, Self::Return<'static>> {
+		ReturnStream::new(r)
+	}
 	fn deserialize_error_stream<R: Read>(&self, r: &mut R) -> io::Result<Self::Error<'static>> {
 		Self::Error::deserialize_stream(r)
 	}
 	fn deserialize_error<'a>(slice: &mut &'a[u8]) -> io::Result<Self::Error<'a>> {
 		Self::Error::deserialize(slice)
 	}
+	/// Reads the error if `is_error` is set, and the return value otherwise.
+	/// How the RPC implementation tells the two apart is up to it.
+	fn deserialize_outcome<R: Read>(&self, r: &mut R, is_error: bool) -> io::Result<CommandOutcome<'x, Self>> where Self: Sized {
+		Ok(if is_error {
+			CommandOutcome::Err(self.deserialize_error_stream(r)?)
+		} else {
+			CommandOutcome::Ok(self.deserialize_return_stream(r)?)
+		})
+	}
 
 	/// Does **not** read the command ID.  
 	/// If you need to read the command ID, use `CommandID::deserialize`
@@ -502,6 +1120,41 @@ pub trait PBCommandExt<'x> {
 	fn deserialize<'a: 'x>(slice: &mut &'a[u8]) -> io::Result<Self> where Self: Sized {
 		Self::deserialize_stream(slice)
 	}
+	/// Does **not** read the command ID. Advances `buf` past the bytes that were read.
+	#[cfg(feature = "bytes")]
+	fn deserialize_from_bytes(buf: &mut bytes::Bytes) -> io::Result<Self> where Self: Sized {
+		use bytes::Buf;
+		let mut slice = &buf[..];
+		let this = Self::deserialize_stream(&mut slice)?;
+		let read = buf.len() - slice.len();
+		buf.advance(read);
+		Ok(this)
+	}
+}
+
+/// The response to the command `C`, see [`PBCommandExt::deserialize_outcome`]
+pub enum CommandOutcome<'x, C: PBCommandExt<'x>> {
+	Ok(C::Return<'static>),
+	Err(C::Error<'static>),
+}
+
+impl<'x, C: PBCommandExt<'x>> CommandOutcome<'x, C> {
+	pub fn into_result(self) -> Result<C::Return<'static>, C::Error<'static>> {
+		match self {
+			Self::Ok(ret) => Ok(ret),
+			Self::Err(err) => Err(err),
+		}
+	}
+}
+
+impl<'x, C: PBCommandExt<'x>> core::fmt::Debug for CommandOutcome<'x, C>
+where C::Return<'static>: core::fmt::Debug, C::Error<'static>: core::fmt::Debug {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			Self::Ok(ret) => f.debug_tuple("Ok").field(ret).finish(),
+			Self::Err(err) => f.debug_tuple("Err").field(err).finish(),
+		}
+	}
 }
 
 /// A trait that all commands implement. The enum of all commands also implements this trait.
@@ -510,6 +1163,8 @@ pub trait PBCommand {
 
 	/// Whether the `Return` type is `Void`
 	fn is_void(&self) -> bool { false }
+	/// Whether this is a `@stream` command, returning any number of values
+	fn is_stream(&self) -> bool { false }
 
 	fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] { &[] }
 	fn required_capability(&self) -> Option<&'static str> {
@@ -524,6 +1179,15 @@ pub trait PBCommand {
 		w.write_all(&self.id().to_be_bytes())?;
 		self.serialize_self(w)
 	}
+
+	/// Writes both the command ID and the argument body into a new [`bytes::Bytes`] buffer
+	#[cfg(feature = "bytes")]
+	fn serialize_to_bytes(&self) -> io::Result<bytes::Bytes> {
+		use bytes::BufMut;
+		let mut w = bytes::BytesMut::new().writer();
+		self.serialize(&mut w)?;
+		Ok(w.into_inner().freeze())
+	}
 }
 
 // TODO: write more tests
@@ -560,6 +1224,283 @@ mod libtest {
 		}
 	}
 	
+	/// How `UInt::serialize` used to write the numbers, one range at a time
+	fn serialize_uint_reference(mut uint: u64) -> Vec<u8> {
+		let (bytes, mask) = if uint < 128 {
+			(uint.to_be_bytes()[7..8].to_vec(), 0)
+		} else if uint < 16512 {
+			uint -= 128;
+			(uint.to_be_bytes()[6..8].to_vec(), 0b10_000000)
+		} else if uint < 2113664 {
+			uint -= 16512;
+			(uint.to_be_bytes()[5..8].to_vec(), 0b110_00000)
+		} else if uint < 68721590400 {
+			uint -= 2113664;
+			(uint.to_be_bytes()[3..8].to_vec(), 0b1110_0000)
+		} else {
+			uint -= 68721590400;
+			(uint.to_be_bytes()[0..8].to_vec(), 0b1111_0000)
+		};
+		let mut bytes = bytes;
+		bytes[0] |= mask;
+		bytes
+	}
+
+	#[tokio::test]
+	async fn uint_encoding_is_unchanged() {
+		use crate::{PBType, UInt};
+		for n in TEST_UINTS {
+			let expected = serialize_uint_reference(*n);
+			let mut v = vec![];
+			PBType::serialize(&UInt(*n), &mut v).unwrap();
+			assert_eq!(v, expected, "{n}");
+			let mut v = vec![];
+			crate::tokio::PBType::serialize(&UInt(*n), &mut v).await.unwrap();
+			assert_eq!(v, expected, "{n}");
+		}
+		assert!(PBType::serialize(&UInt(1152921573328437376), &mut vec![]).is_err());
+	}
+
+	const TEST_SINTS: &[i64] = &[
+		0, -1, 1, -32, 32, -63, -64, 63, 64, 65,
+		-8256, 8255, 8256, -8257,
+		-1056832, 1056831, 1056832, -1056833,
+		-34360795200, 34360795199, 34360795200, -34360795201,
+		-576460786664218688, 576460786664218687
+	];
+
+	#[test]
+	fn sint_correct() {
+		use crate::{PBType, SInt};
+		for n in TEST_SINTS {
+			let mut v = vec![];
+			SInt(*n).serialize(&mut v).unwrap();
+			let r = &mut &v[..];
+			let same = SInt::deserialize_stream(r).unwrap();
+			assert_eq!(same.0, *n);
+			assert_eq!(*r, &[]);
+		}
+		let mut v = vec![];
+		SInt(-64).serialize(&mut v).unwrap();
+		assert_eq!(v, [127]);
+		assert!(SInt(i64::MIN).serialize(&mut vec![]).is_err());
+		assert!(SInt(576460786664218688).serialize(&mut vec![]).is_err());
+	}
+
+	#[test]
+	fn bool_rejects_other_bytes() {
+		use crate::PBType;
+		let mut v = vec![];
+		true.serialize(&mut v).unwrap();
+		false.serialize(&mut v).unwrap();
+		assert_eq!(v, [0, 1]);
+		let r = &mut &v[..];
+		assert!(bool::deserialize(r).unwrap());
+		assert!(!bool::deserialize(r).unwrap());
+
+		let error = bool::deserialize(&mut &[2u8][..]).unwrap_err();
+		assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn equal_values_hash_equally() {
+		use std::{borrow::Cow, hash::DefaultHasher};
+		use crate::{Bytes, HashingWriter, PBType};
+
+		let digest = |value: &(Vec<String>, Bytes)| {
+			let mut w = HashingWriter::new(vec![], DefaultHasher::new());
+			value.0.serialize(&mut w).unwrap();
+			value.1.serialize(&mut w).unwrap();
+			let mut expected = vec![];
+			value.0.serialize(&mut expected).unwrap();
+			value.1.serialize(&mut expected).unwrap();
+			assert_eq!(w.get_ref(), &expected);
+			w.finish()
+		};
+		let a = (vec!["cat".to_string(), "dog".to_string()], Bytes(Cow::Borrowed(&[1, 2, 3])));
+		let b = (vec!["cat".to_string(), "dog".to_string()], Bytes(Cow::Owned(vec![1, 2, 3])));
+		let c = (vec!["catdog".to_string()], Bytes(Cow::Borrowed(&[1, 2, 3])));
+		assert_eq!(digest(&a), digest(&b));
+		assert_ne!(digest(&a), digest(&c));
+	}
+
+	#[test]
+	fn type_names() {
+		use crate::{while_decoding, Bytes, PBType, UInt};
+
+		assert_eq!(UInt::TYPE_NAME, "UInt");
+		assert_eq!(Bytes::TYPE_NAME, "Bytes");
+		assert_eq!(<Vec<u8>>::TYPE_NAME, "Array");
+		assert_eq!(<Option<String>>::TYPE_NAME, "Optional");
+
+		let err = Bytes::deserialize(&mut &[5, 1][..]).map_err(while_decoding::<Bytes>).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+		assert!(err.to_string().starts_with("while decoding Bytes: "), "{err}");
+	}
+
+	#[test]
+	fn max_sizes() {
+		use crate::{Bytes, PBType, UInt};
+
+		let mut biggest = vec![];
+		UInt(1152921573328437375).serialize(&mut biggest).unwrap();
+		assert_eq!(Some(biggest.len()), UInt::MAX_SIZE);
+		assert_eq!(<Option<u32>>::MAX_SIZE, Some(5));
+		assert_eq!(<Option<Option<()>>>::MAX_SIZE, Some(2));
+		assert_eq!(<Option<Bytes>>::MAX_SIZE, None);
+		assert_eq!(<Vec<u8>>::MAX_SIZE, None);
+	}
+
+	#[test]
+	fn byte_vec_is_written_at_once() {
+		use std::{borrow::Cow, io::{self, Write}};
+		use crate::{Bytes, PBType};
+
+		/// Counts the calls to `write`
+		struct CountingWriter(Vec<u8>, usize);
+		impl Write for CountingWriter {
+			fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+				self.1 += 1;
+				self.0.write(buf)
+			}
+			fn flush(&mut self) -> io::Result<()> {
+				Ok(())
+			}
+		}
+
+		let data: Vec<u8> = (0..=255).collect();
+		let mut from_bytes = vec![];
+		Bytes(Cow::Borrowed(&data)).serialize(&mut from_bytes).unwrap();
+
+		let mut w = CountingWriter(vec![], 0);
+		data.serialize(&mut w).unwrap();
+		assert_eq!(w.0, from_bytes);
+		// the length, then all the bytes
+		assert_eq!(w.1, 2);
+
+		assert_eq!(Vec::<u8>::deserialize_stream(&mut &w.0[..]).unwrap(), data);
+	}
+
+	#[test]
+	fn bytes_ref_borrows_from_the_slice() {
+		use std::borrow::Cow;
+		use crate::{Bytes, BytesRef, PBType};
+
+		let data: Vec<u8> = (0..=255).collect();
+		let mut encoded = vec![];
+		Bytes(Cow::Borrowed(&data)).serialize(&mut encoded).unwrap();
+		encoded.push(42);
+
+		let (bytes, rest) = BytesRef::deserialize_from_slice(&encoded).unwrap();
+		assert_eq!(bytes.0, &data[..]);
+		assert_eq!(bytes.0.as_ptr(), encoded[2..].as_ptr());
+		assert_eq!(rest, [42]);
+
+		let mut reencoded = vec![];
+		bytes.serialize(&mut reencoded).unwrap();
+		assert_eq!(reencoded, encoded[..encoded.len() - 1]);
+
+		assert!(BytesRef::deserialize_from_slice(&encoded[..100]).is_err());
+	}
+
+	#[test]
+	fn uint_slice_helpers() {
+		use crate::{PBType, UInt};
+		for n in TEST_UINTS.iter().copied().chain([1152921573328437374]) {
+			let mut serialized = vec![];
+			UInt(n).serialize(&mut serialized).unwrap();
+			assert_eq!(UInt(n).encoded_len(), serialized.len(), "{n}");
+			assert_eq!(UInt(n).encode_to_vec(), serialized);
+
+			serialized.extend([0xff, 0xff]);
+			assert_eq!(UInt::decode_slice(&serialized).unwrap(), (UInt(n), UInt(n).encoded_len()));
+			let err = UInt::decode_slice(&serialized[..UInt(n).encoded_len() - 1]).unwrap_err();
+			assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+		}
+	}
+
+	#[test]
+	fn raw_frames_round_trip() {
+		use crate::{read_framed_raw, write_framed_raw, deserialize_exact, PBType};
+
+		// not a valid encoding of anything in particular
+		let opaque: Vec<u8> = (0..200).map(|i| (i * 7) as u8).collect();
+		let mut value = vec![];
+		vec![1u32, 2, 3].serialize(&mut value).unwrap();
+
+		let mut stream = vec![];
+		write_framed_raw(&mut stream, &opaque).unwrap();
+		write_framed_raw(&mut stream, &value).unwrap();
+		write_framed_raw(&mut stream, &[]).unwrap();
+		// frames are encoded like `Bytes`
+		assert_eq!(stream[..2], [0x80, 200 - 128]);
+
+		let r = &mut &stream[..];
+		assert_eq!(read_framed_raw(r).unwrap(), opaque);
+		let frame = read_framed_raw(r).unwrap();
+		assert_eq!(deserialize_exact::<Vec<u32>, _>(&mut &frame[..]).unwrap(), [1, 2, 3]);
+		assert_eq!(read_framed_raw(r).unwrap(), []);
+		assert!(r.is_empty());
+
+		let err = read_framed_raw(&mut &stream[..100]).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+	}
+
+	#[test]
+	fn pooled_frames_decode_correctly() {
+		use std::borrow::Cow;
+		use crate::{write_framed_raw, pool::{BufferPool, PooledReader}, Bytes, PBType};
+
+		let names = |n: usize| (0..n).map(|i| Cow::Owned(format!("name {i}"))).collect::<Vec<Cow<str>>>();
+		let mut stream = vec![];
+		for n in [3, 50, 1] {
+			let mut body = vec![];
+			names(n).serialize(&mut body).unwrap();
+			write_framed_raw(&mut stream, &body).unwrap();
+		}
+		let mut body = vec![];
+		Bytes(Cow::Borrowed(&[1, 2, 3])).serialize(&mut body).unwrap();
+		body.push(0);
+		write_framed_raw(&mut stream, &body).unwrap();
+
+		let pool = BufferPool::new(1);
+		let mut reader = PooledReader::new(&stream[..], pool.clone());
+		for n in [3, 50, 1] {
+			let frame = reader.read_frame().unwrap();
+			let decoded = frame.decode::<Vec<Cow<str>>>().unwrap();
+			assert_eq!(decoded, names(n));
+			// borrowed from the pooled buffer, not copied out of it
+			assert!(decoded.iter().all(|name| matches!(name, Cow::Borrowed(_))));
+			drop(decoded);
+			drop(frame);
+			assert_eq!(pool.available(), 1);
+		}
+		// the buffer of the longest frame is still there
+		let frame = reader.read_frame().unwrap();
+		assert!(frame.capacity() >= 50 * 7);
+		assert_eq!(pool.available(), 0);
+		assert_eq!(frame.decode::<Bytes>().unwrap_err().to_string(), "trailing bytes");
+
+		// a full pool frees the buffers it gets back
+		let mut other = pool.get();
+		other.push(1);
+		drop(frame);
+		drop(other);
+		assert_eq!(pool.available(), 1);
+	}
+
+	#[tokio::test]
+	async fn async_byte_vec_matches_bytes() {
+		use std::borrow::Cow;
+		use crate::tokio::{Bytes, PBType};
+
+		let data: Vec<u8> = (0..=255).collect();
+		let (mut from_vec, mut from_bytes) = (vec![], vec![]);
+		data.serialize(&mut from_vec).await.unwrap();
+		Bytes(Cow::Borrowed(&data)).serialize(&mut from_bytes).await.unwrap();
+		assert_eq!(from_vec, from_bytes);
+	}
+
 	#[tokio::test]
 	async fn async_uint_correct() {
 		use crate::tokio::{PBType, UInt};
@@ -571,6 +1512,96 @@ mod libtest {
 		}
 	}
 
+	#[tokio::test]
+	async fn async_bool_rejects_other_bytes() {
+		use crate::tokio::PBType;
+		let mut v = vec![];
+		false.serialize(&mut v).await.unwrap();
+		assert_eq!(v, [1]);
+		assert!(!bool::deserialize_stream(&mut &v[..]).await.unwrap());
+		assert!(bool::deserialize_stream(&mut &[2u8][..]).await.is_err());
+	}
+
+	#[tokio::test]
+	async fn async_sint_correct() {
+		use crate::tokio::{PBType, SInt};
+		for n in TEST_SINTS {
+			let mut v = vec![];
+			SInt(*n).serialize(&mut v).await.unwrap();
+			let same = SInt::deserialize_stream(&mut &v[..]).await.unwrap();
+			assert_eq!(same.0, *n);
+		}
+	}
+
+	#[cfg(feature = "bytes")]
+	#[test]
+	fn command_through_bytes() {
+		use std::io::{self, Read, Write};
+		use bytes::Buf;
+		use crate::{PBCommand, PBCommandExt, PBType, UInt};
+
+		#[derive(Debug, PartialEq)]
+		struct SetVolume(UInt);
+		impl<'x> PBCommandExt<'x> for SetVolume {
+			type Error<'a> = ();
+			type Return<'a> = ();
+			const ID: u32 = 0xDEADBEEF;
+			fn deserialize_stream<R: Read>(r: &mut R) -> io::Result<Self> {
+				Ok(Self(UInt::deserialize_stream(r)?))
+			}
+		}
+		impl PBCommand for SetVolume {
+			fn id(&self) -> u32 { Self::ID }
+			fn serialize_self<W: Write>(&self, w: &mut W) -> io::Result<()> {
+				self.0.serialize(w)
+			}
+		}
+
+		let mut buf = SetVolume(UInt(16513)).serialize_to_bytes().unwrap();
+		assert_eq!(buf.get_u32(), SetVolume::ID);
+		let same = SetVolume::deserialize_from_bytes(&mut buf).unwrap();
+		assert_eq!(same, SetVolume(UInt(16513)));
+		assert!(buf.is_empty());
+
+		let mut buf = UInt(129).serialize_to_bytes().unwrap();
+		buf = [&buf[..], b"rest"].concat().into();
+		assert_eq!(UInt::deserialize_from_bytes(&mut buf).unwrap(), UInt(129));
+		assert_eq!(&buf[..], b"rest");
+	}
+
+	#[test]
+	fn unknown_extension_variant() {
+		use crate::{PBType, UInt, discard_extension};
+		// discriminant 7 (unknown), EL = 3, 3 bytes of an unknown value, then the next value
+		let data: &[u8] = &[7, 3, 0xAA, 0xBB, 0xCC, 42];
+		let r = &mut &data[..];
+		assert_eq!(u8::deserialize(r).unwrap(), 7);
+		discard_extension(r).unwrap();
+		assert_eq!(UInt::deserialize(r).unwrap(), UInt(42));
+		assert_eq!(*r, &[]);
+
+		let truncated: &[u8] = &[7, 3, 0xAA];
+		let r = &mut &truncated[..];
+		assert_eq!(u8::deserialize_stream(r).unwrap(), 7);
+		let err = discard_extension(r).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+	}
+
+	#[tokio::test]
+	async fn async_unknown_extension_variant() {
+		use crate::tokio::{PBType, UInt, discard_extension};
+		let data: &[u8] = &[7, 3, 0xAA, 0xBB, 0xCC, 42];
+		let r = &mut &data[..];
+		assert_eq!(u8::deserialize_stream(r).await.unwrap(), 7);
+		discard_extension(r).await.unwrap();
+		assert_eq!(UInt::deserialize_stream(r).await.unwrap(), UInt(42));
+
+		let truncated: &[u8] = &[7, 3, 0xAA];
+		let r = &mut &truncated[..];
+		assert_eq!(u8::deserialize_stream(r).await.unwrap(), 7);
+		assert!(discard_extension(r).await.is_err());
+	}
+
 	const TEST_STRINGS: &[&str] = &[
 		"",
 		"some_string",
@@ -578,6 +1609,85 @@ mod libtest {
 		"emoji: \u{1F431}",
 	];
 
+	#[test]
+	fn exact() {
+		use crate::{deserialize_exact, PBType, StrictString};
+		let mut v = vec![];
+		StrictString::from("exact").serialize(&mut v).unwrap();
+		let value: StrictString = deserialize_exact(&mut &v[..]).unwrap();
+		assert_eq!(&*value, "exact");
+
+		v.extend_from_slice(&[0, 1]);
+		let err = deserialize_exact::<StrictString, _>(&mut &v[..]).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+		assert_eq!(err.to_string(), "trailing bytes");
+	}
+
+	#[tokio::test]
+	async fn async_exact() {
+		use crate::tokio::{deserialize_exact, PBType};
+		let mut v = vec![];
+		1200u32.serialize(&mut v).await.unwrap();
+		assert_eq!(deserialize_exact::<u32, _>(&mut &v[..]).await.unwrap(), 1200);
+
+		v.push(0);
+		let err = deserialize_exact::<u32, _>(&mut &v[..]).await.unwrap_err();
+		assert_eq!(err.to_string(), "trailing bytes");
+	}
+
+	#[cfg(feature = "chrono")]
+	#[tokio::test]
+	async fn date_times() {
+		use crate::{DateTime, PBType, Utc};
+		let times = [
+			DateTime::from_timestamp(1_700_000_000, 123_456_789).unwrap(),
+			DateTime::from_timestamp(-1, 999_999_999).unwrap(),
+			// a leap second
+			DateTime::from_timestamp(1_483_228_799, 1_500_000_000).unwrap(),
+		];
+		for time in times {
+			let mut v = vec![];
+			PBType::serialize(&time, &mut v).unwrap();
+			assert_eq!(v.len(), 12);
+			assert_eq!(<DateTime<Utc> as PBType>::deserialize(&mut &v[..]).unwrap(), time);
+
+			let mut async_v = vec![];
+			crate::tokio::PBType::serialize(&time, &mut async_v).await.unwrap();
+			assert_eq!(async_v, v);
+			let decoded = <DateTime<Utc> as crate::tokio::PBType>::deserialize_stream(&mut &v[..]).await.unwrap();
+			assert_eq!(decoded, time);
+		}
+
+		let mut v = vec![];
+		PBType::serialize(&0i64, &mut v).unwrap();
+		PBType::serialize(&1_500_000_000u32, &mut v).unwrap();
+		let err = <DateTime<Utc> as PBType>::deserialize(&mut &v[..]).unwrap_err();
+		assert_eq!(err.to_string(), "timestamp out of range");
+	}
+
+	#[test]
+	fn strict_strings() {
+		use crate::{PBType, StrictString};
+		let mut v = vec![];
+		for s in TEST_STRINGS {
+			StrictString::from(*s).serialize(&mut v).unwrap();
+		}
+		let mut slice: &[u8] = &v;
+		for s in TEST_STRINGS {
+			assert_eq!(&*StrictString::deserialize(&mut slice).unwrap(), *s);
+		}
+		let mut slice: &[u8] = &v;
+		for s in TEST_STRINGS {
+			assert_eq!(&*StrictString::deserialize_stream(&mut slice).unwrap(), *s);
+		}
+
+		let invalid: &[u8] = &[3, b'a', 0xff, b'b'];
+		let err = StrictString::deserialize(&mut &invalid[..]).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+		let err = StrictString::deserialize_stream(&mut &invalid[..]).unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+	}
+
 	#[test]
 	fn strings() {
 		use std::borrow::Cow;
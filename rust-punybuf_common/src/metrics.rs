@@ -0,0 +1,103 @@
+//! Metrics for the commands, recorded by the code generated with `--rust:metrics`.
+//!
+//! Every command's `serialize_self` and `deserialize_stream`/`deserialize` record these,
+//! labeled with the name of the command (`command="Foo"`):
+//! - `punybuf_encode_total` and `punybuf_decode_total`, counting every attempt,
+//! - `punybuf_encode_errors_total` and `punybuf_decode_errors_total`, counting the failed ones,
+//! - `punybuf_encode_bytes` and `punybuf_decode_bytes`, histograms of the sizes of the
+//!   successfully encoded and decoded commands, without their IDs.
+//!
+//! They're recorded with the [`metrics`](::metrics) crate, so they go to whatever recorder
+//! is installed, and nowhere if there isn't one.
+
+use std::io::{self, Read, Write};
+
+pub use ::metrics::{counter, histogram};
+
+/// Counts the bytes read through it
+pub struct CountingReader<R> {
+	inner: R,
+	count: usize,
+}
+
+impl<R> CountingReader<R> {
+	pub fn new(inner: R) -> Self {
+		Self { inner, count: 0 }
+	}
+	/// The amount of bytes read so far
+	pub fn count(&self) -> usize {
+		self.count
+	}
+}
+
+impl<R: Read> Read for CountingReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = self.inner.read(buf)?;
+		self.count += read;
+		Ok(read)
+	}
+}
+
+/// Counts the bytes written through it
+pub struct CountingWriter<W> {
+	inner: W,
+	count: usize,
+}
+
+impl<W> CountingWriter<W> {
+	pub fn new(inner: W) -> Self {
+		Self { inner, count: 0 }
+	}
+	/// The amount of bytes written so far
+	pub fn count(&self) -> usize {
+		self.count
+	}
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let written = self.inner.write(buf)?;
+		self.count += written;
+		Ok(written)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_impl {
+	use std::{io, pin::Pin, task::{Context, Poll}};
+	use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+	use super::{CountingReader, CountingWriter};
+
+	impl<R: AsyncRead + Unpin> AsyncRead for CountingReader<R> {
+		fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+			let this = self.get_mut();
+			let before = buf.filled().len();
+			let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+			if let Poll::Ready(Ok(())) = result {
+				this.count += buf.filled().len() - before;
+			}
+			result
+		}
+	}
+
+	impl<W: AsyncWrite + Unpin> AsyncWrite for CountingWriter<W> {
+		fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+			let this = self.get_mut();
+			let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+			if let Poll::Ready(Ok(written)) = result {
+				this.count += written;
+			}
+			result
+		}
+		fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+			Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+		}
+		fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+			Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+		}
+	}
+}
@@ -0,0 +1,181 @@
+//! Reusing the buffers that frames are read into, instead of allocating one for every frame.
+//!
+//! A [`PooledReader`] reads each frame (see [`crate::read_framed_raw`]) into a buffer from a
+//! [`BufferPool`], and the buffer goes back to the pool once the [`PooledBuffer`] is dropped.
+//! [`PooledBuffer::decode`] decodes a value that borrows its `Bytes` and `String`s from the
+//! buffer, so a frame is decoded without allocating for them either.
+
+use std::{fmt::Debug, io::{self, Read}, ops::{Deref, DerefMut}, sync::{Arc, Mutex}};
+
+use crate::{initial_capacity, trailing_bytes, PBType, UInt, MAX_BYTES_LENGTH};
+
+/// Buffers to be reused. Cloning it gives another handle to the same pool, so it can be
+/// shared between threads and tasks.
+#[derive(Clone)]
+pub struct BufferPool {
+	buffers: Arc<Mutex<Vec<Vec<u8>>>>,
+	max_buffers: usize,
+}
+
+impl BufferPool {
+	/// A pool that keeps at most `max_buffers` buffers that aren't in use.
+	/// Buffers returned to a full pool are freed.
+	pub fn new(max_buffers: usize) -> Self {
+		Self { buffers: Arc::new(Mutex::new(Vec::new())), max_buffers }
+	}
+	/// Takes an empty buffer from the pool, or allocates a new one if there are none
+	pub fn get(&self) -> PooledBuffer {
+		let buf = self.buffers.lock().unwrap_or_else(|err| err.into_inner()).pop().unwrap_or_default();
+		PooledBuffer { buf, pool: self.clone() }
+	}
+	/// How many buffers are waiting to be reused
+	pub fn available(&self) -> usize {
+		self.buffers.lock().unwrap_or_else(|err| err.into_inner()).len()
+	}
+	fn put(&self, mut buf: Vec<u8>) {
+		buf.clear();
+		let mut buffers = self.buffers.lock().unwrap_or_else(|err| err.into_inner());
+		if buffers.len() < self.max_buffers {
+			buffers.push(buf);
+		}
+	}
+}
+
+impl Debug for BufferPool {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("BufferPool")
+			.field("available", &self.available())
+			.field("max_buffers", &self.max_buffers)
+			.finish()
+	}
+}
+
+/// A buffer from a [`BufferPool`], which goes back to the pool when it's dropped
+pub struct PooledBuffer {
+	buf: Vec<u8>,
+	pool: BufferPool,
+}
+
+impl PooledBuffer {
+	/// Decodes a value from the whole buffer. Its `Bytes` and `String`s are borrowed from the buffer.
+	///
+	/// For the code generated with `--rust:tokio`, use `punybuf_common::tokio::deserialize_exact`
+	/// on the buffer instead.
+	pub fn decode<'a, T: PBType<'a>>(&'a self) -> io::Result<T> {
+		let mut slice = &self.buf[..];
+		let value = T::deserialize(&mut slice)?;
+		if !slice.is_empty() {
+			return Err(trailing_bytes());
+		}
+		Ok(value)
+	}
+	/// Takes the buffer out of the pool for good
+	pub fn into_inner(mut self) -> Vec<u8> {
+		std::mem::take(&mut self.buf)
+	}
+}
+
+impl Deref for PooledBuffer {
+	type Target = Vec<u8>;
+	fn deref(&self) -> &Vec<u8> {
+		&self.buf
+	}
+}
+
+impl DerefMut for PooledBuffer {
+	fn deref_mut(&mut self) -> &mut Vec<u8> {
+		&mut self.buf
+	}
+}
+
+impl Drop for PooledBuffer {
+	fn drop(&mut self) {
+		if self.buf.capacity() != 0 {
+			self.pool.put(std::mem::take(&mut self.buf));
+		}
+	}
+}
+
+impl Debug for PooledBuffer {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("PooledBuffer").field(&self.buf).finish()
+	}
+}
+
+/// Reads frames into buffers from a [`BufferPool`]
+#[derive(Debug)]
+pub struct PooledReader<R> {
+	inner: R,
+	pool: BufferPool,
+}
+
+impl<R> PooledReader<R> {
+	pub fn new(inner: R, pool: BufferPool) -> Self {
+		Self { inner, pool }
+	}
+	pub fn pool(&self) -> &BufferPool {
+		&self.pool
+	}
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: Read> PooledReader<R> {
+	/// Reads the next frame, written with `write_framed_raw` or `write_framed`
+	pub fn read_frame(&mut self) -> io::Result<PooledBuffer> {
+		let len = UInt::deserialize_stream(&mut self.inner)?.into();
+		if len > MAX_BYTES_LENGTH {
+			return Err(io::Error::other("Frame length too large"));
+		}
+		let mut frame = self.pool.get();
+		frame.reserve(initial_capacity::<u8>(len));
+		if (&mut self.inner).take(len as u64).read_to_end(&mut frame)? < len {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream ended in the middle of a frame"));
+		}
+		Ok(frame)
+	}
+}
+
+#[cfg(feature = "tokio")]
+pub mod tokio {
+	//! The same reader, for tokio.
+
+	use std::io;
+	use tokio::io::AsyncReadExt;
+
+	use crate::{initial_capacity, tokio::PBType, UInt, MAX_BYTES_LENGTH};
+	pub use super::{BufferPool, PooledBuffer};
+
+	/// Reads frames into buffers from a [`BufferPool`]
+	#[derive(Debug)]
+	pub struct PooledReader<R> {
+		inner: R,
+		pool: BufferPool,
+	}
+
+	impl<R: AsyncReadExt + Unpin + Send> PooledReader<R> {
+		pub fn new(inner: R, pool: BufferPool) -> Self {
+			Self { inner, pool }
+		}
+		pub fn pool(&self) -> &BufferPool {
+			&self.pool
+		}
+		pub fn into_inner(self) -> R {
+			self.inner
+		}
+		/// Reads the next frame, written with `write_framed_raw` or `write_framed`
+		pub async fn read_frame(&mut self) -> io::Result<PooledBuffer> {
+			let len = UInt::deserialize_stream(&mut self.inner).await?.into();
+			if len > MAX_BYTES_LENGTH {
+				return Err(io::Error::other("Frame length too large"));
+			}
+			let mut frame = self.pool.get();
+			frame.reserve(initial_capacity::<u8>(len));
+			if (&mut self.inner).take(len as u64).read_to_end(&mut frame).await? < len {
+				return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream ended in the middle of a frame"));
+			}
+			Ok(frame)
+		}
+	}
+}
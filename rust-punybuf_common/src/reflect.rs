@@ -0,0 +1,133 @@
+//! Runtime reflection over generated types, enabled in the codegen with `--rust:reflect`.
+
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+use core::fmt::Debug;
+
+use crate::{Bytes, Done, LazyArray, SInt, StrictString, UInt};
+
+/// A borrowed value of a field, as seen by [`PBReflect`]
+#[derive(Debug, Clone)]
+pub enum FieldValue<'a> {
+	/// `Void`, empty structs, and enum variants without a value
+	Unit,
+	/// A flag without a value
+	Bool(bool),
+	U8(u8),
+	U16(u16),
+	U32(u32),
+	U64(u64),
+	I32(i32),
+	I64(i64),
+	F32(f32),
+	F64(f64),
+	UInt(u64),
+	SInt(i64),
+	String(&'a str),
+	Bytes(&'a [u8]),
+	Array(Vec<FieldValue<'a>>),
+	/// A flag with a value
+	Option(Option<Box<FieldValue<'a>>>),
+	/// A generated struct or enum
+	Reflect(&'a dyn PBReflect),
+}
+
+/// Implemented by generated structs and enums when using `--rust:reflect`.
+pub trait PBReflect: Debug + Send + Sync {
+	/// The name of the type, as it's declared in the definition
+	fn type_name(&self) -> &'static str;
+	/// The fields of a struct (with flags as separate fields), in the order
+	/// they're declared in. For enums, this is the current variant and its value.
+	fn fields(&self) -> Vec<(&'static str, FieldValue<'_>)>;
+}
+
+/// Converts a value into a [`FieldValue`]. All values inside a type have to
+/// implement this for the type to implement [`PBReflect`].
+pub trait AsFieldValue {
+	fn as_field_value(&self) -> FieldValue<'_>;
+}
+
+macro_rules! as_field_value {
+	($($tp:ty => $variant:ident),+) => {
+		$(
+			impl AsFieldValue for $tp {
+				fn as_field_value(&self) -> FieldValue<'_> {
+					FieldValue::$variant(*self)
+				}
+			}
+		)+
+	};
+}
+
+as_field_value!(
+	bool => Bool,
+	u8 => U8, u16 => U16, u32 => U32, u64 => U64,
+	i32 => I32, i64 => I64,
+	f32 => F32, f64 => F64
+);
+
+impl AsFieldValue for () {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::Unit
+	}
+}
+
+impl AsFieldValue for Done {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::Unit
+	}
+}
+
+impl AsFieldValue for UInt {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::UInt(self.0)
+	}
+}
+
+impl AsFieldValue for SInt {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::SInt(self.0)
+	}
+}
+
+impl AsFieldValue for Cow<'_, str> {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::String(self)
+	}
+}
+
+impl AsFieldValue for StrictString<'_> {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::String(&self.0)
+	}
+}
+
+impl AsFieldValue for Bytes<'_> {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::Bytes(&self.0)
+	}
+}
+
+impl<T: AsFieldValue> AsFieldValue for Vec<T> {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::Array(self.iter().map(|v| v.as_field_value()).collect())
+	}
+}
+
+/// The encoded items, since the decoded ones would have nowhere to be borrowed from
+impl<T> AsFieldValue for LazyArray<T> {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::Bytes(self.encoded())
+	}
+}
+
+impl<T: AsFieldValue> AsFieldValue for Option<T> {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		FieldValue::Option(self.as_ref().map(|v| Box::new(v.as_field_value())))
+	}
+}
+
+impl<T: AsFieldValue + ?Sized> AsFieldValue for Box<T> {
+	fn as_field_value(&self) -> FieldValue<'_> {
+		(**self).as_field_value()
+	}
+}
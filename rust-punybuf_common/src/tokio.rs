@@ -1,10 +1,14 @@
 use std::io::{self, Error};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
 
 pub use std::borrow::Cow;
 
-use crate::{const_unwrap, from_utf8_lossy_owned};
-pub use crate::{UInt, Done, Void, Bytes};
+use crate::{bool_from_byte, const_unwrap, from_utf8_lossy_owned, initial_capacity, invalid_utf8, trailing_bytes};
+pub use crate::{UInt, SInt, Done, Void, Bytes, StrictString, LazyArray, min_size_of};
+pub use crate::{AsFieldValue, FieldValue, PBReflect};
+pub use crate::{DecodePath, DecodePathError};
+#[cfg(feature = "chrono")]
+pub use crate::{DateTime, Utc};
 
 const MAX_BYTES_LENGTH: usize = const_unwrap!(usize::from_str_radix(env!("PUNYBUF_MAX_BYTES_LENGTH"), 10));
 const MAX_ARRAY_LENGTH: usize = const_unwrap!(usize::from_str_radix(env!("PUNYBUF_MAX_ARRAY_LENGTH"), 10));
@@ -18,12 +22,55 @@ const MAX_ARRAY_LENGTH: usize = const_unwrap!(usize::from_str_radix(env!("PUNYBU
 /// also allows for this change to potentially be non-
 /// breaking.
 pub trait PBType<'x>: Send + Sync {
+	/// The name of the type in the definition. See [`crate::PBType::TYPE_NAME`]
+	const TYPE_NAME: &'static str = "unknown";
+	/// The most bytes `serialize` can write. See [`crate::PBType::MAX_SIZE`]
+	const MAX_SIZE: Option<usize> = None;
+	/// The fewest bytes a value of the type takes. See [`crate::PBType::MIN_SIZE`]
+	const MIN_SIZE: usize = 0;
+	/// How many bytes every value takes, if it's always the same. See [`crate::PBType::FIXED_SIZE`]
+	const FIXED_SIZE: Option<usize> = None;
 	fn attributes() -> &'static [(&'static str, Option<&'static str>)] { &[] }
 	fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> impl std::future::Future<Output = io::Result<()>> + Send;
 	fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> impl std::future::Future<Output = io::Result<Self>> + Send where Self: Sized;
+	/// Serializes the elements of an array, without the length. See [`crate::PBType::serialize_slice`]
+	fn serialize_slice<W: AsyncWriteExt + Unpin + Send>(items: &[Self], w: &mut W) -> impl std::future::Future<Output = io::Result<()>> + Send where Self: Sized {
+		async move {
+			for item in items {
+				item.serialize(w).await?;
+			}
+			Ok(())
+		}
+	}
+
+	/// Serializes the value into a new [`bytes::Bytes`] buffer.
+	#[cfg(feature = "bytes")]
+	fn serialize_to_bytes(&self) -> impl std::future::Future<Output = io::Result<bytes::Bytes>> + Send {
+		async {
+			let mut w = Vec::new();
+			self.serialize(&mut w).await?;
+			Ok(w.into())
+		}
+	}
+	/// Deserializes the value from the start of `buf`, advancing it past the bytes that were read.
+	#[cfg(feature = "bytes")]
+	fn deserialize_from_bytes(buf: &mut bytes::Bytes) -> impl std::future::Future<Output = io::Result<Self>> + Send where Self: Sized {
+		async {
+			use bytes::Buf;
+			let mut slice = &buf[..];
+			let this = Self::deserialize_stream(&mut slice).await?;
+			let read = buf.len() - slice.len();
+			buf.advance(read);
+			Ok(this)
+		}
+	}
 }
 
 impl<'x> PBType<'x> for Done {
+	const TYPE_NAME: &'static str = "Done";
+	const MIN_SIZE: usize = 0;
+	const MAX_SIZE: Option<usize> = Some(0);
+	const FIXED_SIZE: Option<usize> = Some(0);
 	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, _w: &mut W) -> io::Result<()> {
 		Ok(())
 	}
@@ -33,6 +80,10 @@ impl<'x> PBType<'x> for Done {
 }
 
 impl<'x> PBType<'x> for Void {
+	const TYPE_NAME: &'static str = "Void";
+	const MIN_SIZE: usize = 0;
+	const MAX_SIZE: Option<usize> = Some(0);
+	const FIXED_SIZE: Option<usize> = Some(0);
 	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, _: &mut W) -> io::Result<()> {
 		Ok(())
 	}
@@ -42,39 +93,20 @@ impl<'x> PBType<'x> for Void {
 }
 
 impl<'x> PBType<'x> for UInt {
+	const TYPE_NAME: &'static str = "UInt";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(8);
 	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
-		let mut uint = self.0;
-		if uint < 128 {
-			w.write_all(&uint.to_be_bytes()[7..8]).await?;
-
-			} else if uint < 16512 {
-				uint -= 128;
-				let bytes = &mut uint.to_be_bytes()[6..8];
-				bytes[0] |= 0b10_000000;
-				w.write_all(bytes).await?;
-
-			} else if uint < 2113664 {
-				uint -= 16512;
-				let bytes = &mut uint.to_be_bytes()[5..8];
-				bytes[0] |= 0b110_00000;
-				w.write_all(bytes).await?;
-
-			} else if uint < 68721590400 {
-				uint -= 2113664;
-				let bytes = &mut uint.to_be_bytes()[3..8];
-				bytes[0] |= 0b1110_0000;
-				w.write_all(bytes).await?;
-
-			} else if uint < 1152921573328437376 {
-				uint -= 68721590400;
-				let bytes = &mut uint.to_be_bytes()[0..8];
-				bytes[0] |= 0b1111_0000;
-				w.write_all(bytes).await?;
-
-			} else {
-				Err(io::Error::other("number too big (max 1152921573328437375)"))?;
-			}
-			Ok(())
+		// see the sync version
+		let uint = self.0;
+		match uint {
+			0..128 => w.write_all(&[uint as u8]).await,
+			128..16512 => w.write_all(&((uint - 128) as u16 | 0b10 << 14).to_be_bytes()).await,
+			16512..2113664 => w.write_all(&((uint - 16512) as u32 | 0b110 << 21).to_be_bytes()[1..]).await,
+			2113664..68721590400 => w.write_all(&((uint - 2113664) | 0b1110 << 36).to_be_bytes()[3..]).await,
+			68721590400..1152921573328437376 => w.write_all(&((uint - 68721590400) | 0b1111 << 60).to_be_bytes()).await,
+			_ => Err(io::Error::other("number too big (max 1152921573328437375)")),
+		}
 	}
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut first_byte = [0; 1];
@@ -116,7 +148,40 @@ impl<'x> PBType<'x> for UInt {
 	}
 }
 
+impl<'x> PBType<'x> for SInt {
+	const TYPE_NAME: &'static str = "SInt";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(8);
+	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+		if self.to_zigzag().0 > 1152921573328437375 {
+			return Err(io::Error::other("number out of range (min -576460786664218688, max 576460786664218687)"));
+		}
+		self.to_zigzag().serialize(w).await
+	}
+	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+		Ok(Self::from_zigzag(UInt::deserialize_stream(r).await?))
+	}
+}
+
+impl<'x> PBType<'x> for bool {
+	const TYPE_NAME: &'static str = "Boolean";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(1);
+	const FIXED_SIZE: Option<usize> = Some(1);
+	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+		let mut buf = [0; 1];
+		r.read_exact(&mut buf).await?;
+		bool_from_byte(buf[0])
+	}
+	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+		w.write_all(&[if *self { 0 } else { 1 }]).await
+	}
+}
 impl<'x> PBType<'x> for u8 {
+	const TYPE_NAME: &'static str = "U8";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = Some(1);
+	const FIXED_SIZE: Option<usize> = Some(1);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 1];
 		r.read_exact(&mut buf).await?;
@@ -125,8 +190,15 @@ impl<'x> PBType<'x> for u8 {
 	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
 		w.write_all(&[*self]).await
 	}
+	async fn serialize_slice<W: AsyncWriteExt + Unpin + Send>(items: &[Self], w: &mut W) -> io::Result<()> {
+		w.write_all(items).await
+	}
 }
 impl<'x> PBType<'x> for u16 {
+	const TYPE_NAME: &'static str = "U16";
+	const MIN_SIZE: usize = 2;
+	const MAX_SIZE: Option<usize> = Some(2);
+	const FIXED_SIZE: Option<usize> = Some(2);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 2];
 		r.read_exact(&mut buf).await?;
@@ -137,6 +209,10 @@ impl<'x> PBType<'x> for u16 {
 	}
 }
 impl<'x> PBType<'x> for u32 {
+	const TYPE_NAME: &'static str = "U32";
+	const MIN_SIZE: usize = 4;
+	const MAX_SIZE: Option<usize> = Some(4);
+	const FIXED_SIZE: Option<usize> = Some(4);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 4];
 		r.read_exact(&mut buf).await?;
@@ -147,6 +223,10 @@ impl<'x> PBType<'x> for u32 {
 	}
 }
 impl<'x> PBType<'x> for u64 {
+	const TYPE_NAME: &'static str = "U64";
+	const MIN_SIZE: usize = 8;
+	const MAX_SIZE: Option<usize> = Some(8);
+	const FIXED_SIZE: Option<usize> = Some(8);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 8];
 		r.read_exact(&mut buf).await?;
@@ -157,6 +237,10 @@ impl<'x> PBType<'x> for u64 {
 	}
 }
 impl<'x> PBType<'x> for i32 {
+	const TYPE_NAME: &'static str = "I32";
+	const MIN_SIZE: usize = 4;
+	const MAX_SIZE: Option<usize> = Some(4);
+	const FIXED_SIZE: Option<usize> = Some(4);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 4];
 		r.read_exact(&mut buf).await?;
@@ -167,6 +251,10 @@ impl<'x> PBType<'x> for i32 {
 	}
 }
 impl<'x> PBType<'x> for i64 {
+	const TYPE_NAME: &'static str = "I64";
+	const MIN_SIZE: usize = 8;
+	const MAX_SIZE: Option<usize> = Some(8);
+	const FIXED_SIZE: Option<usize> = Some(8);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 8];
 		r.read_exact(&mut buf).await?;
@@ -177,6 +265,10 @@ impl<'x> PBType<'x> for i64 {
 	}
 }
 impl<'x> PBType<'x> for f32 {
+	const TYPE_NAME: &'static str = "F32";
+	const MIN_SIZE: usize = 4;
+	const MAX_SIZE: Option<usize> = Some(4);
+	const FIXED_SIZE: Option<usize> = Some(4);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 4];
 		r.read_exact(&mut buf).await?;
@@ -187,6 +279,10 @@ impl<'x> PBType<'x> for f32 {
 	}
 }
 impl<'x> PBType<'x> for f64 {
+	const TYPE_NAME: &'static str = "F64";
+	const MIN_SIZE: usize = 8;
+	const MAX_SIZE: Option<usize> = Some(8);
+	const FIXED_SIZE: Option<usize> = Some(8);
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let mut buf = [0; 8];
 		r.read_exact(&mut buf).await?;
@@ -197,21 +293,102 @@ impl<'x> PBType<'x> for f64 {
 	}
 }
 
+/// Encoded like `Optional<T>`: a `0` octet for `None`, or a `1` octet followed by the value.
+///
+/// Generated code uses this for `Optional` fields and for the frames returned by `@stream` commands.
+impl<'x, T: PBType<'x>> PBType<'x> for Option<T> {
+	const TYPE_NAME: &'static str = "Optional";
+	const MIN_SIZE: usize = 1;
+	const MAX_SIZE: Option<usize> = match T::MAX_SIZE {
+		Some(size) => Some(1 + size),
+		None => None,
+	};
+	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+		match self {
+			None => w.write_u8(0).await,
+			Some(value) => {
+				w.write_u8(1).await?;
+				value.serialize(w).await
+			}
+		}
+	}
+	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+		match r.read_u8().await? {
+			0 => Ok(None),
+			1 => Ok(Some(T::deserialize_stream(r).await?)),
+			_ => Err(Error::other("Invalid Optional discriminant")),
+		}
+	}
+}
+
+/// Encoded the same as `T`, see [`crate::PBType`]'s implementation for `Box<T>`
+impl<'x, T: PBType<'x>> PBType<'x> for Box<T> {
+	const TYPE_NAME: &'static str = T::TYPE_NAME;
+	const MIN_SIZE: usize = T::MIN_SIZE;
+	// Not `T::MAX_SIZE`, which would depend on itself. A type containing itself has no maximum anyway.
+	const MAX_SIZE: Option<usize> = None;
+	fn attributes() -> &'static [(&'static str, Option<&'static str>)] {
+		T::attributes()
+	}
+	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+		(**self).serialize(w).await
+	}
+	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+		Ok(Box::new(T::deserialize_stream(r).await?))
+	}
+}
+
+/// Reads the values returned by a `@stream` command, see [`PBCommandExt::deserialize_return_items`].
+///
+/// Every value is a frame encoded as `Optional<Return>`, and the first `None` ends the stream.
+/// After the end of the stream or an error, [`Self::next`] only returns `None`.
+pub struct ReturnStream<'r, R, T> {
+	r: &'r mut R,
+	ended: bool,
+	_item: std::marker::PhantomData<T>,
+}
+
+impl<'r, R: AsyncReadExt + Unpin + Send, T: PBType<'static>> ReturnStream<'r, R, T> {
+	pub fn new(r: &'r mut R) -> Self {
+		Self { r, ended: false, _item: std::marker::PhantomData }
+	}
+	/// Whether the end of the stream was reached, or it failed
+	pub fn is_ended(&self) -> bool {
+		self.ended
+	}
+	/// Reads the next value, or returns `None` at the end of the stream
+	pub async fn next(&mut self) -> Option<io::Result<T>> {
+		if self.ended {
+			return None;
+		}
+		match Option::<T>::deserialize_stream(self.r).await {
+			Ok(Some(value)) => Some(Ok(value)),
+			Ok(None) => {
+				self.ended = true;
+				None
+			}
+			Err(err) => {
+				self.ended = true;
+				Some(Err(err))
+			}
+		}
+	}
+}
+
 impl<'x, T: PBType<'x>> PBType<'x> for Vec<T> {
+	const TYPE_NAME: &'static str = "Array";
+	const MIN_SIZE: usize = 1;
 	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
 		let len = self.len() as u64;
 		UInt(len).serialize(w).await?;
-		for item in self {
-			item.serialize(w).await?;
-		}
-		Ok(())
+		T::serialize_slice(self, w).await
 	}
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let len = UInt::deserialize_stream(r).await?.into();
 		if len > MAX_ARRAY_LENGTH {
 			return Err(Error::other("Array length too large"));
 		}
-		let mut this = Vec::with_capacity(len);
+		let mut this = Vec::with_capacity(initial_capacity::<T>(len));
 
 		for _ in 0..len {
 			this.push(T::deserialize_stream(r).await?);
@@ -222,6 +399,8 @@ impl<'x, T: PBType<'x>> PBType<'x> for Vec<T> {
 }
 
 impl<'x> PBType<'x> for Bytes<'_> {
+	const TYPE_NAME: &'static str = "Bytes";
+	const MIN_SIZE: usize = 1;
 	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
 		let len = self.0.len() as u64;
 		UInt(len).serialize(w).await?;
@@ -233,7 +412,7 @@ impl<'x> PBType<'x> for Bytes<'_> {
 		if len > MAX_BYTES_LENGTH {
 			return Err(Error::other("Bytes length too large"));
 		}
-		let mut this = Vec::with_capacity(len);
+		let mut this = Vec::with_capacity(initial_capacity::<u8>(len));
 		let mut taken = r.take(len as u64);
 
 		taken.read_to_end(&mut this).await?;
@@ -243,13 +422,15 @@ impl<'x> PBType<'x> for Bytes<'_> {
 
 
 impl<'x> PBType<'x> for String {
+	const TYPE_NAME: &'static str = "String";
+	const MIN_SIZE: usize = 1;
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		let len = UInt::deserialize_stream(r).await?.into();
 		if len > MAX_BYTES_LENGTH {
 			return Err(Error::other("String length too large"));
 		}
 
-		let mut this = Vec::with_capacity(len);
+		let mut this = Vec::with_capacity(initial_capacity::<u8>(len));
 		let mut taken = r.take(len as u64);
 
 		taken.read_to_end(&mut this).await?;
@@ -265,6 +446,8 @@ impl<'x> PBType<'x> for String {
 }
 
 impl<'x> PBType<'x> for Cow<'_, str> {
+	const TYPE_NAME: &'static str = "String";
+	const MIN_SIZE: usize = 1;
 	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
 		Ok(String::deserialize_stream(r).await?.into())
 	}
@@ -274,6 +457,144 @@ impl<'x> PBType<'x> for Cow<'_, str> {
 	}
 }
 
+impl<'x> PBType<'x> for StrictString<'_> {
+	const TYPE_NAME: &'static str = "String";
+	const MIN_SIZE: usize = 1;
+	async fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Self> {
+		let bytes: Vec<u8> = Bytes::deserialize_stream(r).await?.into();
+		let string = String::from_utf8(bytes).map_err(|e| invalid_utf8(e.utf8_error()))?;
+		Ok(Self(Cow::Owned(string)))
+	}
+
+	async fn serialize<W: AsyncWriteExt + Unpin + Send>(&self, w: &mut W) -> io::Result<()> {
+		self.0.serialize(w).await
+	}
+}
+
+/// Reads the extension length (a `UInt`) and discards exactly that many bytes.
+///
+/// Used to skip the value of an unknown `@extension` enum variant.
+pub async fn discard_extension<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<()> {
+	let len = UInt::deserialize_stream(r).await?.0;
+	let discarded = tokio::io::copy(&mut r.take(len), &mut tokio::io::sink()).await?;
+	if discarded < len {
+		Err(io::Error::new(io::ErrorKind::UnexpectedEof, "buffer too small"))?;
+	}
+	Ok(())
+}
+
+/// Runs `future` to completion right away, which works for decoding from memory, since it
+/// never has to wait. The `<field>_iter` methods of `@lazy_array` fields use it to decode
+/// the items in a regular `Iterator`.
+pub fn decode_now<T>(future: impl Future<Output = io::Result<T>>) -> io::Result<T> {
+	let mut future = std::pin::pin!(future);
+	match future.as_mut().poll(&mut std::task::Context::from_waker(std::task::Waker::noop())) {
+		std::task::Poll::Ready(result) => result,
+		std::task::Poll::Pending => Err(Error::other("decoding had to wait for more data")),
+	}
+}
+
+/// Deserializes a value and makes sure nothing is left in the reader afterwards.
+///
+/// Use this for decoding a complete buffer, where extra data means that the
+/// buffer is corrupted or was encoded with a different schema.
+pub async fn deserialize_exact<'x, T: PBType<'x>, R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<T> {
+	let value = T::deserialize_stream(r).await?;
+	if r.read(&mut [0u8]).await? != 0 {
+		return Err(trailing_bytes());
+	}
+	Ok(value)
+}
+
+/// Writes a value generated for synchronous code into an async writer.
+///
+/// The whole value is serialized into a `Vec<u8>` first, then written in one go, so
+/// this costs an extra allocation and copy of the encoded value. Prefer generating
+/// tokio code (`--rust:tokio` or `--rust:both`) for types that are written often.
+pub async fn write_to_async<'x, T: crate::PBType<'x>, W: AsyncWriteExt + Unpin + Send>(value: &T, w: &mut W) -> io::Result<()> {
+	let mut buf = Vec::new();
+	value.serialize(&mut buf)?;
+	w.write_all(&buf).await
+}
+
+/// Reads a value generated for synchronous code from an async reader.
+///
+/// The encoded length of a value isn't known upfront, so the bytes are buffered in a
+/// `Vec<u8>`, and deserialization is retried every time more bytes arrive, until it stops
+/// failing with [`io::ErrorKind::UnexpectedEof`]. Values that arrive in many small reads
+/// get decoded many times over. Only the bytes of the value are consumed from `r`.
+pub async fn read_from_async<T: crate::PBType<'static>, R: AsyncBufRead + Unpin + Send>(r: &mut R) -> io::Result<T> {
+	let mut buf = Vec::new();
+	loop {
+		let available = r.fill_buf().await?;
+		if available.is_empty() {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream ended in the middle of a value"));
+		}
+		let previous_len = buf.len();
+		buf.extend_from_slice(available);
+
+		let mut slice = &buf[..];
+		match T::deserialize_stream(&mut slice) {
+			Ok(value) => {
+				let read = buf.len() - slice.len();
+				r.consume(read - previous_len);
+				return Ok(value);
+			}
+			Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+				let available_len = buf.len() - previous_len;
+				r.consume(available_len);
+			}
+			Err(err) => {
+				// don't leave the part of the value that was read in the reader
+				r.consume(buf.len() - previous_len);
+				return Err(err);
+			}
+		}
+	}
+}
+
+/// Writes `value` prefixed by its encoded length, as a `UInt`, like `Bytes` are.
+/// Read it with [`read_framed`].
+///
+/// The value is serialized into a `Vec<u8>` first, since its length has to be written before it.
+pub async fn write_framed<'x, W: AsyncWriteExt + Unpin + Send, T: PBType<'x>>(w: &mut W, value: &T) -> io::Result<()> {
+	let mut body = Vec::new();
+	value.serialize(&mut body).await?;
+	write_framed_raw(w, &body).await
+}
+
+/// Reads a value written with [`write_framed`].
+///
+/// The whole frame is read before the value is deserialized, so the next frame can be read
+/// even if this one fails to deserialize. A value that doesn't take up the whole frame is an error.
+pub async fn read_framed<'x, R: AsyncReadExt + Unpin + Send, T: PBType<'x>>(r: &mut R) -> io::Result<T> {
+	let frame = read_framed_raw(r).await?;
+	deserialize_exact(&mut &frame[..]).await
+}
+
+/// Writes `body` prefixed by its length, as a `UInt`, without looking into it.
+/// The same as [`crate::write_framed_raw`], for tokio.
+pub async fn write_framed_raw<W: AsyncWriteExt + Unpin + Send>(w: &mut W, body: &[u8]) -> io::Result<()> {
+	if body.len() > MAX_BYTES_LENGTH {
+		return Err(Error::other("Frame length too large"));
+	}
+	UInt(body.len() as u64).serialize(w).await?;
+	w.write_all(body).await
+}
+
+/// Reads a frame without decoding it, so that it can be passed along as it is.
+/// The same as [`crate::read_framed_raw`], for tokio.
+pub async fn read_framed_raw<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> io::Result<Vec<u8>> {
+	let len = UInt::deserialize_stream(r).await?.into();
+	if len > MAX_BYTES_LENGTH {
+		return Err(Error::other("Frame length too large"));
+	}
+	let mut frame = Vec::with_capacity(initial_capacity::<u8>(len));
+	if r.take(len as u64).read_to_end(&mut frame).await? < len {
+		return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream ended in the middle of a frame"));
+	}
+	Ok(frame)
+}
 
 /// A trait that all individual commands implement. The enum of all commands *does not* implement this trait.
 ///
@@ -290,6 +611,8 @@ pub trait PBCommandExt<'x>: Sized + Send + Sync {
 	const ID: u32;
 	/// Whether the `Return` type is `Void`.
 	const IS_VOID: bool = false;
+	/// Whether this is a `@stream` command, returning any number of `Return` values.
+	const IS_STREAM: bool = false;
 
 	const ATTRIBUTES: &'static [(&'static str, Option<&'static str>)] = &[];
 	const REQUIRED_CAPABILITY: Option<&'static str> = None;
@@ -297,13 +620,66 @@ pub trait PBCommandExt<'x>: Sized + Send + Sync {
 	fn deserialize_return_stream<R: AsyncReadExt + Unpin + Send>(&self, r: &mut R) -> impl std::future::Future<Output = io::Result<Self::Return<'static>>> + Send {
 		async { Self::Return::deserialize_stream(r).await }
 	}
+	/// Reads the values returned by a `@stream` command, until the end of the stream.
+	fn deserialize_return_items<'r, R: AsyncReadExt + Unpin + Send>(&self, r: &'r mut R) -> ReturnStream<'r, R, Self::Return<'static>> {
+		ReturnStream::new(r)
+	}
 	fn deserialize_error_stream<R: AsyncReadExt + Unpin + Send>(&self, r: &mut R) -> impl std::future::Future<Output = io::Result<Self::Error<'static>>> + Send {
 		async { Self::Error::deserialize_stream(r).await }
 	}
+	/// Reads the error if `is_error` is set, and the return value otherwise.
+	/// How the RPC implementation tells the two apart is up to it.
+	fn deserialize_outcome<R: AsyncReadExt + Unpin + Send>(&self, r: &mut R, is_error: bool) -> impl std::future::Future<Output = io::Result<CommandOutcome<'x, Self>>> + Send {
+		async move {
+			Ok(if is_error {
+				CommandOutcome::Err(self.deserialize_error_stream(r).await?)
+			} else {
+				CommandOutcome::Ok(self.deserialize_return_stream(r).await?)
+			})
+		}
+	}
 
 	/// Does **not** read the command ID.  
 	/// If you need to read the command ID, use `Command::deserialize` from the generated file.
 	fn deserialize_stream<R: AsyncReadExt + Unpin + Send>(r: &mut R) -> impl std::future::Future<Output = io::Result<Self>> + Send;
+
+	/// Does **not** read the command ID. Advances `buf` past the bytes that were read.
+	#[cfg(feature = "bytes")]
+	fn deserialize_from_bytes(buf: &mut bytes::Bytes) -> impl std::future::Future<Output = io::Result<Self>> + Send {
+		async {
+			use bytes::Buf;
+			let mut slice = &buf[..];
+			let this = Self::deserialize_stream(&mut slice).await?;
+			let read = buf.len() - slice.len();
+			buf.advance(read);
+			Ok(this)
+		}
+	}
+}
+
+/// The response to the command `C`, see [`PBCommandExt::deserialize_outcome`]
+pub enum CommandOutcome<'x, C: PBCommandExt<'x>> {
+	Ok(C::Return<'static>),
+	Err(C::Error<'static>),
+}
+
+impl<'x, C: PBCommandExt<'x>> CommandOutcome<'x, C> {
+	pub fn into_result(self) -> Result<C::Return<'static>, C::Error<'static>> {
+		match self {
+			Self::Ok(ret) => Ok(ret),
+			Self::Err(err) => Err(err),
+		}
+	}
+}
+
+impl<'x, C: PBCommandExt<'x>> std::fmt::Debug for CommandOutcome<'x, C>
+where C::Return<'static>: std::fmt::Debug, C::Error<'static>: std::fmt::Debug {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Ok(ret) => f.debug_tuple("Ok").field(ret).finish(),
+			Self::Err(err) => f.debug_tuple("Err").field(err).finish(),
+		}
+	}
 }
 
 /// A trait that all commands implement.
@@ -312,6 +688,8 @@ pub trait PBCommand: Sized + Send + Sync {
 
 	/// Whether the `Return` type is `Void`
 	fn is_void(&self) -> bool { false }
+	/// Whether this is a `@stream` command, returning any number of values
+	fn is_stream(&self) -> bool { false }
 
 	fn attributes(&self) -> &'static [(&'static str, Option<&'static str>)] { &[] }
 	fn required_capability(&self) -> Option<&'static str> {
@@ -328,4 +706,14 @@ pub trait PBCommand: Sized + Send + Sync {
 			self.serialize_self(w).await
 		}
 	}
-}
\ No newline at end of file
+
+	/// Writes both the command ID and the argument body into a new [`bytes::Bytes`] buffer
+	#[cfg(feature = "bytes")]
+	fn serialize_to_bytes(&self) -> impl std::future::Future<Output = io::Result<bytes::Bytes>> + Send {
+		async {
+			let mut w = Vec::new();
+			self.serialize(&mut w).await?;
+			Ok(w.into())
+		}
+	}
+}
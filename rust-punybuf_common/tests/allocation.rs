@@ -0,0 +1,44 @@
+//! Makes sure that lengths read from the input don't decide how much memory is allocated up front.
+//! It's a separate test binary, since it replaces the global allocator.
+
+use std::{alloc::{GlobalAlloc, Layout, System}, sync::atomic::{AtomicUsize, Ordering}};
+
+use punybuf_common::{PBType, UInt};
+
+/// Remembers the largest allocation
+struct Tracking;
+
+static LARGEST: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Tracking {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		LARGEST.fetch_max(layout.size(), Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		LARGEST.fetch_max(new_size, Ordering::Relaxed);
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+#[global_allocator]
+static ALLOCATOR: Tracking = Tracking;
+
+#[test]
+fn huge_lengths_with_no_items_allocate_little() {
+	let mut input = vec![];
+	UInt(1_000_000).serialize(&mut input).unwrap();
+	LARGEST.store(0, Ordering::Relaxed);
+
+	assert!(Vec::<u64>::deserialize_stream(&mut &input[..]).is_err());
+	assert!(Vec::<u64>::deserialize(&mut &input[..]).is_err());
+	assert!(Vec::<Vec<u64>>::deserialize_stream(&mut &input[..]).is_err());
+	let mut existing = vec![1u64, 2, 3];
+	assert!(existing.deserialize_into(&mut &input[..]).is_err());
+
+	let largest = LARGEST.load(Ordering::Relaxed);
+	assert!(largest <= 64 * 1024, "allocated {largest} bytes for an empty input");
+}
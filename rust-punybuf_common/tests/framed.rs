@@ -0,0 +1,89 @@
+//! Sends frames written with `write_framed` through a pipe.
+#![cfg(feature = "tokio")]
+
+use std::borrow::Cow;
+
+use punybuf_common::tokio::{read_framed, write_framed, PBType, UInt};
+use tokio::io::AsyncWriteExt;
+
+#[tokio::test]
+async fn frames_go_through_a_pipe() {
+	let (mut client, mut server) = tokio::io::duplex(64);
+
+	let writer = async move {
+		write_framed(&mut client, &vec![1u32, 2, 3]).await.unwrap();
+		// longer than the pipe's buffer
+		write_framed(&mut client, &Cow::<str>::Owned("punybuf ".repeat(100))).await.unwrap();
+		write_framed(&mut client, &vec![4u32, 5]).await.unwrap();
+		write_framed(&mut client, &UInt(300)).await.unwrap();
+		// a frame that ends before its value does
+		UInt(3).serialize(&mut client).await.unwrap();
+		client.write_all(&[0, 0, 0]).await.unwrap();
+	};
+
+	let reader = async {
+		assert_eq!(read_framed::<_, Vec<u32>>(&mut server).await.unwrap(), [1, 2, 3]);
+		assert_eq!(read_framed::<_, Cow<str>>(&mut server).await.unwrap(), "punybuf ".repeat(100));
+		// the rest of the frame is still read, so the next one isn't affected
+		let err = read_framed::<_, u32>(&mut server).await.unwrap_err();
+		assert_eq!(err.to_string(), "trailing bytes");
+		assert_eq!(read_framed::<_, UInt>(&mut server).await.unwrap(), UInt(300));
+		let err = read_framed::<_, u32>(&mut server).await.unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+	};
+
+	tokio::join!(writer, reader);
+}
+
+#[tokio::test]
+async fn raw_frames_are_relayed_unchanged() {
+	use punybuf_common::tokio::{read_framed_raw, write_framed_raw};
+
+	let (mut client, mut relay_in) = tokio::io::duplex(64);
+	let (mut relay_out, mut server) = tokio::io::duplex(64);
+
+	let writer = async move {
+		write_framed(&mut client, &vec![1u64, 2, 3]).await.unwrap();
+		write_framed(&mut client, &Cow::<str>::Borrowed("through the relay")).await.unwrap();
+	};
+	// doesn't know what's inside the frames
+	let relay = async move {
+		for _ in 0..2 {
+			let frame = read_framed_raw(&mut relay_in).await.unwrap();
+			write_framed_raw(&mut relay_out, &frame).await.unwrap();
+		}
+		assert!(read_framed_raw(&mut relay_in).await.is_err());
+	};
+	let reader = async {
+		assert_eq!(read_framed::<_, Vec<u64>>(&mut server).await.unwrap(), [1, 2, 3]);
+		assert_eq!(read_framed::<_, Cow<str>>(&mut server).await.unwrap(), "through the relay");
+	};
+
+	tokio::join!(writer, relay, reader);
+}
+
+#[tokio::test]
+async fn pooled_frames_reuse_buffers() {
+	use punybuf_common::{pool::{tokio::PooledReader, BufferPool}, tokio::deserialize_exact};
+
+	let (mut client, server) = tokio::io::duplex(64);
+	let writer = async move {
+		for i in 0..10u64 {
+			write_framed(&mut client, &vec![Cow::<str>::Owned(format!("frame {i}")); i as usize]).await.unwrap();
+		}
+	};
+	let pool = BufferPool::new(4);
+	let reader = async {
+		let mut reader = PooledReader::new(server, pool.clone());
+		for i in 0..10u64 {
+			let frame = reader.read_frame().await.unwrap();
+			let decoded: Vec<Cow<str>> = deserialize_exact(&mut &frame[..]).await.unwrap();
+			assert_eq!(decoded, vec![format!("frame {i}"); i as usize]);
+		}
+		assert!(reader.read_frame().await.is_err());
+	};
+
+	tokio::join!(writer, reader);
+	// one buffer was enough, since every frame was dropped before the next one was read
+	assert_eq!(pool.available(), 1);
+}
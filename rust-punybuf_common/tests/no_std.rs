@@ -0,0 +1,51 @@
+//! Encodes and decodes values through `punybuf_common::io`, which is the crate's own
+//! `Read` and `Write` without the `std` feature, and `std::io` with it.
+//! Run with `cargo test --no-default-features --test no_std`.
+
+use std::borrow::Cow;
+
+use punybuf_common::{io::{self, Read, Write}, discard_extension, Bytes, PBType, SInt, UInt};
+
+#[test]
+fn values_round_trip() {
+	let mut buf = vec![];
+	UInt(16512).serialize(&mut buf).unwrap();
+	SInt(-3).serialize(&mut buf).unwrap();
+	Cow::<str>::Borrowed("no std").serialize(&mut buf).unwrap();
+	Bytes(Cow::Borrowed(&[1, 2, 3])).serialize(&mut buf).unwrap();
+	vec![1u32, 2].serialize(&mut buf).unwrap();
+
+	let r = &mut &buf[..];
+	assert_eq!(UInt::deserialize_stream(r).unwrap(), UInt(16512));
+	assert_eq!(SInt::deserialize_stream(r).unwrap(), SInt(-3));
+	assert_eq!(Cow::<str>::deserialize_stream(r).unwrap(), "no std");
+	assert_eq!(*Bytes::deserialize_stream(r).unwrap().0, [1, 2, 3]);
+	assert_eq!(Vec::<u32>::deserialize_stream(r).unwrap(), [1, 2]);
+	assert!(r.is_empty());
+
+	let err = UInt::deserialize_stream(&mut &buf[..2]).unwrap_err();
+	assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn readers_and_writers() {
+	// writing into a slice stops at its end
+	let mut array = [0u8; 4];
+	let mut slice = &mut array[..];
+	assert_eq!(slice.write(&[1, 2, 3]).unwrap(), 3);
+	assert_eq!(slice.write(&[4, 5]).unwrap(), 1);
+	assert_eq!(array, [1, 2, 3, 4]);
+	assert_eq!((&mut [0u8; 1][..]).write_all(&[1, 2]).unwrap_err().kind(), io::ErrorKind::WriteZero);
+
+	let mut read = vec![];
+	assert_eq!((&array[..]).take(3).read_to_end(&mut read).unwrap(), 3);
+	assert_eq!(read, [1, 2, 3]);
+
+	let mut extension = vec![];
+	UInt(3).serialize(&mut extension).unwrap();
+	extension.extend([7, 7, 7, 9]);
+	let r = &mut &extension[..];
+	discard_extension(r).unwrap();
+	assert_eq!(*r, [9]);
+	assert!(discard_extension(&mut &extension[..3]).is_err());
+}